@@ -171,6 +171,37 @@ impl KeyGenerator {
         self.create_galois_keys_internal(false)
     }
 
+    /**
+     * Generates Galois keys that support rotating batched rows by exactly
+     * the given steps.
+     *
+     * # Remarks
+     * A positive step rotates rows to the left and a negative step to the
+     * right. A step of 0 creates the key that swaps the two rows (i.e.
+     * rotates the columns). Every time this function is called, new Galois
+     * keys will be generated.
+     *
+     * Unlike [`create_galois_keys`](Self::create_galois_keys), rotating by
+     * a step not in `steps` with the resulting keys fails. In exchange,
+     * programs that rotate by only a few amounts need far smaller keys.
+     */
+    pub fn create_galois_keys_from_steps(&self, steps: &[i32]) -> Result<GaloisKeys> {
+        let mut handle = null_mut();
+        let mut steps = steps.to_owned();
+
+        convert_seal_error(unsafe {
+            bindgen::KeyGenerator_CreateGaloisKeysFromSteps(
+                self.handle,
+                steps.len() as u64,
+                steps.as_mut_ptr(),
+                false,
+                &mut handle,
+            )
+        })?;
+
+        Ok(GaloisKeys { handle })
+    }
+
     fn create_galois_keys_internal(&self, save_seed: bool) -> Result<GaloisKeys> {
         let mut handle = null_mut();
 
@@ -831,6 +862,26 @@ mod tests {
         gen.create_galois_keys().unwrap();
     }
 
+    #[test]
+    fn can_create_galois_key_from_steps() {
+        let params = BfvEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(8192)
+            .set_coefficient_modulus(
+                CoefficientModulus::bfv_default(8192, SecurityLevel::TC128).unwrap(),
+            )
+            .set_plain_modulus(PlainModulus::batching(8192, 32).unwrap())
+            .build()
+            .unwrap();
+
+        let ctx = Context::new(&params, false, SecurityLevel::TC128).unwrap();
+        let gen = KeyGenerator::new(&ctx).unwrap();
+
+        let all = gen.create_galois_keys().unwrap();
+        let some = gen.create_galois_keys_from_steps(&[0, 1, -3]).unwrap();
+
+        assert!(some.as_bytes().unwrap().len() < all.as_bytes().unwrap().len());
+    }
+
     #[test]
     fn can_init_from_existing_secret_key() {
         let params = BfvEncryptionParametersBuilder::new()
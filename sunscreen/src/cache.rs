@@ -0,0 +1,475 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use log::warn;
+use petgraph::visit::{EdgeRef, IntoEdgeReferences};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use sunscreen_runtime::CompiledFheProgram;
+
+use crate::{fhe::FheFrontendCompilation, Error, Result};
+
+/**
+ * The version of the on-disk format of [`ProgramCache`] entries. Bump this
+ * whenever the layout of a cache entry or of [`CompiledFheProgram`] changes.
+ */
+const CACHE_FORMAT_VERSION: u32 = 2;
+
+/**
+ * A SHA3-256 digest of an FHE program's frontend graph. See
+ * [`structural_hash`].
+ */
+pub type StructuralHash = [u8; 32];
+
+/**
+ * Computes a hash of the operations and edges of `compilation`.
+ *
+ * # Remarks
+ * Two programs that build the same graph hash identically, regardless of
+ * their names or how their source is formatted. Node indices feed into
+ * the hash, so graphs that are isomorphic but were built in a different
+ * order hash differently. This errs on the side of cache misses.
+ */
+pub fn structural_hash(compilation: &FheFrontendCompilation) -> StructuralHash {
+    let mut hasher = Sha3_256::new();
+
+    // Bincode only fails on sequences of unknown length, which these types
+    // don't contain.
+    for n in compilation.node_indices() {
+        hasher.update((n.index() as u64).to_le_bytes());
+        hasher.update(bincode::serialize(&compilation[n].operation).unwrap());
+    }
+
+    for e in compilation.edge_references() {
+        hasher.update((e.source().index() as u64).to_le_bytes());
+        hasher.update((e.target().index() as u64).to_le_bytes());
+        hasher.update(bincode::serialize(e.weight()).unwrap());
+    }
+
+    hasher.finalize().into()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * Identifies a compiled FHE program in a [`ProgramCache`].
+ *
+ * # Remarks
+ * A cache key is composed of
+ * * `program`, the name of the FHE program.
+ * * `structural_hashes`, the name and [`structural_hash`] of every FHE
+ *   program compiled alongside it, sorted by name. Programs compiled
+ *   together share parameters, so changing any of them invalidates all of
+ *   them.
+ * * `constraints`, a canonical rendering of the requested compiler
+ *   configuration: the parameter mode (search, auto-tune target, or
+ *   manual parameters), plaintext modulus constraint, security level,
+ *   noise margin, the names of any custom passes, and the
+ *   relinearization strategy.
+ * * `compiler_version`, the version of this crate.
+ *
+ * Parameter search [limits](crate::GenericCompiler::search_timeout) aren't
+ * part of the key. A search that runs out of budget fails rather than
+ * returning different parameters, so every successful compilation under
+ * the same constraints produces the same programs.
+ *
+ * Entries are stored in a file named after the program name and structural
+ * hashes, so changing the constraints or compiler version overwrites the
+ * stale entry rather than accumulating new ones.
+ */
+pub struct CacheKey {
+    /**
+     * The name of the FHE program.
+     */
+    pub program: String,
+
+    /**
+     * The name and structural hash of each FHE program compiled alongside
+     * this one (including itself), sorted by name.
+     */
+    pub structural_hashes: Vec<(String, StructuralHash)>,
+
+    /**
+     * A canonical rendering of the requested compiler configuration.
+     */
+    pub constraints: String,
+
+    /**
+     * The version of the compiler that produced the entry.
+     */
+    pub compiler_version: String,
+}
+
+impl CacheKey {
+    /**
+     * Creates a key for the current compiler version.
+     */
+    pub fn new(
+        program: &str,
+        mut structural_hashes: Vec<(String, StructuralHash)>,
+        constraints: String,
+    ) -> Self {
+        structural_hashes.sort();
+
+        Self {
+            program: program.to_owned(),
+            structural_hashes,
+            constraints,
+            compiler_version: env!("CARGO_PKG_VERSION").to_owned(),
+        }
+    }
+
+    fn file_name(&self) -> String {
+        let mut hasher = Sha3_256::new();
+
+        hasher.update(self.program.as_bytes());
+
+        for (name, hash) in &self.structural_hashes {
+            hasher.update((name.len() as u64).to_le_bytes());
+            hasher.update(name.as_bytes());
+            hasher.update(hash);
+        }
+
+        let digest = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>();
+
+        format!("{digest}.bin")
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    format_version: u32,
+    key: CacheKey,
+    program: CompiledFheProgram,
+}
+
+#[derive(Debug, Clone)]
+/**
+ * A directory of serialized [`CompiledFheProgram`]s, allowing repeated
+ * compilations of unchanged programs to skip parameter search and backend
+ * compilation.
+ *
+ * # Remarks
+ * Pass a cache to the compiler with
+ * [`GenericCompiler::cache`](crate::GenericCompiler::cache). See
+ * [`CacheKey`] for what invalidates an entry.
+ *
+ * Custom passes are identified only by their
+ * [`name`](crate::fhe::FrontendPass::name), so give passes with different
+ * configurations different names or clear the cache when changing them.
+ *
+ * Clones share hit and miss counters.
+ *
+ * # Security
+ * Entries are trusted. Don't point the cache at a directory others can
+ * write to.
+ */
+pub struct ProgramCache {
+    dir: PathBuf,
+    hits: Arc<AtomicUsize>,
+    misses: Arc<AtomicUsize>,
+}
+
+impl ProgramCache {
+    /**
+     * Creates a cache in `dir`, creating the directory if it doesn't exist.
+     */
+    pub fn new<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let dir = dir.as_ref().to_owned();
+
+        fs::create_dir_all(&dir).map_err(|e| Error::cache(&e.to_string()))?;
+
+        Ok(Self {
+            dir,
+            hits: Arc::new(AtomicUsize::new(0)),
+            misses: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /**
+     * The directory holding this cache's entries.
+     */
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /**
+     * The number of lookups that found a valid entry.
+     */
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /**
+     * The number of lookups that found no entry or a stale one.
+     */
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /**
+     * Retrieves the program stored under `key`.
+     *
+     * # Remarks
+     * Returns `None` if there's no entry, the entry was written by a
+     * different compiler version or format, or its constraints differ
+     * from `key`'s. Unreadable entries are treated as missing.
+     */
+    pub fn get(&self, key: &CacheKey) -> Option<CompiledFheProgram> {
+        let entry = fs::read(self.dir.join(key.file_name()))
+            .ok()
+            .and_then(|bytes| bincode::deserialize::<CacheEntry>(&bytes).ok())
+            .filter(|e| e.format_version == CACHE_FORMAT_VERSION && e.key == *key);
+
+        match entry {
+            Some(e) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(e.program)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /**
+     * Stores `program` under `key`, replacing any existing entry for the
+     * same programs.
+     */
+    pub fn insert(&self, key: &CacheKey, program: &CompiledFheProgram) -> Result<()> {
+        #[derive(Serialize)]
+        struct CacheEntryRef<'a> {
+            format_version: u32,
+            key: &'a CacheKey,
+            program: &'a CompiledFheProgram,
+        }
+
+        let bytes = bincode::serialize(&CacheEntryRef {
+            format_version: CACHE_FORMAT_VERSION,
+            key,
+            program,
+        })
+        .map_err(|e| Error::cache(&e.to_string()))?;
+
+        // Write to a temporary file and rename so concurrent readers never
+        // observe a partially written entry.
+        let path = self.dir.join(key.file_name());
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+
+        fs::write(&tmp, bytes)
+            .and_then(|_| fs::rename(&tmp, &path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp);
+                Error::cache(&e.to_string())
+            })
+    }
+
+    /**
+     * Reads every valid entry in the cache directory, skipping unreadable
+     * or stale ones.
+     */
+    fn entries(&self) -> Result<Vec<(CacheKey, CompiledFheProgram)>> {
+        let dir = fs::read_dir(&self.dir).map_err(|e| Error::cache(&e.to_string()))?;
+
+        let entries = dir
+            .filter_map(|f| f.ok())
+            .map(|f| f.path())
+            .filter(|p| p.extension().map_or(false, |e| e == "bin"))
+            .filter_map(|p| fs::read(p).ok())
+            .filter_map(|bytes| bincode::deserialize::<CacheEntry>(&bytes).ok())
+            .filter(|e| {
+                e.format_version == CACHE_FORMAT_VERSION
+                    && e.key.compiler_version == env!("CARGO_PKG_VERSION")
+            })
+            .map(|e| (e.key, e.program))
+            .collect();
+
+        Ok(entries)
+    }
+
+    /**
+     * Looks up every key, returning the programs by name only if all of
+     * them hit.
+     */
+    pub(crate) fn get_all(&self, keys: &[CacheKey]) -> Option<HashMap<String, CompiledFheProgram>> {
+        keys.iter()
+            .map(|k| self.get(k).map(|p| (k.program.clone(), p)))
+            .collect()
+    }
+
+    /**
+     * Stores each program under its key, logging rather than failing on
+     * errors since the programs compiled successfully.
+     */
+    pub(crate) fn insert_all(
+        &self,
+        keys: &[CacheKey],
+        programs: &HashMap<String, CompiledFheProgram>,
+    ) {
+        for key in keys {
+            if let Err(e) = self.insert(key, &programs[&key.program]) {
+                warn!("Failed to cache FHE program {}: {}", key.program, e);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+/**
+ * An in-memory cache of [`CompiledFheProgram`]s for
+ * [`GenericCompiler::compile_cached`](crate::GenericCompiler::compile_cached),
+ * which can be loaded from and stored to disk.
+ *
+ * # Remarks
+ * Entries are keyed and invalidated exactly as in a [`ProgramCache`], and
+ * [`load`](Self::load) and [`store`](Self::store) use the same on-disk
+ * format, so a directory written by either can be read by the other.
+ * Unlike a [`ProgramCache`], nothing touches the disk during compilation.
+ *
+ * # Security
+ * Entries are trusted. Don't load a cache from a directory others can
+ * write to.
+ */
+pub struct CompileCache {
+    entries: HashMap<String, (CacheKey, CompiledFheProgram)>,
+    hits: usize,
+    misses: usize,
+}
+
+impl CompileCache {
+    /**
+     * Creates an empty cache.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+     * Loads the entries stored in `dir`.
+     *
+     * # Remarks
+     * Entries written by a different compiler version or format are
+     * skipped, as are unreadable files. Returns an error if `dir` can't be
+     * created or read.
+     */
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self> {
+        let entries = ProgramCache::new(dir)?
+            .entries()?
+            .into_iter()
+            .map(|(key, program)| (key.file_name(), (key, program)))
+            .collect();
+
+        Ok(Self {
+            entries,
+            hits: 0,
+            misses: 0,
+        })
+    }
+
+    /**
+     * Writes every entry to `dir`, creating it if it doesn't exist and
+     * replacing any existing entries for the same programs.
+     */
+    pub fn store<P: AsRef<Path>>(&self, dir: P) -> Result<()> {
+        let cache = ProgramCache::new(dir)?;
+
+        for (key, program) in self.entries.values() {
+            cache.insert(key, program)?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * The number of entries in the cache.
+     */
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /**
+     * Whether the cache has no entries.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /**
+     * The number of lookups that found a valid entry.
+     */
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /**
+     * The number of lookups that found no entry or a stale one.
+     */
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /**
+     * Retrieves the program stored under `key`.
+     */
+    pub fn get(&mut self, key: &CacheKey) -> Option<CompiledFheProgram> {
+        let program = self
+            .entries
+            .get(&key.file_name())
+            .filter(|(k, _)| k == key)
+            .map(|(_, p)| p.clone());
+
+        if program.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+
+        program
+    }
+
+    /**
+     * Stores `program` under `key`, replacing any existing entry for the
+     * same programs.
+     */
+    pub fn insert(&mut self, key: &CacheKey, program: &CompiledFheProgram) {
+        self.entries
+            .insert(key.file_name(), (key.clone(), program.clone()));
+    }
+
+    /**
+     * Looks up every key, returning the programs by name only if all of
+     * them hit.
+     */
+    pub(crate) fn get_all(
+        &mut self,
+        keys: &[CacheKey],
+    ) -> Option<HashMap<String, CompiledFheProgram>> {
+        keys.iter()
+            .map(|k| self.get(k).map(|p| (k.program.clone(), p)))
+            .collect()
+    }
+
+    /**
+     * Stores each program under its key.
+     */
+    pub(crate) fn insert_all(
+        &mut self,
+        keys: &[CacheKey],
+        programs: &HashMap<String, CompiledFheProgram>,
+    ) {
+        for key in keys {
+            self.insert(key, &programs[&key.program]);
+        }
+    }
+}
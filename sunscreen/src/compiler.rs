@@ -1,12 +1,23 @@
-use crate::fhe::{FheCompile, FheFrontendCompilation};
-use crate::params::{determine_params, PlainModulusConstraint};
+use crate::fhe::{
+    run_passes, validate_literals, ConstantFolding, FheCompile, FheFrontendCompilation,
+    FrontendPass,
+};
+use crate::params::{
+    auto_tune_params, determine_params, probe_params, rank_params, ParamsCandidate, ParamsRanking,
+    PlainModulusConstraint, SearchLimits, SearchPrograms, TuningTarget,
+};
 use crate::zkp::{Linked, NotLinked};
 use crate::{
-    zkp, Application, CallSignature, Error, FheProgramMetadata, Params, RequiredKeys, Result,
+    structural_hash, zkp, Application, CacheKey, CallSignature, CompileCache, Error,
+    FheProgramMetadata, Params, ProgramCache, RelinearizationStrategy, RequiredKeys, Result,
     SchemeType, SecurityLevel, ZkpProgramFn,
 };
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
 use sunscreen_fhe_program::FheProgramTrait;
 use sunscreen_runtime::{
     marker, CompiledFheProgram, CompiledZkpProgram, Fhe, FheRuntime, FheZkp, Zkp,
@@ -17,15 +28,33 @@ use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
 #[derive(Debug, Clone)]
 enum ParamsMode {
     Search,
+    AutoTune(TuningTarget),
+    Rank(Ranker),
     Manual(Params),
 }
 
+#[derive(Clone)]
+struct Ranker(Arc<ParamsRanking>);
+
+impl Debug for Ranker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Ranker")
+    }
+}
+
 /**
  * The operations supported by an `#[fhe_program]` function.
  */
 pub trait FheProgramFn {
     /**
      * Get the call signature of the function
+     *
+     * # Remarks
+     * The signature comes from the function's declared argument and return
+     * types, so this doesn't build or compile the FHE program. As with
+     * [`ZkpProgramFn::signature`](crate::ZkpProgramFn::signature), one can
+     * use it to inspect a program's arity and which of its arguments are
+     * encrypted (see [`Type::is_encrypted`](crate::types::Type::is_encrypted)).
      */
     fn signature(&self) -> CallSignature;
 
@@ -143,6 +172,11 @@ struct FheCompilerData {
     plain_modulus_constraint: PlainModulusConstraint,
     security_level: SecurityLevel,
     noise_margin: u32,
+    passes: Vec<Box<dyn FrontendPass>>,
+    cache: Option<ProgramCache>,
+    search_limits: SearchLimits,
+    annotations: BTreeMap<String, String>,
+    relinearization: RelinearizationStrategy,
 }
 
 impl Default for FheCompilerData {
@@ -155,6 +189,11 @@ impl Default for FheCompilerData {
             plain_modulus_constraint: PlainModulusConstraint::Raw(262_144),
             security_level: SecurityLevel::TC128,
             noise_margin: 20,
+            passes: vec![Box::new(ConstantFolding)],
+            cache: None,
+            search_limits: SearchLimits::default(),
+            annotations: BTreeMap::new(),
+            relinearization: RelinearizationStrategy::default(),
         }
     }
 }
@@ -322,6 +361,104 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
         self
     }
 
+    /**
+     * Set the compiler to search the joint space of lattice dimensions and
+     * coefficient modulus chains for the parameters that best meet `target`.
+     *
+     * # Remarks
+     * Where [`find_params`](Self::find_params) picks the smallest lattice
+     * dimension that works with the default modulus chain, auto-tuning also
+     * considers shortened chains and larger dimensions, keeping the
+     * candidate with the lowest estimated cost. The configured security
+     * level, plaintext modulus constraint, and noise margin are always
+     * respected, and custom passes run during the search as they do during
+     * compilation.
+     *
+     * Costs come from a coarse model of operation counts and ciphertext
+     * limbs rather than measurements, so this is a best-effort optimizer.
+     * Under that model, the result is never worse than
+     * [`find_params`](Self::find_params)'s. Tuning compiles each program
+     * once per candidate, so it's slower than a regular parameter search.
+     *
+     * The chosen parameters are available via the returned
+     * [`Application`]'s `params()`.
+     */
+    pub fn auto_tune(mut self, target: TuningTarget) -> Self {
+        self.data.fhe_data_mut().params_mode = ParamsMode::AutoTune(target);
+        self
+    }
+
+    /**
+     * Set the compiler to compile the FHE programs under every candidate
+     * set of parameters and keep the best according to `ranking`.
+     *
+     * # Remarks
+     * The candidates are every lattice dimension and prefix of the default
+     * coefficient modulus chain under which all programs meet the
+     * configured security level, plaintext modulus constraint and noise
+     * margin. `ranking` compares two [`ParamsCandidate`]s, returning
+     * [`Ordering::Less`] if the first is better. Each candidate carries
+     * its parameters and the predicted [`NoiseReport`](crate::NoiseReport)
+     * of each program, so you can e.g. trade ciphertext size against
+     * leftover noise budget.
+     *
+     * This compiles each program once per candidate, so it's slower than
+     * [`auto_tune`](Self::auto_tune), which prunes the search. Since a
+     * closure can't be part of a [`CacheKey`], compilation with a ranking
+     * doesn't use the [`cache`](Self::cache).
+     */
+    pub fn rank_params<F>(mut self, ranking: F) -> Self
+    where
+        F: Fn(&ParamsCandidate, &ParamsCandidate) -> Ordering + 'static,
+    {
+        self.data.fhe_data_mut().params_mode = ParamsMode::Rank(Ranker(Arc::new(ranking)));
+        self
+    }
+
+    /**
+     * Abort the parameter search with [`Error::ParameterSearchExhausted`]
+     * if it runs longer than `timeout`.
+     *
+     * # Remarks
+     * The search checks the deadline before trying each candidate set of
+     * parameters, so it can overrun `timeout` by the time it takes to
+     * compile the programs under one candidate. This has no effect when
+     * using [`with_params`](Self::with_params).
+     */
+    pub fn search_timeout(mut self, timeout: Duration) -> Self {
+        self.data.fhe_data_mut().search_limits.timeout = Some(timeout);
+        self
+    }
+
+    /**
+     * Abort the parameter search with [`Error::ParameterSearchExhausted`]
+     * if it would try more than `iterations` candidate sets of parameters.
+     *
+     * # Remarks
+     * Each candidate requires compiling every FHE program, so this bounds
+     * the work a search does regardless of how fast the machine is. This
+     * has no effect when using [`with_params`](Self::with_params).
+     */
+    pub fn max_search_iterations(mut self, iterations: usize) -> Self {
+        self.data.fhe_data_mut().search_limits.max_iterations = Some(iterations);
+        self
+    }
+
+    /**
+     * Look up compiled FHE programs in `cache` before compiling them, and
+     * store them there afterwards.
+     *
+     * # Remarks
+     * Compilation hits the cache only when every FHE program in the
+     * application has a valid entry. Otherwise, the compiler compiles all
+     * of them as usual and overwrites their entries. See
+     * [`CacheKey`] for what invalidates an entry.
+     */
+    pub fn cache(mut self, cache: ProgramCache) -> Self {
+        self.data.fhe_data_mut().cache = Some(cache);
+        self
+    }
+
     /**
      * Set the constraint the parameter search algorithm places on the plaintext modulus.
      * You can either force the algorithm to use an exact value or any value that supports
@@ -357,11 +494,85 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
         self
     }
 
-    fn compile_fhe(&self) -> Result<HashMap<String, CompiledFheProgram>> {
+    /**
+     * Choose where backend compilation inserts relinearizations after
+     * ciphertext multiplications. Defaults to
+     * [`RelinearizationStrategy::Eager`].
+     *
+     * # Remarks
+     * Parameter search compiles candidates with the same strategy, so the
+     * chosen parameters account for its noise.
+     */
+    pub fn relinearization_strategy(mut self, strategy: RelinearizationStrategy) -> Self {
+        self.data.fhe_data_mut().relinearization = strategy;
+        self
+    }
+
+    /**
+     * Attach the annotation `key` with the given `value` to the metadata of
+     * every compiled FHE program, replacing any previous value for `key`.
+     *
+     * # Remarks
+     * Annotations appear in
+     * [`FheProgramMetadata::annotations`](crate::FheProgramMetadata::annotations)
+     * and have no effect on compilation or execution. They aren't part of
+     * a [`CacheKey`], so programs loaded from a [`cache`](Self::cache)
+     * get the current annotations.
+     */
+    pub fn annotate<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.data
+            .fhe_data_mut()
+            .annotations
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /**
+     * Append a [`FrontendPass`] to run on each FHE program after it's built
+     * and before backend compilation. Passes run in the order they're added.
+     *
+     * # Remarks
+     * The pipeline starts with the [`ConstantFolding`] pass, so added
+     * passes see folded programs. See [`clear_passes`](Self::clear_passes)
+     * to change this.
+     *
+     * Passes also run during parameter search, so the chosen parameters
+     * reflect the transformed programs.
+     *
+     * See [`FrontendPass`] for the invariants a pass must uphold.
+     */
+    pub fn add_pass(mut self, pass: Box<dyn FrontendPass>) -> Self {
+        self.data.fhe_data_mut().passes.push(pass);
+        self
+    }
+
+    /**
+     * Remove every [`FrontendPass`], including the default
+     * [`ConstantFolding`] pass.
+     *
+     * # Remarks
+     * To run constant folding at a different point in the pipeline, add
+     * [`ConstantFolding`] back with [`add_pass`](Self::add_pass) after
+     * clearing.
+     */
+    pub fn clear_passes(mut self) -> Self {
+        self.data.fhe_data_mut().passes.clear();
+        self
+    }
+
+    /**
+     * Check that the FHE programs can be compiled together, returning their
+     * scheme, or `None` if there are no FHE programs.
+     */
+    fn validate_fhe_programs(&self) -> Result<Option<SchemeType>> {
         let fhe_data: &FheCompilerData = self.data.fhe_data();
 
         if fhe_data.fhe_program_fns.is_empty() {
-            return Ok(HashMap::new());
+            return Ok(None);
         }
 
         // Check that all programs use the same scheme type.
@@ -412,14 +623,73 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
 
         let scheme = fhe_data.fhe_program_fns.first().unwrap().scheme_type();
 
+        Ok(Some(scheme))
+    }
+
+    fn compile_fhe(&self) -> Result<HashMap<String, CompiledFheProgram>> {
+        let fhe_data: &FheCompilerData = self.data.fhe_data();
+
+        let scheme = match self.validate_fhe_programs()? {
+            Some(scheme) => scheme,
+            None => return Ok(HashMap::new()),
+        };
+
+        // Rankings are closures, which we can't include in a cache key.
+        let cache = fhe_data
+            .cache
+            .as_ref()
+            .filter(|_| !matches!(fhe_data.params_mode, ParamsMode::Rank(_)));
+
+        let cache_keys = match cache {
+            Some(cache) => {
+                let keys = self.fhe_cache_keys(scheme)?;
+
+                if let Some(mut programs) = cache.get_all(&keys) {
+                    for program in programs.values_mut() {
+                        program.metadata.annotations = fhe_data.annotations.clone();
+                    }
+
+                    return Ok(programs);
+                }
+
+                keys
+            }
+            None => vec![],
+        };
+
+        let programs = SearchPrograms {
+            fhe_program_fns: &fhe_data.fhe_program_fns,
+            passes: &fhe_data.passes,
+            relinearization: fhe_data.relinearization,
+        };
+
         let params = match &fhe_data.params_mode {
             ParamsMode::Manual(p) => p.clone(),
             ParamsMode::Search => determine_params(
-                &fhe_data.fhe_program_fns,
+                &programs,
                 fhe_data.plain_modulus_constraint,
                 fhe_data.security_level,
                 fhe_data.noise_margin,
                 scheme,
+                &fhe_data.search_limits,
+            )?,
+            ParamsMode::AutoTune(target) => auto_tune_params(
+                &programs,
+                fhe_data.plain_modulus_constraint,
+                fhe_data.security_level,
+                fhe_data.noise_margin,
+                scheme,
+                *target,
+                &fhe_data.search_limits,
+            )?,
+            ParamsMode::Rank(ranker) => rank_params(
+                &programs,
+                fhe_data.plain_modulus_constraint,
+                fhe_data.security_level,
+                fhe_data.noise_margin,
+                scheme,
+                ranker.0.as_ref(),
+                &fhe_data.search_limits,
             )?,
         };
 
@@ -427,9 +697,12 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
             .fhe_program_fns
             .iter()
             .map(|prog| {
-                let execution_graph = prog.build(&params);
+                let mut execution_graph = prog.build(&params)?;
+                run_passes(&mut execution_graph, &fhe_data.passes)?;
+                validate_literals(&execution_graph, &params)?;
+
                 let mut required_keys = vec![];
-                let fhe_program_fn = execution_graph?.compile();
+                let fhe_program_fn = execution_graph.compile_with(fhe_data.relinearization);
 
                 if fhe_program_fn.requires_relin_keys() {
                     required_keys.push(RequiredKeys::Relin);
@@ -439,10 +712,22 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
                     required_keys.push(RequiredKeys::Galois);
                 }
 
+                let required_rotations = fhe_program_fn.required_rotations();
+                let row_size = params.lattice_dimension / 2;
+
+                if let Some(&amount) = required_rotations
+                    .iter()
+                    .find(|x| x.unsigned_abs() >= row_size)
+                {
+                    return Err(Error::RotationOutOfRange { amount, row_size });
+                }
+
                 let metadata = FheProgramMetadata {
                     params: params.clone(),
                     required_keys,
+                    required_rotations,
                     signature: prog.signature(),
+                    annotations: fhe_data.annotations.clone(),
                 };
 
                 let compiled_program = CompiledFheProgram {
@@ -454,8 +739,56 @@ impl<T: marker::Fhe, B> GenericCompiler<T, B> {
             })
             .collect::<Result<HashMap<_, _>>>()?;
 
+        if let Some(cache) = cache {
+            cache.insert_all(&cache_keys, &fhe_programs);
+        }
+
         Ok(fhe_programs)
     }
+
+    /**
+     * Compute the [`CacheKey`] for each FHE program. Programs are built
+     * under the manual parameters if given, or else the first parameters
+     * the search would consider, so hashing doesn't require a search.
+     */
+    fn fhe_cache_keys(&self, scheme: SchemeType) -> Result<Vec<CacheKey>> {
+        let fhe_data: &FheCompilerData = self.data.fhe_data();
+
+        let params = match &fhe_data.params_mode {
+            ParamsMode::Manual(p) => p.clone(),
+            ParamsMode::Search | ParamsMode::AutoTune(_) | ParamsMode::Rank(_) => probe_params(
+                fhe_data.plain_modulus_constraint,
+                fhe_data.security_level,
+                scheme,
+            )?,
+        };
+
+        let hashes = fhe_data
+            .fhe_program_fns
+            .iter()
+            .map(|prog| {
+                let graph = prog.build(&params)?;
+
+                Ok((prog.name().to_owned(), structural_hash(&graph)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let constraints = format!(
+            "{:?};{:?};{:?};{};{:?};{:?}",
+            fhe_data.params_mode,
+            fhe_data.plain_modulus_constraint,
+            fhe_data.security_level,
+            fhe_data.noise_margin,
+            fhe_data.passes.iter().map(|p| p.name()).collect::<Vec<_>>(),
+            fhe_data.relinearization
+        );
+
+        Ok(fhe_data
+            .fhe_program_fns
+            .iter()
+            .map(|prog| CacheKey::new(prog.name(), hashes.clone(), constraints.clone()))
+            .collect())
+    }
 }
 
 // This generic impl can contain public methods where the builder remains the same type, or
@@ -624,6 +957,54 @@ impl FheCompiler {
     pub fn compile(self) -> Result<Application<Fhe>> {
         Application::new(self.compile_fhe()?, HashMap::new())
     }
+
+    /**
+     * Like [`compile`](Self::compile), but returns the FHE programs stored
+     * in `cache` if it has an entry for each of them, skipping parameter
+     * search and compilation. Otherwise, this compiles them and stores the
+     * results in `cache`.
+     *
+     * # Remarks
+     * Each program is keyed on the [`structural_hash`] of its
+     * [`FheFrontendCompilation`] and the compiler options, including any
+     * [`with_params`](Self::with_params) parameters, the
+     * [`plain_modulus_constraint`](Self::plain_modulus_constraint), and the
+     * [`additional_noise_budget`](Self::additional_noise_budget). See
+     * [`CacheKey`] for details.
+     *
+     * Use [`CompileCache::load`] and [`CompileCache::store`] to persist
+     * the cache across processes. Unlike [`cache`](Self::cache), this
+     * doesn't touch the disk. Programs using
+     * [`rank_params`](Self::rank_params) are always compiled.
+     */
+    pub fn compile_cached(self, cache: &mut CompileCache) -> Result<Application<Fhe>> {
+        let fhe_data = self.data.fhe_data();
+
+        let scheme = match self.validate_fhe_programs()? {
+            Some(scheme) if !matches!(fhe_data.params_mode, ParamsMode::Rank(_)) => scheme,
+            _ => return self.compile(),
+        };
+
+        let keys = self.fhe_cache_keys(scheme)?;
+
+        let programs = match cache.get_all(&keys) {
+            Some(mut programs) => {
+                for program in programs.values_mut() {
+                    program.metadata.annotations = fhe_data.annotations.clone();
+                }
+
+                programs
+            }
+            None => {
+                let programs = self.compile_fhe()?;
+                cache.insert_all(&keys, &programs);
+
+                programs
+            }
+        };
+
+        Application::new(programs, HashMap::new())
+    }
 }
 
 impl<B> FheZkpCompiler<B>
@@ -701,7 +1082,7 @@ where
             .next()
             .map(|p| &p.metadata.params)
             .or_else(|| match &self.data.fhe_data().params_mode {
-                ParamsMode::Search => None,
+                ParamsMode::Search | ParamsMode::AutoTune(_) | ParamsMode::Rank(_) => None,
                 ParamsMode::Manual(p) => Some(p),
             });
         let zkp_programs = self.compile_zkp(params)?;
@@ -823,4 +1204,832 @@ mod tests {
         assert_eq!(app.fhe_programs.len(), 1);
         assert_eq!(app.zkp_programs.len(), 1);
     }
+
+    #[test]
+    fn custom_passes_run_in_order() {
+        use crate::fhe::{FheOperation, FrontendPass};
+        use crate::types::{bfv::Signed, Cipher};
+        use std::sync::{Arc, Mutex};
+
+        struct RecordingPass {
+            name: &'static str,
+            log: Arc<Mutex<Vec<(&'static str, usize)>>>,
+        }
+
+        impl FrontendPass for RecordingPass {
+            fn name(&self) -> &str {
+                self.name
+            }
+
+            fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+                let outputs = compilation
+                    .node_weights()
+                    .filter(|n| n.operation == FheOperation::Output)
+                    .count();
+
+                self.log.lock().unwrap().push((self.name, outputs));
+
+                Ok(())
+            }
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        let log = Arc::new(Mutex::new(vec![]));
+
+        Compiler::new()
+            .fhe_program(kitty)
+            .with_params(&Params {
+                lattice_dimension: 4096,
+                coeff_modulus: vec![0xffffee001, 0xffffc4001, 0x1ffffe0001],
+                plain_modulus: 4096,
+                scheme_type: SchemeType::Bfv,
+                security_level: SecurityLevel::TC128,
+            })
+            .add_pass(Box::new(RecordingPass {
+                name: "first",
+                log: log.clone(),
+            }))
+            .add_pass(Box::new(RecordingPass {
+                name: "second",
+                log: log.clone(),
+            }))
+            .compile()
+            .unwrap();
+
+        assert_eq!(*log.lock().unwrap(), vec![("first", 1), ("second", 1)]);
+    }
+
+    #[test]
+    fn cse_pass_removes_redundant_multiplications() {
+        use crate::fhe::CommonSubexpressionElimination;
+        use crate::types::{bfv::Signed, Cipher};
+        use sunscreen_fhe_program::Operation;
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b + a * b
+        }
+
+        let count_multiplies = |app: &Application<Fhe>| {
+            app.get_fhe_program(kitty)
+                .unwrap()
+                .fhe_program_fn
+                .graph
+                .node_weights()
+                .filter(|n| n.operation == Operation::Multiply)
+                .count()
+        };
+
+        let app = Compiler::new().fhe_program(kitty).compile().unwrap();
+
+        assert_eq!(count_multiplies(&app), 2);
+
+        let app = Compiler::new()
+            .fhe_program(kitty)
+            .add_pass(Box::new(CommonSubexpressionElimination))
+            .compile()
+            .unwrap();
+
+        assert_eq!(count_multiplies(&app), 1);
+    }
+
+    #[test]
+    fn can_reorder_or_disable_constant_folding() {
+        use crate::fhe::ConstantFolding;
+        use crate::types::{bfv::Signed, Cipher};
+        use sunscreen_fhe_program::Operation;
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>) -> Cipher<Signed> {
+            a * 2 * 3
+        }
+
+        let count_multiplies = |app: &Application<Fhe>| {
+            app.get_fhe_program(kitty)
+                .unwrap()
+                .fhe_program_fn
+                .graph
+                .node_weights()
+                .filter(|n| n.operation == Operation::MultiplyPlaintext)
+                .count()
+        };
+
+        let app = Compiler::new().fhe_program(kitty).compile().unwrap();
+
+        assert_eq!(count_multiplies(&app), 1);
+
+        let app = Compiler::new()
+            .fhe_program(kitty)
+            .clear_passes()
+            .compile()
+            .unwrap();
+
+        assert_eq!(count_multiplies(&app), 2);
+
+        let app = Compiler::new()
+            .fhe_program(kitty)
+            .clear_passes()
+            .add_pass(Box::new(ConstantFolding))
+            .compile()
+            .unwrap();
+
+        assert_eq!(count_multiplies(&app), 1);
+    }
+
+    #[test]
+    fn auto_tune_beats_default_params() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        // Bytes in a fresh ciphertext. SEAL reserves the last prime in a
+        // multi-prime chain for key switching.
+        let ciphertext_size = |params: &Params| {
+            let data_primes = usize::max(params.coeff_modulus.len() - 1, 1);
+            2 * params.lattice_dimension as usize * data_primes * 8
+        };
+
+        // This noise margin is unattainable for n=4096, so the default
+        // search selects n=8192 with its full modulus chain, which has far
+        // more noise budget than the program needs.
+        let default_app = Compiler::new()
+            .fhe_program(kitty)
+            .additional_noise_budget(55)
+            .compile()
+            .unwrap();
+
+        let tuned_app = Compiler::new()
+            .fhe_program(kitty)
+            .additional_noise_budget(55)
+            .auto_tune(TuningTarget::CiphertextSize)
+            .compile()
+            .unwrap();
+
+        assert!(ciphertext_size(tuned_app.params()) < ciphertext_size(default_app.params()));
+
+        let runtime = FheRuntime::new(tuned_app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(27), &public_key).unwrap();
+
+        let result = runtime
+            .run(
+                tuned_app.get_fhe_program(kitty).unwrap(),
+                vec![a, b],
+                &public_key,
+            )
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, 42.into());
+    }
+
+    #[test]
+    fn rank_params_picks_preferred_candidate() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        let chain_bits = |c: &ParamsCandidate| {
+            assert_eq!(c.noise_reports.len(), 1);
+            assert!(c.noise_reports[0].min_budget > 0.);
+
+            c.params.lattice_dimension as usize * c.params.coeff_modulus.len()
+        };
+
+        // Prefer the largest parameters, which the default search never picks.
+        let app = Compiler::new()
+            .fhe_program(kitty)
+            .rank_params(move |a, b| chain_bits(b).cmp(&chain_bits(a)))
+            .compile()
+            .unwrap();
+
+        let default_app = Compiler::new().fhe_program(kitty).compile().unwrap();
+
+        assert!(app.params().lattice_dimension > default_app.params().lattice_dimension);
+
+        let runtime = FheRuntime::new(app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(6), &public_key).unwrap();
+        let b = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(kitty).unwrap(), vec![a, b], &public_key)
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, 42.into());
+    }
+
+    #[test]
+    fn search_stops_at_iteration_limit() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        // The smallest lattice dimension doesn't have enough noise budget.
+        let result = Compiler::new()
+            .fhe_program(kitty)
+            .max_search_iterations(1)
+            .compile();
+
+        assert_eq!(result.err(), Some(Error::ParameterSearchExhausted(None)));
+
+        // Ranking considers every candidate, so it runs out after finding one.
+        let result = Compiler::new()
+            .fhe_program(kitty)
+            .rank_params(|_, _| Ordering::Equal)
+            .max_search_iterations(8)
+            .compile();
+
+        match result {
+            Err(Error::ParameterSearchExhausted(Some(best))) => {
+                let app = Compiler::new()
+                    .fhe_program(kitty)
+                    .with_params(&best)
+                    .compile();
+
+                assert!(app.is_ok());
+            }
+            _ => panic!("Expected the search to exhaust with a candidate."),
+        }
+
+        assert_eq!(
+            Compiler::new()
+                .fhe_program(kitty)
+                .search_timeout(Duration::ZERO)
+                .compile()
+                .err(),
+            Some(Error::ParameterSearchExhausted(None))
+        );
+    }
+
+    #[test]
+    fn selector_check_flags_non_boolean_selector() {
+        use crate::fhe::SelectorCheck;
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn good_mux(s: Cipher<Signed>, a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            let not_s = -(s - 1);
+            s * a + not_s * b
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn bad_mux(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            // Attempts to select based on a comparison.
+            let s = a - b;
+            let not_s = -(s - 1);
+            s * a + not_s * b
+        }
+
+        let good = Compiler::new()
+            .fhe_program(good_mux)
+            .add_pass(Box::new(SelectorCheck { deny: true }))
+            .compile();
+
+        assert!(good.is_ok());
+
+        let bad = Compiler::new()
+            .fhe_program(bad_mux)
+            .add_pass(Box::new(SelectorCheck { deny: true }))
+            .compile();
+
+        assert_eq!(bad.err(), Some(Error::NonBooleanSelector(1)));
+    }
+
+    #[test]
+    fn dead_code_check_finds_unused_inputs() {
+        use crate::fhe::{find_dead_code, DeadCodeCheck};
+        use crate::types::{bfv::Signed, Cipher};
+        use petgraph::stable_graph::NodeIndex;
+
+        #[fhe_program(scheme = "bfv")]
+        fn all_used(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn forgot_c(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+            let _unused = b * c;
+            a + b
+        }
+
+        let app = Compiler::new()
+            .fhe_program(all_used)
+            .add_pass(Box::new(DeadCodeCheck { deny: true }))
+            .compile();
+
+        assert!(app.is_ok());
+
+        let dead_code = find_dead_code(&forgot_c.build(app.unwrap().params()).unwrap());
+
+        assert_eq!(dead_code.unused_inputs, vec![NodeIndex::new(2)]);
+        assert_eq!(dead_code.dead_nodes.len(), 1);
+
+        // Warnings alone don't fail compilation.
+        assert!(Compiler::new()
+            .fhe_program(forgot_c)
+            .add_pass(Box::new(DeadCodeCheck { deny: false }))
+            .compile()
+            .is_ok());
+
+        let denied = Compiler::new()
+            .fhe_program(forgot_c)
+            .add_pass(Box::new(DeadCodeCheck { deny: true }))
+            .compile();
+
+        assert_eq!(
+            denied.err(),
+            Some(Error::DeadCode {
+                unused_inputs: 1,
+                dead_nodes: 1
+            })
+        );
+    }
+
+    #[test]
+    fn negation_simplification_removes_redundant_negations() {
+        use crate::fhe::NegationSimplification;
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn double_negate(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            -(-a) * b
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn sub_negate(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a - (-b)
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn add_negate(a: Cipher<Signed>, b: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+            (a + (-b), -a + b)
+        }
+
+        let compile = |simplify: bool| {
+            let compiler = Compiler::new()
+                .fhe_program(double_negate)
+                .fhe_program(sub_negate)
+                .fhe_program(add_negate);
+
+            let compiler = if simplify {
+                compiler.add_pass(Box::new(NegationSimplification))
+            } else {
+                compiler
+            };
+
+            compiler.compile().unwrap()
+        };
+
+        let node_count = |app: &Application<Fhe>, name: &str| {
+            app.get_fhe_program(name)
+                .unwrap()
+                .fhe_program_fn
+                .graph
+                .node_count()
+        };
+
+        let baseline = compile(false);
+        let app = compile(true);
+
+        // -(-a) * b => a * b
+        assert_eq!(
+            node_count(&app, "double_negate"),
+            node_count(&baseline, "double_negate") - 2
+        );
+        // a - (-b) => a + b
+        assert_eq!(
+            node_count(&app, "sub_negate"),
+            node_count(&baseline, "sub_negate") - 1
+        );
+        // a + (-b) => a - b and -a + b => b - a
+        assert_eq!(
+            node_count(&app, "add_negate"),
+            node_count(&baseline, "add_negate") - 2
+        );
+
+        let runtime = FheRuntime::new(app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let run = |name: &str| {
+            let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+            let b = runtime.encrypt(Signed::from(-27), &public_key).unwrap();
+
+            runtime
+                .run(app.get_fhe_program(name).unwrap(), vec![a, b], &public_key)
+                .unwrap()
+                .iter()
+                .map(|c| runtime.decrypt::<Signed>(c, &private_key).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run("double_negate"), vec![Signed::from(-405)]);
+        assert_eq!(run("sub_negate"), vec![Signed::from(-12)]);
+        assert_eq!(run("add_negate"), vec![Signed::from(42), Signed::from(-42)]);
+    }
+
+    #[test]
+    fn constant_folding_collapses_literal_chains() {
+        use crate::fhe::{fold_constants, FheOperation};
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn chains(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+            let b = a * 2;
+
+            (b * -3 * 5, (a + 4) - 9 + 1 - 2, b)
+        }
+
+        let app = Compiler::new().fhe_program(chains).compile().unwrap();
+
+        let mut compilation = chains.build(app.params()).unwrap();
+        fold_constants(&mut compilation);
+
+        let count = |pred: fn(&FheOperation) -> bool| {
+            compilation
+                .node_weights()
+                .filter(|n| pred(&n.operation))
+                .count()
+        };
+
+        // a * 2 remains since it's an output.
+        assert_eq!(count(|o| *o == FheOperation::MultiplyPlaintext), 2);
+        assert_eq!(
+            count(|o| matches!(o, FheOperation::AddPlaintext | FheOperation::SubPlaintext)),
+            1
+        );
+        assert_eq!(count(|o| matches!(o, FheOperation::Literal(_))), 3);
+
+        let runtime = FheRuntime::new(app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(chains).unwrap(), vec![a], &public_key)
+            .unwrap()
+            .iter()
+            .map(|c| runtime.decrypt::<Signed>(c, &private_key).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            result,
+            vec![Signed::from(-210), Signed::from(1), Signed::from(14)]
+        );
+    }
+
+    #[test]
+    fn constant_folding_collapses_plaintext_subtractions() {
+        use crate::fhe::{fold_constants, FheOperation};
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn chains(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+            (10i64 - (a + 4) - 3, 20i64 - (5i64 - a))
+        }
+
+        let app = Compiler::new().fhe_program(chains).compile().unwrap();
+
+        let mut compilation = chains.build(app.params()).unwrap();
+        fold_constants(&mut compilation);
+
+        let count = |pred: fn(&FheOperation) -> bool| {
+            compilation
+                .node_weights()
+                .filter(|n| pred(&n.operation))
+                .count()
+        };
+
+        // 3 - a and a + 15
+        assert_eq!(count(|o| *o == FheOperation::PlaintextSub), 1);
+        assert_eq!(count(|o| *o == FheOperation::AddPlaintext), 1);
+        assert_eq!(count(|o| *o == FheOperation::SubPlaintext), 0);
+        assert_eq!(count(|o| matches!(o, FheOperation::Literal(_))), 2);
+
+        let runtime = FheRuntime::new(app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(chains).unwrap(), vec![a], &public_key)
+            .unwrap()
+            .iter()
+            .map(|c| runtime.decrypt::<Signed>(c, &private_key).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(result, vec![Signed::from(-4), Signed::from(22)]);
+    }
+
+    #[test]
+    fn constant_folding_rotates_plaintext_literals() {
+        use crate::fhe::{fold_constants, FheOperation};
+        use crate::types::{bfv::Batched, ops::GraphCipherInsert, Cipher, Rotate, SwapRows};
+
+        #[fhe_program(scheme = "bfv")]
+        fn rotated(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+            let p = Batched::<4>::graph_cipher_insert(3);
+
+            a * p.rotate_left(1).swap_rows() * 2
+        }
+
+        let app = Compiler::new()
+            .fhe_program(rotated)
+            .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+            .compile()
+            .unwrap();
+
+        let mut compilation = rotated.build(app.params()).unwrap();
+        fold_constants(&mut compilation);
+
+        let count = |pred: fn(&FheOperation) -> bool| {
+            compilation
+                .node_weights()
+                .filter(|n| pred(&n.operation))
+                .count()
+        };
+
+        // The rotated literal folds into the constant multiplication.
+        assert_eq!(
+            count(|o| matches!(
+                o,
+                FheOperation::RotateLeftPlaintext | FheOperation::SwapRowsPlaintext
+            )),
+            0
+        );
+        assert_eq!(count(|o| *o == FheOperation::MultiplyPlaintext), 1);
+        assert_eq!(count(|o| matches!(o, FheOperation::Literal(_))), 1);
+
+        let runtime = FheRuntime::new(app.params()).unwrap();
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+        let a = runtime.encrypt(a, &public_key).unwrap();
+
+        let result = runtime
+            .run(app.get_fhe_program(rotated).unwrap(), vec![a], &public_key)
+            .unwrap();
+
+        let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, [[6, 12, 18, 24], [30, 36, 42, 48]].into());
+    }
+
+    #[test]
+    fn second_compile_hits_program_cache() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        let dir = std::env::temp_dir().join(format!(
+            "sunscreen_program_cache_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let cache = ProgramCache::new(&dir).unwrap();
+
+        let compile = |noise_margin| {
+            Compiler::new()
+                .fhe_program(kitty)
+                .additional_noise_budget(noise_margin)
+                .cache(cache.clone())
+                .compile()
+                .unwrap()
+        };
+
+        let first = compile(20);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+
+        let second = compile(20);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        let first = first.get_fhe_program(kitty).unwrap();
+        let second = second.get_fhe_program(kitty).unwrap();
+
+        assert_eq!(first.metadata, second.metadata);
+        assert!(first.fhe_program_fn.graph == second.fhe_program_fn.graph);
+
+        // Changing the constraints invalidates the entry.
+        compile(30);
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn second_compile_cached_skips_compilation() {
+        use crate::fhe::FrontendPass;
+        use crate::types::{bfv::Signed, Cipher};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        struct CountingPass(Arc<AtomicUsize>);
+
+        impl FrontendPass for CountingPass {
+            fn name(&self) -> &str {
+                "counting"
+            }
+
+            fn run(&self, _compilation: &mut FheFrontendCompilation) -> Result<()> {
+                self.0.fetch_add(1, Ordering::Relaxed);
+
+                Ok(())
+            }
+        }
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a * b
+        }
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let mut cache = CompileCache::new();
+
+        let compile = |cache: &mut CompileCache, noise_margin| {
+            Compiler::new()
+                .fhe_program(kitty)
+                .additional_noise_budget(noise_margin)
+                .add_pass(Box::new(CountingPass(runs.clone())))
+                .compile_cached(cache)
+                .unwrap()
+        };
+
+        let first = compile(&mut cache, 20);
+        let first_runs = runs.load(Ordering::Relaxed);
+        assert!(first_runs > 0);
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+
+        let second = compile(&mut cache, 20);
+        assert_eq!(runs.load(Ordering::Relaxed), first_runs);
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        let first = first.get_fhe_program(kitty).unwrap();
+        let second = second.get_fhe_program(kitty).unwrap();
+
+        assert_eq!(first.metadata, second.metadata);
+        assert!(first.fhe_program_fn.graph == second.fhe_program_fn.graph);
+
+        // Entries survive a round trip through the disk.
+        let dir = std::env::temp_dir().join(format!(
+            "sunscreen_compile_cache_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        cache.store(&dir).unwrap();
+        let mut loaded = CompileCache::load(&dir).unwrap();
+        assert_eq!(loaded.len(), 1);
+
+        compile(&mut loaded, 20);
+        assert_eq!(runs.load(Ordering::Relaxed), first_runs);
+        assert_eq!((loaded.hits(), loaded.misses()), (1, 0));
+
+        // Changing the noise budget invalidates the entry.
+        compile(&mut loaded, 30);
+        assert!(runs.load(Ordering::Relaxed) > first_runs);
+        assert_eq!((loaded.hits(), loaded.misses()), (1, 1));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn program_cache_keys_on_plain_modulus_and_not_search_limits() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        let dir = std::env::temp_dir().join(format!(
+            "sunscreen_program_cache_key_test_{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let cache = ProgramCache::new(&dir).unwrap();
+
+        let compiler = |plain_modulus| {
+            Compiler::new()
+                .fhe_program(kitty)
+                .plain_modulus_constraint(PlainModulusConstraint::Raw(plain_modulus))
+                .cache(cache.clone())
+        };
+
+        compiler(1024).compile().unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (0, 1));
+
+        compiler(1024).max_search_iterations(100).compile().unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        let app = compiler(4096).compile().unwrap();
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+        assert_eq!(app.params().plain_modulus, 4096);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn annotations_survive_serialization() {
+        use crate::types::{bfv::Signed, Cipher};
+
+        #[fhe_program(scheme = "bfv")]
+        fn kitty(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+            a + b
+        }
+
+        let app = Compiler::new()
+            .fhe_program(kitty)
+            .annotate("build", "1234")
+            .annotate("commit", "deadbeef")
+            .annotate("build", "1235")
+            .compile()
+            .unwrap();
+
+        let program = app.get_fhe_program(kitty).unwrap();
+
+        let expected = BTreeMap::from([
+            ("build".to_owned(), "1235".to_owned()),
+            ("commit".to_owned(), "deadbeef".to_owned()),
+        ]);
+
+        assert_eq!(program.metadata.annotations, expected);
+
+        let metadata: FheProgramMetadata =
+            bincode::deserialize(&bincode::serialize(&program.metadata).unwrap()).unwrap();
+
+        assert_eq!(metadata, program.metadata);
+    }
+
+    #[test]
+    fn relinearization_strategy_controls_relinearizations() {
+        use crate::types::{bfv::Signed, Cipher};
+        use sunscreen_fhe_program::Operation;
+
+        #[fhe_program(scheme = "bfv")]
+        fn dot(a: Cipher<Signed>, b: Cipher<Signed>, c: Cipher<Signed>) -> Cipher<Signed> {
+            a * b + b * c + c * a
+        }
+
+        for (strategy, expected_relins) in [
+            (RelinearizationStrategy::Eager, 3),
+            (RelinearizationStrategy::Lazy, 1),
+            (RelinearizationStrategy::Auto, 1),
+        ] {
+            let app = Compiler::new()
+                .fhe_program(dot)
+                .relinearization_strategy(strategy)
+                .compile()
+                .unwrap();
+
+            let program = app.get_fhe_program(dot).unwrap();
+
+            let relins = program
+                .fhe_program_fn
+                .graph
+                .node_weights()
+                .filter(|n| matches!(n.operation, Operation::Relinearize))
+                .count();
+
+            assert_eq!(relins, expected_relins, "{strategy:?}");
+
+            let runtime = FheRuntime::new(app.params()).unwrap();
+            let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+            let args = [2, -3, 5]
+                .into_iter()
+                .map(|x| runtime.encrypt(Signed::from(x), &public_key).unwrap())
+                .collect::<Vec<_>>();
+
+            let result = runtime.run(program, args, &public_key).unwrap();
+            let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+            assert_eq!(c, Signed::from(-6 - 15 + 10), "{strategy:?}");
+        }
+    }
 }
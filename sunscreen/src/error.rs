@@ -18,6 +18,21 @@ pub enum Error {
     #[error("Failed to find satisfying parameters")]
     NoParams,
 
+    /**
+     * The parameter search hit its
+     * [timeout](crate::GenericCompiler::search_timeout) or
+     * [iteration limit](crate::GenericCompiler::max_search_iterations)
+     * before finishing.
+     *
+     * # Remarks
+     * Contains the best parameters found so far, if any. When searching
+     * with [`find_params`](crate::GenericCompiler::find_params), the
+     * search stops at the first feasible candidate, so this is always
+     * `None`.
+     */
+    #[error("Parameter search exhausted its budget")]
+    ParameterSearchExhausted(Option<Box<crate::Params>>),
+
     /**
      * Attempted to compile the given FHE program with the wrong scheme.
      */
@@ -77,6 +92,109 @@ pub enum Error {
      */
     #[error("Unsupported: {0}")]
     Unsupported(Box<String>),
+
+    /**
+     * The given number of multiplexer selectors aren't provably boolean.
+     * See [`SelectorCheck`](crate::fhe::SelectorCheck).
+     */
+    #[error("{0} multiplexer selector(s) aren't provably 0 or 1")]
+    NonBooleanSelector(usize),
+
+    /**
+     * The FHE program has inputs or operations that don't contribute to
+     * any output. See [`DeadCodeCheck`](crate::fhe::DeadCodeCheck).
+     */
+    #[error("{unused_inputs} unused input(s) and {dead_nodes} dead node(s)")]
+    DeadCode {
+        /**
+         * The number of unused inputs.
+         */
+        unused_inputs: usize,
+
+        /**
+         * The number of dead non-input nodes.
+         */
+        dead_nodes: usize,
+    },
+
+    /**
+     * The FHE program rotates a batched ciphertext by at least the number
+     * of lanes in a row, which SEAL can't generate Galois keys for.
+     */
+    #[error("Rotation by {amount} doesn't fit in a row of {row_size} lanes")]
+    RotationOutOfRange {
+        /**
+         * The offending rotation step. Negative steps rotate right.
+         */
+        amount: i64,
+
+        /**
+         * The number of lanes in each batched row.
+         */
+        row_size: u64,
+    },
+
+    /**
+     * A literal doesn't fit under the plaintext modulus, so encoding it
+     * would wrap. See [`Literal::check_plain_modulus`](crate::fhe::Literal::check_plain_modulus).
+     */
+    #[error("Literal {value} doesn't fit under plaintext modulus {plain_modulus}")]
+    LiteralOutOfRange {
+        /**
+         * The offending literal.
+         */
+        value: u64,
+
+        /**
+         * The plaintext modulus of the context's parameters.
+         */
+        plain_modulus: u64,
+    },
+
+    /**
+     * An FHE program divides by a constant that has no inverse modulo the
+     * plaintext modulus. See
+     * [`DivByConstant`](crate::types::DivByConstant).
+     */
+    #[error("{value} isn't invertible modulo the plaintext modulus {plain_modulus}")]
+    NotInvertible {
+        /**
+         * The offending divisor.
+         */
+        value: i64,
+
+        /**
+         * The plaintext modulus of the context's parameters.
+         */
+        plain_modulus: u64,
+    },
+
+    /**
+     * Failed to access a [`ProgramCache`](crate::ProgramCache).
+     */
+    #[error("Program cache error: {0}")]
+    CacheError(Box<String>),
+
+    /**
+     * Failed to serialize or deserialize a
+     * [`FrontendCompilation`](crate::FrontendCompilation).
+     */
+    #[error("Serialization error: {0}")]
+    SerializationError(Box<String>),
+
+    /**
+     * An FHE program was built without binding a value to one of its
+     * `#[constant]` arguments.
+     */
+    #[error("No value bound to constant argument `{0}`")]
+    UnboundConstant(Box<String>),
+
+    /**
+     * A frontend graph doesn't describe a valid FHE program. See
+     * [`FrontendCompilation::from_operations`](crate::FrontendCompilation::from_operations).
+     */
+    #[error("Malformed graph: {0}")]
+    MalformedGraph(Box<String>),
 }
 
 const_assert!(std::mem::size_of::<Error>() <= 24);
@@ -88,6 +206,34 @@ impl Error {
     pub fn unsupported(msg: &str) -> Self {
         Self::Unsupported(Box::new(msg.to_owned()))
     }
+
+    /**
+     * Create an [`Error::CacheError`]
+     */
+    pub fn cache(msg: &str) -> Self {
+        Self::CacheError(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::SerializationError`]
+     */
+    pub fn serialization(msg: &str) -> Self {
+        Self::SerializationError(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::UnboundConstant`]
+     */
+    pub fn unbound_constant(name: &str) -> Self {
+        Self::UnboundConstant(Box::new(name.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::MalformedGraph`]
+     */
+    pub fn malformed_graph(msg: &str) -> Self {
+        Self::MalformedGraph(Box::new(msg.to_owned()))
+    }
 }
 
 /**
@@ -1,8 +1,8 @@
 use petgraph::stable_graph::NodeIndex;
 use serde::{Deserialize, Serialize};
-use sunscreen_backend::compile_inplace;
+use sunscreen_backend::{compile_inplace_with, RelinearizationStrategy};
 use sunscreen_compiler_common::{
-    CompilationResult, Context, EdgeInfo, NodeInfo, Operation as OperationTrait,
+    CompilationResult, Context, EdgeInfo, NodeInfo, Operation as OperationTrait, Render,
 };
 use sunscreen_fhe_program::{
     FheProgram, Literal as FheProgramLiteral, Operation as FheProgramOperation, SchemeType,
@@ -11,6 +11,9 @@ use sunscreen_runtime::{InnerPlaintext, Params};
 
 use std::cell::RefCell;
 
+mod passes;
+pub use passes::*;
+
 #[derive(Clone, Debug, Deserialize, Hash, Serialize, PartialEq, Eq)]
 /**
  * Represents a literal node's data.
@@ -27,6 +30,57 @@ pub enum Literal {
     Plaintext(InnerPlaintext),
 }
 
+impl Literal {
+    /**
+     * Checks this literal's values fit under `plain_modulus`.
+     *
+     * # Remarks
+     * For a [`Literal::Plaintext`], this checks each coefficient of the
+     * encoding. A value at or above the plaintext modulus wraps, so the
+     * program would silently compute the wrong result.
+     */
+    pub fn check_plain_modulus(&self, plain_modulus: u64) -> crate::Result<()> {
+        let check = |value: u64| {
+            if value >= plain_modulus {
+                Err(crate::Error::LiteralOutOfRange {
+                    value,
+                    plain_modulus,
+                })
+            } else {
+                Ok(())
+            }
+        };
+
+        match self {
+            Literal::U64(x) => check(*x),
+            Literal::Plaintext(InnerPlaintext::Seal(p)) => p
+                .iter()
+                .flat_map(|p| (0..p.len()).map(|i| p.get_coefficient(i)))
+                .try_for_each(check),
+        }
+    }
+}
+
+/**
+ * Checks every plaintext literal in `graph` fits under the plaintext
+ * modulus in `params`.
+ *
+ * # Remarks
+ * The compiler runs this on each FHE program after its frontend passes, so
+ * literals from any source get checked, whether a constant operand, a
+ * constant input or a pass. [`Literal::U64`] nodes are rotation amounts,
+ * which are never encoded into a plaintext, so this skips them.
+ */
+pub fn validate_literals(graph: &FheFrontendCompilation, params: &Params) -> crate::Result<()> {
+    graph
+        .node_weights()
+        .filter_map(|n| match &n.operation {
+            FheOperation::Literal(x @ Literal::Plaintext(_)) => Some(x),
+            _ => None,
+        })
+        .try_for_each(|x| x.check_plain_modulus(params.plain_modulus))
+}
+
 #[derive(Clone, Debug, Hash, Deserialize, Serialize, PartialEq, Eq)]
 /**
  * Represents an operation occurring in the frontend AST.
@@ -101,6 +155,27 @@ pub enum FheOperation {
      * This node indicates the previous node's result should be a result of the [`fhe_program`](crate::fhe_program).
      */
     Output,
+
+    /**
+     * Rotate a plaintext left.
+     */
+    RotateLeftPlaintext,
+
+    /**
+     * Rotate a plaintext right.
+     */
+    RotateRightPlaintext,
+
+    /**
+     * In the BFV scheme, swap rows in a plaintext Batched vector.
+     */
+    SwapRowsPlaintext,
+
+    /**
+     * Subtract a ciphertext from a plaintext. The left operand is the
+     * plaintext.
+     */
+    PlaintextSub,
 }
 
 impl OperationTrait for FheOperation {
@@ -112,7 +187,10 @@ impl OperationTrait for FheOperation {
                 | FheOperation::Sub
                 | FheOperation::RotateLeft
                 | FheOperation::RotateRight
+                | FheOperation::RotateLeftPlaintext
+                | FheOperation::RotateRightPlaintext
                 | FheOperation::SubPlaintext
+                | FheOperation::PlaintextSub
                 | FheOperation::AddPlaintext
                 | FheOperation::MultiplyPlaintext
         )
@@ -129,7 +207,10 @@ impl OperationTrait for FheOperation {
     }
 
     fn is_unary(&self) -> bool {
-        matches!(self, FheOperation::Negate | FheOperation::SwapRows)
+        matches!(
+            self,
+            FheOperation::Negate | FheOperation::SwapRows | FheOperation::SwapRowsPlaintext
+        )
     }
 
     fn is_unordered(&self) -> bool {
@@ -141,6 +222,17 @@ impl OperationTrait for FheOperation {
     }
 }
 
+impl Render for FheOperation {
+    fn render(&self) -> String {
+        match self {
+            Self::Literal(Literal::U64(x)) => format!("Literal({x})"),
+            // Encoded plaintexts are large and not meaningful to a human.
+            Self::Literal(Literal::Plaintext(_)) => "Literal(Plaintext)".to_owned(),
+            x => format!("{x:?}"),
+        }
+    }
+}
+
 /**
  * The context for constructing the [`fhe_program`](crate::fhe_program) graph during compilation.
  *
@@ -161,6 +253,32 @@ thread_local! {
      * implementation detail and not for public consumption.
      */
     pub static CURRENT_FHE_CTX: RefCell<Option<&'static mut FheContext>> = RefCell::new(None);
+
+    static BUILD_ERROR: RefCell<Option<crate::Error>> = RefCell::new(None);
+}
+
+/**
+ * Records an error found while building the current
+ * [`fhe_program`](crate::fhe_program)'s graph.
+ *
+ * # Remarks
+ * Operations that can't fail without panicking call this and return a
+ * placeholder node instead. Once the program finishes building,
+ * [`build`](crate::FheProgramFn::build) returns the first recorded error.
+ * An implementation detail and not for public consumption.
+ */
+pub fn record_build_error(e: crate::Error) {
+    BUILD_ERROR.with(|err| {
+        err.borrow_mut().get_or_insert(e);
+    });
+}
+
+/**
+ * Takes the error recorded by [`record_build_error`], if any. An
+ * implementation detail and not for public consumption.
+ */
+pub fn take_build_error() -> Option<crate::Error> {
+    BUILD_ERROR.with(|err| err.borrow_mut().take())
 }
 
 /**
@@ -211,6 +329,12 @@ pub trait FheContextOps {
      */
     fn add_subtraction_plaintext(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex;
 
+    /**
+     * Add a subtraction of ciphertext `right` from plaintext `left` to
+     * this context.
+     */
+    fn add_plaintext_subtraction(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex;
+
     /**
      * Adds a negation to this context.
      */
@@ -241,6 +365,21 @@ pub trait FheContextOps {
      */
     fn add_literal(&mut self, literal: Literal) -> NodeIndex;
 
+    /**
+     * Adds a literal to this context after checking it fits under the
+     * context's plaintext modulus.
+     *
+     * # Remarks
+     * A [`Literal::U64`] or plaintext coefficient at or above the
+     * plaintext modulus wraps when encoded, so the program would silently
+     * compute the wrong result. This returns
+     * [`Error::LiteralOutOfRange`](crate::Error::LiteralOutOfRange)
+     * instead. See [`Literal::check_plain_modulus`]. Rotation amounts aren't
+     * encoded into a plaintext, so they should keep using
+     * [`add_literal`](Self::add_literal).
+     */
+    fn try_add_literal(&mut self, literal: Literal) -> crate::Result<NodeIndex>;
+
     /**
      * Add a rotate left.
      */
@@ -255,6 +394,21 @@ pub trait FheContextOps {
      */
     fn add_swap_rows(&mut self, x: NodeIndex) -> NodeIndex;
 
+    /**
+     * Add a rotate left of a plaintext.
+     */
+    fn add_rotate_left_plaintext(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex;
+
+    /**
+     * Add a rotate right of a plaintext.
+     */
+    fn add_rotate_right_plaintext(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex;
+
+    /**
+     * Adds a row swap of a plaintext.
+     */
+    fn add_swap_rows_plaintext(&mut self, x: NodeIndex) -> NodeIndex;
+
     /**
      * Add a node that captures the previous node as an output.
      */
@@ -282,6 +436,10 @@ impl FheContextOps for FheContext {
         self.add_binary_operation(FheOperation::SubPlaintext, left, right)
     }
 
+    fn add_plaintext_subtraction(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex {
+        self.add_binary_operation(FheOperation::PlaintextSub, left, right)
+    }
+
     fn add_negate(&mut self, x: NodeIndex) -> NodeIndex {
         self.add_unary_operation(FheOperation::Negate, x)
     }
@@ -319,6 +477,12 @@ impl FheContextOps for FheContext {
         }
     }
 
+    fn try_add_literal(&mut self, literal: Literal) -> crate::Result<NodeIndex> {
+        literal.check_plain_modulus(self.data.plain_modulus)?;
+
+        Ok(self.add_literal(literal))
+    }
+
     fn add_rotate_left(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex {
         self.add_binary_operation(FheOperation::RotateLeft, left, right)
     }
@@ -331,6 +495,18 @@ impl FheContextOps for FheContext {
         self.add_unary_operation(FheOperation::SwapRows, x)
     }
 
+    fn add_rotate_left_plaintext(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex {
+        self.add_binary_operation(FheOperation::RotateLeftPlaintext, left, right)
+    }
+
+    fn add_rotate_right_plaintext(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex {
+        self.add_binary_operation(FheOperation::RotateRightPlaintext, left, right)
+    }
+
+    fn add_swap_rows_plaintext(&mut self, x: NodeIndex) -> NodeIndex {
+        self.add_unary_operation(FheOperation::SwapRowsPlaintext, x)
+    }
+
     fn add_output(&mut self, i: NodeIndex) -> NodeIndex {
         self.add_unary_operation(FheOperation::Output, i)
     }
@@ -343,15 +519,36 @@ pub trait FheCompile {
     /**
      * Performs frontend compilation of this intermediate representation into a backend [`FheProgram`],
      * then perform backend compilation and return the result.
+     *
+     * # Remarks
+     * Plaintext-minus-ciphertext subtractions are lowered (see
+     * [`lower_plaintext_subtractions`]) and the nodes are put in a
+     * canonical order (see [`canonicalize`]) before lowering, so the same
+     * program always produces the same [`FheProgram`].
+     *
+     * This doesn't fold constants; the compiler does so in the
+     * [`ConstantFolding`] pass.
      */
-    fn compile(&self) -> FheProgram;
+    fn compile(&self) -> FheProgram {
+        self.compile_with(RelinearizationStrategy::default())
+    }
+
+    /**
+     * Like [`compile`](Self::compile), but backend compilation inserts
+     * relinearizations according to `strategy`.
+     */
+    fn compile_with(&self, strategy: RelinearizationStrategy) -> FheProgram;
 }
 
 impl FheCompile for FheFrontendCompilation {
-    fn compile(&self) -> FheProgram {
+    fn compile_with(&self, strategy: RelinearizationStrategy) -> FheProgram {
         let mut fhe_program = FheProgram::new(SchemeType::Bfv);
 
-        let mapped_graph = self.0.map(
+        let mut lowered = self.clone();
+        lower_plaintext_subtractions(&mut lowered);
+        canonicalize(&mut lowered);
+
+        let mapped_graph = lowered.0.map(
             |id, n| match &n.operation {
                 FheOperation::Add => NodeInfo::new(FheProgramOperation::Add),
                 FheOperation::InputCiphertext => {
@@ -385,7 +582,19 @@ impl FheCompile for FheFrontendCompilation {
                 FheOperation::RotateLeft => NodeInfo::new(FheProgramOperation::ShiftLeft),
                 FheOperation::RotateRight => NodeInfo::new(FheProgramOperation::ShiftRight),
                 FheOperation::SwapRows => NodeInfo::new(FheProgramOperation::SwapRows),
+                FheOperation::RotateLeftPlaintext => {
+                    NodeInfo::new(FheProgramOperation::ShiftLeftPlaintext)
+                }
+                FheOperation::RotateRightPlaintext => {
+                    NodeInfo::new(FheProgramOperation::ShiftRightPlaintext)
+                }
+                FheOperation::SwapRowsPlaintext => {
+                    NodeInfo::new(FheProgramOperation::SwapRowsPlaintext)
+                }
                 FheOperation::AddPlaintext => NodeInfo::new(FheProgramOperation::AddPlaintext),
+                FheOperation::PlaintextSub => {
+                    unreachable!("Plaintext subtractions are lowered before this.")
+                }
             },
             |_, e| match e {
                 EdgeInfo::Left => EdgeInfo::Left,
@@ -398,6 +607,87 @@ impl FheCompile for FheFrontendCompilation {
 
         fhe_program.graph = CompilationResult(mapped_graph);
 
-        compile_inplace(fhe_program)
+        compile_inplace_with(fhe_program, strategy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, SchemeType, SealPlaintext, SecurityLevel, WithContext};
+
+    #[test]
+    fn try_add_literal_checks_plain_modulus() {
+        let mut context = FheContext::new(Params {
+            lattice_dimension: 4096,
+            coeff_modulus: vec![],
+            plain_modulus: 1024,
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        });
+
+        let a = context.try_add_literal(Literal::U64(1023)).unwrap();
+        assert_eq!(context.add_literal(Literal::U64(1023)), a);
+
+        assert_eq!(
+            context.try_add_literal(Literal::U64(1024)),
+            Err(Error::LiteralOutOfRange {
+                value: 1024,
+                plain_modulus: 1024
+            })
+        );
+        assert_eq!(context.graph.node_count(), 1);
+    }
+
+    #[test]
+    fn validate_literals_checks_plaintext_coefficients() {
+        let params = Params {
+            lattice_dimension: 4096,
+            coeff_modulus: vec![],
+            plain_modulus: 1024,
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let plaintext = |coeffs: &[u64]| {
+            let mut data = SealPlaintext::new().unwrap();
+            data.resize(coeffs.len());
+
+            for (i, c) in coeffs.iter().enumerate() {
+                data.set_coefficient(i, *c);
+            }
+
+            Literal::Plaintext(InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data,
+            }]))
+        };
+
+        let mut context = FheContext::new(params.clone());
+
+        // Rotation amounts aren't encoded, so they may exceed the modulus.
+        context.add_literal(Literal::U64(2048));
+        context.add_literal(plaintext(&[1, 1023]));
+        assert_eq!(validate_literals(&context.graph, &params), Ok(()));
+
+        let bad = plaintext(&[1, 1024]);
+
+        assert_eq!(
+            context.try_add_literal(bad.clone()),
+            Err(Error::LiteralOutOfRange {
+                value: 1024,
+                plain_modulus: 1024
+            })
+        );
+
+        context.add_literal(bad);
+
+        assert_eq!(
+            validate_literals(&context.graph, &params),
+            Err(Error::LiteralOutOfRange {
+                value: 1024,
+                plain_modulus: 1024
+            })
+        );
     }
 }
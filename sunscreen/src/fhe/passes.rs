@@ -0,0 +1,1263 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use petgraph::{
+    algo::toposort,
+    stable_graph::{NodeIndex, StableGraph},
+    visit::EdgeRef,
+    Direction,
+};
+use sunscreen_compiler_common::{
+    forward_traverse, transforms::common_subexpression_elimination, EdgeInfo, GraphQuery, NodeInfo,
+};
+use sunscreen_runtime::{rotate_plaintext, InnerPlaintext, PlaintextRotation, WithContext};
+
+use crate::{
+    fhe::{FheFrontendCompilation, FheOperation, Literal},
+    Error, Result, SealPlaintext,
+};
+
+/**
+ * A transformation over an FHE program's frontend graph. Passes run in
+ * the order they were added to the compiler (see
+ * [`GenericCompiler::add_pass`](crate::GenericCompiler::add_pass)) after
+ * building each `#[fhe_program]` and before backend compilation.
+ *
+ * # Remarks
+ * A pass must leave the graph in a valid state:
+ * * Every binary operation has exactly one left and one right operand and
+ *   every unary operation (including `Output`) has exactly one operand.
+ * * The graph remains acyclic.
+ * * Input nodes must not be added, removed, or reordered, as their index
+ *   determines which program argument they correspond to.
+ * * `Output` nodes must not be added, removed, or reordered, and each
+ *   must still compute the same value.
+ *
+ * Violating these invariants may cause backend compilation to panic or
+ * produce an incorrect program.
+ */
+pub trait FrontendPass {
+    /**
+     * The name of this pass, used for logging.
+     */
+    fn name(&self) -> &str;
+
+    /**
+     * Transform the given frontend compilation in place.
+     */
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/**
+ * A [`FrontendPass`] that performs common subexpression elimination.
+ *
+ * See [`common_subexpression_elimination`] for more details.
+ */
+pub struct CommonSubexpressionElimination;
+
+impl FrontendPass for CommonSubexpressionElimination {
+    fn name(&self) -> &str {
+        "common_subexpression_elimination"
+    }
+
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+        common_subexpression_elimination(&mut compilation.0);
+
+        Ok(())
+    }
+}
+
+type FheGraph = StableGraph<NodeInfo<FheOperation>, EdgeInfo>;
+type FheQuery<'a> = GraphQuery<'a, NodeInfo<FheOperation>, EdgeInfo>;
+
+fn binary_operands(
+    query: &FheQuery,
+    node: NodeIndex,
+    op: FheOperation,
+) -> Option<(NodeIndex, NodeIndex)> {
+    if query.get_node(node)?.operation != op {
+        return None;
+    }
+
+    query.get_binary_operands(node).ok()
+}
+
+/**
+ * Returns the value of `node` if it's a literal encoding a constant.
+ */
+fn literal_value(query: &FheQuery, node: NodeIndex) -> Option<u64> {
+    match &query.get_node(node)?.operation {
+        FheOperation::Literal(Literal::U64(x)) => Some(*x),
+        FheOperation::Literal(Literal::Plaintext(InnerPlaintext::Seal(p))) => {
+            let [p] = p.as_slice() else {
+                return None;
+            };
+
+            let is_constant = (1..p.len()).all(|i| p.get_coefficient(i) == 0);
+
+            match (p.len(), is_constant) {
+                (0, _) => Some(0),
+                (_, true) => Some(p.get_coefficient(0)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/**
+ * If `node` computes `1 - x` as `1 - x`, `-(x - 1)` or `-x + 1`, returns
+ * `x`.
+ */
+fn complement_of(query: &FheQuery, node: NodeIndex) -> Option<NodeIndex> {
+    let is_one = |n: NodeIndex| literal_value(query, n) == Some(1);
+
+    match query.get_node(node)?.operation {
+        FheOperation::PlaintextSub => {
+            let (one, x) = query.get_binary_operands(node).ok()?;
+
+            is_one(one).then_some(x)
+        }
+        FheOperation::Negate => {
+            let operand = query.get_unary_operand(node).ok()?;
+            let (x, one) = binary_operands(query, operand, FheOperation::SubPlaintext)?;
+
+            is_one(one).then_some(x)
+        }
+        FheOperation::AddPlaintext => {
+            let (neg, one) = query.get_binary_operands(node).ok()?;
+
+            if !is_one(one) || query.get_node(neg)?.operation != FheOperation::Negate {
+                return None;
+            }
+
+            query.get_unary_operand(neg).ok()
+        }
+        _ => None,
+    }
+}
+
+/**
+ * Whether `node` provably computes 0 or 1, assuming every input does.
+ * `is_bool` must contain the result for each of `node`'s ancestors.
+ */
+fn is_boolean(query: &FheQuery, node: NodeIndex, is_bool: &HashMap<NodeIndex, bool>) -> bool {
+    let is_bool = |n: NodeIndex| is_bool.get(&n).copied().unwrap_or(false);
+    let same_pair = |(a, b): (NodeIndex, NodeIndex), (c, d): (NodeIndex, NodeIndex)| {
+        (a == c && b == d) || (a == d && b == c)
+    };
+
+    if let Some(x) = complement_of(query, node) {
+        return is_bool(x);
+    }
+
+    match &query.get_node(node).unwrap().operation {
+        FheOperation::InputCiphertext | FheOperation::InputPlaintext => true,
+        FheOperation::Literal(_) => literal_value(query, node).is_some_and(|x| x <= 1),
+        // And
+        FheOperation::Multiply | FheOperation::MultiplyPlaintext => {
+            let (a, b) = query.get_binary_operands(node).unwrap();
+
+            is_bool(a) && is_bool(b)
+        }
+        // Or (a + b - ab) and xor (a + b - 2ab)
+        FheOperation::Sub => {
+            let (sum, rhs) = query.get_binary_operands(node).unwrap();
+
+            let Some(operands) = binary_operands(query, sum, FheOperation::Add) else {
+                return false;
+            };
+
+            let prod = binary_operands(query, rhs, FheOperation::Add)
+                .filter(|(x, y)| x == y)
+                .map(|(x, _)| x)
+                .unwrap_or(rhs);
+
+            let is_or_xor = binary_operands(query, prod, FheOperation::Multiply)
+                .map(|prod_operands| same_pair(operands, prod_operands))
+                .unwrap_or(false);
+
+            is_or_xor && is_bool(operands.0) && is_bool(operands.1)
+        }
+        _ => false,
+    }
+}
+
+/**
+ * Finds multiplexers of the form `s * a + (1 - s) * b` in `compilation`
+ * whose selector `s` isn't provably 0 or 1. Returns the selectors' node
+ * indices.
+ *
+ * # Remarks
+ * See [`SelectorCheck`] for the analysis and its limitations.
+ */
+pub fn find_non_boolean_selectors(compilation: &FheFrontendCompilation) -> Vec<NodeIndex> {
+    let mut is_bool = HashMap::new();
+    let mut selectors = vec![];
+    let mut seen = HashSet::new();
+
+    forward_traverse(&compilation.0, |query, node| {
+        is_bool.insert(node, is_boolean(&query, node, &is_bool));
+
+        let Some((l, r)) = binary_operands(&query, node, FheOperation::Add) else {
+            return Ok::<_, Error>(());
+        };
+
+        for (selected, other) in [(l, r), (r, l)] {
+            let (Some((x, y)), Some((u, v))) = (
+                binary_operands(&query, selected, FheOperation::Multiply),
+                binary_operands(&query, other, FheOperation::Multiply),
+            ) else {
+                continue;
+            };
+
+            for s in [x, y] {
+                let is_mux = [u, v]
+                    .into_iter()
+                    .any(|c| complement_of(&query, c) == Some(s));
+
+                if is_mux && !is_bool[&s] && seen.insert(s) {
+                    selectors.push(s);
+                }
+            }
+        }
+
+        Ok(())
+    })
+    .unwrap();
+
+    selectors
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/**
+ * A [`FrontendPass`] that warns about multiplexers whose selector isn't
+ * provably boolean, which usually indicates an attempt to branch on
+ * encrypted data.
+ *
+ * # Remarks
+ * FHE programs can't branch on encrypted values; Rust control flow in an
+ * `#[fhe_program]` runs once while building the graph. Data-dependent
+ * choices must instead be written as a multiplexer `s * a + (1 - s) * b`,
+ * which is only correct when `s` encrypts 0 or 1. A common mistake is to
+ * use a value such as `a - b` as the selector, expecting it to behave like
+ * a comparison.
+ *
+ * This pass finds multiplexers of that form (with `1 - s` written
+ * directly, as `-(s - 1)` or as `-s + 1`) and checks their selector is
+ * built only from inputs, the literals 0 and 1, and operations that
+ * preserve booleans: and (`a * b`), or (`a + b - a * b`), xor
+ * (`a + b - 2 * a * b`), and not (`1 - a`). This is a heuristic with the following limitations:
+ * * The pass can't see the values of encrypted inputs, so it assumes
+ *   inputs are boolean. Validating inputs is the data owner's
+ *   responsibility.
+ * * Multiplexers written in other forms (e.g. `s * (a - b) + b`) aren't
+ *   recognized.
+ * * Booleans computed in other ways are reported even if they're correct.
+ *
+ * Each suspect selector is logged as a warning. When `deny` is true, the
+ * pass instead fails with [`Error::NonBooleanSelector`].
+ */
+pub struct SelectorCheck {
+    /**
+     * Whether to fail compilation rather than warn.
+     */
+    pub deny: bool,
+}
+
+impl FrontendPass for SelectorCheck {
+    fn name(&self) -> &str {
+        "selector_check"
+    }
+
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+        let selectors = find_non_boolean_selectors(compilation);
+
+        for s in &selectors {
+            log::warn!(
+                "Multiplexer selector {:?} isn't provably 0 or 1; FHE programs can't branch on encrypted data.",
+                s
+            );
+        }
+
+        if self.deny && !selectors.is_empty() {
+            return Err(Error::NonBooleanSelector(selectors.len()));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/**
+ * The nodes of an FHE program that don't contribute to any output. See
+ * [`find_dead_code`].
+ */
+pub struct DeadCode {
+    /**
+     * The `InputCiphertext` and `InputPlaintext` nodes no output depends
+     * on. Since input nodes are created first, in argument order, a node's
+     * index is its position among the program's inputs.
+     */
+    pub unused_inputs: Vec<NodeIndex>,
+
+    /**
+     * The non-input nodes no output depends on.
+     */
+    pub dead_nodes: Vec<NodeIndex>,
+}
+
+impl DeadCode {
+    /**
+     * Whether every node contributes to an output.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.unused_inputs.is_empty() && self.dead_nodes.is_empty()
+    }
+}
+
+/**
+ * Finds the nodes in `compilation` from which no `Output` node is
+ * reachable, i.e. those whose values the program computes and discards.
+ *
+ * # Remarks
+ * An unused input usually means the program forgot to use an argument,
+ * which still costs the caller a ciphertext (or plaintext) in the call
+ * signature. Dead internal nodes are harmless, as backend compilation
+ * prunes them, but often share the same cause.
+ *
+ * See [`DeadCodeCheck`] to report these during compilation.
+ */
+pub fn find_dead_code(compilation: &FheFrontendCompilation) -> DeadCode {
+    let graph = &compilation.0;
+
+    let mut live = HashSet::new();
+    let mut stack = graph
+        .node_indices()
+        .filter(|n| graph[*n].operation == FheOperation::Output)
+        .collect::<Vec<_>>();
+
+    while let Some(node) = stack.pop() {
+        if live.insert(node) {
+            stack.extend(graph.neighbors_directed(node, Direction::Incoming));
+        }
+    }
+
+    let mut dead_code = DeadCode::default();
+
+    for node in graph.node_indices().filter(|n| !live.contains(n)) {
+        match graph[node].operation {
+            FheOperation::InputCiphertext | FheOperation::InputPlaintext => {
+                dead_code.unused_inputs.push(node)
+            }
+            _ => dead_code.dead_nodes.push(node),
+        }
+    }
+
+    dead_code
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/**
+ * A [`FrontendPass`] that warns about inputs and operations that don't
+ * contribute to any output of an FHE program.
+ *
+ * # Remarks
+ * Each unused input and dead node (see [`find_dead_code`]) is logged as a
+ * warning. When `deny` is true, the pass instead fails with
+ * [`Error::DeadCode`] if there are any.
+ *
+ * This pass doesn't modify the program.
+ */
+pub struct DeadCodeCheck {
+    /**
+     * Whether to fail compilation rather than warn.
+     */
+    pub deny: bool,
+}
+
+impl FrontendPass for DeadCodeCheck {
+    fn name(&self) -> &str {
+        "dead_code_check"
+    }
+
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+        let dead_code = find_dead_code(compilation);
+
+        for i in &dead_code.unused_inputs {
+            log::warn!("Input {} doesn't contribute to any output.", i.index());
+        }
+
+        for n in &dead_code.dead_nodes {
+            log::warn!(
+                "Node {:?} ({:?}) doesn't contribute to any output.",
+                n,
+                compilation.0[*n].operation
+            );
+        }
+
+        if self.deny && !dead_code.is_empty() {
+            return Err(Error::DeadCode {
+                unused_inputs: dead_code.unused_inputs.len(),
+                dead_nodes: dead_code.dead_nodes.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/**
+ * Returns the operand of `node` if it's a `Negate`.
+ */
+fn negated_operand(graph: &FheGraph, node: NodeIndex) -> Option<NodeIndex> {
+    if graph[node].operation != FheOperation::Negate {
+        return None;
+    }
+
+    Some(GraphQuery::new(graph).get_unary_operand(node).unwrap())
+}
+
+/**
+ * Removes `node` if nothing consumes its result.
+ */
+fn remove_if_unused(graph: &mut FheGraph, node: NodeIndex) {
+    if graph
+        .neighbors_directed(node, Direction::Outgoing)
+        .next()
+        .is_none()
+    {
+        graph.remove_node(node);
+    }
+}
+
+/**
+ * Removes redundant negations from `compilation`, rewriting
+ * * `-(-x)` to `x`
+ * * `a - (-b)` to `a + b`
+ * * `a + (-b)` and `(-b) + a` to `a - b`
+ *
+ * # Remarks
+ * A `Negate` is deleted once no other operation consumes it, so the
+ * rewrites reduce the node count unless the negation is shared.
+ *
+ * See [`NegationSimplification`] for more details.
+ */
+pub fn simplify_negations(compilation: &mut FheFrontendCompilation) {
+    let graph = &mut compilation.0;
+
+    // Visiting nodes in topological order lets a rewrite expose negations
+    // to its consumers, e.g. `-(-(-x))` becomes `-x`.
+    let order = toposort(&*graph, None).expect("FHE program graph should be acyclic");
+
+    for node in order {
+        // An earlier rewrite may have deleted this node.
+        if !graph.contains_node(node) {
+            continue;
+        }
+
+        match graph[node].operation {
+            FheOperation::Negate => {
+                let neg = GraphQuery::new(graph).get_unary_operand(node).unwrap();
+
+                let Some(x) = negated_operand(graph, neg) else {
+                    continue;
+                };
+
+                // Consumers of `-(-x)` take `x` with the same edge info
+                // instead.
+                let consumers = graph
+                    .edges_directed(node, Direction::Outgoing)
+                    .map(|e| (e.target(), *e.weight()))
+                    .collect::<Vec<_>>();
+
+                for (consumer, info) in consumers {
+                    graph.add_edge(x, consumer, info);
+                }
+
+                graph.remove_node(node);
+                remove_if_unused(graph, neg);
+            }
+            FheOperation::Add | FheOperation::Sub => {
+                let is_sub = graph[node].operation == FheOperation::Sub;
+                let (l, r) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+                let (op, neg, left, right) =
+                    match (is_sub, negated_operand(graph, l), negated_operand(graph, r)) {
+                        (true, _, Some(b)) => (FheOperation::Add, r, l, b),
+                        (false, _, Some(b)) => (FheOperation::Sub, r, l, b),
+                        (false, Some(b), None) => (FheOperation::Sub, l, r, b),
+                        _ => continue,
+                    };
+
+                let operand_edges = graph
+                    .edges_directed(node, Direction::Incoming)
+                    .map(|e| e.id())
+                    .collect::<Vec<_>>();
+
+                for e in operand_edges {
+                    graph.remove_edge(e);
+                }
+
+                graph.add_edge(left, node, EdgeInfo::Left);
+                graph.add_edge(right, node, EdgeInfo::Right);
+                graph[node].operation = op;
+
+                remove_if_unused(graph, neg);
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/**
+ * A [`FrontendPass`] that removes redundant negations, which commonly
+ * arise from macro expansion and generated code.
+ *
+ * See [`simplify_negations`] for the rewrites it performs.
+ *
+ * # Remarks
+ * In BFV, negation maps each coefficient `c` of a ciphertext to `q - c`,
+ * which adds no noise and is its own inverse. Likewise, `a - b` is computed
+ * as `a + (-b)`. As such, these rewrites are exact: the rewritten program
+ * computes the same ciphertexts with the same noise while evaluating fewer
+ * operations.
+ */
+pub struct NegationSimplification;
+
+impl FrontendPass for NegationSimplification {
+    fn name(&self) -> &str {
+        "negation_simplification"
+    }
+
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+        simplify_negations(compilation);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+/**
+ * A [`FrontendPass`] that folds chains of operations on plaintext
+ * literals into a single literal.
+ *
+ * See [`fold_constants`] for the rewrites it performs.
+ *
+ * # Remarks
+ * The compiler runs this pass first by default. Call
+ * [`clear_passes`](crate::GenericCompiler::clear_passes) to disable it,
+ * or to run it at a different point by adding it back with
+ * [`add_pass`](crate::GenericCompiler::add_pass).
+ */
+pub struct ConstantFolding;
+
+impl FrontendPass for ConstantFolding {
+    fn name(&self) -> &str {
+        "constant_folding"
+    }
+
+    fn run(&self, compilation: &mut FheFrontendCompilation) -> Result<()> {
+        fold_constants(&mut compilation.0);
+
+        Ok(())
+    }
+}
+
+/**
+ * Returns the plaintext `node` encodes if it's a literal containing a
+ * single SEAL plaintext.
+ */
+fn plaintext_literal(graph: &FheGraph, node: NodeIndex) -> Option<&WithContext<SealPlaintext>> {
+    match &graph[node].operation {
+        FheOperation::Literal(Literal::Plaintext(InnerPlaintext::Seal(p))) => match p.as_slice() {
+            [p] => Some(p),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FoldOp {
+    Add,
+    Sub,
+    Mul,
+}
+
+/**
+ * Computes `a op b` in the plaintext ring `Z_t[x]/(x^n + 1)`, where `t`
+ * and `n` are the plain modulus and lattice dimension. Returns [`None`]
+ * if `a` and `b` were encoded under different parameters or their product
+ * is zero.
+ */
+fn fold_plaintexts(
+    a: &WithContext<SealPlaintext>,
+    b: &WithContext<SealPlaintext>,
+    op: FoldOp,
+) -> Option<InnerPlaintext> {
+    if a.params != b.params {
+        return None;
+    }
+
+    let n = a.params.lattice_dimension as usize;
+    let t = a.params.plain_modulus as u128;
+
+    let coeff = |p: &SealPlaintext, i: usize| {
+        if i < p.len() {
+            p.get_coefficient(i) as u128
+        } else {
+            0
+        }
+    };
+
+    let mut c = vec![0u128; n];
+
+    match op {
+        FoldOp::Add | FoldOp::Sub => {
+            for (i, c_i) in c.iter_mut().enumerate() {
+                let (a_i, b_i) = (coeff(&a.data, i), coeff(&b.data, i));
+
+                *c_i = match op {
+                    FoldOp::Add => (a_i + b_i) % t,
+                    _ => (a_i + t - b_i) % t,
+                };
+            }
+        }
+        FoldOp::Mul => {
+            for i in 0..a.data.len() {
+                let a_i = coeff(&a.data, i);
+
+                if a_i == 0 {
+                    continue;
+                }
+
+                for j in 0..b.data.len() {
+                    let prod = a_i * coeff(&b.data, j) % t;
+
+                    // x^n = -1, so terms past the last coefficient wrap
+                    // around negated.
+                    if i + j < n {
+                        c[i + j] = (c[i + j] + prod) % t;
+                    } else {
+                        c[i + j - n] = (c[i + j - n] + t - prod) % t;
+                    }
+                }
+            }
+        }
+    }
+
+    let len = c.iter().rposition(|x| *x != 0).map_or(0, |x| x + 1);
+
+    // SEAL refuses to multiply by a zero plaintext, so keep the original
+    // operations if the product of nonzero literals vanishes mod t.
+    if op == FoldOp::Mul && len == 0 {
+        return None;
+    }
+
+    let mut data = SealPlaintext::new().ok()?;
+    data.resize(len);
+
+    for (i, c_i) in c.iter().take(len).enumerate() {
+        data.set_coefficient(i, *c_i as u64);
+    }
+
+    Some(InnerPlaintext::Seal(vec![WithContext {
+        params: a.params.clone(),
+        data,
+    }]))
+}
+
+/**
+ * Replaces `node` with a literal if it rotates or swaps the rows of a
+ * plaintext literal by a literal amount. Returns whether it did.
+ */
+fn fold_plaintext_rotation(graph: &mut FheGraph, node: NodeIndex) -> bool {
+    let (x_node, amount_node, rotation) = match graph[node].operation {
+        FheOperation::SwapRowsPlaintext => {
+            let x = GraphQuery::new(graph).get_unary_operand(node).unwrap();
+
+            (x, None, PlaintextRotation::SwapRows)
+        }
+        FheOperation::RotateLeftPlaintext | FheOperation::RotateRightPlaintext => {
+            let (x, amount) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+            let FheOperation::Literal(Literal::U64(y)) = graph[amount].operation else {
+                return false;
+            };
+
+            let rotation = match graph[node].operation {
+                FheOperation::RotateLeftPlaintext => PlaintextRotation::Left(y),
+                _ => PlaintextRotation::Right(y),
+            };
+
+            (x, Some(amount), rotation)
+        }
+        _ => return false,
+    };
+
+    let FheOperation::Literal(Literal::Plaintext(InnerPlaintext::Seal(p))) =
+        &graph[x_node].operation
+    else {
+        return false;
+    };
+
+    let rotated = p
+        .iter()
+        .map(|p| {
+            rotate_plaintext(&p.data, rotation, &p.params).map(|data| WithContext {
+                params: p.params.clone(),
+                data,
+            })
+        })
+        .collect::<std::result::Result<Vec<_>, _>>();
+
+    let Ok(rotated) = rotated else {
+        return false;
+    };
+
+    let operand_edges = graph
+        .edges_directed(node, Direction::Incoming)
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+
+    for e in operand_edges {
+        graph.remove_edge(e);
+    }
+
+    graph[node].operation =
+        FheOperation::Literal(Literal::Plaintext(InnerPlaintext::Seal(rotated)));
+
+    remove_if_unused(graph, x_node);
+
+    if let Some(amount_node) = amount_node {
+        remove_if_unused(graph, amount_node);
+    }
+
+    true
+}
+
+/**
+ * An operation combining a ciphertext `x` with a plaintext literal `p`
+ * that [`fold_constants`] can fold into a chain.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChainOp {
+    Add,
+    Sub,
+    Mul,
+
+    /**
+     * `p - x`.
+     */
+    SubFrom,
+}
+
+/**
+ * If `node` combines a ciphertext with a plaintext, returns the operation
+ * along with the ciphertext and plaintext operands.
+ */
+fn chain_operation(graph: &FheGraph, node: NodeIndex) -> Option<(ChainOp, NodeIndex, NodeIndex)> {
+    let op = match graph[node].operation {
+        FheOperation::AddPlaintext => ChainOp::Add,
+        FheOperation::SubPlaintext => ChainOp::Sub,
+        FheOperation::MultiplyPlaintext => ChainOp::Mul,
+        FheOperation::PlaintextSub => ChainOp::SubFrom,
+        _ => return None,
+    };
+
+    let (left, right) = GraphQuery::new(graph).get_binary_operands(node).ok()?;
+
+    // Only plaintext-minus-ciphertext puts the plaintext on the left.
+    if op == ChainOp::SubFrom {
+        Some((op, right, left))
+    } else {
+        Some((op, left, right))
+    }
+}
+
+/**
+ * Folds chains of plaintext literal operations in `graph` into a single
+ * literal, rewriting
+ * * `(x * p) * q` to `x * (p * q)`
+ * * `(x + p) + q` to `x + (p + q)`
+ * * `(x + p) - q` and `(x - q) + p` to `x + (p - q)`
+ * * `(x - p) - q` to `x - (p + q)`
+ * * `(p - x) + q` to `(p + q) - x` and `(p - x) - q` to `(p - q) - x`
+ * * `q - (x + p)` to `(q - p) - x` and `q - (x - p)` to `(q + p) - x`
+ * * `q - (p - x)` to `x + (q - p)`
+ *
+ * where `p` and `q` are plaintext literals, which the frontend emits when
+ * combining a ciphertext with a constant. Additionally, rotating or
+ * swapping the rows of a plaintext literal by a literal amount is replaced
+ * with the rotated literal, which may then fold into the chain consuming it.
+ *
+ * # Remarks
+ * The folded literal is computed exactly in BFV's plaintext ring, so the
+ * rewritten program computes the same values. Since literals only appear
+ * as operands of ciphertext-plaintext operations, chains are folded
+ * starting from the first ciphertext operand; `x` is never a literal.
+ *
+ * Intermediate results that other operations consume (e.g. `x * p` when
+ * it's also an output) are left in place, while those that become unused
+ * are deleted.
+ *
+ * `graph` is the graph of an [`FheFrontendCompilation`], which is what the
+ * compiler lowers to the backend, rather than the
+ * [`FrontendCompilation`](crate::FrontendCompilation) graph of bare
+ * operations. Since an [`FheFrontendCompilation`] dereferences to its graph,
+ * you can pass `&mut compilation` directly.
+ *
+ * [`ConstantFolding`] runs this as a [`FrontendPass`].
+ */
+pub fn fold_constants(graph: &mut StableGraph<NodeInfo<FheOperation>, EdgeInfo>) {
+    // Visiting nodes in topological order folds each chain into its
+    // operands before the chain's consumers, collapsing long chains
+    // entirely.
+    let order = toposort(&*graph, None).expect("FHE program graph should be acyclic");
+
+    for node in order {
+        // An earlier rewrite may have deleted this node.
+        if !graph.contains_node(node) {
+            continue;
+        }
+
+        if fold_plaintext_rotation(graph, node) {
+            continue;
+        }
+
+        let Some((outer, inner_node, q_node)) = chain_operation(graph, node) else {
+            continue;
+        };
+
+        let Some((inner, x, p_node)) = chain_operation(graph, inner_node) else {
+            continue;
+        };
+
+        let (Some(p), Some(q)) = (
+            plaintext_literal(graph, p_node),
+            plaintext_literal(graph, q_node),
+        ) else {
+            continue;
+        };
+
+        let folded = match (inner, outer) {
+            (ChainOp::Mul, ChainOp::Mul) => {
+                fold_plaintexts(p, q, FoldOp::Mul).map(|l| (FheOperation::MultiplyPlaintext, l))
+            }
+            (ChainOp::Add, ChainOp::Add) => {
+                fold_plaintexts(p, q, FoldOp::Add).map(|l| (FheOperation::AddPlaintext, l))
+            }
+            (ChainOp::Add, ChainOp::Sub) => {
+                fold_plaintexts(p, q, FoldOp::Sub).map(|l| (FheOperation::AddPlaintext, l))
+            }
+            (ChainOp::Sub, ChainOp::Add) => {
+                fold_plaintexts(q, p, FoldOp::Sub).map(|l| (FheOperation::AddPlaintext, l))
+            }
+            (ChainOp::Sub, ChainOp::Sub) => {
+                fold_plaintexts(p, q, FoldOp::Add).map(|l| (FheOperation::SubPlaintext, l))
+            }
+            (ChainOp::SubFrom, ChainOp::Add) => {
+                fold_plaintexts(p, q, FoldOp::Add).map(|l| (FheOperation::PlaintextSub, l))
+            }
+            (ChainOp::SubFrom, ChainOp::Sub) => {
+                fold_plaintexts(p, q, FoldOp::Sub).map(|l| (FheOperation::PlaintextSub, l))
+            }
+            (ChainOp::Add, ChainOp::SubFrom) => {
+                fold_plaintexts(q, p, FoldOp::Sub).map(|l| (FheOperation::PlaintextSub, l))
+            }
+            (ChainOp::Sub, ChainOp::SubFrom) => {
+                fold_plaintexts(q, p, FoldOp::Add).map(|l| (FheOperation::PlaintextSub, l))
+            }
+            (ChainOp::SubFrom, ChainOp::SubFrom) => {
+                fold_plaintexts(q, p, FoldOp::Sub).map(|l| (FheOperation::AddPlaintext, l))
+            }
+            _ => None,
+        };
+
+        let Some((op, literal)) = folded else {
+            continue;
+        };
+
+        let operand_edges = graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+
+        for e in operand_edges {
+            graph.remove_edge(e);
+        }
+
+        let literal = graph.add_node(NodeInfo::new(FheOperation::Literal(Literal::Plaintext(
+            literal,
+        ))));
+
+        if op == FheOperation::PlaintextSub {
+            graph.add_edge(literal, node, EdgeInfo::Left);
+            graph.add_edge(x, node, EdgeInfo::Right);
+        } else {
+            graph.add_edge(x, node, EdgeInfo::Left);
+            graph.add_edge(literal, node, EdgeInfo::Right);
+        }
+
+        graph[node].operation = op;
+
+        remove_if_unused(graph, q_node);
+
+        // The intermediate result stays if, e.g., it's also an output.
+        remove_if_unused(graph, inner_node);
+
+        if !graph.contains_node(inner_node) {
+            remove_if_unused(graph, p_node);
+        }
+    }
+}
+
+/**
+ * Rewrites each plaintext-minus-ciphertext subtraction `p - x` in
+ * `compilation` to `-x + p`.
+ *
+ * # Remarks
+ * SEAL can only subtract a plaintext from a ciphertext, so the backend
+ * has no operation for `p - x`. Negation adds no noise, so the rewritten
+ * program has the same noise as the subtraction would.
+ *
+ * [`FheCompile::compile`](crate::fhe::FheCompile::compile) runs this
+ * before lowering each program to the backend.
+ */
+pub fn lower_plaintext_subtractions(compilation: &mut FheFrontendCompilation) {
+    let graph = &mut compilation.0;
+
+    let nodes = graph
+        .node_indices()
+        .filter(|n| graph[*n].operation == FheOperation::PlaintextSub)
+        .collect::<Vec<_>>();
+
+    for node in nodes {
+        let (p, x) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+        let operand_edges = graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+
+        for e in operand_edges {
+            graph.remove_edge(e);
+        }
+
+        let neg = graph.add_node(NodeInfo::new(FheOperation::Negate));
+        graph.add_edge(x, neg, EdgeInfo::Unary);
+
+        graph.add_edge(neg, node, EdgeInfo::Left);
+        graph.add_edge(p, node, EdgeInfo::Right);
+        graph[node].operation = FheOperation::AddPlaintext;
+    }
+}
+
+/**
+ * Replaces `node` with a literal holding `value`, deleting its operands
+ * if nothing else uses them.
+ */
+fn replace_with_literal(graph: &mut FheGraph, node: NodeIndex, value: InnerPlaintext) {
+    let operands = graph
+        .neighbors_directed(node, Direction::Incoming)
+        .collect::<Vec<_>>();
+
+    let operand_edges = graph
+        .edges_directed(node, Direction::Incoming)
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+
+    for e in operand_edges {
+        graph.remove_edge(e);
+    }
+
+    graph[node].operation = FheOperation::Literal(Literal::Plaintext(value));
+
+    for operand in operands {
+        // Both operands may be the same node.
+        if graph.contains_node(operand) {
+            remove_if_unused(graph, operand);
+        }
+    }
+}
+
+/**
+ * Replaces `node`'s operation with the binary operation `op` over `left`
+ * and `right`.
+ */
+fn replace_binary_operation(
+    graph: &mut FheGraph,
+    node: NodeIndex,
+    op: FheOperation,
+    left: NodeIndex,
+    right: NodeIndex,
+) {
+    let operand_edges = graph
+        .edges_directed(node, Direction::Incoming)
+        .map(|e| e.id())
+        .collect::<Vec<_>>();
+
+    for e in operand_edges {
+        graph.remove_edge(e);
+    }
+
+    graph.add_edge(left, node, EdgeInfo::Left);
+    graph.add_edge(right, node, EdgeInfo::Right);
+    graph[node].operation = op;
+}
+
+/**
+ * Rewrites ciphertext operations on plaintext literals in `compilation`
+ * into plaintext operations, evaluating those whose operands are all
+ * literals.
+ *
+ * # Remarks
+ * Some operations yield a constant where the program's types expect a
+ * ciphertext. E.g. [`Pow::pow`](crate::types::Pow::pow) returns the
+ * literal 1 for `x^0`, since computing 1 from an encrypted input would
+ * produce a transparent ciphertext, which SEAL rejects. This pass rewrites
+ * e.g. `1 * x` to a plaintext multiplication, `1 - x` to a
+ * plaintext-minus-ciphertext subtraction and `1 + 1` to the literal 2.
+ *
+ * Returns [`Error::Unsupported`] if a constant is an output of the
+ * program or meets an operation that needs a ciphertext operand, such as
+ * multiplying it by a plaintext input.
+ *
+ * The compiler runs this before the [`FrontendPass`]es, so they never
+ * see a literal in place of a ciphertext.
+ */
+pub fn lower_literal_operands(compilation: &mut FheFrontendCompilation) -> Result<()> {
+    let graph = &mut compilation.0;
+
+    let is_literal = |graph: &FheGraph, node| plaintext_literal(graph, node).is_some();
+
+    // Visiting nodes in topological order evaluates each constant before
+    // its consumers.
+    let order = toposort(&*graph, None).expect("FHE program graph should be acyclic");
+
+    for node in order {
+        match graph[node].operation {
+            FheOperation::Add | FheOperation::Sub | FheOperation::Multiply => {
+                let (left, right) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+                let (fold_op, plain_op) = match graph[node].operation {
+                    FheOperation::Add => (FoldOp::Add, FheOperation::AddPlaintext),
+                    FheOperation::Sub => (FoldOp::Sub, FheOperation::SubPlaintext),
+                    _ => (FoldOp::Mul, FheOperation::MultiplyPlaintext),
+                };
+
+                match (is_literal(graph, left), is_literal(graph, right)) {
+                    (true, true) => fold_literal_operands(graph, node, fold_op)?,
+                    (true, false) if fold_op == FoldOp::Sub => {
+                        graph[node].operation = FheOperation::PlaintextSub;
+                    }
+                    (true, false) => replace_binary_operation(graph, node, plain_op, right, left),
+                    (false, true) => graph[node].operation = plain_op,
+                    (false, false) => {}
+                }
+            }
+            FheOperation::AddPlaintext
+            | FheOperation::SubPlaintext
+            | FheOperation::MultiplyPlaintext
+            | FheOperation::PlaintextSub => {
+                let (left, right) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+                let (fold_op, x) = match graph[node].operation {
+                    FheOperation::AddPlaintext => (FoldOp::Add, left),
+                    FheOperation::SubPlaintext => (FoldOp::Sub, left),
+                    FheOperation::MultiplyPlaintext => (FoldOp::Mul, left),
+                    _ => (FoldOp::Sub, right),
+                };
+
+                if !is_literal(graph, x) {
+                    continue;
+                }
+
+                if !is_literal(graph, left) || !is_literal(graph, right) {
+                    return Err(Error::unsupported(
+                        "Can't combine a constant ciphertext with a plaintext input",
+                    ));
+                }
+
+                // Each operation computes `left op right`.
+                fold_literal_operands(graph, node, fold_op)?;
+            }
+            FheOperation::Negate => {
+                let x = GraphQuery::new(graph).get_unary_operand(node).unwrap();
+
+                let Some(p) = plaintext_literal(graph, x) else {
+                    continue;
+                };
+
+                let zero = WithContext {
+                    params: p.params.clone(),
+                    data: SealPlaintext::new()?,
+                };
+
+                let negated = fold_plaintexts(&zero, p, FoldOp::Sub).unwrap();
+
+                replace_with_literal(graph, node, negated);
+            }
+            FheOperation::RotateLeft | FheOperation::RotateRight => {
+                let (x, _) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+                if is_literal(graph, x) {
+                    graph[node].operation = match graph[node].operation {
+                        FheOperation::RotateLeft => FheOperation::RotateLeftPlaintext,
+                        _ => FheOperation::RotateRightPlaintext,
+                    };
+
+                    fold_plaintext_rotation(graph, node);
+                }
+            }
+            FheOperation::SwapRows => {
+                let x = GraphQuery::new(graph).get_unary_operand(node).unwrap();
+
+                if is_literal(graph, x) {
+                    graph[node].operation = FheOperation::SwapRowsPlaintext;
+
+                    fold_plaintext_rotation(graph, node);
+                }
+            }
+            FheOperation::Output => {
+                let x = GraphQuery::new(graph).get_unary_operand(node).unwrap();
+
+                if is_literal(graph, x) {
+                    return Err(Error::unsupported(
+                        "An FHE program output is a constant. Encrypt it outside the program instead.",
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Replaces the binary operation `node` with the literal
+ * `left op right`, where both operands are plaintext literals.
+ */
+fn fold_literal_operands(graph: &mut FheGraph, node: NodeIndex, op: FoldOp) -> Result<()> {
+    let (left, right) = GraphQuery::new(graph).get_binary_operands(node).unwrap();
+
+    let value = fold_plaintexts(
+        plaintext_literal(graph, left).unwrap(),
+        plaintext_literal(graph, right).unwrap(),
+        op,
+    )
+    .ok_or_else(|| Error::unsupported("Can't evaluate operation on constants"))?;
+
+    replace_with_literal(graph, node, value);
+
+    Ok(())
+}
+
+/**
+ * The rank of `op` in [`canonicalize`]'s ordering. Nodes with a lower rank
+ * come first among those whose operands are all placed.
+ */
+fn canonical_rank(op: &FheOperation) -> u8 {
+    match op {
+        FheOperation::InputCiphertext | FheOperation::InputPlaintext => 0,
+        FheOperation::Literal(_) => 1,
+        FheOperation::Output => 3,
+        _ => 2,
+    }
+}
+
+/**
+ * Rebuilds `compilation`'s graph with its nodes in a canonical
+ * topological order, so the same program always lowers to the same
+ * backend graph.
+ *
+ * # Remarks
+ * Among the nodes whose operands have all been placed, inputs come first,
+ * then literals, then other operations and finally outputs. Ties go to
+ * the node inserted first. Since inputs are inserted first and in
+ * argument order, they keep their indices, and outputs keep their
+ * relative order.
+ *
+ * The rebuilt graph has no holes left by previously removed nodes, and
+ * each node's operand edges are inserted in order of their source.
+ *
+ * [`FheCompile::compile`](crate::fhe::FheCompile::compile) runs this
+ * before lowering each program to the backend.
+ */
+pub fn canonicalize(compilation: &mut FheFrontendCompilation) {
+    let graph = &compilation.0;
+
+    let mut pending = graph
+        .node_indices()
+        .map(|n| (n, graph.edges_directed(n, Direction::Incoming).count()))
+        .collect::<HashMap<_, _>>();
+
+    let mut ready = graph
+        .node_indices()
+        .filter(|n| pending[n] == 0)
+        .map(|n| Reverse((canonical_rank(&graph[n].operation), n)))
+        .collect::<BinaryHeap<_>>();
+
+    let mut canonical = FheGraph::default();
+    let mut mapping = HashMap::new();
+
+    while let Some(Reverse((_, node))) = ready.pop() {
+        let new_node = canonical.add_node(graph[node].clone());
+        mapping.insert(node, new_node);
+
+        let mut operands = graph
+            .edges_directed(node, Direction::Incoming)
+            .map(|e| (mapping[&e.source()], e.weight().clone()))
+            .collect::<Vec<_>>();
+
+        operands.sort_by_key(|(source, info)| (*source, !info.is_left()));
+
+        for (source, info) in operands {
+            canonical.add_edge(source, new_node, info);
+        }
+
+        for dependent in graph.neighbors_directed(node, Direction::Outgoing) {
+            let count = pending.get_mut(&dependent).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                ready.push(Reverse((
+                    canonical_rank(&graph[dependent].operation),
+                    dependent,
+                )));
+            }
+        }
+    }
+
+    assert_eq!(
+        canonical.node_count(),
+        graph.node_count(),
+        "FHE program graph should be acyclic"
+    );
+
+    compilation.0 = canonical;
+}
+
+/**
+ * Lowers ciphertext operations on literals in `compilation` (see
+ * [`lower_literal_operands`]), then runs each pass in `passes` over it in
+ * order.
+ */
+pub(crate) fn run_passes(
+    compilation: &mut FheFrontendCompilation,
+    passes: &[Box<dyn FrontendPass>],
+) -> Result<()> {
+    lower_literal_operands(compilation)?;
+
+    for pass in passes {
+        log::trace!("Running frontend pass {}", pass.name());
+
+        pass.run(compilation)?;
+    }
+
+    Ok(())
+}
@@ -36,6 +36,7 @@
 //! ```
 //!
 
+mod cache;
 mod compiler;
 mod error;
 mod params;
@@ -50,31 +51,46 @@ pub mod types;
 /// This module contains types used internally when compiling [`zkp_program`]s.
 pub mod zkp;
 
-use fhe::{FheOperation, Literal};
-use petgraph::stable_graph::StableGraph;
+use fhe::{FheFrontendCompilation, FheOperation, Literal};
+use petgraph::algo::is_isomorphic_matching;
+use petgraph::dot::{Config, Dot};
+use petgraph::stable_graph::{NodeIndex, StableGraph};
+use petgraph::visit::EdgeRef;
+use petgraph::{Direction, Graph};
 use serde::{Deserialize, Serialize};
+use sunscreen_compiler_common::{EdgeInfo, Render};
 use sunscreen_runtime::{marker, Fhe, FheZkp, Zkp};
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::marker::PhantomData;
 
+pub use cache::{structural_hash, CacheKey, CompileCache, ProgramCache, StructuralHash};
 pub use compiler::{Compiler, FheProgramFn, FheProgramFnExt, GenericCompiler};
 pub use error::{Error, Result};
-pub use params::PlainModulusConstraint;
+pub use params::{ParamsCandidate, ParamsRanking, PlainModulusConstraint, TuningTarget};
 pub use seal_fhe::Plaintext as SealPlaintext;
+pub use sunscreen_backend::{
+    noise_model::{EstimateNoiseBudget, NoiseReport},
+    RelinearizationStrategy,
+};
 pub use sunscreen_compiler_macros::*;
 pub use sunscreen_fhe_program::{SchemeType, SecurityLevel};
 pub use sunscreen_runtime::{
-    CallSignature, Ciphertext, CompiledFheProgram, CompiledZkpProgram, Error as RuntimeError,
-    FheProgramInput, FheProgramInputTrait, FheProgramMetadata, FheRuntime, FheZkpRuntime,
-    InnerCiphertext, InnerPlaintext, Params, Plaintext, PrivateKey, ProofBuilder, PublicKey,
-    RequiredKeys, Runtime, VerificationBuilder, WithContext, ZkpProgramInput, ZkpRuntime,
+    BootstrapKeys, CallSignature, Ciphertext, CiphertextComponent, CiphertextShape,
+    CompiledFheProgram, CompiledZkpProgram, CostModel, DecodeError, Error as RuntimeError,
+    FheApplication, FheProgramInput, FheProgramInputTrait, FheProgramMetadata, FheProgramOutputs,
+    FheRuntime, FheZkpRuntime, InnerCiphertext, InnerPlaintext, KeySizeReport, Params, Plaintext,
+    PrivateKey, ProofBuilder, PublicKey, RequiredKeys, Runtime, VerificationBuilder, WithContext,
+    ZkpProgramInput, ZkpRuntime,
 };
 #[cfg(feature = "bulletproofs")]
 pub use sunscreen_zkp_backend::bulletproofs;
-pub use sunscreen_zkp_backend::{Error as ZkpError, Proof, Result as ZkpResult, ZkpBackend};
-pub use zkp::{invoke_gadget, ZkpProgramFn, ZkpProgramFnExt};
+pub use sunscreen_zkp_backend::{
+    render_compiled_zkp_program, ConstraintCount, ConstraintStats, Error as ZkpError, GadgetStats,
+    Proof, Result as ZkpResult, ZkpBackend,
+};
+pub use zkp::{invoke_gadget, GadgetOutputs, ZkpProgramFn, ZkpProgramFnExt};
 
 #[derive(Clone)]
 /**
@@ -169,6 +185,30 @@ where
         self.fhe_programs.iter()
     }
 
+    /**
+     * Bundles the compiled FHE programs and their shared [`Params`] into
+     * a single serializable [`FheApplication`].
+     *
+     * # Remarks
+     * Returns [`Error::NoPrograms`] if no [`fhe_program`] was specified.
+     */
+    pub fn fhe_application(&self) -> Result<FheApplication> {
+        let params = &self
+            .fhe_programs
+            .values()
+            .next()
+            .ok_or(Error::NoPrograms)?
+            .metadata
+            .params;
+
+        Ok(FheApplication::new(
+            params.clone(),
+            self.fhe_programs
+                .iter()
+                .map(|(name, program)| (name.clone(), program.clone())),
+        )?)
+    }
+
     /// Take ownership of a compiled program with the given name, removing it from this
     /// `Application`.
     ///
@@ -238,6 +278,12 @@ pub enum OperandInfo {
     Unary,
 }
 
+impl Render for OperandInfo {
+    fn render(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
 /**
  * This trait specifies a type as being able to be used as an input or output of an [`fhe_program`].
  */
@@ -264,6 +310,696 @@ pub struct FrontendCompilation {
     pub graph: StableGraph<FheOperation, OperandInfo>,
 }
 
+impl FrontendCompilation {
+    /**
+     * Renders this compilation's graph in Graphviz's DOT format.
+     *
+     * # Remarks
+     * Each node is labeled with its index and operation, and literal nodes
+     * additionally show their value. Inputs are drawn as inverted houses,
+     * outputs as houses and literals as boxes. Each edge is labeled with
+     * the operand it provides to its destination node.
+     *
+     * You can view the result with e.g. `dot -Tsvg`.
+     */
+    pub fn to_dot(&self) -> String {
+        let data = Dot::with_attr_getters(
+            &self.graph,
+            &[Config::NodeNoLabel, Config::EdgeNoLabel],
+            &|_, e| format!("label=\"{}\"", e.weight().render()),
+            &|_, n| {
+                let (index, op) = n;
+
+                let shape = match op {
+                    FheOperation::InputCiphertext | FheOperation::InputPlaintext => "invhouse",
+                    FheOperation::Output => "house",
+                    FheOperation::Literal(_) => "box",
+                    _ => "ellipse",
+                };
+
+                format!(
+                    "label=\"{}: {}\" shape={}",
+                    index.index(),
+                    op.render(),
+                    shape
+                )
+            },
+        );
+
+        format!("{data:?}")
+    }
+
+    /**
+     * Copies `other`'s graph into this compilation, feeding `other`'s
+     * inputs from existing nodes in `self`.
+     *
+     * # Remarks
+     * `other`'s input nodes, in node index order, are replaced by the
+     * corresponding nodes in `input_map` rather than copied. `other`'s
+     * output nodes aren't copied either; instead, this returns the
+     * (renumbered) nodes that fed them, in node index order, so the
+     * caller can use them as operands or wire them to outputs of its own.
+     * All other nodes get fresh indices in `self` and keep their
+     * [`OperandInfo`] edges.
+     *
+     * # Panics
+     * If `input_map`'s length doesn't match the number of inputs in
+     * `other`, if any node in `input_map` doesn't exist in `self` or if an
+     * output in `other` doesn't have exactly one operand.
+     */
+    pub fn append(
+        &mut self,
+        other: &FrontendCompilation,
+        input_map: &[NodeIndex],
+    ) -> Vec<NodeIndex> {
+        let is_input = |n: NodeIndex| {
+            matches!(
+                other.graph[n],
+                FheOperation::InputCiphertext | FheOperation::InputPlaintext
+            )
+        };
+
+        let inputs = other
+            .graph
+            .node_indices()
+            .filter(|n| is_input(*n))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            inputs.len(),
+            input_map.len(),
+            "Expected {} input mappings, got {}",
+            inputs.len(),
+            input_map.len()
+        );
+
+        for n in input_map {
+            assert!(self.graph.contains_node(*n), "Node {n:?} doesn't exist");
+        }
+
+        let mut mapping = inputs
+            .into_iter()
+            .zip(input_map.iter().copied())
+            .collect::<HashMap<_, _>>();
+
+        for n in other.graph.node_indices() {
+            if is_input(n) || other.graph[n] == FheOperation::Output {
+                continue;
+            }
+
+            mapping.insert(n, self.graph.add_node(other.graph[n].clone()));
+        }
+
+        let mut outputs = vec![];
+
+        for n in other.graph.node_indices() {
+            if other.graph[n] == FheOperation::Output {
+                let mut operands = other.graph.neighbors_directed(n, Direction::Incoming);
+
+                match (operands.next(), operands.next()) {
+                    (Some(operand), None) => outputs.push(mapping[&operand]),
+                    _ => panic!("Output {n:?} must have exactly one operand"),
+                }
+            }
+        }
+
+        for e in other.graph.edge_indices() {
+            let (from, to) = other.graph.edge_endpoints(e).unwrap();
+
+            if other.graph[to] == FheOperation::Output {
+                continue;
+            }
+
+            self.graph
+                .add_edge(mapping[&from], mapping[&to], other.graph[e].clone());
+        }
+
+        outputs
+    }
+
+    /**
+     * Creates a compilation from a list of operations and their operands,
+     * validating that the result is a well-formed graph.
+     *
+     * # Remarks
+     * Node `i` in the result is `ops[i]`, and each operand is a
+     * `(node, kind)` pair naming an earlier node in `ops`. Since operands
+     * must precede their consumers, the graph is always acyclic.
+     *
+     * Fails with [`Error::MalformedGraph`] if
+     * * an operand refers to itself, a later node or an output.
+     * * an input or literal has operands.
+     * * a binary operation doesn't have exactly one
+     *   [`OperandInfo::Left`] and one [`OperandInfo::Right`] operand.
+     * * a unary operation or output doesn't have exactly one
+     *   [`OperandInfo::Unary`] operand.
+     * * an operand has the wrong kind. Ciphertext operands must be
+     *   ciphertexts or plaintext literals, plaintext operands must be
+     *   plaintexts or plaintext literals, rotation amounts must be
+     *   [`Literal::U64`](crate::fhe::Literal::U64) nodes and outputs must be
+     *   ciphertexts. Since the compiler evaluates operations whose
+     *   ciphertext operands are all literals, a plaintext operation whose
+     *   ciphertext operand is a literal needs a literal plaintext operand
+     *   too.
+     * * an input follows a non-input node, as inputs are numbered by their
+     *   node index.
+     * * the graph has no inputs or no outputs.
+     *
+     * This is useful for fuzzers and test generators that need to build
+     * arbitrary, but valid, programs.
+     */
+    pub fn from_operations(ops: &[(FheOperation, Vec<(NodeIndex, OperandInfo)>)]) -> Result<Self> {
+        // The kind of value a node produces. Operations on constants get
+        // evaluated during compilation, so they produce constants too.
+        #[derive(Clone, Copy, PartialEq)]
+        enum Kind {
+            Ciphertext,
+            Plaintext,
+            Constant,
+            RotationAmount,
+        }
+
+        let is_input = |op: &FheOperation| {
+            matches!(
+                op,
+                FheOperation::InputCiphertext | FheOperation::InputPlaintext
+            )
+        };
+
+        let is_cipher = |kind: Kind| matches!(kind, Kind::Ciphertext | Kind::Constant);
+        let is_plain = |kind: Kind| matches!(kind, Kind::Plaintext | Kind::Constant);
+
+        let mut graph = StableGraph::new();
+        let mut kinds: Vec<Option<Kind>> = Vec::with_capacity(ops.len());
+
+        for (i, (op, operands)) in ops.iter().enumerate() {
+            let expected: &[OperandInfo] = match op {
+                FheOperation::InputCiphertext
+                | FheOperation::InputPlaintext
+                | FheOperation::Literal(_) => &[],
+                FheOperation::Negate
+                | FheOperation::SwapRows
+                | FheOperation::SwapRowsPlaintext
+                | FheOperation::Output => &[OperandInfo::Unary],
+                FheOperation::Add
+                | FheOperation::AddPlaintext
+                | FheOperation::Sub
+                | FheOperation::SubPlaintext
+                | FheOperation::PlaintextSub
+                | FheOperation::Multiply
+                | FheOperation::MultiplyPlaintext
+                | FheOperation::RotateLeft
+                | FheOperation::RotateRight
+                | FheOperation::RotateLeftPlaintext
+                | FheOperation::RotateRightPlaintext => &[OperandInfo::Left, OperandInfo::Right],
+            };
+
+            if is_input(op) && i > 0 && !is_input(&ops[i - 1].0) {
+                return Err(Error::malformed_graph(&format!(
+                    "Input node {i} follows a non-input node"
+                )));
+            }
+
+            if operands.len() != expected.len()
+                || !expected
+                    .iter()
+                    .all(|e| operands.iter().any(|(_, kind)| kind == e))
+            {
+                return Err(Error::malformed_graph(&format!(
+                    "Node {i} ({}) expects operands {expected:?}",
+                    op.render()
+                )));
+            }
+
+            for (operand, _) in operands {
+                if operand.index() >= i {
+                    return Err(Error::malformed_graph(&format!(
+                        "Node {i} references node {} which doesn't precede it",
+                        operand.index()
+                    )));
+                }
+
+                if ops[operand.index()].0 == FheOperation::Output {
+                    return Err(Error::malformed_graph(&format!(
+                        "Node {i} uses output node {} as an operand",
+                        operand.index()
+                    )));
+                }
+            }
+
+            // Outputs are never operands, so every operand has a kind.
+            let operand = |info: OperandInfo| {
+                let (n, _) = operands.iter().find(|(_, x)| *x == info).unwrap();
+
+                kinds[n.index()].unwrap()
+            };
+
+            let ensure = |valid: bool| {
+                if valid {
+                    Ok(())
+                } else {
+                    Err(Error::malformed_graph(&format!(
+                        "Node {i} ({}) has an operand of the wrong kind",
+                        op.render()
+                    )))
+                }
+            };
+
+            let kind = match op {
+                FheOperation::InputCiphertext => Some(Kind::Ciphertext),
+                FheOperation::InputPlaintext => Some(Kind::Plaintext),
+                FheOperation::Literal(Literal::U64(_)) => Some(Kind::RotationAmount),
+                FheOperation::Literal(Literal::Plaintext(_)) => Some(Kind::Constant),
+                FheOperation::Add | FheOperation::Sub | FheOperation::Multiply => {
+                    let (left, right) = (operand(OperandInfo::Left), operand(OperandInfo::Right));
+
+                    ensure(is_cipher(left) && is_cipher(right))?;
+
+                    if left == Kind::Constant && right == Kind::Constant {
+                        Some(Kind::Constant)
+                    } else {
+                        Some(Kind::Ciphertext)
+                    }
+                }
+                FheOperation::AddPlaintext
+                | FheOperation::SubPlaintext
+                | FheOperation::MultiplyPlaintext
+                | FheOperation::PlaintextSub => {
+                    let (cipher, plain) = if *op == FheOperation::PlaintextSub {
+                        (operand(OperandInfo::Right), operand(OperandInfo::Left))
+                    } else {
+                        (operand(OperandInfo::Left), operand(OperandInfo::Right))
+                    };
+
+                    ensure(
+                        is_cipher(cipher)
+                            && is_plain(plain)
+                            && (cipher == Kind::Ciphertext || plain == Kind::Constant),
+                    )?;
+
+                    Some(cipher)
+                }
+                FheOperation::Negate | FheOperation::SwapRows => {
+                    let x = operand(OperandInfo::Unary);
+
+                    ensure(is_cipher(x))?;
+
+                    Some(x)
+                }
+                FheOperation::RotateLeft | FheOperation::RotateRight => {
+                    let x = operand(OperandInfo::Left);
+
+                    ensure(is_cipher(x) && operand(OperandInfo::Right) == Kind::RotationAmount)?;
+
+                    Some(x)
+                }
+                FheOperation::RotateLeftPlaintext | FheOperation::RotateRightPlaintext => {
+                    let x = operand(OperandInfo::Left);
+
+                    ensure(is_plain(x) && operand(OperandInfo::Right) == Kind::RotationAmount)?;
+
+                    Some(x)
+                }
+                FheOperation::SwapRowsPlaintext => {
+                    let x = operand(OperandInfo::Unary);
+
+                    ensure(is_plain(x))?;
+
+                    Some(x)
+                }
+                FheOperation::Output => {
+                    ensure(operand(OperandInfo::Unary) == Kind::Ciphertext)?;
+
+                    None
+                }
+            };
+
+            kinds.push(kind);
+
+            let node = graph.add_node(op.clone());
+
+            for (operand, kind) in operands {
+                graph.add_edge(*operand, node, kind.clone());
+            }
+        }
+
+        if !ops.iter().any(|(op, _)| is_input(op)) {
+            return Err(Error::malformed_graph("The graph has no inputs"));
+        }
+
+        if !ops.iter().any(|(op, _)| *op == FheOperation::Output) {
+            return Err(Error::malformed_graph("The graph has no outputs"));
+        }
+
+        Ok(Self { graph })
+    }
+
+    /**
+     * Serializes this compilation into a versioned format that doesn't
+     * depend on petgraph's internal representation.
+     *
+     * # Remarks
+     * The output starts with a magic header and a format version byte,
+     * followed by the list of nodes, the indices of holes left by removed
+     * nodes and the list of edges. Each node and edge endpoint carries its
+     * explicit index, so [`from_bytes`](Self::from_bytes) restores the
+     * same node indices even if the graph has holes.
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let wire = WireCompilation {
+            nodes: self
+                .graph
+                .node_indices()
+                .map(|n| (n.index() as u32, self.graph[n].clone()))
+                .collect(),
+            holes: (0..self.graph.node_bound())
+                .filter(|i| !self.graph.contains_node(NodeIndex::new(*i)))
+                .map(|i| i as u32)
+                .collect(),
+            edges: self
+                .graph
+                .edge_indices()
+                .map(|e| {
+                    let (from, to) = self.graph.edge_endpoints(e).unwrap();
+
+                    (
+                        from.index() as u32,
+                        to.index() as u32,
+                        self.graph[e].clone(),
+                    )
+                })
+                .collect(),
+        };
+
+        let mut bytes = FRONTEND_COMPILATION_MAGIC.to_vec();
+        bytes.push(FRONTEND_COMPILATION_VERSION);
+
+        bincode::serialize_into(&mut bytes, &wire)
+            .map_err(|e| Error::serialization(&e.to_string()))?;
+
+        Ok(bytes)
+    }
+
+    /**
+     * Deserializes a compilation produced by [`to_bytes`](Self::to_bytes).
+     *
+     * # Remarks
+     * Fails with [`Error::SerializationError`] if `bytes` doesn't start
+     * with the expected magic header, was written with a different format
+     * version, or is otherwise malformed (e.g. duplicate node indices,
+     * node indices beyond the number of nodes and holes, or edges
+     * referencing missing nodes).
+     */
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let header_len = FRONTEND_COMPILATION_MAGIC.len();
+
+        if bytes.len() <= header_len || &bytes[..header_len] != FRONTEND_COMPILATION_MAGIC {
+            return Err(Error::serialization("Not a serialized FrontendCompilation"));
+        }
+
+        let version = bytes[header_len];
+
+        if version != FRONTEND_COMPILATION_VERSION {
+            return Err(Error::serialization(&format!(
+                "Unsupported FrontendCompilation format version {version}, expected {FRONTEND_COMPILATION_VERSION}"
+            )));
+        }
+
+        let wire: WireCompilation = bincode::deserialize(&bytes[header_len + 1..])
+            .map_err(|e| Error::serialization(&e.to_string()))?;
+
+        // Every index below the number of nodes and holes must appear
+        // exactly once, which bounds the work here by the input's size.
+        let mut slots = vec![None; wire.nodes.len() + wire.holes.len()];
+
+        let entries = wire
+            .nodes
+            .into_iter()
+            .map(|(i, op)| (i, Some(op)))
+            .chain(wire.holes.into_iter().map(|i| (i, None)));
+
+        for (index, op) in entries {
+            let slot = slots
+                .get_mut(index as usize)
+                .ok_or_else(|| Error::serialization("Node index out of range"))?;
+
+            if slot.is_some() {
+                return Err(Error::serialization("Duplicate node index"));
+            }
+
+            *slot = Some(op);
+        }
+
+        // Recreate holes left by removed nodes with placeholders, then
+        // remove them so the remaining nodes keep their original indices.
+        let mut graph = StableGraph::new();
+        let mut placeholders = vec![];
+
+        // As there are as many distinct indices as slots, all are filled.
+        for slot in slots.into_iter().flatten() {
+            match slot {
+                Some(op) => {
+                    graph.add_node(op);
+                }
+                None => placeholders.push(graph.add_node(FheOperation::InputCiphertext)),
+            }
+        }
+
+        for p in placeholders {
+            graph.remove_node(p);
+        }
+
+        for (from, to, info) in wire.edges {
+            let (from, to) = (NodeIndex::new(from as usize), NodeIndex::new(to as usize));
+
+            if !graph.contains_node(from) || !graph.contains_node(to) {
+                return Err(Error::serialization("Edge references a missing node"));
+            }
+
+            graph.add_edge(from, to, info);
+        }
+
+        Ok(Self { graph })
+    }
+
+    /**
+     * Reports how `other` differs from this compilation.
+     *
+     * # Remarks
+     * If the two graphs are isomorphic (i.e. they compute the same
+     * program, possibly with different node numbering), this returns an
+     * empty diff with [`GraphDiff::isomorphic`] set. Otherwise, it falls
+     * back to a structural diff that matches nodes by index: nodes only in
+     * `other` are added, nodes only in `self` are removed and nodes whose
+     * operation differs are changed. Edges are matched by their endpoints
+     * and [`OperandInfo`].
+     *
+     * Graph isomorphism is NP-complete, so this is intended for tests and
+     * debugging rather than hot paths.
+     */
+    pub fn diff(&self, other: &Self) -> GraphDiff {
+        let isomorphic = is_isomorphic_matching(
+            &Graph::from(self.graph.clone()),
+            &Graph::from(other.graph.clone()),
+            |n1, n2| n1 == n2,
+            |e1, e2| e1 == e2,
+        );
+
+        let mut diff = GraphDiff {
+            isomorphic,
+            ..GraphDiff::default()
+        };
+
+        if isomorphic {
+            return diff;
+        }
+
+        for n in self.graph.node_indices() {
+            match other.graph.node_weight(n) {
+                Some(op) if *op != self.graph[n] => {
+                    diff.changed_nodes
+                        .push((n, self.graph[n].clone(), op.clone()))
+                }
+                Some(_) => {}
+                None => diff.removed_nodes.push((n, self.graph[n].clone())),
+            }
+        }
+
+        for n in other.graph.node_indices() {
+            if !self.graph.contains_node(n) {
+                diff.added_nodes.push((n, other.graph[n].clone()));
+            }
+        }
+
+        let edges = |c: &Self| {
+            c.graph
+                .edge_references()
+                .map(|e| (e.source(), e.target(), e.weight().clone()))
+                .collect::<Vec<_>>()
+        };
+
+        let (ours, theirs) = (edges(self), edges(other));
+
+        diff.removed_edges = ours
+            .iter()
+            .filter(|e| !theirs.contains(e))
+            .cloned()
+            .collect();
+        diff.added_edges = theirs
+            .iter()
+            .filter(|e| !ours.contains(e))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
+
+const FRONTEND_COMPILATION_MAGIC: &[u8; 4] = b"SSFC";
+const FRONTEND_COMPILATION_VERSION: u8 = 2;
+
+#[derive(Deserialize, Serialize)]
+/**
+ * The wire format of a [`FrontendCompilation`]: nodes, holes and edges
+ * with explicit indices.
+ */
+struct WireCompilation {
+    nodes: Vec<(u32, FheOperation)>,
+    holes: Vec<u32>,
+    edges: Vec<(u32, u32, OperandInfo)>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/**
+ * The differences between two [`FrontendCompilation`]s, as reported by
+ * [`FrontendCompilation::diff`].
+ *
+ * # Remarks
+ * The [`Display`](std::fmt::Display) implementation renders one line per
+ * difference, prefixed with `+` for additions, `-` for removals and `~`
+ * for changes.
+ */
+pub struct GraphDiff {
+    /**
+     * Whether the two graphs are isomorphic. When set, all the other
+     * lists are empty.
+     */
+    pub isomorphic: bool,
+
+    /**
+     * Nodes that only exist in the other graph.
+     */
+    pub added_nodes: Vec<(NodeIndex, FheOperation)>,
+
+    /**
+     * Nodes that only exist in this graph.
+     */
+    pub removed_nodes: Vec<(NodeIndex, FheOperation)>,
+
+    /**
+     * Nodes that exist in both graphs with different operations, as
+     * `(index, ours, theirs)`.
+     */
+    pub changed_nodes: Vec<(NodeIndex, FheOperation, FheOperation)>,
+
+    /**
+     * Edges, as `(source, target, operand)`, that only exist in the other
+     * graph.
+     */
+    pub added_edges: Vec<(NodeIndex, NodeIndex, OperandInfo)>,
+
+    /**
+     * Edges, as `(source, target, operand)`, that only exist in this
+     * graph.
+     */
+    pub removed_edges: Vec<(NodeIndex, NodeIndex, OperandInfo)>,
+}
+
+impl GraphDiff {
+    /**
+     * Whether the diff found no differences.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+    }
+}
+
+impl std::fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.isomorphic {
+            return writeln!(f, "Graphs are isomorphic");
+        }
+
+        for (n, op) in &self.added_nodes {
+            writeln!(f, "+ node {}: {}", n.index(), op.render())?;
+        }
+
+        for (n, op) in &self.removed_nodes {
+            writeln!(f, "- node {}: {}", n.index(), op.render())?;
+        }
+
+        for (n, ours, theirs) in &self.changed_nodes {
+            writeln!(
+                f,
+                "~ node {}: {} -> {}",
+                n.index(),
+                ours.render(),
+                theirs.render()
+            )?;
+        }
+
+        for (from, to, info) in &self.added_edges {
+            writeln!(
+                f,
+                "+ edge {} -> {}: {}",
+                from.index(),
+                to.index(),
+                info.render()
+            )?;
+        }
+
+        for (from, to, info) in &self.removed_edges {
+            writeln!(
+                f,
+                "- edge {} -> {}: {}",
+                from.index(),
+                to.index(),
+                info.render()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<&FheFrontendCompilation> for FrontendCompilation {
+    /**
+     * Converts the result of building an [`fhe_program`] into a
+     * [`FrontendCompilation`].
+     *
+     * # Panics
+     * If the graph contains ordered or unordered edges, which FHE
+     * programs never produce.
+     */
+    fn from(compilation: &FheFrontendCompilation) -> Self {
+        let graph = compilation.map(
+            |_, n| n.operation.clone(),
+            |_, e| match e {
+                EdgeInfo::Left => OperandInfo::Left,
+                EdgeInfo::Right => OperandInfo::Right,
+                EdgeInfo::Unary => OperandInfo::Unary,
+                x => panic!("FHE programs don't support {x:?} edges"),
+            },
+        );
+
+        Self { graph }
+    }
+}
+
 thread_local! {
     /// An arena containing slices of indicies. An implementation detail of FHE/ZKP programs.
     pub static INDEX_ARENA: RefCell<bumpalo::Bump> = RefCell::new(bumpalo::Bump::new());
@@ -1,13 +1,21 @@
-use crate::{fhe::FheCompile, Error, FheProgramFn, Result, SecurityLevel};
+use crate::{
+    fhe::{run_passes, FheCompile, FrontendPass},
+    Error, FheProgramFn, Result, SecurityLevel,
+};
 
 use log::{debug, trace};
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
 
 use seal_fhe::{
     BfvEncryptionParametersBuilder, CoefficientModulus, Context, KeyGenerator, Modulus,
     PlainModulus,
 };
-use sunscreen_backend::noise_model::{
-    noise_budget_to_noise, predict_noise, MeasuredModel, TargetNoiseLevel,
+use sunscreen_backend::{
+    noise_model::{
+        noise_budget_to_noise, predict_noise, MeasuredModel, NoiseReport, TargetNoiseLevel,
+    },
+    RelinearizationStrategy,
 };
 use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation, SchemeType};
 pub use sunscreen_runtime::Params;
@@ -24,12 +32,23 @@ pub enum PlainModulusConstraint {
     Raw(u64),
 
     /**
-     * Specifies a minimal requested number of bits in the prime number generated in the
-     * prime modulus. This is only a minimum, if the parameters require more, you will receive
-     * a larger plain modulus.
+     * Use a plaintext modulus that supports batching with at least the given
+     * number of bits. The compiler picks the smallest prime of at least this
+     * many bits that's congruent to 1 modulo twice the lattice dimension it
+     * selects, as batching requires.
      *
      * # Remarks
-     * You can set this to 0 if all your values will be `2^14-1` or less.
+     * This is only a minimum; if the lattice dimension requires more bits,
+     * you will receive a larger plain modulus. You can set this to 0 if all
+     * your values have magnitude `2^12` or less.
+     *
+     * Smaller plaintext moduli consume less noise budget, so request no more
+     * bits than your values need. A lane holding a value of magnitude `2^k`
+     * needs at least `k + 2` bits.
+     *
+     * Compilation fails with [`Error::UnsatisfiableConstraint`] if no lattice
+     * dimension admits such a prime within SEAL's 60-bit plaintext modulus
+     * limit.
      */
     BatchingMinimum(u32),
 }
@@ -37,6 +56,91 @@ pub enum PlainModulusConstraint {
 const LATTICE_DIMENSIONS: &[u64] = &[1024, 2048, 4096, 8192, 16384, 32768];
 const BATCHING_MIN_BITS: &[u32] = &[14, 14, 16, 17, 17, 17];
 
+/**
+ * The largest number of bits SEAL allows in a plaintext modulus.
+ */
+const MAX_PLAIN_MODULUS_BITS: u32 = 60;
+
+/**
+ * Returns whether `x` is prime.
+ *
+ * # Remarks
+ * Uses Miller-Rabin with the first 12 primes as witnesses, which is
+ * deterministic for all 64-bit integers.
+ */
+fn is_prime(x: u64) -> bool {
+    const WITNESSES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+    if x < 2 {
+        return false;
+    }
+
+    for p in WITNESSES {
+        if x % p == 0 {
+            return x == p;
+        }
+    }
+
+    let mul_mod = |a: u64, b: u64| ((a as u128 * b as u128) % x as u128) as u64;
+
+    let pow_mod = |mut b: u64, mut e: u64| {
+        let mut result = 1;
+
+        while e > 0 {
+            if e & 1 == 1 {
+                result = mul_mod(result, b);
+            }
+
+            b = mul_mod(b, b);
+            e >>= 1;
+        }
+
+        result
+    };
+
+    let s = (x - 1).trailing_zeros();
+    let d = (x - 1) >> s;
+
+    WITNESSES.iter().all(|a| {
+        let mut y = pow_mod(*a, d);
+
+        if y == 1 || y == x - 1 {
+            return true;
+        }
+
+        for _ in 1..s {
+            y = mul_mod(y, y);
+
+            if y == x - 1 {
+                return true;
+            }
+        }
+
+        false
+    })
+}
+
+/**
+ * Returns the smallest prime of at least `bits` bits congruent to 1 modulo
+ * `2 * lattice_dimension`, or [`None`] if no such prime fits in
+ * [`MAX_PLAIN_MODULUS_BITS`] bits.
+ */
+fn smallest_batching_prime(lattice_dimension: u64, bits: u32) -> Option<u64> {
+    if bits > MAX_PLAIN_MODULUS_BITS {
+        return None;
+    }
+
+    let m = 2 * lattice_dimension;
+    let lower = 1u64 << bits.saturating_sub(1);
+
+    // The first candidate congruent to 1 mod m that's at least lower.
+    let first = lower + (1 + m - lower % m) % m;
+
+    (first..1 << MAX_PLAIN_MODULUS_BITS)
+        .step_by(m as usize)
+        .find(|x| is_prime(*x))
+}
+
 /**
  * Returns a plaintext modulus that satisfies the given
  * PlainModulusConstraint and lattice dimension.
@@ -55,16 +159,15 @@ fn plaintext_constraint_to_modulus(
     let plaintext_modulus = match constraint {
         PlainModulusConstraint::Raw(v) => PlainModulus::raw(v).unwrap(),
         PlainModulusConstraint::BatchingMinimum(min) => {
-            let min_batching_bits = BATCHING_MIN_BITS[lattice_dimension_index];
+            let bits = u32::max(BATCHING_MIN_BITS[lattice_dimension_index], min);
 
-            match PlainModulus::batching(lattice_dimension, u32::max(min_batching_bits, min)) {
-                Ok(v) => v,
-                Err(e) => {
+            match smallest_batching_prime(lattice_dimension, bits) {
+                Some(v) => PlainModulus::raw(v)?,
+                None => {
                     trace!(
-                        "Can't use batching with {} bits for dimension n={}: {:#?}",
-                        min_batching_bits,
-                        lattice_dimension,
-                        e
+                        "Can't use batching with {} bits for dimension n={}",
+                        bits,
+                        lattice_dimension
                     );
                     return Err(Error::UnsatisfiableConstraint);
                 }
@@ -75,6 +178,21 @@ fn plaintext_constraint_to_modulus(
     Ok(plaintext_modulus)
 }
 
+/**
+ * Returns [`Error::UnsatisfiableConstraint`] if no lattice dimension
+ * admits a plaintext modulus meeting `constraint`.
+ */
+fn check_constraint_satisfiable(constraint: PlainModulusConstraint) -> Result<()> {
+    let satisfiable = (0..LATTICE_DIMENSIONS.len())
+        .any(|i| plaintext_constraint_to_modulus(constraint, i).is_ok());
+
+    if satisfiable {
+        Ok(())
+    } else {
+        Err(Error::UnsatisfiableConstraint)
+    }
+}
+
 /**
  * Verifies the keys required by the fhe_program can be created
  * with the given parameter set.
@@ -112,24 +230,184 @@ fn can_make_required_keys(fhe_program: &FheProgram, params: &Params) -> Result<b
     Ok(create_galois && create_relin)
 }
 
+/**
+ * Builds each FHE program under `params` and checks it can run under them.
+ * Returns the compiled programs if every program's keys can be created and
+ * its outputs retain at least `noise_margin_bits` of noise budget after
+ * chaining. Returns [`None`] if the parameters are unsuitable.
+ */
+fn compile_if_feasible(
+    programs: &SearchPrograms,
+    params: &Params,
+    noise_margin_bits: u32,
+) -> Result<Option<Vec<FheProgram>>> {
+    let n = params.lattice_dimension;
+    let mut irs = vec![];
+
+    for program in programs.fhe_program_fns {
+        trace!("Running backend compilation for {}", program.name());
+        let mut frontend = program.build(params)?;
+        run_passes(&mut frontend, programs.passes)?;
+        let ir = frontend.compile_with(programs.relinearization);
+
+        ir.validate().map_err(Error::FheProgramError)?;
+        trace!("Built and validated {}", program.name());
+
+        match can_make_required_keys(&ir, params) {
+            Ok(can_make_keys) => {
+                if !can_make_keys {
+                    return Ok(None);
+                }
+            }
+            Err(_) => {
+                return Ok(None);
+            }
+        };
+
+        let mut chain_noise_level = 0f64;
+
+        for _ in 0..program.chain_count() {
+            let noise_targets = ir
+                .graph
+                .node_weights()
+                .filter(|n| {
+                    matches!(
+                        n.operation,
+                        Operation::InputCiphertext(_) | Operation::InputPlaintext(_)
+                    )
+                })
+                .map(|n| match n.operation {
+                    Operation::InputCiphertext(_) => {
+                        if chain_noise_level == 0f64 {
+                            TargetNoiseLevel::Fresh
+                        } else {
+                            TargetNoiseLevel::InvariantNoise(chain_noise_level)
+                        }
+                    }
+                    Operation::InputPlaintext(_) => TargetNoiseLevel::NotApplicable,
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<TargetNoiseLevel>>();
+
+            let model = match MeasuredModel::new(&ir, params, &noise_targets) {
+                Ok(v) => v,
+                Err(_) => {
+                    trace!(
+                        "Failed to construct noise model for {} with lattice_dimension={}",
+                        program.name(),
+                        n
+                    );
+                    return Ok(None);
+                }
+            };
+
+            let output_noises = predict_noise(&model, &ir);
+
+            let target_noise = noise_budget_to_noise(noise_margin_bits as f64);
+
+            for output_noise in output_noises {
+                if output_noise > target_noise {
+                    trace!(
+                        "Failed to meet noise constraints with lattice dimension {} for program {}",
+                        n,
+                        program.name()
+                    );
+                    return Ok(None);
+                } else if output_noise > chain_noise_level {
+                    chain_noise_level = output_noise
+                }
+            }
+        }
+
+        irs.push(ir);
+    }
+
+    Ok(Some(irs))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+/**
+ * Bounds on how long a parameter search may run. See
+ * [`search_timeout`](crate::GenericCompiler::search_timeout) and
+ * [`max_search_iterations`](crate::GenericCompiler::max_search_iterations).
+ */
+pub(crate) struct SearchLimits {
+    pub timeout: Option<Duration>,
+    pub max_iterations: Option<usize>,
+}
+
+/**
+ * The FHE programs a parameter search compiles under each candidate and
+ * how it compiles them.
+ */
+pub(crate) struct SearchPrograms<'a> {
+    pub fhe_program_fns: &'a [Box<dyn FheProgramFn>],
+    pub passes: &'a [Box<dyn FrontendPass>],
+    pub relinearization: RelinearizationStrategy,
+}
+
+/**
+ * Tracks a parameter search's progress against its [`SearchLimits`].
+ */
+struct SearchBudget {
+    deadline: Option<Instant>,
+    remaining: Option<usize>,
+}
+
+impl SearchBudget {
+    fn new(limits: &SearchLimits) -> Self {
+        Self {
+            // Only read the clock when needed, as some targets lack one.
+            deadline: limits.timeout.map(|t| Instant::now() + t),
+            remaining: limits.max_iterations,
+        }
+    }
+
+    /**
+     * Claims the budget to try one more candidate, returning
+     * [`Error::ParameterSearchExhausted`] with `best` if there's none left.
+     */
+    fn next_candidate(&mut self, best: Option<&Params>) -> Result<()> {
+        let timed_out = self.deadline.map_or(false, |d| Instant::now() >= d);
+        let out_of_iterations = self.remaining == Some(0);
+
+        if timed_out || out_of_iterations {
+            debug!("Parameter search exhausted its budget");
+
+            return Err(Error::ParameterSearchExhausted(best.cloned().map(Box::new)));
+        }
+
+        if let Some(r) = &mut self.remaining {
+            *r -= 1;
+        }
+
+        Ok(())
+    }
+}
+
 /**
  * Determines the minimal parameters required to satisfy the noise constraint for
  * the given FHE program and plaintext modulo and security level.
  */
 pub fn determine_params(
-    fhe_program_fns: &[Box<dyn FheProgramFn>],
+    programs: &SearchPrograms,
     plaintext_constraint: PlainModulusConstraint,
     security_level: SecurityLevel,
     noise_margin_bits: u32,
     scheme_type: SchemeType,
+    limits: &SearchLimits,
 ) -> Result<Params> {
-    'params_loop: for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
+    check_constraint_satisfiable(plaintext_constraint)?;
+
+    let mut budget = SearchBudget::new(limits);
+
+    for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
         // Select a plain modulus that meets needs of the passed
         // constraint.
         let plaintext_modulus = match plaintext_constraint_to_modulus(plaintext_constraint, i) {
             Ok(v) => v,
             Err(_) => {
-                continue 'params_loop;
+                continue;
             }
         };
 
@@ -152,85 +430,412 @@ pub fn determine_params(
             n
         );
 
-        for program in fhe_program_fns {
-            trace!("Successfully created parameters.");
-            trace!("Running backend compilation for {}", program.name());
-            let ir = program.build(&params)?.compile();
+        budget.next_candidate(None)?;
 
-            ir.validate().map_err(Error::FheProgramError)?;
-            trace!("Built and validated {}", program.name());
+        if compile_if_feasible(programs, &params, noise_margin_bits)?.is_some() {
+            debug!("Using params lattice_dimension={} and ={:#?}", n, coeff);
 
-            match can_make_required_keys(&ir, &params) {
-                Ok(can_make_keys) => {
-                    if !can_make_keys {
-                        continue 'params_loop;
-                    }
-                }
-                Err(_) => {
-                    continue 'params_loop;
-                }
+            return Ok(params);
+        }
+    }
+
+    Err(Error::NoParams)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * The quantity [`auto_tune`](crate::GenericCompiler::auto_tune) tries to
+ * minimize.
+ */
+pub enum TuningTarget {
+    /**
+     * Minimize the estimated time to run the FHE programs.
+     */
+    Latency,
+
+    /**
+     * Minimize the size of ciphertexts, and thus of the programs' inputs
+     * and outputs. Ties are broken by estimated latency.
+     */
+    CiphertextSize,
+}
+
+/**
+ * The number of primes that hold ciphertext data in a modulus chain of
+ * `chain_len` primes. When the chain has more than one prime, SEAL reserves
+ * the last as the special prime for key switching.
+ */
+fn data_primes(chain_len: usize) -> usize {
+    usize::max(chain_len - 1, 1)
+}
+
+/**
+ * Estimates the cost of running `irs` under `params` as
+ * `(primary, secondary)` where lower is better. `secondary` breaks ties.
+ *
+ * # Remarks
+ * Latency is the sum of each operation's cost, weighted by the number of
+ * times its program is chained. Element-wise operations cost `n` per data
+ * prime, multiplications require NTTs and cost `n log n` per data prime,
+ * and key switching operations (relinearization and rotations) cost
+ * `n log n` per data prime per decomposition digit. These are relative
+ * units, not seconds.
+ *
+ * Ciphertext size is the number of bytes in a fresh, 2 polynomial
+ * ciphertext.
+ */
+fn estimate_cost(
+    fhe_program_fns: &[Box<dyn FheProgramFn>],
+    irs: &[FheProgram],
+    params: &Params,
+    target: TuningTarget,
+) -> (f64, f64) {
+    let n = params.lattice_dimension as f64;
+    let l = data_primes(params.coeff_modulus.len()) as f64;
+    let ntt = n * n.log2();
+
+    let latency = fhe_program_fns
+        .iter()
+        .zip(irs)
+        .map(|(program, ir)| {
+            let program_cost = ir
+                .graph
+                .node_weights()
+                .map(|node| match node.operation {
+                    Operation::Add
+                    | Operation::AddPlaintext
+                    | Operation::Sub
+                    | Operation::SubPlaintext
+                    | Operation::Negate => n * l,
+                    Operation::ShiftLeftPlaintext
+                    | Operation::ShiftRightPlaintext
+                    | Operation::SwapRowsPlaintext => n,
+                    Operation::MultiplyPlaintext => ntt * l,
+                    Operation::Multiply => 4. * ntt * l,
+                    Operation::Relinearize
+                    | Operation::ShiftLeft
+                    | Operation::ShiftRight
+                    | Operation::SwapRows => ntt * l * (l + 1.),
+                    Operation::InputCiphertext(_)
+                    | Operation::InputPlaintext(_)
+                    | Operation::Literal(_)
+                    | Operation::OutputCiphertext => 0.,
+                })
+                .sum::<f64>();
+
+            program_cost * program.chain_count() as f64
+        })
+        .sum::<f64>();
+
+    let size = 2. * n * l * std::mem::size_of::<u64>() as f64;
+
+    match target {
+        TuningTarget::Latency => (latency, size),
+        TuningTarget::CiphertextSize => (size, latency),
+    }
+}
+
+/**
+ * Returns the first parameters a parameter search would consider for the
+ * given constraints, without checking whether any program fits in them.
+ *
+ * # Remarks
+ * The [`ProgramCache`](crate::ProgramCache) builds programs under these
+ * parameters to compute their structural hashes before the (expensive)
+ * parameter search runs.
+ */
+pub(crate) fn probe_params(
+    plaintext_constraint: PlainModulusConstraint,
+    security_level: SecurityLevel,
+    scheme_type: SchemeType,
+) -> Result<Params> {
+    for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
+        let Ok(plaintext_modulus) = plaintext_constraint_to_modulus(plaintext_constraint, i) else {
+            continue;
+        };
+
+        let coeff = CoefficientModulus::bfv_default(*n, security_level)?;
+
+        return Ok(Params {
+            coeff_modulus: coeff.iter().map(|v| v.value()).collect(),
+            lattice_dimension: *n,
+            plain_modulus: plaintext_modulus.value(),
+            security_level,
+            scheme_type,
+        });
+    }
+
+    Err(Error::UnsatisfiableConstraint)
+}
+
+/**
+ * Searches lattice dimensions and coefficient modulus chains for the
+ * parameters that minimize the estimated cost of `target` while meeting the
+ * noise and security constraints.
+ *
+ * # Remarks
+ * For each lattice dimension, the candidate modulus chains are the prefixes
+ * of SEAL's default chain for `security_level`. Shorter chains have less
+ * noise budget but smaller, faster ciphertexts; they never reduce security
+ * because they shrink the modulus. Within a lattice dimension, the search
+ * stops at the shortest feasible chain, as longer chains only cost more.
+ * Across lattice dimensions, the search stops once even the shortest chain
+ * of a dimension can't beat the best candidate found so far.
+ *
+ * The cost model is a coarse heuristic (see `estimate_cost`), so this is
+ * a best-effort optimizer and the result isn't guaranteed to be optimal.
+ * The result is never worse under the model than [`determine_params`]'s,
+ * as its parameters are among the candidates.
+ */
+pub fn auto_tune_params(
+    programs: &SearchPrograms,
+    plaintext_constraint: PlainModulusConstraint,
+    security_level: SecurityLevel,
+    noise_margin_bits: u32,
+    scheme_type: SchemeType,
+    target: TuningTarget,
+    limits: &SearchLimits,
+) -> Result<Params> {
+    check_constraint_satisfiable(plaintext_constraint)?;
+
+    let mut budget = SearchBudget::new(limits);
+
+    let mut best: Option<((f64, f64), Params, Vec<FheProgram>)> = None;
+
+    for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
+        let plaintext_modulus = match plaintext_constraint_to_modulus(plaintext_constraint, i) {
+            Ok(v) => v,
+            Err(_) => {
+                continue;
+            }
+        };
+
+        let coeff = CoefficientModulus::bfv_default(*n, security_level).unwrap();
+
+        let make_params = |chain_len: usize| Params {
+            coeff_modulus: coeff[..chain_len].iter().map(|v| v.value()).collect(),
+            lattice_dimension: *n,
+            plain_modulus: plaintext_modulus.value(),
+            security_level,
+            scheme_type,
+        };
+
+        // Every cost grows with the lattice dimension, so once the shortest
+        // chain here can't beat the best candidate, no larger dimension can
+        // either. A program's structure generally doesn't depend on the
+        // parameters, so bound its cost using the best candidate's compilation.
+        if let Some((best_cost, _, best_irs)) = &best {
+            let lower_bound =
+                estimate_cost(programs.fhe_program_fns, best_irs, &make_params(1), target);
+
+            if lower_bound.0 > best_cost.0 {
+                break;
+            }
+        }
+
+        for chain_len in 1..=coeff.len() {
+            let params = make_params(chain_len);
+
+            trace!(
+                "Auto-tune trying \\lambda={:#?} p={} n={} c=default(\\lambda, n)[..{}].",
+                security_level,
+                plaintext_modulus.value(),
+                n,
+                chain_len
+            );
+
+            budget.next_candidate(best.as_ref().map(|(_, p, _)| p))?;
+
+            let irs = match compile_if_feasible(programs, &params, noise_margin_bits)? {
+                Some(irs) => irs,
+                None => continue,
             };
 
-            let mut chain_noise_level = 0f64;
-
-            for _ in 0..program.chain_count() {
-                let noise_targets = ir
-                    .graph
-                    .node_weights()
-                    .filter(|n| {
-                        matches!(
-                            n.operation,
-                            Operation::InputCiphertext(_) | Operation::InputPlaintext(_)
-                        )
-                    })
-                    .map(|n| match n.operation {
-                        Operation::InputCiphertext(_) => {
-                            if chain_noise_level == 0f64 {
-                                TargetNoiseLevel::Fresh
-                            } else {
-                                TargetNoiseLevel::InvariantNoise(chain_noise_level)
-                            }
-                        }
-                        Operation::InputPlaintext(_) => TargetNoiseLevel::NotApplicable,
-                        _ => unreachable!(),
-                    })
-                    .collect::<Vec<TargetNoiseLevel>>();
-
-                let model = match MeasuredModel::new(&ir, &params, &noise_targets) {
-                    Ok(v) => v,
-                    Err(_) => {
-                        trace!(
-                            "Failed to construct noise model for {} with lattice_dimension={}",
-                            program.name(),
-                            n
-                        );
-                        continue 'params_loop;
-                    }
-                };
-
-                let output_noises = predict_noise(&model, &ir);
-
-                let target_noise = noise_budget_to_noise(noise_margin_bits as f64);
-
-                for output_noise in output_noises {
-                    if output_noise > target_noise {
-                        trace!(
-                            "Failed to meet noise constraints with lattice dimension {} for program {}",
-                            n,
-                            program.name()
-                        );
-                        continue 'params_loop;
-                    } else if output_noise > chain_noise_level {
-                        chain_noise_level = output_noise
-                    }
-                }
+            let cost = estimate_cost(programs.fhe_program_fns, &irs, &params, target);
+
+            trace!(
+                "Auto-tune candidate n={} chain_len={} cost={:?}",
+                n,
+                chain_len,
+                cost
+            );
+
+            let is_better = match &best {
+                Some((best_cost, _, _)) => cost < *best_cost,
+                None => true,
+            };
+
+            if is_better {
+                best = Some((cost, params, irs));
             }
 
-            debug!("Using params lattice_dimension={} and ={:#?}", n, coeff);
+            // Longer chains at this dimension only cost more.
+            break;
         }
+    }
+
+    match best {
+        Some((cost, params, _)) => {
+            debug!(
+                "Auto-tune chose lattice_dimension={} chain_len={} with cost {:?}",
+                params.lattice_dimension,
+                params.coeff_modulus.len(),
+                cost
+            );
 
-        return Ok(params);
+            Ok(params)
+        }
+        None => Err(Error::NoParams),
     }
+}
 
-    Err(Error::NoParams)
+#[derive(Debug, Clone)]
+/**
+ * Parameters under which every FHE program meets the noise constraints.
+ * See [`rank_params`](crate::GenericCompiler::rank_params).
+ */
+pub struct ParamsCandidate {
+    /**
+     * The candidate parameters.
+     */
+    pub params: Params,
+
+    /**
+     * The predicted noise budgets of each FHE program under
+     * [`params`](Self::params), in the order the programs were given to
+     * the compiler.
+     */
+    pub noise_reports: Vec<NoiseReport>,
+}
+
+/**
+ * Compares two [`ParamsCandidate`]s, returning [`Ordering::Less`] if the
+ * first is preferable.
+ */
+pub type ParamsRanking = dyn Fn(&ParamsCandidate, &ParamsCandidate) -> Ordering;
+
+/**
+ * Compiles the FHE programs under every lattice dimension and prefix of
+ * SEAL's default coefficient modulus chain that satisfy the constraints
+ * and returns the best candidate under `ranking`.
+ *
+ * # Remarks
+ * Unlike [`auto_tune_params`], this can't prune the search since it
+ * knows nothing about `ranking`, so it considers every candidate. Among
+ * equally ranked candidates, it prefers the one found first, i.e. the
+ * smallest lattice dimension and shortest chain.
+ */
+pub fn rank_params(
+    programs: &SearchPrograms,
+    plaintext_constraint: PlainModulusConstraint,
+    security_level: SecurityLevel,
+    noise_margin_bits: u32,
+    scheme_type: SchemeType,
+    ranking: &ParamsRanking,
+    limits: &SearchLimits,
+) -> Result<Params> {
+    check_constraint_satisfiable(plaintext_constraint)?;
+
+    let mut budget = SearchBudget::new(limits);
+
+    let mut best: Option<ParamsCandidate> = None;
+
+    for (i, n) in LATTICE_DIMENSIONS.iter().enumerate() {
+        let plaintext_modulus = match plaintext_constraint_to_modulus(plaintext_constraint, i) {
+            Ok(v) => v,
+            Err(_) => {
+                continue;
+            }
+        };
+
+        let coeff = CoefficientModulus::bfv_default(*n, security_level).unwrap();
+
+        for chain_len in 1..=coeff.len() {
+            let params = Params {
+                coeff_modulus: coeff[..chain_len].iter().map(|v| v.value()).collect(),
+                lattice_dimension: *n,
+                plain_modulus: plaintext_modulus.value(),
+                security_level,
+                scheme_type,
+            };
+
+            budget.next_candidate(best.as_ref().map(|b| &b.params))?;
+
+            let irs = match compile_if_feasible(programs, &params, noise_margin_bits)? {
+                Some(irs) => irs,
+                None => continue,
+            };
+
+            let candidate = ParamsCandidate {
+                noise_reports: irs.iter().map(|ir| NoiseReport::new(ir, &params)).collect(),
+                params,
+            };
+
+            trace!(
+                "Ranking candidate n={} chain_len={}",
+                n,
+                candidate.params.coeff_modulus.len()
+            );
+
+            let is_better = match &best {
+                Some(b) => ranking(&candidate, b) == Ordering::Less,
+                None => true,
+            };
+
+            if is_better {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    match best {
+        Some(b) => {
+            debug!(
+                "Ranking chose lattice_dimension={} chain_len={}",
+                b.params.lattice_dimension,
+                b.params.coeff_modulus.len()
+            );
+
+            Ok(b.params)
+        }
+        None => Err(Error::NoParams),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_test_primality() {
+        let naive = |x: u64| x >= 2 && (2..).take_while(|d| d * d <= x).all(|d| x % d != 0);
+
+        for x in 0..10_000 {
+            assert_eq!(is_prime(x), naive(x));
+        }
+
+        assert!(is_prime(1_152_921_504_606_830_593));
+
+        // A strong pseudoprime to bases 2, 3, 5, and 7.
+        assert!(!is_prime(3_215_031_751));
+    }
+
+    #[test]
+    fn batching_modulus_is_smallest_ntt_friendly_prime() {
+        assert_eq!(smallest_batching_prime(1024, 14), Some(12289));
+        assert_eq!(smallest_batching_prime(4096, 16), Some(40961));
+        assert_eq!(smallest_batching_prime(32768, 17), Some(65537));
+
+        let p = smallest_batching_prime(8192, 60).unwrap();
+        assert!(p >= 1 << 59);
+        assert_eq!(p % (2 * 8192), 1);
+
+        assert_eq!(smallest_batching_prime(1024, 61), None);
+
+        assert_eq!(
+            check_constraint_satisfiable(PlainModulusConstraint::BatchingMinimum(61)),
+            Err(Error::UnsatisfiableConstraint)
+        );
+        assert!(check_constraint_satisfiable(PlainModulusConstraint::BatchingMinimum(20)).is_ok());
+    }
 }
@@ -1,10 +1,10 @@
 use crate::{
-    fhe::{with_fhe_ctx, FheContextOps, Literal},
+    fhe::{record_build_error, with_fhe_ctx, FheContextOps, Literal},
     types::{
         intern::{Cipher, FheProgramNode},
         ops::*,
-        BfvType, FheType, LaneCount, NumCiphertexts, SwapRows, TryFromPlaintext, TryIntoPlaintext,
-        Type, TypeName, TypeNameInstance, Version,
+        BfvType, FheType, LaneCount, NumCiphertexts, Rotate, SwapRows, TryFromPlaintext,
+        TryIntoPlaintext, Type, TypeName, TypeNameInstance, Version,
     },
     FheProgramInputTrait, InnerPlaintext, Params, Plaintext, WithContext,
 };
@@ -53,6 +53,30 @@ use sunscreen_runtime::{Error as RuntimeError, Result as RuntimeResult};
  *   For example, `[0, 1, 2, 3; 4, 5, 6, 7] >> 1` yields `[3, 0, 1, 2; 7, 4, 5, 6]`.
  * * `x.swap_rows()` swaps the rows. For example, `[0, 1, 2, 3; 4, 5, 6, 7].swap_rows()` yields `[4, 5, 6, 7; 0, 1, 2, 3]`.
  *
+ * The [`Rotate`] trait additionally exposes `<<` and `>>` as
+ * `x.rotate_left(n)` and `x.rotate_right(n)`.
+ *
+ * These operations also work on plaintext `Batched` values. Plaintext
+ * rotations don't require Galois keys and cost far less than their
+ * ciphertext counterparts. Rotating a literal folds during compilation.
+ *
+ * For example, the following sums the lanes of each row in
+ * `log2(LANES)` rotations and additions, leaving the sum in every lane:
+ *
+ * ```rust
+ * # use sunscreen::{fhe_program, types::{bfv::Batched, Cipher, Rotate}};
+ * #[fhe_program(scheme = "bfv")]
+ * fn dot(a: Cipher<Batched<4>>, b: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+ *     let mut x = a * b;
+ *
+ *     for i in [2, 1] {
+ *         x = x + x.rotate_left(i);
+ *     }
+ *
+ *     x
+ * }
+ * ```
+ *
  * # Performance
  * The BFV scheme is parameterized by a number of values. Generally,
  * the polynomial degree has primacy in determining execution time.
@@ -492,6 +516,18 @@ impl<const LANES: usize> SwapRows for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> Rotate for Batched<LANES> {
+    type Output = Self;
+
+    fn rotate_left(self, n: u64) -> Self::Output {
+        self << n
+    }
+
+    fn rotate_right(self, n: u64) -> Self::Output {
+        self >> n
+    }
+}
+
 impl<const LANES: usize> Index<(usize, usize)> for Batched<LANES> {
     type Output = i64;
 
@@ -554,6 +590,38 @@ impl<const LANES: usize> GraphCipherMul for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> GraphCipherPlainAdd for Batched<LANES> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_cipher_plain_add(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Self::Right>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_addition_plaintext(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const LANES: usize> GraphCipherPlainMul for Batched<LANES> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_cipher_plain_mul(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Self::Right>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication_plaintext(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
 impl<const LANES: usize> GraphCipherInsert for Batched<LANES> {
     type Lit = i64;
     type Val = Self;
@@ -623,6 +691,38 @@ impl<const LANES: usize> GraphCipherRotateRight for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> GraphPlainSwapRows for Batched<LANES> {
+    fn graph_plain_swap_rows(x: FheProgramNode<Self>) -> FheProgramNode<Self> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_swap_rows_plaintext(x.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const LANES: usize> GraphPlainRotateLeft for Batched<LANES> {
+    fn graph_plain_rotate_left(x: FheProgramNode<Self>, y: u64) -> FheProgramNode<Self> {
+        with_fhe_ctx(|ctx| {
+            let y = ctx.add_literal(Literal::U64(y));
+            let n = ctx.add_rotate_left_plaintext(x.ids[0], y);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const LANES: usize> GraphPlainRotateRight for Batched<LANES> {
+    fn graph_plain_rotate_right(x: FheProgramNode<Self>, y: u64) -> FheProgramNode<Self> {
+        with_fhe_ctx(|ctx| {
+            let y = ctx.add_literal(Literal::U64(y));
+            let n = ctx.add_rotate_right_plaintext(x.ids[0], y);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
 impl<const LANES: usize> GraphCipherNeg for Batched<LANES> {
     type Val = Self;
 
@@ -635,6 +735,129 @@ impl<const LANES: usize> GraphCipherNeg for Batched<LANES> {
     }
 }
 
+impl<const LANES: usize> GraphCipherDivByConstant for Batched<LANES> {
+    /**
+     * Multiplies each lane by the inverse of `d` modulo the plaintext
+     * modulus.
+     *
+     * # Remarks
+     * The result equals the quotient only in lanes whose values `d` evenly
+     * divides. Other lanes contain the value times the modular inverse,
+     * which generally looks like garbage.
+     *
+     * If `d` isn't invertible modulo the plaintext modulus, building the
+     * FHE program fails with [`Error::NotInvertible`](crate::Error::NotInvertible).
+     */
+    fn graph_cipher_div_by_constant(
+        x: FheProgramNode<Cipher<Self>>,
+        d: i64,
+    ) -> FheProgramNode<Cipher<Self>> {
+        let plain_modulus = with_fhe_ctx(|ctx| ctx.data.plain_modulus);
+
+        let Some(inverse) = mod_inverse(d, plain_modulus) else {
+            record_build_error(crate::Error::NotInvertible {
+                value: d,
+                plain_modulus,
+            });
+
+            return x;
+        };
+
+        Self::graph_cipher_const_mul(x, inverse as i64)
+    }
+}
+
+impl<const LANES: usize> FheProgramNode<Cipher<Batched<LANES>>> {
+    /**
+     * Arithmetically shifts each lane left by `bits`, i.e. multiplies it by
+     * `2^bits` modulo the plaintext modulus.
+     *
+     * # Remarks
+     * Unlike `<<`, which rotates the lanes, this scales each lane's value.
+     * It emits a single plaintext multiplication, which costs less noise
+     * and time than multiplying by an encrypted power of two. Chained
+     * shifts fold into one multiplication when compiled.
+     *
+     * # Panics
+     * If `bits` is 63 or more.
+     */
+    pub fn shl(self, bits: u32) -> Self {
+        Batched::<LANES>::graph_cipher_const_mul(self, power_of_two(bits))
+    }
+
+    /**
+     * Arithmetically shifts each lane right by `bits`, i.e. multiplies it by
+     * the inverse of `2^bits` modulo the plaintext modulus.
+     *
+     * # Remarks
+     * As with [`div_by_constant`](crate::types::DivByConstant::div_by_constant), this
+     * only yields the quotient in lanes whose values `2^bits` evenly
+     * divides, which makes it suited to rescaling fixed-point values that
+     * were previously shifted left. It emits a single plaintext
+     * multiplication and chained shifts fold into one multiplication when
+     * compiled.
+     *
+     * If `2^bits` isn't invertible modulo the plaintext modulus (i.e. the
+     * plaintext modulus is even), building the FHE program fails with
+     * [`Error::NotInvertible`](crate::Error::NotInvertible).
+     *
+     * # Panics
+     * If `bits` is 63 or more.
+     */
+    pub fn shr(self, bits: u32) -> Self {
+        Batched::<LANES>::graph_cipher_div_by_constant(self, power_of_two(bits))
+    }
+
+    /**
+     * Computes `self - (self << amount)`, i.e. subtracts from each lane the
+     * lane `amount` places to its right in the same row.
+     *
+     * # Remarks
+     * This emits exactly one rotation and one subtraction, reusing any
+     * existing literal node for `amount`. The compiler records the
+     * rotation in
+     * [`required_rotations`](crate::CompiledFheProgram::required_rotations),
+     * so [`generate_keys_for`](crate::GenericRuntime::generate_keys_for)
+     * creates its Galois key. Chaining calls with different amounts
+     * computes differences of shifts for sliding-window computations such
+     * as convolutions.
+     */
+    pub fn rotate_sub(self, amount: u64) -> Self {
+        let rotated = Batched::<LANES>::graph_cipher_rotate_left(self, amount);
+
+        Batched::<LANES>::graph_cipher_sub(self, rotated)
+    }
+}
+
+fn power_of_two(bits: u32) -> i64 {
+    assert!(bits < 63, "Can't shift by {bits} bits");
+
+    1 << bits
+}
+
+/**
+ * Computes the inverse of `d` modulo `m` using the extended Euclidean
+ * algorithm, or `None` if `d` and `m` aren't coprime.
+ */
+fn mod_inverse(d: i64, m: u64) -> Option<u64> {
+    let m = m as i128;
+    let (mut r_0, mut r_1) = (m, (d as i128).rem_euclid(m));
+    let (mut s_0, mut s_1) = (0i128, 1i128);
+
+    while r_1 != 0 {
+        let q = r_0 / r_1;
+
+        (r_0, r_1) = (r_1, r_0 - q * r_1);
+        (s_0, s_1) = (s_1, s_0 - q * s_1);
+    }
+
+    if r_0 == 1 {
+        Some(s_0.rem_euclid(m) as u64)
+    } else {
+        None
+    }
+}
+
 impl<const LANES: usize> LaneCount for Batched<LANES> {
     fn lane_count() -> usize {
         LANES
@@ -725,4 +948,15 @@ mod tests {
 
         assert_eq!(a.swap_rows(), [[5, 6, 7, 8], [1, 2, 3, 4]].into());
     }
+
+    #[test]
+    fn can_compute_mod_inverse() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(1, 65537), Some(1));
+        assert_eq!(mod_inverse(4, 65537), Some(49153));
+        assert_eq!(mod_inverse(6, 9), None);
+        assert_eq!(mod_inverse(0, 7), None);
+        assert_eq!(mod_inverse(14, 7), None);
+    }
 }
@@ -0,0 +1,287 @@
+use seal_fhe::Plaintext as SealPlaintext;
+
+use crate as sunscreen;
+use crate::types::ops::GraphCipherInsert;
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        ops::{GraphCipherAnd, GraphCipherNot, GraphCipherOr, GraphCipherXor},
+        Cipher,
+    },
+};
+use crate::{
+    types::{intern::FheProgramNode, BfvType, FheType, TypeNameInstance},
+    FheProgramInputTrait, Params, TypeName as DeriveTypeName, WithContext,
+};
+
+use sunscreen_runtime::{
+    InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext, TryIntoPlaintext,
+};
+
+use std::ops::*;
+
+#[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq)]
+/**
+ * A single boolean value.
+ *
+ * # Remarks
+ * A [`Bool`] encodes `false` and `true` as the constant polynomials `0`
+ * and `1`. Encrypted [`Bool`]s only support operations that map `{0, 1}`
+ * back into `{0, 1}`:
+ * * `a & b` computes `a * b`.
+ * * `a | b` computes `a + b - a * b`.
+ * * `a ^ b` computes `a + b - 2 * a * b`.
+ * * `!a` computes `1 - a`.
+ *
+ * `&`, `|`, and `^` each consume one ciphertext multiplication, while
+ * `!` only needs plaintext arithmetic.
+ *
+ * Decrypting a [`Bool`] yields a value you can convert into a [`bool`]
+ * with `bool::from`. Decryption fails if the
+ * underlying plaintext isn't `0` or `1`, which can only happen if the
+ * ciphertext wasn't produced by the above operations.
+ */
+pub struct Bool {
+    val: bool,
+}
+
+impl NumCiphertexts for Bool {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl FheProgramInputTrait for Bool {}
+impl FheType for Bool {}
+impl BfvType for Bool {}
+
+impl std::fmt::Display for Bool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.val)
+    }
+}
+
+impl Default for Bool {
+    fn default() -> Self {
+        Self::from(false)
+    }
+}
+
+impl TryIntoPlaintext for Bool {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        let mut seal_plaintext = SealPlaintext::new()?;
+
+        seal_plaintext.resize(1);
+        seal_plaintext.set_coefficient(0, self.val as u64);
+
+        Ok(Plaintext {
+            data_type: self.type_name_instance(),
+            inner: InnerPlaintext::Seal(vec![WithContext {
+                params: params.clone(),
+                data: seal_plaintext,
+            }]),
+        })
+    }
+}
+
+impl TryFromPlaintext for Bool {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        _params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        let val = match &plaintext.inner {
+            InnerPlaintext::Seal(p) => {
+                if p.len() != 1 {
+                    return Err(sunscreen_runtime::Error::IncorrectCiphertextCount);
+                }
+
+                let len = p[0].len();
+
+                if (1..len).any(|i| p[0].get_coefficient(i) != 0) {
+                    return Err(sunscreen_runtime::Error::fhe_type_error(
+                        "Bool plaintext has non-constant terms",
+                    ));
+                }
+
+                let val = if len > 0 { p[0].get_coefficient(0) } else { 0 };
+
+                match val {
+                    0 => Self { val: false },
+                    1 => Self { val: true },
+                    _ => {
+                        return Err(sunscreen_runtime::Error::fhe_type_error(
+                            "Bool plaintext is not 0 or 1",
+                        ))
+                    }
+                }
+            }
+        };
+
+        Ok(val)
+    }
+}
+
+impl From<bool> for Bool {
+    fn from(val: bool) -> Self {
+        Self { val }
+    }
+}
+
+impl From<Bool> for bool {
+    fn from(b: Bool) -> Self {
+        b.val
+    }
+}
+
+impl BitAnd for Bool {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::Output {
+            val: self.val & rhs.val,
+        }
+    }
+}
+
+impl BitOr for Bool {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::Output {
+            val: self.val | rhs.val,
+        }
+    }
+}
+
+impl BitXor for Bool {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::Output {
+            val: self.val ^ rhs.val,
+        }
+    }
+}
+
+impl Not for Bool {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        Self::Output { val: !self.val }
+    }
+}
+
+impl GraphCipherInsert for Bool {
+    type Lit = bool;
+    type Val = Self;
+
+    fn graph_cipher_insert(lit: Self::Lit) -> FheProgramNode<Self::Val> {
+        with_fhe_ctx(|ctx| {
+            let lit = Self::from(lit).try_into_plaintext(&ctx.data).unwrap();
+            let lit = ctx.add_plaintext_literal(lit.inner);
+
+            FheProgramNode::new(&[lit])
+        })
+    }
+}
+
+impl GraphCipherAnd for Bool {
+    type Left = Bool;
+    type Right = Bool;
+
+    fn graph_cipher_and(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_multiplication(a.ids[0], b.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherOr for Bool {
+    type Left = Bool;
+    type Right = Bool;
+
+    fn graph_cipher_or(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let sum = ctx.add_addition(a.ids[0], b.ids[0]);
+            let prod = ctx.add_multiplication(a.ids[0], b.ids[0]);
+            let n = ctx.add_subtraction(sum, prod);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherXor for Bool {
+    type Left = Bool;
+    type Right = Bool;
+
+    fn graph_cipher_xor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let sum = ctx.add_addition(a.ids[0], b.ids[0]);
+            let prod = ctx.add_multiplication(a.ids[0], b.ids[0]);
+            let prod_2 = ctx.add_addition(prod, prod);
+            let n = ctx.add_subtraction(sum, prod_2);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl GraphCipherNot for Bool {
+    type Val = Bool;
+
+    fn graph_cipher_not(a: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self>> {
+        let one = Self::graph_cipher_insert(true);
+
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction_plaintext(a.ids[0], one.ids[0]);
+            let n = ctx.add_negate(n);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_and_non_fhe() {
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(Bool::from(a) & Bool::from(b), (a & b).into());
+        }
+    }
+
+    #[test]
+    fn can_or_non_fhe() {
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(Bool::from(a) | Bool::from(b), (a | b).into());
+        }
+    }
+
+    #[test]
+    fn can_xor_non_fhe() {
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(Bool::from(a) ^ Bool::from(b), (a ^ b).into());
+        }
+    }
+
+    #[test]
+    fn can_not_non_fhe() {
+        assert_eq!(!Bool::from(false), true.into());
+        assert_eq!(!Bool::from(true), false.into());
+    }
+}
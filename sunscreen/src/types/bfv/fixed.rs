@@ -0,0 +1,407 @@
+use paste::paste;
+
+use crate::{
+    fhe::{with_fhe_ctx, FheContextOps},
+    types::{
+        bfv::Fractional,
+        ops::{
+            GraphCipherAdd, GraphCipherConstAdd, GraphCipherConstMul, GraphCipherConstSub,
+            GraphCipherInsert, GraphCipherMul, GraphCipherNeg, GraphCipherPlainAdd,
+            GraphCipherPlainMul, GraphCipherPlainSub, GraphCipherSub, GraphConstCipherSub,
+            GraphPlainCipherSub,
+        },
+        Cipher,
+    },
+};
+use crate::{
+    types::{intern::FheProgramNode, BfvType, FheType, Type, Version},
+    FheProgramInputTrait, Params,
+};
+
+use sunscreen_runtime::{
+    NumCiphertexts, Plaintext, TryFromPlaintext, TryIntoPlaintext, TypeName, TypeNameInstance,
+};
+
+use std::ops::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+/**
+ * A fixed-point value with `INT` integer bits and `FRAC` fractional bits.
+ *
+ * # Remarks
+ * Converting an [`f64`] into a [`Fixed`] rounds it to the nearest multiple
+ * of `2^-FRAC`. This makes encoding deterministic and, unlike
+ * [`Fractional`], keeps the number of nonzero plaintext coefficients
+ * bounded by `INT + FRAC` regardless of how many mantissa bits the [`f64`]
+ * uses. Since addition and multiplication are carryless, fewer digits mean
+ * less growth towards the `plain_modulus`.
+ *
+ * ## Representation
+ * A [`Fixed`] uses the same encoding as [`Fractional<INT>`](Fractional):
+ * integer digits occupy the low order coefficients and fractional digits
+ * wrap around to the high order coefficients with negated sign. As such,
+ * addition, subtraction and multiplication each take exactly one
+ * operation and require no rescaling.
+ *
+ * Multiplying two values doubles the number of fractional bits. The
+ * product of values with `FRAC` fractional bits is exact and decodes
+ * correctly, but has up to `2 * FRAC` fractional bits. In general, `k`
+ * chained multiplications produce up to `(k + 1) * FRAC` fractional bits,
+ * which must stay below `poly_degree - INT` to avoid overflowing into the
+ * integer digits. Decrypting and re-encrypting a value rounds it back to
+ * `FRAC` bits.
+ *
+ * ## Limitations
+ * Encoding fails if the value is NaN, infinite, or its magnitude is at
+ * least `2^INT`. It also fails if `INT + FRAC` exceeds the lattice
+ * dimension.
+ *
+ * Decryption returns the exact encoded value, subject to [`f64`]'s 53-bit
+ * mantissa.
+ */
+pub struct Fixed<const INT: usize, const FRAC: usize> {
+    val: f64,
+}
+
+impl<const INT: usize, const FRAC: usize> std::ops::Deref for Fixed<INT, FRAC> {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> NumCiphertexts for Fixed<INT, FRAC> {
+    const NUM_CIPHERTEXTS: usize = 1;
+}
+
+impl<const INT: usize, const FRAC: usize> FheProgramInputTrait for Fixed<INT, FRAC> {}
+
+impl<const INT: usize, const FRAC: usize> Default for Fixed<INT, FRAC> {
+    fn default() -> Self {
+        Self::from(0.0)
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> TypeName for Fixed<INT, FRAC> {
+    fn type_name() -> Type {
+        let version = env!("CARGO_PKG_VERSION");
+
+        Type {
+            name: format!("sunscreen::types::Fixed<{}, {}>", INT, FRAC),
+            version: Version::parse(version).expect("Crate version is not a valid semver"),
+            is_encrypted: false,
+        }
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> TypeNameInstance for Fixed<INT, FRAC> {
+    fn type_name_instance(&self) -> Type {
+        Self::type_name()
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> FheType for Fixed<INT, FRAC> {}
+impl<const INT: usize, const FRAC: usize> BfvType for Fixed<INT, FRAC> {}
+
+macro_rules! impl_graph_cipher_op {
+    ($(($op:ident, $op_noun:ident)),+) => {
+        $(
+            paste! {
+                impl<const INT: usize, const FRAC: usize> [<GraphCipher $op>] for Fixed<INT, FRAC> {
+                    type Left = Self;
+                    type Right = Self;
+
+                    fn [<graph_cipher_ $op:lower>](
+                        a: FheProgramNode<Cipher<Self::Left>>,
+                        b: FheProgramNode<Cipher<Self::Right>>,
+                    ) -> FheProgramNode<Cipher<Self::Left>> {
+                        with_fhe_ctx(|ctx| {
+                            let n = ctx.[<add_ $op_noun>](a.ids[0], b.ids[0]);
+
+                            FheProgramNode::new(&[n])
+                        })
+                    }
+                }
+
+                impl<const INT: usize, const FRAC: usize> [<GraphCipherPlain $op>] for Fixed<INT, FRAC> {
+                    type Left = Self;
+                    type Right = Self;
+
+                    fn [<graph_cipher_plain_ $op:lower>](
+                        a: FheProgramNode<Cipher<Self::Left>>,
+                        b: FheProgramNode<Self::Right>,
+                    ) -> FheProgramNode<Cipher<Self::Left>> {
+                        with_fhe_ctx(|ctx| {
+                            let n = ctx.[<add_ $op_noun _plaintext>](a.ids[0], b.ids[0]);
+
+                            FheProgramNode::new(&[n])
+                        })
+                    }
+                }
+
+                impl<const INT: usize, const FRAC: usize> [<GraphCipherConst $op>] for Fixed<INT, FRAC> {
+                    type Left = Self;
+                    type Right = f64;
+
+                    fn [<graph_cipher_const_ $op:lower>](
+                        a: FheProgramNode<Cipher<Self::Left>>,
+                        b: f64,
+                    ) -> FheProgramNode<Cipher<Self::Left>> {
+                        let lit = Self::graph_cipher_insert(b);
+                        with_fhe_ctx(|ctx| {
+                            let n = ctx.[<add_ $op_noun _plaintext>](a.ids[0], lit.ids[0]);
+
+                            FheProgramNode::new(&[n])
+                        })
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_graph_cipher_op! {
+    (Add, addition),
+    (Sub, subtraction),
+    (Mul, multiplication)
+}
+
+impl<const INT: usize, const FRAC: usize> GraphCipherInsert for Fixed<INT, FRAC> {
+    type Lit = f64;
+    type Val = Self;
+
+    fn graph_cipher_insert(lit: Self::Lit) -> FheProgramNode<Self::Val> {
+        with_fhe_ctx(|ctx| {
+            let lit = Self::from(lit).try_into_plaintext(&ctx.data).unwrap();
+            let lit = ctx.add_plaintext_literal(lit.inner);
+
+            FheProgramNode::new(&[lit])
+        })
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> GraphPlainCipherSub for Fixed<INT, FRAC> {
+    type Left = Self;
+    type Right = Self;
+
+    fn graph_plain_cipher_sub(
+        a: FheProgramNode<Self::Left>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction_plaintext(b.ids[0], a.ids[0]);
+            let n = ctx.add_negate(n);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> GraphConstCipherSub for Fixed<INT, FRAC> {
+    type Left = f64;
+    type Right = Self;
+
+    fn graph_const_cipher_sub(
+        a: Self::Left,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Right>> {
+        let lit = Self::graph_cipher_insert(a);
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_subtraction_plaintext(b.ids[0], lit.ids[0]);
+            let n = ctx.add_negate(n);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> GraphCipherNeg for Fixed<INT, FRAC> {
+    type Val = Self;
+
+    fn graph_cipher_neg(a: FheProgramNode<Cipher<Self>>) -> FheProgramNode<Cipher<Self::Val>> {
+        with_fhe_ctx(|ctx| {
+            let n = ctx.add_negate(a.ids[0]);
+
+            FheProgramNode::new(&[n])
+        })
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> TryIntoPlaintext for Fixed<INT, FRAC> {
+    fn try_into_plaintext(
+        &self,
+        params: &Params,
+    ) -> std::result::Result<Plaintext, sunscreen_runtime::Error> {
+        if self.val.is_nan() {
+            return Err(sunscreen_runtime::Error::fhe_type_error("Value is NaN."));
+        }
+
+        if self.val.is_infinite() {
+            return Err(sunscreen_runtime::Error::fhe_type_error(
+                "Value is infinite.",
+            ));
+        }
+
+        if INT + FRAC > params.lattice_dimension as usize {
+            return Err(sunscreen_runtime::Error::fhe_type_error(
+                "INT + FRAC exceeds the lattice dimension.",
+            ));
+        }
+
+        if self.val.abs() >= (INT as f64).exp2() {
+            return Err(sunscreen_runtime::Error::fhe_type_error("Out of range"));
+        }
+
+        let mut plaintext = Fractional::<INT>::from(self.val).try_into_plaintext(params)?;
+        plaintext.data_type = self.type_name_instance();
+
+        Ok(plaintext)
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> TryFromPlaintext for Fixed<INT, FRAC> {
+    fn try_from_plaintext(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<Self, sunscreen_runtime::Error> {
+        let val = Fractional::<INT>::try_from_plaintext(plaintext, params)?;
+
+        Ok(Self { val: *val })
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> From<f64> for Fixed<INT, FRAC> {
+    fn from(val: f64) -> Self {
+        let scale = (FRAC as f64).exp2();
+
+        Self {
+            val: (val * scale).round() / scale,
+        }
+    }
+}
+
+impl<const INT: usize, const FRAC: usize> From<Fixed<INT, FRAC>> for f64 {
+    fn from(fixed: Fixed<INT, FRAC>) -> Self {
+        fixed.val
+    }
+}
+
+macro_rules! impl_std_op {
+    ($($op:ident),+) => {
+        $(
+            paste! {
+                impl<const INT: usize, const FRAC: usize> $op for Fixed<INT, FRAC> {
+                    type Output = Self;
+
+                    fn [<$op:lower>](self, rhs: Self) -> Self {
+                        Self {
+                            val: self.val.[<$op:lower>](rhs.val),
+                        }
+                    }
+                }
+
+                impl<const INT: usize, const FRAC: usize> $op<f64> for Fixed<INT, FRAC> {
+                    type Output = Self;
+
+                    fn [<$op:lower>](self, rhs: f64) -> Self {
+                        self.[<$op:lower>](Self::from(rhs))
+                    }
+                }
+
+                impl<const INT: usize, const FRAC: usize> $op<Fixed<INT, FRAC>> for f64 {
+                    type Output = Fixed<INT, FRAC>;
+
+                    fn [<$op:lower>](self, rhs: Fixed<INT, FRAC>) -> Self::Output {
+                        Fixed::from(self).[<$op:lower>](rhs)
+                    }
+                }
+            }
+        )+
+    };
+}
+
+// Like their FHE counterparts, these don't round results, so products
+// carry up to twice as many fractional bits.
+impl_std_op! {
+    Add, Sub, Mul
+}
+
+impl<const INT: usize, const FRAC: usize> Neg for Fixed<INT, FRAC> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { val: -self.val }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SchemeType, SecurityLevel};
+
+    fn params() -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus: 1_000_000,
+            coeff_modulus: vec![],
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        }
+    }
+
+    #[test]
+    fn can_encode_decode_fixed() {
+        let round_trip = |x: f64, expected: f64| {
+            let f_1 = Fixed::<16, 8>::from(x);
+            let pt = f_1.try_into_plaintext(&params()).unwrap();
+            let f_2 = Fixed::<16, 8>::try_from_plaintext(&pt, &params()).unwrap();
+
+            assert_eq!(f_1, f_2);
+            assert_eq!(f64::from(f_2), expected);
+        };
+
+        round_trip(0.0, 0.0);
+        round_trip(3.25, 3.25);
+        round_trip(-3.25, -3.25);
+        round_trip(5.8125, 5.8125);
+        round_trip(65535.5, 65535.5);
+        // Rounds to the nearest 2^-8
+        round_trip(3.14, 3.140625);
+        round_trip(-3.14, -3.140625);
+        round_trip(0.001, 0.0);
+    }
+
+    #[test]
+    fn encoding_rejects_overflow() {
+        let encode = |x: f64| Fixed::<16, 8>::from(x).try_into_plaintext(&params());
+
+        assert!(encode(65536.0).is_err());
+        assert!(encode(-65536.0).is_err());
+        assert!(encode(f64::NAN).is_err());
+        assert!(encode(f64::INFINITY).is_err());
+        assert!(encode(-65535.0).is_ok());
+
+        assert!(Fixed::<4096, 8>::from(1.0)
+            .try_into_plaintext(&params())
+            .is_err());
+    }
+
+    #[test]
+    fn can_add_sub_mul_non_fhe() {
+        let a = Fixed::<16, 4>::from(3.25);
+        let b = Fixed::<16, 4>::from(1.5);
+
+        assert_eq!(a + b, 4.75.into());
+        assert_eq!(a - b, 1.75.into());
+        assert_eq!(a + 1.5, 4.75.into());
+        assert_eq!(3.25 - b, 1.75.into());
+        assert_eq!(-a, (-3.25).into());
+
+        // 0.0625 * 0.0625 needs 8 fractional bits, which isn't rounded away.
+        let c = Fixed::<16, 4>::from(0.0625);
+
+        assert_eq!(*(a * b), 4.875);
+        assert_eq!(*(c * c), 0.00390625);
+    }
+}
@@ -1,10 +1,14 @@
 mod batched;
+mod boolean;
+mod fixed;
 mod fractional;
 mod rational;
 mod signed;
 mod unsigned;
 
 pub use batched::*;
+pub use boolean::*;
+pub use fixed::*;
 pub use fractional::*;
 pub use rational::*;
 pub use signed::*;
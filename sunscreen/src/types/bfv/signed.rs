@@ -14,12 +14,12 @@ use crate::{
     },
 };
 use crate::{
-    types::{intern::FheProgramNode, BfvType, FheType, TypeNameInstance},
+    types::{intern::FheProgramNode, BfvType, FheType, Pow, TypeNameInstance},
     FheProgramInputTrait, Params, TypeName as DeriveTypeName, WithContext,
 };
 
 use sunscreen_runtime::{
-    InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext, TryIntoPlaintext,
+    DecodeError, InnerPlaintext, NumCiphertexts, Plaintext, TryFromPlaintext, TryIntoPlaintext,
 };
 
 use std::ops::*;
@@ -32,6 +32,100 @@ pub struct Signed {
     val: i64,
 }
 
+impl Signed {
+    /**
+     * The default threshold used by [`Signed::try_decode`].
+     */
+    pub const DEFAULT_OVERFLOW_THRESHOLD: f64 = 0.9;
+
+    /**
+     * Decodes the given plaintext, failing if it likely overflowed the
+     * plaintext modulus.
+     *
+     * # Remarks
+     * Equivalent to [`Signed::try_decode_with_threshold`] with
+     * [`Signed::DEFAULT_OVERFLOW_THRESHOLD`].
+     */
+    pub fn try_decode(
+        plaintext: &Plaintext,
+        params: &Params,
+    ) -> std::result::Result<i64, sunscreen_runtime::Error> {
+        Self::try_decode_with_threshold(plaintext, params, Self::DEFAULT_OVERFLOW_THRESHOLD)
+    }
+
+    /**
+     * Decodes the given plaintext, failing if it likely overflowed the
+     * plaintext modulus.
+     *
+     * # Remarks
+     * [`Signed`] stores a binary digit in each coefficient, which
+     * computation grows in either direction. Once a coefficient's
+     * magnitude exceeds half the plaintext modulus `t`, it wraps around and
+     * decodes to a valid looking but wrong value. As such, this method
+     * returns [`DecodeError::PossibleOverflow`] if any coefficient's
+     * magnitude is at least `threshold * t / 2`. Lower thresholds catch
+     * more overflows at the risk of rejecting correct results.
+     *
+     * This method also returns [`DecodeError::PossibleOverflow`] if the
+     * decoded value doesn't fit in an [`i64`].
+     *
+     * # Panics
+     * If `threshold` is not in `(0, 1]`.
+     */
+    pub fn try_decode_with_threshold(
+        plaintext: &Plaintext,
+        params: &Params,
+        threshold: f64,
+    ) -> std::result::Result<i64, sunscreen_runtime::Error> {
+        assert!(
+            threshold > 0.0 && threshold <= 1.0,
+            "Overflow threshold must be in (0, 1]."
+        );
+
+        let p = match &plaintext.inner {
+            InnerPlaintext::Seal(p) => p,
+        };
+
+        if p.len() != 1 {
+            return Err(sunscreen_runtime::Error::IncorrectCiphertextCount);
+        }
+
+        let negative_cutoff = (params.plain_modulus + 1) / 2;
+        let overflow_cutoff = ((params.plain_modulus / 2) as f64 * threshold) as u64;
+
+        let mut val: i128 = 0;
+
+        for i in 0..p[0].len() {
+            let coeff = p[0].get_coefficient(i);
+
+            let magnitude = if coeff < negative_cutoff {
+                coeff
+            } else {
+                params.plain_modulus - coeff
+            };
+
+            if magnitude == 0 {
+                continue;
+            }
+
+            if magnitude >= overflow_cutoff || i >= 64 {
+                return Err(DecodeError::PossibleOverflow.into());
+            }
+
+            let digit = (magnitude as i128) << i;
+
+            val = if coeff < negative_cutoff {
+                val.checked_add(digit)
+            } else {
+                val.checked_sub(digit)
+            }
+            .ok_or(DecodeError::PossibleOverflow)?;
+        }
+
+        i64::try_from(val).map_err(|_| DecodeError::PossibleOverflow.into())
+    }
+}
+
 impl NumCiphertexts for Signed {
     const NUM_CIPHERTEXTS: usize = 1;
 }
@@ -224,6 +318,16 @@ impl Mul<Signed> for i64 {
     }
 }
 
+impl Pow for Signed {
+    type Output = Self;
+
+    fn pow(self, exp: u32) -> Self::Output {
+        Self {
+            val: self.val.pow(exp),
+        }
+    }
+}
+
 impl Sub for Signed {
     type Output = Self;
 
@@ -366,8 +470,7 @@ impl GraphPlainCipherSub for Signed {
         b: FheProgramNode<Cipher<Self::Right>>,
     ) -> FheProgramNode<Cipher<Self::Left>> {
         with_fhe_ctx(|ctx| {
-            let n = ctx.add_subtraction_plaintext(b.ids[0], a.ids[0]);
-            let n = ctx.add_negate(n);
+            let n = ctx.add_plaintext_subtraction(a.ids[0], b.ids[0]);
 
             FheProgramNode::new(&[n])
         })
@@ -400,8 +503,7 @@ impl GraphConstCipherSub for Signed {
     ) -> FheProgramNode<Cipher<Self::Right>> {
         let lit = Self::graph_cipher_insert(a);
         with_fhe_ctx(|ctx| {
-            let n = ctx.add_subtraction_plaintext(b.ids[0], lit.ids[0]);
-            let n = ctx.add_negate(n);
+            let n = ctx.add_plaintext_subtraction(lit.ids[0], b.ids[0]);
 
             FheProgramNode::new(&[n])
         })
@@ -468,6 +570,31 @@ impl GraphCipherPlainMul for Signed {
     }
 }
 
+/**
+ * Computes `cond ? a : b`, i.e. `cond * a + (1 - cond) * b`, in an FHE
+ * program.
+ *
+ * # Remarks
+ * `cond` must encrypt 0 or 1. Nothing checks this, and any other value
+ * produces a meaningless combination of `a` and `b`.
+ *
+ * This lowers to the equivalent `b + cond * (a - b)`, which costs one
+ * ciphertext multiplication rather than two.
+ */
+pub fn select(
+    cond: FheProgramNode<Cipher<Signed>>,
+    a: FheProgramNode<Cipher<Signed>>,
+    b: FheProgramNode<Cipher<Signed>>,
+) -> FheProgramNode<Cipher<Signed>> {
+    with_fhe_ctx(|ctx| {
+        let diff = ctx.add_subtraction(a.ids[0], b.ids[0]);
+        let swap = ctx.add_multiplication(cond.ids[0], diff);
+        let n = ctx.add_addition(b.ids[0], swap);
+
+        FheProgramNode::new(&[n])
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -508,4 +635,65 @@ mod tests {
 
         assert_eq!(-a, (-5).into());
     }
+
+    #[test]
+    fn try_decode_detects_overflow() {
+        let params = Params {
+            lattice_dimension: 1024,
+            plain_modulus: 1024,
+            coeff_modulus: vec![1, 2, 3, 4],
+            security_level: crate::SecurityLevel::TC128,
+            scheme_type: crate::SchemeType::Bfv,
+        };
+
+        let plaintext = |coeffs: &[u64]| {
+            let mut data = SealPlaintext::new().unwrap();
+            data.resize(coeffs.len());
+
+            for (i, c) in coeffs.iter().enumerate() {
+                data.set_coefficient(i, *c);
+            }
+
+            Plaintext {
+                data_type: Signed::default().type_name_instance(),
+                inner: InnerPlaintext::Seal(vec![WithContext {
+                    params: params.clone(),
+                    data,
+                }]),
+            }
+        };
+
+        let is_overflow = |r: std::result::Result<i64, sunscreen_runtime::Error>| {
+            matches!(
+                r,
+                Err(sunscreen_runtime::Error::DecodeError(
+                    DecodeError::PossibleOverflow
+                ))
+            )
+        };
+
+        let encoded = Signed::from(-42).try_into_plaintext(&params).unwrap();
+        assert_eq!(Signed::try_decode(&encoded, &params).unwrap(), -42);
+
+        // 3 + 2 * -4
+        assert_eq!(
+            Signed::try_decode(&plaintext(&[3, 1020]), &params).unwrap(),
+            -5
+        );
+
+        // A coefficient near 512 likely wrapped.
+        assert!(is_overflow(Signed::try_decode(&plaintext(&[500]), &params)));
+        assert!(is_overflow(Signed::try_decode(&plaintext(&[524]), &params)));
+
+        // Lowering the threshold rejects smaller coefficients.
+        assert_eq!(
+            Signed::try_decode(&plaintext(&[200]), &params).unwrap(),
+            200
+        );
+        assert!(is_overflow(Signed::try_decode_with_threshold(
+            &plaintext(&[200]),
+            &params,
+            0.25
+        )));
+    }
 }
@@ -29,6 +29,11 @@ use crate::{
 #[derive(Debug, Clone, Copy, DeriveTypeName, PartialEq, Eq)]
 /**
  * A single unsigned integer.
+ *
+ * # Remarks
+ * Arithmetic wraps modulo `2^(64 * LIMBS)`, except that decrypting a
+ * value less than zero (e.g. the result of `0 - 1`) fails rather than
+ * wrapping.
  */
 pub struct Unsigned<const LIMBS: usize> {
     val: Uint<LIMBS>,
@@ -91,6 +96,12 @@ impl<const LIMBS: usize> TryFromPlaintext for Unsigned<LIMBS> {
                     return Err(sunscreen_runtime::Error::IncorrectCiphertextCount);
                 }
 
+                if is_negative(&p[0], params.plain_modulus) {
+                    return Err(sunscreen_runtime::Error::fhe_type_error(
+                        "Unsigned value is negative",
+                    ));
+                }
+
                 let bits = usize::min(std::mem::size_of::<Uint<LIMBS>>() * 8, p[0].len());
 
                 let negative_cutoff = (params.plain_modulus + 1) / 2;
@@ -122,6 +133,38 @@ impl<const LIMBS: usize> TryFromPlaintext for Unsigned<LIMBS> {
     }
 }
 
+/**
+ * Whether the integer encoded in `p` is negative, where coefficients of at
+ * least `(plain_modulus + 1) / 2` represent negative digits.
+ */
+fn is_negative(p: &SealPlaintext, plain_modulus: u64) -> bool {
+    let t = plain_modulus as i128;
+    let negative_cutoff = (t + 1) / 2;
+
+    // Horner's method from the highest coefficient. After visiting
+    // coefficient i, the encoded value is 2^i * (v + r), where the remaining
+    // coefficients contribute |r| < t / 2. Once |v| reaches t / 2, its sign
+    // is final, which also keeps v from overflowing.
+    let mut v = 0i128;
+
+    for i in (0..p.len()).rev() {
+        let coeff = p.get_coefficient(i) as i128;
+        let coeff = if coeff < negative_cutoff {
+            coeff
+        } else {
+            coeff - t
+        };
+
+        v = 2 * v + coeff;
+
+        if v.abs() >= t / 2 {
+            break;
+        }
+    }
+
+    v < 0
+}
+
 impl<const LIMBS: usize> From<Uint<LIMBS>> for Unsigned<LIMBS> {
     fn from(val: Uint<LIMBS>) -> Self {
         Self { val }
@@ -393,4 +436,44 @@ mod tests {
         assert_eq!(b - a, 6.into());
         assert_eq!(b - 5, 6.into());
     }
+
+    #[test]
+    fn decoding_negative_value_fails() {
+        let params = Params {
+            lattice_dimension: 1024,
+            plain_modulus: 1024,
+            coeff_modulus: vec![1, 2, 3, 4],
+            security_level: crate::SecurityLevel::TC128,
+            scheme_type: crate::SchemeType::Bfv,
+        };
+
+        let decode = |coeffs: &[u64]| {
+            let mut data = SealPlaintext::new().unwrap();
+            data.resize(coeffs.len());
+
+            for (i, c) in coeffs.iter().enumerate() {
+                data.set_coefficient(i, *c);
+            }
+
+            let plaintext = Plaintext {
+                data_type: Unsigned64::default().type_name_instance(),
+                inner: InnerPlaintext::Seal(vec![WithContext {
+                    params: params.clone(),
+                    data,
+                }]),
+            };
+
+            Unsigned64::try_from_plaintext(&plaintext, &params)
+        };
+
+        // 2 - 1
+        assert_eq!(decode(&[1023, 1]).unwrap(), Unsigned64::from(1));
+        // 1 - 2
+        assert!(decode(&[1, 1023]).is_err());
+        // -1
+        assert!(decode(&[1023]).is_err());
+        // 4 - 2 - 1 - 1
+        assert_eq!(decode(&[1023, 1023, 1]).unwrap(), Unsigned64::from(1));
+        assert_eq!(decode(&[]).unwrap(), Unsigned64::from(0));
+    }
 }
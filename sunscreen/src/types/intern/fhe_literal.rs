@@ -8,4 +8,5 @@ pub trait FheLiteral {}
 impl FheLiteral for f64 {}
 impl FheLiteral for u64 {}
 impl FheLiteral for i64 {}
+impl FheLiteral for bool {}
 impl<const LIMBS: usize> FheLiteral for Uint<LIMBS> {} // is this true?
@@ -1,8 +1,8 @@
 use crate::{
     fhe::with_fhe_ctx,
     types::{
-        intern::FheLiteral, ops::*, Cipher, FheType, LaneCount, NumCiphertexts, SwapRows, Type,
-        TypeName,
+        intern::FheLiteral, ops::*, Cipher, DivByConstant, FheType, LaneCount, NumCiphertexts, Pow,
+        Rotate, SwapRows, Type, TypeName,
     },
     INDEX_ARENA,
 };
@@ -10,7 +10,7 @@ use paste::paste;
 use petgraph::stable_graph::NodeIndex;
 use sunscreen_runtime::TypeNameInstance;
 
-use std::ops::{Add, Div, Mul, Neg, Shl, Shr, Sub};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Shl, Shr, Sub};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 /**
@@ -109,6 +109,30 @@ impl<T: NumCiphertexts, S> FheProgramNode<T, S> {
     }
 }
 
+impl<T> FheProgramNode<Cipher<T>>
+where
+    T: FheType,
+{
+    /**
+     * Computes `self * b + c`.
+     *
+     * # Remarks
+     * `b` and `c` may be any operand `*` and `+` respectively accept
+     * alongside this ciphertext, i.e. ciphertexts, plaintexts, or literals.
+     *
+     * This currently lowers to a multiplication followed by an addition
+     * and is no cheaper than writing `self * b + c`. It exists to express
+     * intent and so backends can fuse the two operations in the future
+     * without changing FHE programs.
+     */
+    pub fn mul_add<B, C>(self, b: B, c: C) -> Self
+    where
+        Self: Mul<B, Output = Self> + Add<C, Output = Self>,
+    {
+        self * b + c
+    }
+}
+
 // cipher + cipher
 impl<T> Add for FheProgramNode<Cipher<T>>
 where
@@ -463,6 +487,54 @@ where
     }
 }
 
+// cipher & cipher
+impl<T> BitAnd for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherAnd<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_and(self, rhs)
+    }
+}
+
+// cipher | cipher
+impl<T> BitOr for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherOr<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_or(self, rhs)
+    }
+}
+
+// cipher ^ cipher
+impl<T> BitXor for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherXor<Left = T, Right = T>,
+{
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        T::graph_cipher_xor(self, rhs)
+    }
+}
+
+// !cipher
+impl<T> Not for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherNot<Val = T>,
+{
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        T::graph_cipher_not(self)
+    }
+}
+
 // ciphertext
 impl<T> SwapRows for FheProgramNode<Cipher<T>>
 where
@@ -475,6 +547,90 @@ where
     }
 }
 
+impl<T> Rotate for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherRotateLeft + GraphCipherRotateRight,
+{
+    type Output = Self;
+
+    fn rotate_left(self, n: u64) -> Self::Output {
+        T::graph_cipher_rotate_left(self, n)
+    }
+
+    fn rotate_right(self, n: u64) -> Self::Output {
+        T::graph_cipher_rotate_right(self, n)
+    }
+}
+
+impl<T> DivByConstant for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherDivByConstant,
+{
+    type Output = Self;
+
+    fn div_by_constant(self, d: i64) -> Self::Output {
+        T::graph_cipher_div_by_constant(self, d)
+    }
+}
+
+impl<T> Pow for FheProgramNode<Cipher<T>>
+where
+    T: FheType + GraphCipherMul<Left = T, Right = T> + GraphCipherInsert<Val = T>,
+    T::Lit: From<u8>,
+{
+    type Output = Self;
+
+    /**
+     * Computes `self^exp` with a multiplication tree of depth
+     * `ceil(log2(exp))`.
+     *
+     * # Remarks
+     * We square repeatedly to get `self^(2^i)` for each bit `i` set in
+     * `exp`, then multiply these together, always combining the two
+     * shallowest factors so the product stays as shallow as possible.
+     * Computing `self^exp` with `exp - 1` chained multiplications would
+     * instead have depth `exp - 1`.
+     *
+     * `x^0` is the literal 1. Since SEAL rejects ciphertexts that don't
+     * depend on an encryption, the compiler turns operations on it into
+     * plaintext operations (see
+     * [`lower_literal_operands`](crate::fhe::lower_literal_operands)) and
+     * fails if the program returns it directly.
+     */
+    fn pow(self, exp: u32) -> Self::Output {
+        if exp == 0 {
+            let one = T::graph_cipher_insert(T::Lit::from(1));
+
+            return FheProgramNode::new(one.ids);
+        }
+
+        // Track each factor's multiplicative depth along with it.
+        let mut factors = vec![];
+        let mut square = self;
+
+        for i in 0..(u32::BITS - exp.leading_zeros()) {
+            if exp & (1 << i) != 0 {
+                factors.push((i, FheProgramNode::new(square.ids)));
+            }
+
+            if exp >> (i + 1) != 0 {
+                square = T::graph_cipher_mul(FheProgramNode::new(square.ids), square);
+            }
+        }
+
+        while factors.len() > 1 {
+            factors.sort_by_key(|(depth, _)| std::cmp::Reverse(*depth));
+
+            let (a_depth, a) = factors.pop().unwrap();
+            let (b_depth, b) = factors.pop().unwrap();
+
+            factors.push((u32::max(a_depth, b_depth) + 1, T::graph_cipher_mul(a, b)));
+        }
+
+        factors.pop().unwrap().1
+    }
+}
+
 impl<T> LaneCount for FheProgramNode<Cipher<T>>
 where
     T: FheType + LaneCount,
@@ -506,6 +662,55 @@ where
     }
 }
 
+// plaintext
+impl<T> SwapRows for FheProgramNode<T>
+where
+    T: FheType + GraphPlainSwapRows,
+{
+    type Output = Self;
+
+    fn swap_rows(self) -> Self::Output {
+        T::graph_plain_swap_rows(self)
+    }
+}
+
+impl<T> Rotate for FheProgramNode<T>
+where
+    T: FheType + GraphPlainRotateLeft + GraphPlainRotateRight,
+{
+    type Output = Self;
+
+    fn rotate_left(self, n: u64) -> Self::Output {
+        T::graph_plain_rotate_left(self, n)
+    }
+
+    fn rotate_right(self, n: u64) -> Self::Output {
+        T::graph_plain_rotate_right(self, n)
+    }
+}
+
+impl<T> Shl<u64> for FheProgramNode<T>
+where
+    T: FheType + GraphPlainRotateLeft,
+{
+    type Output = Self;
+
+    fn shl(self, x: u64) -> Self {
+        T::graph_plain_rotate_left(self, x)
+    }
+}
+
+impl<T> Shr<u64> for FheProgramNode<T>
+where
+    T: FheType + GraphPlainRotateRight,
+{
+    type Output = Self;
+
+    fn shr(self, x: u64) -> Self {
+        T::graph_plain_rotate_right(self, x)
+    }
+}
+
 impl<T, S> NumCiphertexts for FheProgramNode<T, S>
 where
     T: NumCiphertexts,
@@ -1,5 +1,7 @@
 use crate::fhe::{with_fhe_ctx, FheContextOps};
+use crate::types::TryIntoPlaintext;
 pub use crate::types::{intern::FheProgramNode, Cipher, FheType, NumCiphertexts, TypeName};
+use crate::{InnerPlaintext, Plaintext};
 
 /**
  * Create an input node from an Fhe Program input argument.
@@ -65,6 +67,48 @@ where
     }
 }
 
+/**
+ * Create literal nodes from a `#[constant]` Fhe Program argument.
+ */
+pub trait Constant {
+    /**
+     * Creates a new FheProgramNode holding the given encoded value as
+     * plaintext literals, so the compiler can specialize the Fhe Program
+     * graph on it.
+     *
+     * You should not call this, but rather allow the [`fhe_program`](crate::fhe_program) macro to do this on your behalf.
+     *
+     * # Panics
+     * If `plaintext` doesn't contain exactly as many plaintexts as the
+     * type has ciphertexts.
+     */
+    fn constant(plaintext: &Plaintext) -> Self;
+}
+
+impl<T> Constant for FheProgramNode<T>
+where
+    T: NumCiphertexts + TryIntoPlaintext,
+{
+    fn constant(plaintext: &Plaintext) -> Self {
+        let ids = match &plaintext.inner {
+            InnerPlaintext::Seal(p) => {
+                assert_eq!(p.len(), T::NUM_CIPHERTEXTS);
+
+                // Each literal holds exactly one plaintext.
+                p.iter()
+                    .map(|p| {
+                        with_fhe_ctx(|ctx| {
+                            ctx.add_plaintext_literal(InnerPlaintext::Seal(vec![p.clone()]))
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        FheProgramNode::new(&ids)
+    }
+}
+
 #[test]
 fn can_create_inputs() {
     use crate::{
@@ -39,6 +39,9 @@
  *   Arithmetic operations semantically execute per-lane, enabling high-throughput;
  *   e.g. a single addition operation `a + b` will element-wise add the many lanes of a to the
  *   many lanes in b.
+ * * The [`Bool`](crate::types::bfv::Bool) type encodes a single boolean as `0` or `1`.
+ *   It only supports operations that keep values boolean: and (`&`), or (`|`),
+ *   xor (`^`), and not (`!`). Decrypted values convert into [`bool`].
  *
  * Type comparison:
  *
@@ -95,6 +98,67 @@ pub trait SwapRows {
     fn swap_rows(self) -> Self::Output;
 }
 
+/**
+ * A trait that allows data types to rotate their lanes. E.g.
+ * [`Batched`](crate::types::bfv::Batched)
+ *
+ * # Remarks
+ * These are equivalent to the `<<` and `>>` operators, but read more
+ * clearly in generic code.
+ */
+pub trait Rotate {
+    /**
+     * The result type. Typically, this should just be `Self`.
+     */
+    type Output;
+
+    /**
+     * Rotates each row `n` places to the left.
+     */
+    fn rotate_left(self, n: u64) -> Self::Output;
+
+    /**
+     * Rotates each row `n` places to the right.
+     */
+    fn rotate_right(self, n: u64) -> Self::Output;
+}
+
+/**
+ * A trait that allows data types to be raised to a constant power.
+ */
+pub trait Pow {
+    /**
+     * The result type. Typically, this should just be `Self`.
+     */
+    type Output;
+
+    /**
+     * Raises this value to the power `exp`.
+     */
+    fn pow(self, exp: u32) -> Self::Output;
+}
+
+/**
+ * A trait that allows data types to divide by a constant using
+ * multiplication by its inverse modulo the plaintext modulus. E.g.
+ * [`Batched`](crate::types::bfv::Batched)
+ */
+pub trait DivByConstant {
+    /**
+     * The result type. Typically, this should just be `Self`.
+     */
+    type Output;
+
+    /**
+     * Divides by `d`.
+     *
+     * # Remarks
+     * If `d` has no inverse modulo the plaintext modulus, building the FHE
+     * program fails with [`Error::NotInvertible`](crate::Error::NotInvertible).
+     */
+    fn div_by_constant(self, d: i64) -> Self::Output;
+}
+
 /**
  * On Batched types, returns the number of Batched lanes.
  */
@@ -135,3 +135,22 @@ pub trait GraphConstCipherDiv {
         b: FheProgramNode<Cipher<Self::Right>>,
     ) -> FheProgramNode<Cipher<Self::Right>>;
 }
+
+/**
+ * Called when an Fhe Program divides an encrypted value by a constant.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherDivByConstant
+where
+    Self: FheType,
+{
+    /**
+     * Process the division.
+     */
+    fn graph_cipher_div_by_constant(
+        x: FheProgramNode<Cipher<Self>>,
+        d: i64,
+    ) -> FheProgramNode<Cipher<Self>>;
+}
@@ -0,0 +1,103 @@
+use crate::types::{
+    intern::{FheProgramNode, FheType},
+    Cipher,
+};
+
+/**
+ * Called when an Fhe Program encounters a & operation on two encrypted
+ * types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherAnd {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the & operation
+     */
+    fn graph_cipher_and(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when an Fhe Program encounters a | operation on two encrypted
+ * types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherOr {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the | operation
+     */
+    fn graph_cipher_or(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when an Fhe Program encounters a ^ operation on two encrypted
+ * types.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherXor {
+    /**
+     * The type of the left operand
+     */
+    type Left: FheType;
+
+    /**
+     * The type of the right operand
+     */
+    type Right: FheType;
+
+    /**
+     * Process the ^ operation
+     */
+    fn graph_cipher_xor(
+        a: FheProgramNode<Cipher<Self::Left>>,
+        b: FheProgramNode<Cipher<Self::Right>>,
+    ) -> FheProgramNode<Cipher<Self::Left>>;
+}
+
+/**
+ * Called when the user performs logical negation (!) on a ciphertext.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphCipherNot {
+    /**
+     * The unary type.
+     */
+    type Val: FheType;
+
+    /**
+     * Logically negates the given ciphertext (e.g. !x).
+     */
+    fn graph_cipher_not(a: FheProgramNode<Cipher<Self::Val>>) -> FheProgramNode<Cipher<Self::Val>>;
+}
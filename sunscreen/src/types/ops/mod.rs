@@ -1,6 +1,7 @@
 mod add;
 mod div;
 mod insert;
+mod logic;
 mod mul;
 mod neg;
 mod rotate;
@@ -9,6 +10,7 @@ mod sub;
 pub use add::*;
 pub use div::*;
 pub use insert::*;
+pub use logic::*;
 pub use mul::*;
 pub use neg::*;
 pub use rotate::*;
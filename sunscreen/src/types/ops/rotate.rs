@@ -35,3 +35,51 @@ where
         amount: u64,
     ) -> FheProgramNode<Cipher<Self>>;
 }
+
+/**
+ * Swaps the rows of the given plaintext.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphPlainSwapRows
+where
+    Self: FheType,
+{
+    /**
+     * Swap the rows in the given plaintext.
+     */
+    fn graph_plain_swap_rows(x: FheProgramNode<Self>) -> FheProgramNode<Self>;
+}
+
+/**
+ * Rotates the rows of the given plaintext to the left.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphPlainRotateLeft
+where
+    Self: FheType,
+{
+    /**
+     * Rotate the rows in the given plaintext `amount` places to the left.
+     */
+    fn graph_plain_rotate_left(x: FheProgramNode<Self>, amount: u64) -> FheProgramNode<Self>;
+}
+
+/**
+ * Rotates the rows of the given plaintext to the right.
+ *
+ * This trait is an implementation detail of FHE program compilation;
+ * you should not directly call methods on this trait.
+ */
+pub trait GraphPlainRotateRight
+where
+    Self: FheType,
+{
+    /**
+     * Rotate the rows in the given plaintext `amount` places to the right.
+     */
+    fn graph_plain_rotate_right(x: FheProgramNode<Self>, amount: u64) -> FheProgramNode<Self>;
+}
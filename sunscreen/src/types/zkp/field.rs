@@ -18,7 +18,10 @@ use crate::types::zkp::{
 
 use crate as sunscreen;
 
-use super::{gadgets::SignedModulus, ConstrainCmpVarVar, SubVar};
+use super::{
+    gadgets::{Inverse, SignedModulus},
+    ConstrainCmpVarVar, DivVar, SubVar,
+};
 
 // Shouldn't need Clone + Copy, but there appears to be a bug in the Rust
 // compiler that prevents ProgramNode from being Copy if we don't.
@@ -194,6 +197,26 @@ impl<F: FieldSpec> MulVar for Field<F> {
     }
 }
 
+impl<F: FieldSpec> DivVar for Field<F> {
+    /**
+     * Computes `lhs / rhs` as `lhs * rhs^-1`.
+     *
+     * # Remarks
+     * The prover supplies `rhs^-1` as a hidden input and the circuit
+     * constrains `rhs * rhs^-1 == 1`. Since zero has no inverse, proving
+     * fails when `rhs` is zero.
+     */
+    fn div(lhs: ProgramNode<Self>, rhs: ProgramNode<Self>) -> ProgramNode<Self> {
+        let inverse = invoke_gadget(Inverse::new(F::FIELD_MODULUS), rhs.ids);
+
+        with_zkp_ctx(|ctx| {
+            let o = ctx.add_multiplication(lhs.ids[0], inverse[0]);
+
+            ProgramNode::new(&[o])
+        })
+    }
+}
+
 impl<F: FieldSpec> NegVar for Field<F> {
     fn neg(lhs: ProgramNode<Self>) -> ProgramNode<Self> {
         with_zkp_ctx(|ctx| {
@@ -207,9 +230,7 @@ impl<F: FieldSpec> NegVar for Field<F> {
 impl<F: FieldSpec> ConstrainEqVarVar for Field<F> {
     fn constrain_eq(lhs: ProgramNode<Self>, rhs: ProgramNode<Self>) -> ProgramNode<Self> {
         with_zkp_ctx(|ctx| {
-            let sub = ctx.add_subtraction(lhs.ids[0], rhs.ids[0]);
-
-            let constraint = ctx.add_constraint(sub, &BigInt::ZERO);
+            let constraint = ctx.add_equal_constraint(lhs.ids[0], rhs.ids[0]);
 
             ProgramNode::new(&[constraint])
         })
@@ -342,7 +363,10 @@ mod tests {
         ZkpBackend, ZkpInto,
     };
 
-    use crate::{types::zkp::ConstrainCmp, Compiler};
+    use crate::{
+        types::zkp::{ConstrainCmp, ConstrainEq},
+        Compiler,
+    };
 
     use super::*;
 
@@ -639,4 +663,48 @@ mod tests {
         test_case(-2, -1, false);
         test_case(5, 6, false);
     }
+
+    #[test]
+    fn can_divide() {
+        #[zkp_program]
+        fn div<F: FieldSpec>(x: Field<F>, y: Field<F>, #[public] q: Field<F>) {
+            (x / y).constrain_eq(q);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(div)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let program = app.get_zkp_program(div).unwrap();
+
+        type BpField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+        let test_case = |x: BpField, y: i64, q: BpField| {
+            let proof = runtime.prove(program, vec![x, BpField::from(y)], vec![q], vec![])?;
+
+            runtime.verify(program, &proof, vec![q], Vec::<ZkpProgramInput>::new())
+        };
+
+        test_case(BpField::from(42), 6, BpField::from(7)).unwrap();
+        test_case(BpField::from(-42), 6, BpField::from(-7)).unwrap();
+        test_case(BpField::from(0), 5, BpField::from(0)).unwrap();
+        assert!(test_case(BpField::from(42), 6, BpField::from(8)).is_err());
+
+        // 1 / 2 is the inverse of 2, i.e. (p + 1) / 2.
+        let half = BigInt::from(
+            BulletproofsFieldSpec::FIELD_MODULUS
+                .wrapping_add(&BigInt::ONE)
+                .shr_vartime(1),
+        );
+
+        test_case(BpField::from(1), 2, BpField::from(half)).unwrap();
+
+        // Zero has no inverse.
+        assert!(test_case(BpField::from(0), 0, BpField::from(0)).is_err());
+        assert!(test_case(BpField::from(1), 0, BpField::from(0)).is_err());
+    }
 }
@@ -53,9 +53,7 @@ impl Gadget for SignedModulus {
             // assert mq + r == x
             let t = ctx.add_multiplication(m, q);
             let res = ctx.add_addition(t, r);
-            let diff = ctx.add_subtraction(x, res);
-
-            ctx.add_constraint(diff, &BigInt::ZERO);
+            ctx.add_equal_constraint(x, res);
 
             // Compute m - 1 - r so we can later attempt a binary expansion
             let one = ctx.add_constant(&BigInt::ONE);
@@ -180,6 +178,73 @@ impl Gadget for Inverse {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Proves a value is nonzero.
+ *
+ * # Remarks
+ * The gadget takes a single input `x` and supplies its multiplicative
+ * inverse as a hidden input. The circuit constrains `x * x^-1 == 1`, which
+ * is only satisfiable when `x != 0`.
+ *
+ * Unlike [`Inverse`], this gadget has no outputs.
+ */
+pub struct NonZeroGadget {
+    field_modulus: BigInt,
+}
+
+impl NonZeroGadget {
+    /**
+     * Creates a new [`NonZeroGadget`] for the field with the given modulus.
+     *
+     * # Panics
+     * If `field_modulus` is zero.
+     */
+    pub fn new(field_modulus: BigInt) -> Self {
+        if field_modulus == BigInt::ZERO {
+            panic!("Field modulus cannot be zero.");
+        }
+
+        Self { field_modulus }
+    }
+}
+
+impl Gadget for NonZeroGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        let x = gadget_inputs[0];
+
+        if x == BigInt::ZERO {
+            return Err(ZkpError::gadget_error("Value is zero."));
+        }
+
+        Ok(vec![x.inverse_fp(&self.field_modulus)])
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        1
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        let x = gadget_inputs[0];
+        let x_inv = hidden_inputs[0];
+
+        with_zkp_ctx(|ctx| {
+            let prod = ctx.add_multiplication(x, x_inv);
+            ctx.add_constraint(prod, &BigInt::ONE);
+        });
+
+        vec![]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use sunscreen_compiler_macros::zkp_program;
@@ -289,4 +354,38 @@ mod tests {
         // Divide by zero error
         test_case(4, 0, 0, 0, false);
     }
+
+    #[test]
+    fn nonzero_gadget_works() {
+        #[zkp_program]
+        fn nonzero<F: FieldSpec>(x: Field<F>) {
+            invoke_gadget(NonZeroGadget::new(F::FIELD_MODULUS), &[x.ids[0]]);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(nonzero)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let prog = app.get_zkp_program(nonzero).unwrap();
+
+        type BpField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+        for x in [1, 47, -3] {
+            let proof = runtime
+                .prove(prog, vec![BpField::from(x)], vec![], vec![])
+                .unwrap();
+
+            runtime
+                .verify(prog, &proof, vec![], Vec::<ZkpProgramInput>::new())
+                .unwrap();
+        }
+
+        let result = runtime.prove(prog, vec![BpField::from(0)], vec![], vec![]);
+
+        assert!(result.is_err());
+    }
 }
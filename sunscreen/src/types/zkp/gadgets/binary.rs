@@ -73,13 +73,9 @@ impl Gadget for ToUInt {
                     prev_addition = ctx.add_addition(prev_addition, *i);
                 }
 
-                let sub = ctx.add_subtraction(prev_addition, val);
-
-                ctx.add_constraint(sub, &BigInt::ZERO);
+                ctx.add_equal_constraint(prev_addition, val);
             } else {
-                let sub = ctx.add_subtraction(muls[0], val);
-
-                ctx.add_constraint(sub, &BigInt::ZERO);
+                ctx.add_equal_constraint(muls[0], val);
             }
 
             hidden_inputs
@@ -103,6 +99,55 @@ impl Gadget for ToUInt {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Decomposes the input `x` into `bits` binary digits and outputs them.
+ *
+ * # Remarks
+ * The outputs are little-endian: output `i` is the digit with weight
+ * `2^i`, so the first output is the least significant bit. The circuit
+ * constrains each digit to be 0 or 1 and their weighted sum to equal `x`,
+ * which also proves `x` lies in `[0, 2^bits)`. Use the outputs to build
+ * bitwise operations; e.g. the AND of two bits is their product.
+ *
+ * `2^bits` must not exceed the backend's field modulus `p`, as the
+ * weighted sum would otherwise wrap around `p` and the digits would no
+ * longer be unique.
+ *
+ * # Panics
+ * When building the circuit if `bits` is 0 or `bits > 512`.
+ */
+pub struct ToBitsGadget {
+    /**
+     * The number of bits to decompose `x` into.
+     */
+    pub bits: usize,
+}
+
+impl Gadget for ToBitsGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        ToUInt::new(self.bits).compute_hidden_inputs(gadget_inputs)
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        assert!(self.bits > 0, "Cannot decompose into 0 bits.");
+
+        ToUInt::new(self.bits).gen_circuit(gadget_inputs, hidden_inputs)
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        self.bits
+    }
+}
+
 /**
  * Proves the given input is 0 or 1. We do this by:
  * * Constrain (a - 1) * a = 0
@@ -155,7 +200,7 @@ mod tests {
     use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
     use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
 
-    use crate::types::zkp::{Field, ToBinary};
+    use crate::types::zkp::{Field, IntoProgramNode, ProgramNode, ToBinary};
     use crate::{self as sunscreen};
     use crate::{zkp_program, Compiler};
 
@@ -233,4 +278,61 @@ mod tests {
             .verify(prog, &proof, Vec::<ZkpProgramInput>::new(), vec![])
             .unwrap();
     }
+
+    #[test]
+    fn can_compute_bitwise_and_from_bits() {
+        #[zkp_program]
+        fn and<F: FieldSpec>(a: Field<F>, b: Field<F>, #[public] expected: Field<F>) {
+            let a_bits = invoke_gadget(ToBitsGadget { bits: 4 }, a.ids);
+            let b_bits = invoke_gadget(ToBitsGadget { bits: 4 }, b.ids);
+
+            let mut result = ProgramNode::<Field<F>>::new(&[a_bits[0]])
+                * ProgramNode::<Field<F>>::new(&[b_bits[0]]);
+
+            for i in 1..4 {
+                let bit = ProgramNode::<Field<F>>::new(&[a_bits[i]])
+                    * ProgramNode::<Field<F>>::new(&[b_bits[i]]);
+
+                result = result + bit * Field::from(1u64 << i).into_program_node();
+            }
+
+            result.constrain_eq(expected);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(and)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let prog = app.get_zkp_program(and).unwrap();
+
+        type BPField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+        let test_proof = |a: u8, b: u8, expect_pass: bool| {
+            let expected = vec![BPField::from(a & b)];
+
+            let result = runtime.prove(
+                prog,
+                vec![BPField::from(a), BPField::from(b)],
+                expected.clone(),
+                vec![],
+            );
+
+            let proof = if expect_pass {
+                result.unwrap()
+            } else {
+                assert!(result.is_err());
+                return;
+            };
+
+            runtime.verify(prog, &proof, expected, vec![]).unwrap();
+        };
+
+        test_proof(0b1101, 0b1011, true);
+        test_proof(0b1111, 0b0110, true);
+        test_proof(0b10000, 0b0001, false);
+    }
 }
@@ -0,0 +1,158 @@
+use sunscreen_zkp_backend::{BigInt, Error as ZkpError, Gadget, Result as ZkpResult};
+
+use crate::zkp::{invoke_gadget, with_zkp_ctx, ZkpContextOps};
+
+use super::BoundedRangeGadget;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Proves knowledge of a nontrivial factorization `n = p * q`.
+ *
+ * # Remarks
+ * The gadget takes inputs `[n, p, q]`. Typically, `n` is a public input
+ * while `p` and `q` are private.
+ *
+ * The circuit constrains `p * q == n` and proves `p` and `q` both lie in
+ * `[2, 2^factor_bits)`. The lower bound rules out the trivial
+ * factorization `1 * n`.
+ *
+ * The product is computed in the backend's field, whose modulus we'll call
+ * `f`. The upper bound on the factors ensures `p * q < 2^(2 * factor_bits)`,
+ * so the product equals `n` over the integers only if this doesn't wrap
+ * around `f`. You must choose `factor_bits` such that
+ * `2^(2 * factor_bits) < f`. For example, Bulletproofs' field has a
+ * modulus slightly larger than `2^252`, so `factor_bits` must not exceed
+ * 126.
+ *
+ * # Panics
+ * During circuit generation, if `factor_bits` is less than 2.
+ */
+pub struct FactorizationGadget {
+    /**
+     * The maximum number of bits in either factor.
+     */
+    pub factor_bits: usize,
+}
+
+impl FactorizationGadget {
+    fn factor_range(&self) -> BoundedRangeGadget {
+        BoundedRangeGadget {
+            lo: BigInt::from(2u64),
+            hi: BigInt::ONE << self.factor_bits,
+            inclusive_hi: false,
+        }
+    }
+}
+
+impl Gadget for FactorizationGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        let n = gadget_inputs[0];
+        let p = gadget_inputs[1];
+        let q = gadget_inputs[2];
+
+        let range = self.factor_range();
+        let in_range = |x: &BigInt| range.lo <= *x && *x < range.hi;
+
+        if !in_range(&p) || !in_range(&q) {
+            return Err(ZkpError::gadget_error("Factor is out of range."));
+        }
+
+        // When factor_bits satisfies the field size requirement, this
+        // product doesn't wrap.
+        if BigInt::from(p.wrapping_mul(&q)) != n {
+            return Err(ZkpError::gadget_error("Factors don't multiply to n."));
+        }
+
+        Ok(vec![])
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        _hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        assert!(self.factor_bits >= 2);
+
+        let n = gadget_inputs[0];
+        let p = gadget_inputs[1];
+        let q = gadget_inputs[2];
+
+        with_zkp_ctx(|ctx| {
+            let product = ctx.add_multiplication(p, q);
+            ctx.add_equal_constraint(product, n);
+        });
+
+        invoke_gadget(self.factor_range(), &[p]);
+        invoke_gadget(self.factor_range(), &[q]);
+
+        vec![]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        3
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sunscreen_runtime::Runtime;
+    use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
+    use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
+
+    use crate::types::zkp::Field;
+    use crate::{self as sunscreen};
+    use crate::{zkp_program, Compiler};
+
+    use super::*;
+
+    type BPField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+    #[test]
+    fn can_prove_factorization() {
+        #[zkp_program]
+        fn factors<F: FieldSpec>(p: Field<F>, q: Field<F>, #[public] n: Field<F>) {
+            invoke_gadget(
+                FactorizationGadget { factor_bits: 16 },
+                &[n.ids[0], p.ids[0], q.ids[0]],
+            );
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(factors)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let prog = app.get_zkp_program(factors).unwrap();
+
+        let n = BPField::from(391u64);
+
+        let proof = runtime
+            .prove(
+                prog,
+                vec![BPField::from(17u64), BPField::from(23u64)],
+                vec![n],
+                vec![],
+            )
+            .unwrap();
+
+        runtime.verify(prog, &proof, vec![n], vec![]).unwrap();
+
+        for (p, q) in [(1, 391), (391, 1), (17, 24)] {
+            let result = runtime.prove(
+                prog,
+                vec![BPField::from(p as u64), BPField::from(q as u64)],
+                vec![n],
+                vec![],
+            );
+
+            assert!(result.is_err(), "{p} * {q} shouldn't prove");
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use petgraph::stable_graph::NodeIndex;
+use sunscreen_zkp_backend::{BigInt, Gadget, Result as ZkpResult};
+
+use crate::zkp::{with_zkp_ctx, ZkpContextOps};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/**
+ * Proves the weighted sum `Σ w_i * x_i` of the inputs equals a target.
+ *
+ * # Remarks
+ * The gadget takes `weights.len() + 1` inputs: the `x_i` in the same order
+ * as `weights`, followed by the target. The target may be any node,
+ * including a public input, in which case the verifier learns the sum
+ * but not the `x_i`.
+ *
+ * Weights are public constants. As arithmetic occurs in the backend's
+ * field, the sum is taken modulo the field modulus `p`, and a "negative"
+ * weight `-w` should be given as `p - w`.
+ *
+ * The circuit multiplies each input by its weight and sums the products
+ * in a balanced tree, then constrains the difference from the target to
+ * be zero. This emits `n` scalar multiplications, `n - 1` additions, and
+ * a single constraint, where `n` is the number of terms. Scaling by a
+ * constant is linear, so on R1CS backends such as Bulletproofs this adds
+ * no multiplication gates.
+ *
+ * An empty list of weights constrains the target to equal zero.
+ */
+pub struct LinearCombinationGadget {
+    /**
+     * The public weight of each term.
+     */
+    pub weights: Vec<BigInt>,
+}
+
+impl Gadget for LinearCombinationGadget {
+    fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        Ok(vec![])
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[NodeIndex],
+        _hidden_inputs: &[NodeIndex],
+    ) -> Vec<NodeIndex> {
+        let (xs, target) = gadget_inputs.split_at(self.weights.len());
+        let target = target[0];
+
+        with_zkp_ctx(|ctx| {
+            let mut terms = self
+                .weights
+                .iter()
+                .zip(xs)
+                .map(|(w, x)| {
+                    let w = ctx.add_constant(w);
+                    ctx.add_multiplication(w, *x)
+                })
+                .collect::<Vec<_>>();
+
+            // Sum adjacent pairs until one term remains, yielding a tree
+            // of depth ceil(log2(n)).
+            while terms.len() > 1 {
+                terms = terms
+                    .chunks(2)
+                    .map(|pair| match pair {
+                        [a, b] => ctx.add_addition(*a, *b),
+                        [a] => *a,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+            }
+
+            let sum = match terms.first() {
+                Some(sum) => *sum,
+                None => ctx.add_constant(&BigInt::ZERO),
+            };
+
+            ctx.add_equal_constraint(sum, target);
+        });
+
+        vec![]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        self.weights.len() + 1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sunscreen_runtime::Runtime;
+    use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
+    use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
+
+    use crate::types::zkp::Field;
+    use crate::zkp::invoke_gadget;
+    use crate::{self as sunscreen};
+    use crate::{zkp_program, Compiler};
+
+    use super::*;
+
+    type BPField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+    #[test]
+    fn can_prove_weighted_sum() {
+        #[zkp_program]
+        fn weighted_sum<F: FieldSpec>(
+            x0: Field<F>,
+            x1: Field<F>,
+            x2: Field<F>,
+            x3: Field<F>,
+            x4: Field<F>,
+            #[public] target: Field<F>,
+        ) {
+            let weights = [3u64, 5, 7, 11, 13].map(BigInt::from).to_vec();
+
+            invoke_gadget(
+                LinearCombinationGadget { weights },
+                &[
+                    x0.ids[0],
+                    x1.ids[0],
+                    x2.ids[0],
+                    x3.ids[0],
+                    x4.ids[0],
+                    target.ids[0],
+                ],
+            );
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(weighted_sum)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+        let prog = app.get_zkp_program(weighted_sum).unwrap();
+
+        let xs = [2u64, 4, 6, 8, 10].map(BPField::from).to_vec();
+
+        // 3*2 + 5*4 + 7*6 + 11*8 + 13*10
+        let target = BPField::from(286u64);
+        let wrong_target = BPField::from(287u64);
+
+        let proof = runtime
+            .prove(prog, xs.clone(), vec![target], vec![])
+            .unwrap();
+
+        runtime.verify(prog, &proof, vec![target], vec![]).unwrap();
+
+        assert!(runtime
+            .verify(prog, &proof, vec![wrong_target], vec![])
+            .is_err());
+
+        assert!(runtime.prove(prog, xs, vec![wrong_target], vec![]).is_err());
+    }
+}
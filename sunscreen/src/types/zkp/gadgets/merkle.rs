@@ -0,0 +1,279 @@
+use std::sync::Arc;
+
+use petgraph::stable_graph::NodeIndex;
+use sunscreen_zkp_backend::{BigInt, Error as ZkpError, Gadget, Result as ZkpResult};
+
+use crate::zkp::{invoke_gadget, with_zkp_ctx, ZkpContextOps};
+
+use super::AssertBinary;
+
+/**
+ * Forwards to a type-erased gadget so we can pass it to [`invoke_gadget`].
+ */
+struct DynGadget(Arc<dyn Gadget>);
+
+impl Gadget for DynGadget {
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[NodeIndex],
+        hidden_inputs: &[NodeIndex],
+    ) -> Vec<NodeIndex> {
+        self.0.gen_circuit(gadget_inputs, hidden_inputs)
+    }
+
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        self.0.compute_hidden_inputs(gadget_inputs)
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        self.0.gadget_input_count()
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        self.0.hidden_input_count()
+    }
+
+    fn public_outputs(&self) -> Vec<usize> {
+        self.0.public_outputs()
+    }
+
+    fn debug_name(&self) -> &'static str {
+        self.0.debug_name()
+    }
+}
+
+#[derive(Clone)]
+/**
+ * Proves a leaf lies in a Merkle tree of the given depth without revealing
+ * its position.
+ *
+ * The gadget inputs are, in order:
+ * * the leaf.
+ * * `depth` sibling hashes, starting with the leaf's sibling.
+ * * `depth` direction bits, where the `i`th bit is 1 if the `i`th node on
+ *   the path (starting with the leaf) is the right child of its parent and
+ *   0 if it's the left child.
+ *
+ * The gadget has a single output, the tree's root. This is a
+ * [public output](Gadget::public_outputs), so bind it to the expected root
+ * with [`GadgetOutputs::bind_public`](crate::GadgetOutputs::bind_public).
+ *
+ * # Remarks
+ * At each level, the circuit uses the direction bit `d` to order the
+ * current node `x` and its sibling `s` as
+ * `(x + d * (s - x), s - d * (s - x))` and then invokes the hash gadget on
+ * the pair. The hash gadget's hidden inputs compute the intermediate node
+ * hashes during proving. The circuit also constrains each direction bit to
+ * be 0 or 1.
+ *
+ * The hash gadget must take 2 gadget inputs (the left and right child)
+ * and its first output must be their hash. Its security determines the
+ * security of the membership proof.
+ */
+pub struct MerklePathGadget {
+    depth: usize,
+    hash: Arc<dyn Gadget>,
+}
+
+impl MerklePathGadget {
+    /**
+     * Creates a [`MerklePathGadget`] for trees of the given depth that
+     * hashes pairs of nodes with the `hash` gadget.
+     *
+     * # Panics
+     * If `hash` doesn't take exactly 2 gadget inputs.
+     */
+    pub fn new<G: Gadget>(depth: usize, hash: G) -> Self {
+        assert_eq!(
+            hash.gadget_input_count(),
+            2,
+            "{} must take 2 gadget inputs to hash Merkle tree nodes",
+            hash.debug_name()
+        );
+
+        Self {
+            depth,
+            hash: Arc::new(hash),
+        }
+    }
+
+    /**
+     * The depth of the tree, i.e. the number of hashes between a leaf and
+     * the root.
+     */
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl Gadget for MerklePathGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        let directions = &gadget_inputs[1 + self.depth..];
+
+        if directions
+            .iter()
+            .any(|d| *d != BigInt::ZERO && *d != BigInt::ONE)
+        {
+            return Err(ZkpError::gadget_error("Direction bits must be 0 or 1."));
+        }
+
+        Ok(vec![])
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[NodeIndex],
+        _hidden_inputs: &[NodeIndex],
+    ) -> Vec<NodeIndex> {
+        let (leaf, path) = gadget_inputs.split_at(1);
+        let (siblings, directions) = path.split_at(self.depth);
+
+        let mut node = leaf[0];
+
+        for (sibling, direction) in siblings.iter().zip(directions) {
+            invoke_gadget(AssertBinary, &[*direction]);
+
+            let (left, right) = with_zkp_ctx(|ctx| {
+                let diff = ctx.add_subtraction(*sibling, node);
+                let swap = ctx.add_multiplication(*direction, diff);
+
+                (
+                    ctx.add_addition(node, swap),
+                    ctx.add_subtraction(*sibling, swap),
+                )
+            });
+
+            node = invoke_gadget(DynGadget(self.hash.clone()), &[left, right])[0];
+        }
+
+        vec![node]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1 + 2 * self.depth
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        0
+    }
+
+    fn public_outputs(&self) -> Vec<usize> {
+        vec![0]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sunscreen_runtime::{Runtime, ZkpProgramInput};
+    use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
+    use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
+
+    use crate::types::zkp::Field;
+    use crate::{self as sunscreen};
+    use crate::{zkp_program, Compiler};
+
+    use super::*;
+
+    type BPField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+    /// A toy (insecure) hash computing `l + 2r + 1`.
+    struct ToyHash;
+
+    impl ToyHash {
+        fn hash(l: u64, r: u64) -> u64 {
+            l + 2 * r + 1
+        }
+    }
+
+    impl Gadget for ToyHash {
+        fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+            Ok(vec![])
+        }
+
+        fn gen_circuit(
+            &self,
+            gadget_inputs: &[NodeIndex],
+            _hidden_inputs: &[NodeIndex],
+        ) -> Vec<NodeIndex> {
+            with_zkp_ctx(|ctx| {
+                let one = ctx.add_constant(&BigInt::ONE);
+                let two = ctx.add_constant(&BigInt::from(2u32));
+
+                let r_2 = ctx.add_multiplication(gadget_inputs[1], two);
+                let sum = ctx.add_addition(gadget_inputs[0], r_2);
+
+                vec![ctx.add_addition(sum, one)]
+            })
+        }
+
+        fn gadget_input_count(&self) -> usize {
+            2
+        }
+
+        fn hidden_input_count(&self) -> usize {
+            0
+        }
+    }
+
+    #[test]
+    fn can_prove_merkle_membership() {
+        #[zkp_program]
+        fn member<F: FieldSpec>(
+            leaf: Field<F>,
+            siblings: [Field<F>; 3],
+            directions: [Field<F>; 3],
+            #[public] root: Field<F>,
+        ) {
+            let inputs = [leaf.ids, siblings.ids, directions.ids].concat();
+
+            invoke_gadget(MerklePathGadget::new(3, ToyHash), &inputs).bind_public(root.ids);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(member)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let program = app.get_zkp_program(member).unwrap();
+
+        let leaves = [3u64, 1, 4, 1, 5, 9, 2, 6];
+
+        let level_1 = leaves
+            .chunks(2)
+            .map(|x| ToyHash::hash(x[0], x[1]))
+            .collect::<Vec<_>>();
+        let level_2 = level_1
+            .chunks(2)
+            .map(|x| ToyHash::hash(x[0], x[1]))
+            .collect::<Vec<_>>();
+        let root = ToyHash::hash(level_2[0], level_2[1]);
+
+        let prove = |leaf: u64, siblings: [u64; 3], directions: [u64; 3], root: u64| {
+            let args: Vec<ZkpProgramInput> = vec![
+                BPField::from(leaf).into(),
+                siblings.map(BPField::from).into(),
+                directions.map(BPField::from).into(),
+            ];
+
+            let proof = runtime.prove(program, args, vec![BPField::from(root).into()], vec![])?;
+
+            runtime.verify(program, &proof, vec![BPField::from(root)], vec![])
+        };
+
+        // Leaf 5 is at index 4: left child, left child, right child.
+        let siblings = [9, level_1[3], level_2[0]];
+
+        prove(5, siblings, [0, 0, 1], root).unwrap();
+
+        // Wrong leaf, wrong directions, or the wrong root.
+        assert!(prove(6, siblings, [0, 0, 1], root).is_err());
+        assert!(prove(5, siblings, [1, 0, 1], root).is_err());
+        assert!(prove(5, siblings, [0, 0, 1], root + 1).is_err());
+
+        // Direction bits must be binary.
+        assert!(prove(5, siblings, [0, 0, 2], root).is_err());
+    }
+}
@@ -1,5 +1,13 @@
 mod arithmetic;
 mod binary;
+mod factorization;
+mod linear;
+mod merkle;
+mod range;
 
 pub use arithmetic::*;
 pub use binary::*;
+pub use factorization::*;
+pub use linear::*;
+pub use merkle::*;
+pub use range::*;
@@ -0,0 +1,592 @@
+use sunscreen_zkp_backend::{BigInt, Error as ZkpError, Gadget, Result as ZkpResult};
+
+use crate::zkp::{invoke_gadget, with_zkp_ctx, ZkpContextOps};
+
+use super::ToUInt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Proves the input `x` lies in the public range `[lo, hi]` (when
+ * `inclusive_hi` is true) or `[lo, hi)` (when `inclusive_hi` is false).
+ *
+ * # Remarks
+ * Let `hi'` be `hi` or `hi - 1` depending on `inclusive_hi`. The circuit
+ * proves `x - lo` and `hi' - x` both decompose into `k`-bit unsigned
+ * integers, where `k` is the number of bits in `hi' - lo`. When
+ * `lo == hi'`, the circuit instead directly constrains `x == lo`.
+ *
+ * `lo` and `hi` are interpreted as unsigned integers less than the
+ * backend's field modulus `p`. For the two checks to imply
+ * `lo <= x <= hi'`, their sum must not wrap around `p`, so you must
+ * ensure `2^(k + 1) < p`. In practice, this means the width of the range
+ * must be less than `p / 2`.
+ *
+ * An empty range (i.e. `hi < lo`, or `lo == hi` with `inclusive_hi` false)
+ * yields an unsatisfiable circuit.
+ */
+pub struct BoundedRangeGadget {
+    /**
+     * The inclusive lower bound.
+     */
+    pub lo: BigInt,
+
+    /**
+     * The upper bound.
+     */
+    pub hi: BigInt,
+
+    /**
+     * Whether `hi` itself lies in the range.
+     */
+    pub inclusive_hi: bool,
+}
+
+impl BoundedRangeGadget {
+    /**
+     * The largest value in the range, or [`None`] if the range is empty.
+     */
+    fn max(&self) -> Option<BigInt> {
+        let max = if self.inclusive_hi {
+            self.hi
+        } else if self.hi == BigInt::ZERO {
+            return None;
+        } else {
+            BigInt::from(self.hi.wrapping_sub(&BigInt::ONE))
+        };
+
+        if max < self.lo {
+            None
+        } else {
+            Some(max)
+        }
+    }
+}
+
+impl Gadget for BoundedRangeGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        let x = gadget_inputs[0];
+
+        match self.max() {
+            Some(max) if self.lo <= x && x <= max => Ok(vec![]),
+            Some(_) => Err(ZkpError::gadget_error("Value is out of range.")),
+            None => Err(ZkpError::gadget_error("Range is empty.")),
+        }
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        _hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        let x = gadget_inputs[0];
+
+        let max = match self.max() {
+            Some(max) => max,
+            None => {
+                // Constrain 1 == 0 so no prover can satisfy the circuit.
+                with_zkp_ctx(|ctx| {
+                    let one = ctx.add_constant(&BigInt::ONE);
+                    ctx.add_constraint(one, &BigInt::ZERO);
+                });
+
+                return vec![];
+            }
+        };
+
+        let width = BigInt::from(max.wrapping_sub(&self.lo));
+
+        let (x_min_lo, max_min_x) = with_zkp_ctx(|ctx| {
+            let lo = ctx.add_constant(&self.lo);
+            let max = ctx.add_constant(&max);
+
+            (ctx.add_subtraction(x, lo), ctx.add_subtraction(max, x))
+        });
+
+        if width == BigInt::ZERO {
+            with_zkp_ctx(|ctx| {
+                ctx.add_constraint(x_min_lo, &BigInt::ZERO);
+            });
+
+            return vec![];
+        }
+
+        let bits = width.bits_vartime();
+
+        // Show x >= lo
+        invoke_gadget(ToUInt::new(bits), &[x_min_lo]);
+
+        // Show x <= hi'
+        invoke_gadget(ToUInt::new(bits), &[max_min_x]);
+
+        vec![]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Proves the input `x` lies in the range `[0, 2^bits)`.
+ *
+ * # Remarks
+ * The prover decomposes `x` into `bits` binary digits by shifting and
+ * masking. The circuit constrains each digit to be 0 or 1 and their
+ * weighted sum to equal `x`. This is the same decomposition [`ToUInt`]
+ * performs, but the digits remain hidden inputs rather than gadget
+ * outputs. When `bits` is 0, the circuit instead directly constrains
+ * `x == 0`.
+ *
+ * `2^bits` must not exceed the backend's field modulus `p`, as the
+ * weighted sum would otherwise wrap around `p` and no longer imply the
+ * range.
+ *
+ * # Panics
+ * When building the circuit if `bits > 512`.
+ */
+pub struct RangeCheckGadget {
+    /**
+     * The number of bits `x` must fit in.
+     */
+    pub bits: usize,
+}
+
+impl Gadget for RangeCheckGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        if self.bits > 0 {
+            return ToUInt::new(self.bits).compute_hidden_inputs(gadget_inputs);
+        }
+
+        if gadget_inputs[0] == BigInt::ZERO {
+            Ok(vec![])
+        } else {
+            Err(ZkpError::gadget_error("Value is out of range."))
+        }
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        if self.bits > 0 {
+            ToUInt::new(self.bits).gen_circuit(gadget_inputs, hidden_inputs);
+        } else {
+            with_zkp_ctx(|ctx| {
+                ctx.add_constraint(gadget_inputs[0], &BigInt::ZERO);
+            });
+        }
+
+        vec![]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        self.bits
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Compares the inputs `a` and `b` and outputs 1 if `a < b` and 0
+ * otherwise. Constrain the output to 1 to prove `a < b`.
+ *
+ * # Remarks
+ * `a < b` holds exactly when `b - a - 1` lies in `[0, 2^bits)`. To also
+ * handle the case where it doesn't, the circuit decomposes
+ * `d = b - a - 1 + 2^bits` into `bits + 1` binary digits as in
+ * [`RangeCheckGadget`] and outputs the top digit, which is 1 exactly when
+ * the lower `bits` digits hold `b - a - 1`. If you only need to assert
+ * `a < b`, [`constrain_lt_bounded`](crate::types::zkp::ConstrainCmp::constrain_lt_bounded)
+ * needs one fewer digit.
+ *
+ * This is only sound if both `a` and `b` lie in `[0, 2^bits)`, which
+ * keeps `d` in `[0, 2^(bits + 1))`. The prover refuses to compute the
+ * digits for operands outside this range, but the circuit doesn't check
+ * it; range check the operands yourself (e.g. with [`RangeCheckGadget`])
+ * unless they're already known to fit. Additionally, `2^(bits + 1)` must
+ * not exceed the backend's field modulus.
+ *
+ * # Panics
+ * When building the circuit if `bits >= 512`.
+ */
+pub struct LessThanGadget {
+    /**
+     * The number of bits both operands fit in.
+     */
+    pub bits: usize,
+}
+
+impl Gadget for LessThanGadget {
+    fn compute_hidden_inputs(&self, gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        let (a, b) = (gadget_inputs[0], gadget_inputs[1]);
+        let offset = BigInt::from(BigInt::ONE.shl_vartime(self.bits));
+
+        if a >= offset || b >= offset {
+            return Err(ZkpError::gadget_error(&format!(
+                "Operands must fit in {} bits.",
+                self.bits
+            )));
+        }
+
+        let d = BigInt::from(
+            b.wrapping_add(&offset)
+                .wrapping_sub(&a)
+                .wrapping_sub(&BigInt::ONE),
+        );
+
+        ToUInt::new(self.bits + 1).compute_hidden_inputs(&[d])
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        let (a, b) = (gadget_inputs[0], gadget_inputs[1]);
+
+        let d = with_zkp_ctx(|ctx| {
+            let offset = BigInt::from(
+                BigInt::ONE
+                    .shl_vartime(self.bits)
+                    .wrapping_sub(&BigInt::ONE),
+            );
+            let offset = ctx.add_constant(&offset);
+
+            let b_plus_offset = ctx.add_addition(b, offset);
+
+            ctx.add_subtraction(b_plus_offset, a)
+        });
+
+        let digits = ToUInt::new(self.bits + 1).gen_circuit(&[d], hidden_inputs);
+
+        vec![digits[self.bits]]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        2
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        self.bits + 1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Proves the input `x` represents a signed integer in
+ * `[-2^(bits - 1), 2^(bits - 1))` and outputs the value reconstructed from
+ * its sign bit and magnitude.
+ *
+ * # Remarks
+ * Negative integers are represented as `p - |x|`, where `p` is the
+ * backend's field modulus. The circuit decomposes
+ * `x + 2^(bits - 1)` into `bits` binary digits as in [`ToUInt`]. The top
+ * digit is 1 exactly when `x` is non-negative, so the sign bit is its
+ * complement, and the remaining digits form a `bits - 1` bit magnitude.
+ * The output is `magnitude - sign * 2^(bits - 1)`, which the
+ * decomposition constrains to equal `x`.
+ *
+ * `2^bits` must be less than `p`, as the decomposition would otherwise
+ * wrap around `p` and no longer imply the range.
+ *
+ * # Panics
+ * When building the circuit if `bits == 0` or `bits > 512`.
+ */
+pub struct BoundedIntGadget {
+    /**
+     * The number of bits in the two's complement representation of `x`,
+     * including the sign bit.
+     */
+    pub bits: usize,
+}
+
+impl Gadget for BoundedIntGadget {
+    fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+        // The nested ToUInt computes the digits from x + 2^(bits - 1),
+        // which we can't compute here without the field modulus.
+        Ok(vec![])
+    }
+
+    fn gen_circuit(
+        &self,
+        gadget_inputs: &[petgraph::stable_graph::NodeIndex],
+        _hidden_inputs: &[petgraph::stable_graph::NodeIndex],
+    ) -> Vec<petgraph::stable_graph::NodeIndex> {
+        assert!(
+            self.bits > 0,
+            "BoundedIntGadget needs at least 1 bit for the sign."
+        );
+
+        let x = gadget_inputs[0];
+        let half = BigInt::from(BigInt::ONE.shl_vartime(self.bits - 1));
+
+        let offset_x = with_zkp_ctx(|ctx| {
+            let half = ctx.add_constant(&half);
+
+            ctx.add_addition(x, half)
+        });
+
+        let digits = invoke_gadget(ToUInt::new(self.bits), &[offset_x]);
+        let (magnitude_digits, top) = digits.split_at(self.bits - 1);
+
+        let value = with_zkp_ctx(|ctx| {
+            let mut magnitude = ctx.add_constant(&BigInt::ZERO);
+
+            for (i, d) in magnitude_digits.iter().enumerate() {
+                let weight = ctx.add_constant(&BigInt::from(BigInt::ONE.shl_vartime(i)));
+                let term = ctx.add_multiplication(*d, weight);
+
+                magnitude = ctx.add_addition(magnitude, term);
+            }
+
+            let one = ctx.add_constant(&BigInt::ONE);
+            let sign = ctx.add_subtraction(one, top[0]);
+
+            let half = ctx.add_constant(&half);
+            let negative_offset = ctx.add_multiplication(sign, half);
+
+            ctx.add_subtraction(magnitude, negative_offset)
+        });
+
+        vec![value]
+    }
+
+    fn gadget_input_count(&self) -> usize {
+        1
+    }
+
+    fn hidden_input_count(&self) -> usize {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sunscreen_runtime::{Runtime, ZkpProgramInput};
+    use sunscreen_zkp_backend::bulletproofs::BulletproofsBackend;
+    use sunscreen_zkp_backend::{FieldSpec, ZkpBackend};
+
+    use crate::types::zkp::{ConstrainEq, Field, ProgramNode};
+    use crate::{self as sunscreen};
+    use crate::{zkp_program, Compiler};
+
+    use super::*;
+
+    type BPField = Field<<BulletproofsBackend as ZkpBackend>::Field>;
+
+    /// Proves each `x` in `cases` against `[lo, hi]` or `[lo, hi)` and
+    /// checks whether proving succeeds as expected.
+    macro_rules! check_range {
+        ($lo:expr, $hi:expr, $inclusive_hi:expr, $cases:expr) => {{
+            #[zkp_program]
+            fn test<F: FieldSpec>(x: Field<F>) {
+                invoke_gadget(
+                    BoundedRangeGadget {
+                        lo: BigInt::from($lo as u64),
+                        hi: BigInt::from($hi as u64),
+                        inclusive_hi: $inclusive_hi,
+                    },
+                    x.ids,
+                );
+            }
+
+            let app = Compiler::new()
+                .zkp_backend::<BulletproofsBackend>()
+                .zkp_program(test)
+                .compile()
+                .unwrap();
+
+            let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+            let prog = app.get_zkp_program(test).unwrap();
+
+            for (x, expect_pass) in $cases {
+                let result = runtime.prove(prog, vec![BPField::from(x as u64)], vec![], vec![]);
+
+                let proof = if expect_pass {
+                    result.unwrap()
+                } else {
+                    assert!(result.is_err(), "{x} should be out of range");
+                    continue;
+                };
+
+                runtime
+                    .verify(prog, &proof, Vec::<ZkpProgramInput>::new(), vec![])
+                    .unwrap();
+            }
+        }};
+    }
+
+    /// Proves each `x` in `cases` lies in `[0, 2^bits)` and checks whether
+    /// proving succeeds as expected.
+    macro_rules! check_bits {
+        ($bits:expr, $cases:expr) => {{
+            #[zkp_program]
+            fn test<F: FieldSpec>(x: Field<F>) {
+                invoke_gadget(RangeCheckGadget { bits: $bits }, x.ids);
+            }
+
+            let app = Compiler::new()
+                .zkp_backend::<BulletproofsBackend>()
+                .zkp_program(test)
+                .compile()
+                .unwrap();
+
+            let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+            let prog = app.get_zkp_program(test).unwrap();
+
+            for (x, expect_pass) in $cases {
+                let result = runtime.prove(prog, vec![BPField::from(x as u64)], vec![], vec![]);
+
+                let proof = if expect_pass {
+                    result.unwrap()
+                } else {
+                    assert!(result.is_err(), "{x} should be out of range");
+                    continue;
+                };
+
+                runtime
+                    .verify(prog, &proof, Vec::<ZkpProgramInput>::new(), vec![])
+                    .unwrap();
+            }
+        }};
+    }
+
+    #[test]
+    fn can_prove_inclusive_range() {
+        check_range!(
+            10,
+            20,
+            true,
+            [(9, false), (10, true), (15, true), (20, true), (21, false)]
+        );
+    }
+
+    #[test]
+    fn can_prove_exclusive_range() {
+        check_range!(
+            10,
+            20,
+            false,
+            [(9, false), (10, true), (19, true), (20, false)]
+        );
+    }
+
+    #[test]
+    fn can_prove_single_value_range() {
+        check_range!(7, 7, true, [(6, false), (7, true), (8, false)]);
+    }
+
+    #[test]
+    fn empty_range_is_unsatisfiable() {
+        check_range!(7, 7, false, [(6, false), (7, false), (8, false)]);
+    }
+
+    #[test]
+    fn can_range_check_bits() {
+        check_bits!(
+            8,
+            [
+                (0, true),
+                (1, true),
+                (255, true),
+                (256, false),
+                (1000, false)
+            ]
+        );
+    }
+
+    #[test]
+    fn can_range_check_zero_bits() {
+        check_bits!(0, [(0, true), (1, false)]);
+    }
+
+    #[test]
+    fn can_compare_less_than() {
+        #[zkp_program]
+        fn less_than<F: FieldSpec>(a: Field<F>, b: Field<F>, #[public] expected: Field<F>) {
+            let lt = invoke_gadget(LessThanGadget { bits: 8 }, &[a.ids[0], b.ids[0]]);
+
+            ProgramNode::<Field<F>>::new(&lt).constrain_eq(expected);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(less_than)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let prog = app.get_zkp_program(less_than).unwrap();
+
+        let prove = |a: u64, b: u64, expected: u64| {
+            let proof = runtime.prove(
+                prog,
+                vec![BPField::from(a), BPField::from(b)],
+                vec![BPField::from(expected)],
+                vec![],
+            )?;
+
+            runtime.verify(prog, &proof, vec![BPField::from(expected)], vec![])
+        };
+
+        for (a, b) in [(0, 1), (3, 200), (254, 255)] {
+            prove(a, b, 1).unwrap();
+            assert!(prove(a, b, 0).is_err());
+        }
+
+        for (a, b) in [(0, 0), (7, 7), (200, 3), (255, 0)] {
+            prove(a, b, 0).unwrap();
+            assert!(prove(a, b, 1).is_err());
+        }
+
+        // Operands must fit in 8 bits.
+        assert!(prove(3, 256, 1).is_err());
+    }
+
+    #[test]
+    fn can_prove_bounded_int() {
+        #[zkp_program]
+        fn bounded_int<F: FieldSpec>(x: Field<F>, #[public] expected: Field<F>) {
+            let value = invoke_gadget(BoundedIntGadget { bits: 8 }, x.ids);
+
+            ProgramNode::<Field<F>>::new(&value).constrain_eq(expected);
+        }
+
+        let app = Compiler::new()
+            .zkp_backend::<BulletproofsBackend>()
+            .zkp_program(bounded_int)
+            .compile()
+            .unwrap();
+
+        let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+        let prog = app.get_zkp_program(bounded_int).unwrap();
+
+        let prove = |x: i64| {
+            let proof =
+                runtime.prove(prog, vec![BPField::from(x)], vec![BPField::from(x)], vec![])?;
+
+            runtime.verify(prog, &proof, vec![BPField::from(x)], vec![])
+        };
+
+        for x in [-128, -127, -1, 0, 1, 42, 127] {
+            prove(x).unwrap();
+        }
+
+        for x in [-129, 128, 1000, -1000] {
+            assert!(prove(x).is_err(), "{x} should be out of range");
+        }
+    }
+}
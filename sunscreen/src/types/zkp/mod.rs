@@ -8,6 +8,10 @@ mod rns_polynomial;
 #[cfg(feature = "linkedproofs")]
 pub use bfv_plaintext::*;
 pub use field::*;
+pub use gadgets::{
+    BoundedIntGadget, BoundedRangeGadget, FactorizationGadget, LessThanGadget,
+    LinearCombinationGadget, MerklePathGadget, NonZeroGadget, RangeCheckGadget, ToBitsGadget,
+};
 pub use petgraph::stable_graph::NodeIndex;
 pub use program_node::*;
 pub use rns_polynomial::*;
@@ -1,7 +1,8 @@
 use petgraph::Graph;
 use sunscreen_runtime::{CallSignature, ZkpRuntime};
 use sunscreen_zkp_backend::{
-    BigInt, CompiledZkpProgram, FieldSpec, Gadget, Operation as JitOperation, ZkpBackend,
+    render_compiled_zkp_program, BigInt, CompiledZkpProgram, FieldSpec, Gadget,
+    Operation as JitOperation, ZkpBackend,
 };
 
 use crate::{Compiler, Result};
@@ -212,8 +213,9 @@ pub enum Operation {
     Constraint(BigInt),
     /// A constant value.
     Constant(BigInt),
-    /// An invoked gadget (which will generate more of the circuit on the backend).
-    InvokeGadget(Arc<dyn Gadget>),
+    /// An invoked gadget (which will generate more of the circuit on the backend)
+    /// and the number of nodes following it that belong to its circuit.
+    InvokeGadget(Arc<dyn Gadget>, usize),
     /// Addition.
     Add,
     /// Subtraction.
@@ -247,9 +249,10 @@ impl Hash for Operation {
                 state.write_u8(4);
                 x.hash(state);
             }
-            Self::InvokeGadget(g) => {
+            Self::InvokeGadget(g, n) => {
                 state.write_u8(5);
                 g.type_id().hash(state);
+                state.write_usize(*n);
             }
             Self::Add => state.write_u8(6),
             Self::Sub => state.write_u8(7),
@@ -271,7 +274,9 @@ impl PartialEq for Operation {
             (Self::HiddenInput(x), Self::HiddenInput(y)) => x == y,
             (Self::Constraint(x), Self::Constraint(y)) => x == y,
             (Self::Constant(x), Self::Constant(y)) => x == y,
-            (Self::InvokeGadget(x), Self::InvokeGadget(y)) => x.type_id() == y.type_id(),
+            (Self::InvokeGadget(x, m), Self::InvokeGadget(y, n)) => {
+                x.type_id() == y.type_id() && m == n
+            }
             (Self::Add, Self::Add) => true,
             (Self::Sub, Self::Sub) => true,
             (Self::Mul, Self::Mul) => true,
@@ -292,7 +297,7 @@ impl Debug for Operation {
             Self::HiddenInput(x) => write!(f, "HiddenInput({x})"),
             Self::Constraint(x) => write!(f, "Constraint({x:#?})"),
             Self::Constant(x) => write!(f, "Constant({x:#?})"),
-            Self::InvokeGadget(g) => write!(f, "InvokeGadget({})", g.debug_name()),
+            Self::InvokeGadget(g, _) => write!(f, "InvokeGadget({})", g.debug_name()),
             Self::Add => write!(f, "Add"),
             Self::Sub => write!(f, "Sub"),
             Self::Mul => write!(f, "Mul"),
@@ -319,7 +324,7 @@ impl OperationTrait for Operation {
     }
 
     fn is_ordered(&self) -> bool {
-        matches!(self, Operation::InvokeGadget(_))
+        matches!(self, Operation::InvokeGadget(..))
     }
 }
 
@@ -459,6 +464,14 @@ pub trait ZkpContextOps {
      */
     fn add_constraint(&mut self, left: NodeIndex, val: &BigInt) -> NodeIndex;
 
+    /**
+     * Add a constraint that `left` and `right` are equal to this context.
+     *
+     * # Remarks
+     * This constrains `left - right` to equal zero.
+     */
+    fn add_equal_constraint(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex;
+
     /**
      * Add a constant to this context
      */
@@ -520,6 +533,12 @@ impl ZkpContextOps for ZkpContext {
         constraint
     }
 
+    fn add_equal_constraint(&mut self, left: NodeIndex, right: NodeIndex) -> NodeIndex {
+        let diff = self.add_subtraction(left, right);
+
+        self.add_constraint(diff, &BigInt::ZERO)
+    }
+
     fn add_constant(&mut self, val: &BigInt) -> NodeIndex {
         let existing_constant = self.data.constant_map.get(val);
 
@@ -534,7 +553,7 @@ impl ZkpContextOps for ZkpContext {
     }
 
     fn add_invoke_gadget<G: Gadget>(&mut self, gadget: &Arc<G>) -> NodeIndex {
-        self.add_node(Operation::InvokeGadget(gadget.clone()))
+        self.add_node(Operation::InvokeGadget(gadget.clone(), 0))
     }
 }
 
@@ -575,6 +594,28 @@ where
  * run.
  */
 pub(crate) fn compile(program: &ZkpFrontendCompilation) -> CompiledZkpProgram {
+    // Convert in and out of Graph to compact all the node indices.
+    let jit = Graph::from(to_jit_operations(program).0).into();
+
+    CompilationResult(jit)
+}
+
+/**
+ * Renders a ZKP program's frontend graph in Graphviz's DOT format.
+ *
+ * # Remarks
+ * See [`render_compiled_zkp_program`] for how nodes and edges are drawn.
+ * Unlike compilation, this preserves the frontend graph's node indices.
+ */
+pub fn render_zkp_program(prog: &ZkpFrontendCompilation) -> String {
+    render_compiled_zkp_program(&to_jit_operations(prog))
+}
+
+/**
+ * Maps each frontend operation to its JIT counterpart without changing the
+ * graph's structure.
+ */
+fn to_jit_operations(program: &ZkpFrontendCompilation) -> CompiledZkpProgram {
     let jit = program.0.map(
         |_, n| {
             let operation = match n.operation {
@@ -582,7 +623,7 @@ pub(crate) fn compile(program: &ZkpFrontendCompilation) -> CompiledZkpProgram {
                 Operation::PublicInput(x) => JitOperation::PublicInput(x),
                 Operation::ConstantInput(x) => JitOperation::ConstantInput(x),
                 Operation::HiddenInput(x) => JitOperation::HiddenInput(x),
-                Operation::InvokeGadget(ref g) => JitOperation::InvokeGadget(g.clone()),
+                Operation::InvokeGadget(ref g, n) => JitOperation::InvokeGadget(g.clone(), n),
                 Operation::Add => JitOperation::Add,
                 Operation::Mul => JitOperation::Mul,
                 Operation::Neg => JitOperation::Neg,
@@ -596,20 +637,88 @@ pub(crate) fn compile(program: &ZkpFrontendCompilation) -> CompiledZkpProgram {
         |_, e| *e,
     );
 
-    // Convert in and out of Graph to compact all the node indices.
-    let jit = Graph::from(jit).into();
-
     CompilationResult(jit)
 }
 
+/**
+ * The outputs of a gadget invoked with [`invoke_gadget`]. Dereferences to
+ * the node indices of all the gadget's outputs.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GadgetOutputs {
+    outputs: Vec<NodeIndex>,
+    public: Vec<usize>,
+}
+
+impl GadgetOutputs {
+    /**
+     * The outputs the gadget designated as public via
+     * [`Gadget::public_outputs`], in that order.
+     */
+    pub fn public_outputs(&self) -> Vec<NodeIndex> {
+        self.public.iter().map(|i| self.outputs[*i]).collect()
+    }
+
+    /**
+     * Constrains each public output to equal the corresponding node in
+     * `expected`, which is typically a `#[public]` program argument. The
+     * verifier then checks the gadget's results against the public inputs
+     * it supplies.
+     *
+     * # Remarks
+     * Public outputs aren't checked until bound, so you should call this
+     * for any gadget with public outputs.
+     *
+     * # Panics
+     * * Calling this function inside a [`with_zkp_ctx`] callback
+     * * `expected.len()` doesn't equal the number of public outputs
+     */
+    pub fn bind_public(&self, expected: &[NodeIndex]) {
+        let public_outputs = self.public_outputs();
+
+        assert_eq!(
+            public_outputs.len(),
+            expected.len(),
+            "Expected {} public output values found {}",
+            public_outputs.len(),
+            expected.len()
+        );
+
+        with_zkp_ctx(|ctx| {
+            for (output, expected) in public_outputs.iter().zip(expected) {
+                ctx.add_equal_constraint(*output, *expected);
+            }
+        });
+    }
+}
+
+impl std::ops::Deref for GadgetOutputs {
+    type Target = [NodeIndex];
+
+    fn deref(&self) -> &Self::Target {
+        &self.outputs
+    }
+}
+
+impl From<GadgetOutputs> for Vec<NodeIndex> {
+    fn from(outputs: GadgetOutputs) -> Self {
+        outputs.outputs
+    }
+}
+
 /**
  * Invokes a gadget and adds its sub-circuit to the graph.
  *
+ * Returns the gadget's outputs. Use [`GadgetOutputs::bind_public`] to
+ * check any [public outputs](Gadget::public_outputs) against values the
+ * verifier supplies.
+ *
  * # Panics
  * * Calling this function inside a [`with_zkp_ctx`] callback
  * * `gadget_inputs.len() != g.get_gadget_input_count()`
+ * * The gadget designates a public output that doesn't exist
  */
-pub fn invoke_gadget<G: Gadget>(g: G, gadget_inputs: &[NodeIndex]) -> Vec<NodeIndex> {
+pub fn invoke_gadget<G: Gadget>(g: G, gadget_inputs: &[NodeIndex]) -> GadgetOutputs {
     let hidden_inputs_count = g.hidden_input_count();
     let gadget_input_count = g.gadget_input_count();
 
@@ -624,9 +733,12 @@ pub fn invoke_gadget<G: Gadget>(g: G, gadget_inputs: &[NodeIndex]) -> Vec<NodeIn
     let g = Arc::new(g);
 
     let mut hidden_inputs = vec![];
+    let mut gadget = NodeIndex::default();
+    let mut node_count = 0;
 
     with_zkp_ctx(|ctx| {
-        let gadget = ctx.add_invoke_gadget(&g);
+        gadget = ctx.add_invoke_gadget(&g);
+        node_count = ctx.graph.node_count();
 
         for i in 0..hidden_inputs_count {
             let hidden_input = ctx.add_hidden_input(i);
@@ -640,5 +752,22 @@ pub fn invoke_gadget<G: Gadget>(g: G, gadget_inputs: &[NodeIndex]) -> Vec<NodeIn
         }
     });
 
-    g.gen_circuit(gadget_inputs, &hidden_inputs)
+    let outputs = g.gen_circuit(gadget_inputs, &hidden_inputs);
+    let public = g.public_outputs();
+
+    // Record how many nodes the hidden inputs and circuit added, so we can
+    // attribute them to the gadget.
+    with_zkp_ctx(|ctx| {
+        let span = ctx.graph.node_count() - node_count;
+
+        ctx.graph[gadget].operation = Operation::InvokeGadget(g.clone(), span);
+    });
+
+    assert!(
+        public.iter().all(|i| *i < outputs.len()),
+        "{} designates a public output that doesn't exist",
+        g.debug_name()
+    );
+
+    GadgetOutputs { outputs, public }
 }
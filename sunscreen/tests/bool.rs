@@ -0,0 +1,121 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Bool, Cipher},
+    Compiler, FheProgramInput, Runtime,
+};
+
+const TRUTH_TABLE: [(bool, bool); 4] = [(false, false), (false, true), (true, false), (true, true)];
+
+#[fhe_program(scheme = "bfv")]
+fn and(a: Cipher<Bool>, b: Cipher<Bool>) -> Cipher<Bool> {
+    a & b
+}
+
+#[fhe_program(scheme = "bfv")]
+fn or(a: Cipher<Bool>, b: Cipher<Bool>) -> Cipher<Bool> {
+    a | b
+}
+
+#[fhe_program(scheme = "bfv")]
+fn xor(a: Cipher<Bool>, b: Cipher<Bool>) -> Cipher<Bool> {
+    a ^ b
+}
+
+#[fhe_program(scheme = "bfv")]
+fn not(a: Cipher<Bool>) -> Cipher<Bool> {
+    !a
+}
+
+#[test]
+fn bool_ops_match_truth_tables() {
+    let app = Compiler::new()
+        .fhe_program(and)
+        .fhe_program(or)
+        .fhe_program(xor)
+        .fhe_program(not)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let binary_ops: [(_, fn(bool, bool) -> bool); 3] = [
+        (app.get_fhe_program(and).unwrap(), |a, b| a & b),
+        (app.get_fhe_program(or).unwrap(), |a, b| a | b),
+        (app.get_fhe_program(xor).unwrap(), |a, b| a ^ b),
+    ];
+
+    for (program, expected) in binary_ops {
+        for (a, b) in TRUTH_TABLE {
+            let a_c = runtime.encrypt(Bool::from(a), &public_key).unwrap();
+            let b_c = runtime.encrypt(Bool::from(b), &public_key).unwrap();
+
+            let args: Vec<FheProgramInput> = vec![a_c.into(), b_c.into()];
+
+            let result = runtime.run(program, args, &public_key).unwrap();
+
+            let c: Bool = runtime.decrypt(&result[0], &private_key).unwrap();
+
+            assert_eq!(bool::from(c), expected(a, b));
+        }
+    }
+
+    for a in [false, true] {
+        let a_c = runtime.encrypt(Bool::from(a), &public_key).unwrap();
+
+        let args: Vec<FheProgramInput> = vec![a_c.into()];
+
+        let result = runtime
+            .run(app.get_fhe_program(not).unwrap(), args, &public_key)
+            .unwrap();
+
+        let c: Bool = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(bool::from(c), !a);
+    }
+}
+
+#[fhe_program(scheme = "bfv")]
+fn full_adder(
+    a: Cipher<Bool>,
+    b: Cipher<Bool>,
+    c_in: Cipher<Bool>,
+) -> (Cipher<Bool>, Cipher<Bool>) {
+    let a_xor_b = a ^ b;
+
+    let sum = a_xor_b ^ c_in;
+    let c_out = (a & b) | (a_xor_b & c_in);
+
+    (sum, c_out)
+}
+
+#[test]
+fn can_run_full_adder() {
+    let app = Compiler::new().fhe_program(full_adder).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    for (a, b) in TRUTH_TABLE {
+        for c_in in [false, true] {
+            let args: Vec<FheProgramInput> = [a, b, c_in]
+                .into_iter()
+                .map(|x| runtime.encrypt(Bool::from(x), &public_key).unwrap().into())
+                .collect();
+
+            let result = runtime
+                .run(app.get_fhe_program(full_adder).unwrap(), args, &public_key)
+                .unwrap();
+
+            let sum: Bool = runtime.decrypt(&result[0], &private_key).unwrap();
+            let c_out: Bool = runtime.decrypt(&result[1], &private_key).unwrap();
+
+            let total = a as u8 + b as u8 + c_in as u8;
+
+            assert_eq!(bool::from(sum), total % 2 == 1);
+            assert_eq!(bool::from(c_out), total >= 2);
+        }
+    }
+}
@@ -29,3 +29,162 @@ fn can_encrypt_decrypt() {
 
     assert_eq!(c, 20.into());
 }
+
+#[test]
+fn can_mod_switch_ciphertext() {
+    use seal_fhe::{CoefficientModulus, SecurityLevel};
+
+    let runtime = Runtime::new_fhe(&Params {
+        lattice_dimension: 8192,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    })
+    .unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(42), &public_key).unwrap();
+    let b = runtime.mod_switch(&a).unwrap();
+
+    let a_len = bincode::serialize(&a).unwrap().len();
+    let b_len = bincode::serialize(&b).unwrap().len();
+
+    assert!(b_len < a_len);
+
+    let c: Signed = runtime.decrypt(&b, &private_key).unwrap();
+
+    assert_eq!(c, 42.into());
+}
+
+#[test]
+fn refresh_is_unsupported_for_bfv_params() {
+    #[fhe_program(scheme = "bfv")]
+    fn double(a: Cipher<Signed>) -> Cipher<Signed> {
+        a + a
+    }
+
+    let app = Compiler::new()
+        .fhe_program(double)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (_, private_key) = runtime.generate_keys().unwrap();
+
+    assert_eq!(
+        runtime.generate_bootstrap_keys(&private_key).err(),
+        Some(RuntimeError::refresh_unsupported(app.params()))
+    );
+}
+
+#[test]
+fn can_add_assign_ciphertexts() {
+    use seal_fhe::{CoefficientModulus, SecurityLevel};
+
+    let runtime = Runtime::new_fhe(&Params {
+        lattice_dimension: 4096,
+        plain_modulus: 1024,
+        coeff_modulus: CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+            .unwrap()
+            .iter()
+            .map(|c| c.value())
+            .collect(),
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    })
+    .unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let mut acc = runtime.encrypt(Signed::from(0), &public_key).unwrap();
+
+    for x in 1..=20 {
+        let c = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+        runtime.add_assign(&mut acc, &c).unwrap();
+    }
+
+    let sum: Signed = runtime.decrypt(&acc, &private_key).unwrap();
+
+    assert_eq!(sum, 210.into());
+
+    let other = runtime
+        .encrypt(types::bfv::Unsigned64::from(1), &public_key)
+        .unwrap();
+
+    assert!(matches!(
+        runtime.add_assign(&mut acc, &other),
+        Err(RuntimeError::TypeMismatch(_))
+    ));
+
+    let rational = types::bfv::Rational::try_from(1.5).unwrap();
+    let mut acc = runtime.encrypt(rational, &public_key).unwrap();
+    let other = runtime.encrypt(rational, &public_key).unwrap();
+
+    assert!(matches!(
+        runtime.add_assign(&mut acc, &other),
+        Err(RuntimeError::FheTypeError(_))
+    ));
+}
+
+#[test]
+fn can_inspect_ciphertext_shape() {
+    use seal_fhe::{CoefficientModulus, SecurityLevel};
+    use sunscreen::types::{bfv::Rational, NumCiphertexts, TypeName};
+
+    let coeff_modulus = CoefficientModulus::bfv_default(4096, SecurityLevel::TC128)
+        .unwrap()
+        .iter()
+        .map(|c| c.value())
+        .collect::<Vec<_>>();
+    let num_moduli = coeff_modulus.len() as u64;
+
+    let runtime = Runtime::new_fhe(&Params {
+        lattice_dimension: 4096,
+        plain_modulus: 1024,
+        coeff_modulus,
+        security_level: SecurityLevel::TC128,
+        scheme_type: SchemeType::Bfv,
+    })
+    .unwrap();
+
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    let a = runtime
+        .encrypt(Rational::try_from(-3.14).unwrap(), &public_key)
+        .unwrap();
+
+    let fresh = CiphertextComponent::Seal {
+        num_polynomials: 2,
+        coeff_modulus_size: num_moduli - 1,
+    };
+
+    assert_eq!(a.inner_len(), Rational::NUM_CIPHERTEXTS);
+    assert_eq!(
+        a.shape(),
+        CiphertextShape {
+            data_type: Rational::type_name(),
+            components: vec![fresh; 2],
+        }
+    );
+
+    let b = runtime
+        .mod_switch(&runtime.encrypt(Signed::from(42), &public_key).unwrap())
+        .unwrap();
+
+    assert_eq!(
+        b.shape().components,
+        [CiphertextComponent::Seal {
+            num_polynomials: 2,
+            coeff_modulus_size: num_moduli - 2,
+        }]
+    );
+}
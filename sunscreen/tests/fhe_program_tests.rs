@@ -1,12 +1,19 @@
 use petgraph::stable_graph::node_index;
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
 use sunscreen::{
-    fhe::{FheFrontendCompilation, FheOperation, Literal, CURRENT_FHE_CTX},
+    fhe::{
+        canonicalize, lower_plaintext_subtractions, FheFrontendCompilation, FheOperation, Literal,
+        CURRENT_FHE_CTX,
+    },
     fhe_program, fhe_var,
     types::{bfv::Signed, Cipher, TypeName},
-    CallSignature, FheProgramFn, Params, SchemeType, SecurityLevel,
+    CallSignature, Error, FheProgramFn, FrontendCompilation, OperandInfo, Params, SchemeType,
+    SecurityLevel,
 };
 
 use serde_json::json;
+use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
 
 fn get_params() -> Params {
     Params {
@@ -33,6 +40,7 @@ fn fhe_program_gets_called() {
         arguments: vec![],
         returns: vec![],
         num_ciphertexts: vec![],
+        return_names: vec![],
     };
 
     assert_eq!(simple_fhe_program.signature(), expected_signature);
@@ -62,6 +70,7 @@ fn panicing_fhe_program_clears_ctx() {
             arguments: vec![],
             returns: vec![],
             num_ciphertexts: vec![],
+            return_names: vec![],
         };
 
         assert_eq!(panic_fhe_program.signature(), expected_signature);
@@ -97,6 +106,7 @@ fn capture_fhe_program_input_args() {
         ],
         returns: vec![],
         num_ciphertexts: vec![],
+        return_names: vec![],
     };
 
     assert_eq!(expected_signature, fhe_program_with_args.signature());
@@ -119,6 +129,7 @@ fn can_add() {
         arguments: vec![type_name.clone(), type_name.clone(), type_name],
         returns: vec![],
         num_ciphertexts: vec![],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_with_args.signature(), expected_signature);
     assert_eq!(fhe_program_with_args.scheme_type(), SchemeType::Bfv);
@@ -176,6 +187,7 @@ fn can_add_plaintext() {
         arguments: vec![Cipher::<Signed>::type_name(), Signed::type_name()],
         returns: vec![],
         num_ciphertexts: vec![],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_with_args.signature(), expected_signature);
     assert_eq!(fhe_program_with_args.scheme_type(), SchemeType::Bfv);
@@ -210,6 +222,78 @@ fn can_add_plaintext() {
     );
 }
 
+#[test]
+fn can_sub_from_plaintext() {
+    #[fhe_program(scheme = "bfv")]
+    fn fhe_program_with_args(a: Cipher<Signed>, b: Signed) {
+        let _ = b - a;
+    }
+
+    let mut context = fhe_program_with_args.build(&get_params()).unwrap();
+
+    let expected = json!({
+        "nodes": [
+            { "operation": "InputCiphertext" },
+            { "operation": "InputPlaintext" },
+            { "operation": "PlaintextSub" },
+        ],
+        "node_holes": [],
+        "edge_property": "directed",
+        "edges": [
+            [
+                1,
+                2,
+                "Left"
+            ],
+            [
+                0,
+                2,
+                "Right"
+            ],
+        ]
+    });
+
+    assert_eq!(
+        context,
+        serde_json::from_value::<FheFrontendCompilation>(expected).unwrap()
+    );
+
+    lower_plaintext_subtractions(&mut context);
+
+    let expected = json!({
+        "nodes": [
+            { "operation": "InputCiphertext" },
+            { "operation": "InputPlaintext" },
+            { "operation": "AddPlaintext" },
+            { "operation": "Negate" },
+        ],
+        "node_holes": [],
+        "edge_property": "directed",
+        "edges": [
+            [
+                0,
+                3,
+                "Unary"
+            ],
+            [
+                3,
+                2,
+                "Left"
+            ],
+            [
+                1,
+                2,
+                "Right"
+            ],
+        ]
+    });
+
+    assert_eq!(
+        context,
+        serde_json::from_value::<FheFrontendCompilation>(expected).unwrap()
+    );
+}
+
 #[test]
 fn can_mul() {
     #[fhe_program(scheme = "bfv")]
@@ -223,6 +307,7 @@ fn can_mul() {
         arguments: vec![type_name.clone(), type_name.clone(), type_name],
         returns: vec![],
         num_ciphertexts: vec![],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_with_args.signature(), expected_signature);
     assert_eq!(fhe_program_with_args.scheme_type(), SchemeType::Bfv);
@@ -287,6 +372,7 @@ fn can_insert_literals() {
         arguments: vec![arg_type_name],
         returns: vec![ret_type_name],
         num_ciphertexts: vec![1],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_sum.signature(), expected_signature);
     assert_eq!(fhe_program_sum.scheme_type(), SchemeType::Bfv);
@@ -311,6 +397,49 @@ fn can_insert_literals() {
     assert_eq!(context[node_index(5)].operation, FheOperation::Output);
 }
 
+#[test]
+fn can_bind_constant_arguments() {
+    #[fhe_program(scheme = "bfv")]
+    fn scale(a: Cipher<Signed>, #[constant] k: Signed, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * k + b
+    }
+
+    let expected_signature = CallSignature {
+        arguments: vec![Cipher::<Signed>::type_name(), Cipher::<Signed>::type_name()],
+        returns: vec![Cipher::<Signed>::type_name()],
+        num_ciphertexts: vec![1],
+        return_names: vec![],
+    };
+    assert_eq!(scale.signature(), expected_signature);
+
+    assert!(matches!(
+        scale.build(&get_params()),
+        Err(Error::UnboundConstant(_))
+    ));
+
+    let context = scale.with_k(Signed::from(3)).build(&get_params()).unwrap();
+
+    assert_eq!(context.node_count(), 6);
+    assert_eq!(
+        context[node_index(0)].operation,
+        FheOperation::InputCiphertext
+    );
+    assert_eq!(
+        context[node_index(1)].operation,
+        FheOperation::InputCiphertext
+    );
+    assert!(matches!(
+        context[node_index(2)].operation,
+        FheOperation::Literal(Literal::Plaintext(_))
+    ));
+    assert_eq!(
+        context[node_index(3)].operation,
+        FheOperation::MultiplyPlaintext
+    );
+    assert_eq!(context[node_index(4)].operation, FheOperation::Add);
+    assert_eq!(context[node_index(5)].operation, FheOperation::Output);
+}
+
 #[test]
 fn can_collect_output() {
     #[fhe_program(scheme = "bfv")]
@@ -324,6 +453,7 @@ fn can_collect_output() {
         arguments: vec![type_name.clone(), type_name.clone()],
         returns: vec![type_name],
         num_ciphertexts: vec![1],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_with_args.signature(), expected_signature);
     assert_eq!(fhe_program_with_args.scheme_type(), SchemeType::Bfv);
@@ -391,6 +521,7 @@ fn can_collect_multiple_outputs() {
         arguments: vec![type_name.clone(), type_name.clone()],
         returns: vec![type_name.clone(), type_name],
         num_ciphertexts: vec![1, 1],
+        return_names: vec![],
     };
     assert_eq!(fhe_program_with_args.signature(), expected_signature);
     assert_eq!(fhe_program_with_args.scheme_type(), SchemeType::Bfv);
@@ -465,3 +596,376 @@ fn coercion_supports_arbitrarily_nested_outputs() {
         ([sum; 2], [[sum; 3]; 2])
     }
 }
+
+#[test]
+fn can_render_frontend_compilation_to_dot() {
+    #[fhe_program(scheme = "bfv")]
+    fn dot_fhe_program(a: Cipher<Signed>, b: Signed) -> Cipher<Signed> {
+        -(a * b)
+    }
+
+    let context = dot_fhe_program.build(&get_params()).unwrap();
+    let mut compilation = FrontendCompilation::from(&context);
+
+    let dot = compilation.to_dot();
+
+    assert!(dot.starts_with("digraph"));
+    assert!(dot.contains("label=\"0: InputCiphertext\" shape=invhouse"));
+    assert!(dot.contains("label=\"1: InputPlaintext\" shape=invhouse"));
+    assert!(dot.contains("label=\"2: MultiplyPlaintext\" shape=ellipse"));
+    assert!(dot.contains("label=\"3: Negate\" shape=ellipse"));
+    assert!(dot.contains("label=\"4: Output\" shape=house"));
+    assert!(dot.contains("0 -> 2 [ label=\"Left\"]"));
+    assert!(dot.contains("1 -> 2 [ label=\"Right\"]"));
+    assert!(dot.contains("2 -> 3 [ label=\"Unary\"]"));
+
+    let literal = compilation
+        .graph
+        .add_node(FheOperation::Literal(Literal::U64(7)));
+    compilation
+        .graph
+        .add_edge(literal, node_index(4), OperandInfo::Unary);
+
+    let dot = compilation.to_dot();
+
+    assert!(dot.contains("label=\"5: Literal(7)\" shape=box"));
+    assert!(dot.contains("5 -> 4 [ label=\"Unary\"]"));
+}
+
+#[test]
+fn can_round_trip_frontend_compilation_bytes() {
+    #[fhe_program(scheme = "bfv")]
+    fn serialize_fhe_program(a: Cipher<Signed>, b: Signed) -> Cipher<Signed> {
+        -(a * b) + a
+    }
+
+    let context = serialize_fhe_program.build(&get_params()).unwrap();
+    let mut compilation = FrontendCompilation::from(&context);
+
+    // Leave a hole in the node indices.
+    let literal = compilation
+        .graph
+        .add_node(FheOperation::Literal(Literal::U64(7)));
+    compilation.graph.remove_node(node_index(3));
+    compilation
+        .graph
+        .add_edge(literal, node_index(2), OperandInfo::Unary);
+
+    let bytes = compilation.to_bytes().unwrap();
+    let restored = FrontendCompilation::from_bytes(&bytes).unwrap();
+
+    assert!(restored
+        .graph
+        .node_indices()
+        .eq(compilation.graph.node_indices()));
+
+    for n in compilation.graph.node_indices() {
+        assert_eq!(restored.graph[n], compilation.graph[n]);
+
+        let edges = |c: &FrontendCompilation| {
+            let mut edges = c
+                .graph
+                .edges_directed(n, Direction::Incoming)
+                .map(|e| (e.source(), e.weight().clone()))
+                .collect::<Vec<_>>();
+            edges.sort_by_key(|(s, _)| *s);
+            edges
+        };
+
+        assert_eq!(edges(&restored), edges(&compilation));
+    }
+
+    let mut bad_magic = bytes.clone();
+    bad_magic[0] = b'X';
+    assert!(FrontendCompilation::from_bytes(&bad_magic).is_err());
+
+    let mut bad_version = bytes.clone();
+    bad_version[4] += 1;
+    assert!(FrontendCompilation::from_bytes(&bad_version).is_err());
+
+    assert!(FrontendCompilation::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // The first node's index follows the header and the node list's
+    // 8-byte length. A huge index must fail rather than allocate
+    // placeholders up to it.
+    let mut huge_index = bytes.clone();
+    huge_index[13..17].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(FrontendCompilation::from_bytes(&huge_index).is_err());
+}
+
+#[test]
+fn can_append_frontend_compilation() {
+    #[fhe_program(scheme = "bfv")]
+    fn outer(a: Cipher<Signed>, b: Signed) -> Cipher<Signed> {
+        a * b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn inner(x: Cipher<Signed>, y: Cipher<Signed>) -> Cipher<Signed> {
+        x - y
+    }
+
+    let mut compilation = FrontendCompilation::from(&outer.build(&get_params()).unwrap());
+    let other = FrontendCompilation::from(&inner.build(&get_params()).unwrap());
+
+    let outputs = compilation.append(&other, &[node_index(2), node_index(0)]);
+
+    assert_eq!(outputs, vec![node_index(4)]);
+    assert_eq!(compilation.graph.node_count(), 5);
+    assert_eq!(compilation.graph[node_index(4)], FheOperation::Sub);
+
+    let mut edges = compilation
+        .graph
+        .edges_directed(node_index(4), Direction::Incoming)
+        .map(|e| (e.source(), e.weight().clone()))
+        .collect::<Vec<_>>();
+    edges.sort_by_key(|(s, _)| *s);
+
+    assert_eq!(
+        edges,
+        vec![
+            (node_index(0), OperandInfo::Right),
+            (node_index(2), OperandInfo::Left)
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn append_frontend_compilation_panics_on_input_count_mismatch() {
+    #[fhe_program(scheme = "bfv")]
+    fn unary(x: Cipher<Signed>) -> Cipher<Signed> {
+        -x
+    }
+
+    let mut compilation = FrontendCompilation::from(&unary.build(&get_params()).unwrap());
+    let other = compilation.clone();
+
+    compilation.append(&other, &[]);
+}
+
+#[test]
+fn can_build_frontend_compilation_from_operations() {
+    use FheOperation::*;
+    use OperandInfo::{Left, Right, Unary};
+
+    let n = node_index;
+
+    let compilation = FrontendCompilation::from_operations(&[
+        (InputCiphertext, vec![]),
+        (InputCiphertext, vec![]),
+        (Multiply, vec![(n(0), Left), (n(1), Right)]),
+        (Negate, vec![(n(2), Unary)]),
+        (Output, vec![(n(3), Unary)]),
+    ])
+    .unwrap();
+
+    assert_eq!(compilation.graph.node_count(), 5);
+    assert_eq!(compilation.graph.edge_count(), 4);
+    assert_eq!(compilation.graph[n(2)], Multiply);
+
+    let is_malformed = |ops: &[(FheOperation, Vec<_>)]| {
+        matches!(
+            FrontendCompilation::from_operations(ops),
+            Err(Error::MalformedGraph(_))
+        )
+    };
+
+    // Wrong arity.
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (Add, vec![(n(0), Left), (n(0), Left)]),
+        (Output, vec![(n(1), Unary)]),
+    ]));
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (Negate, vec![(n(0), Left)]),
+        (Output, vec![(n(1), Unary)]),
+    ]));
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![(n(0), Unary)]),
+        (Output, vec![(n(0), Unary)]),
+    ]));
+
+    // Forward references and cycles.
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (Add, vec![(n(0), Left), (n(2), Right)]),
+        (Output, vec![(n(1), Unary)]),
+    ]));
+
+    // Consuming an output.
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (Output, vec![(n(0), Unary)]),
+        (Negate, vec![(n(1), Unary)]),
+        (Output, vec![(n(2), Unary)]),
+    ]));
+
+    // Inputs must come first.
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (Negate, vec![(n(0), Unary)]),
+        (InputCiphertext, vec![]),
+        (Output, vec![(n(1), Unary)]),
+    ]));
+
+    // Operands of the wrong kind.
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (InputCiphertext, vec![]),
+        (RotateLeft, vec![(n(0), Left), (n(1), Right)]),
+        (Output, vec![(n(2), Unary)]),
+    ]));
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (InputPlaintext, vec![]),
+        (AddPlaintext, vec![(n(1), Left), (n(0), Right)]),
+        (Output, vec![(n(2), Unary)]),
+    ]));
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (InputPlaintext, vec![]),
+        (Multiply, vec![(n(0), Left), (n(1), Right)]),
+        (Output, vec![(n(2), Unary)]),
+    ]));
+    assert!(is_malformed(&[
+        (InputCiphertext, vec![]),
+        (InputPlaintext, vec![]),
+        (Output, vec![(n(1), Unary)]),
+    ]));
+
+    let rotated = FrontendCompilation::from_operations(&[
+        (InputCiphertext, vec![]),
+        (InputPlaintext, vec![]),
+        (Literal(sunscreen::fhe::Literal::U64(2)), vec![]),
+        (MultiplyPlaintext, vec![(n(0), Left), (n(1), Right)]),
+        (RotateLeft, vec![(n(3), Left), (n(2), Right)]),
+        (Output, vec![(n(4), Unary)]),
+    ]);
+
+    assert!(rotated.is_ok());
+
+    // Missing inputs or outputs.
+    assert!(is_malformed(&[(InputCiphertext, vec![])]));
+    assert!(is_malformed(&[
+        (Literal(sunscreen::fhe::Literal::U64(1)), vec![]),
+        (Output, vec![(n(0), Unary)]),
+    ]));
+}
+
+#[test]
+fn can_diff_frontend_compilations() {
+    use FheOperation::*;
+    use OperandInfo::{Left, Right, Unary};
+
+    let n = node_index;
+
+    let a = FrontendCompilation::from_operations(&[
+        (InputCiphertext, vec![]),
+        (InputCiphertext, vec![]),
+        (Multiply, vec![(n(0), Left), (n(1), Right)]),
+        (Negate, vec![(n(2), Unary)]),
+        (Output, vec![(n(3), Unary)]),
+    ])
+    .unwrap();
+
+    // Same program with the negate and multiply numbered differently.
+    let mut renumbered = FrontendCompilation::from_operations(&[
+        (InputCiphertext, vec![]),
+        (InputCiphertext, vec![]),
+        (Negate, vec![(n(0), Unary)]),
+        (Output, vec![(n(2), Unary)]),
+    ])
+    .unwrap();
+    let mul = renumbered.graph.add_node(Multiply);
+    let edge = renumbered.graph.find_edge(n(0), n(2)).unwrap();
+    renumbered.graph.remove_edge(edge);
+    renumbered.graph.add_edge(n(0), mul, Left);
+    renumbered.graph.add_edge(n(1), mul, Right);
+    renumbered.graph.add_edge(mul, n(2), Unary);
+
+    let diff = a.diff(&renumbered);
+
+    assert!(diff.isomorphic);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "Graphs are isomorphic\n");
+
+    let b = FrontendCompilation::from_operations(&[
+        (InputCiphertext, vec![]),
+        (InputCiphertext, vec![]),
+        (Add, vec![(n(0), Left), (n(1), Right)]),
+        (Output, vec![(n(2), Unary)]),
+    ])
+    .unwrap();
+
+    let diff = a.diff(&b);
+
+    assert!(!diff.isomorphic);
+    assert!(diff.added_nodes.is_empty());
+    assert_eq!(diff.removed_nodes, vec![(n(4), Output)]);
+    assert_eq!(
+        diff.changed_nodes,
+        vec![(n(2), Multiply, Add), (n(3), Negate, Output)]
+    );
+    assert_eq!(diff.added_edges, vec![]);
+    assert_eq!(diff.removed_edges, vec![(n(3), n(4), Unary)]);
+
+    let rendered = diff.to_string();
+
+    assert!(rendered.contains("- node 4: Output"));
+    assert!(rendered.contains("~ node 2: Multiply -> Add"));
+    assert!(rendered.contains("- edge 3 -> 4: Unary"));
+
+    let diff = b.diff(&a);
+
+    assert_eq!(diff.added_nodes, vec![(n(4), Output)]);
+    assert_eq!(diff.added_edges, vec![(n(3), n(4), Unary)]);
+}
+
+#[test]
+fn canonicalize_orders_nodes_topologically() {
+    #[fhe_program(scheme = "bfv")]
+    fn fhe_program_with_args(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let mut context = fhe_program_with_args.build(&get_params()).unwrap();
+
+    // Negate the product after the output node was already inserted.
+    let output_edge = context.0.find_edge(node_index(2), node_index(3)).unwrap();
+    context.0.remove_edge(output_edge);
+    let negate = context.0.add_node(NodeInfo::new(FheOperation::Negate));
+    context.0.add_edge(node_index(2), negate, EdgeInfo::Unary);
+    context.0.add_edge(negate, node_index(3), EdgeInfo::Unary);
+
+    canonicalize(&mut context);
+
+    let expected = json!({
+        "nodes": [
+            { "operation": "InputCiphertext" },
+            { "operation": "InputCiphertext" },
+            { "operation": "Multiply" },
+            { "operation": "Negate" },
+            { "operation": "Output" }
+        ],
+        "node_holes": [],
+        "edge_property": "directed",
+        "edges": [
+            [0, 2, "Left"],
+            [1, 2, "Right"],
+            [2, 3, "Unary"],
+            [3, 4, "Unary"]
+        ]
+    });
+
+    assert_eq!(
+        context,
+        serde_json::from_value::<FheFrontendCompilation>(expected).unwrap()
+    );
+
+    let canonical = context.clone();
+    canonicalize(&mut context);
+
+    assert_eq!(context, canonical);
+}
@@ -0,0 +1,70 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Fixed, Cipher},
+    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+};
+
+type Fixed16 = Fixed<16, 8>;
+type CipherFixed = Cipher<Fixed16>;
+
+#[test]
+fn can_compute_with_fixed() {
+    #[fhe_program(scheme = "bfv")]
+    fn poly(a: CipherFixed, b: Fixed16, c: CipherFixed) -> (CipherFixed, CipherFixed) {
+        ((a + b) * c - 1.5, -(0.25 * a) + 3.0 - c)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(poly)
+        .additional_noise_budget(30)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let do_poly = |a: f64, b: f64, c: f64| {
+        let a_c = runtime.encrypt(Fixed16::from(a), &public_key).unwrap();
+        let c_c = runtime.encrypt(Fixed16::from(c), &public_key).unwrap();
+
+        let args: Vec<FheProgramInput> = vec![a_c.into(), Fixed16::from(b).into(), c_c.into()];
+
+        let result = runtime
+            .run(app.get_fhe_program(poly).unwrap(), args, &public_key)
+            .unwrap();
+
+        let x: Fixed16 = runtime.decrypt(&result[0], &private_key).unwrap();
+        let y: Fixed16 = runtime.decrypt(&result[1], &private_key).unwrap();
+
+        // These inputs are exact in 8 fractional bits, so the results are
+        // too.
+        assert_eq!(f64::from(x), (a + b) * c - 1.5);
+        assert_eq!(f64::from(y), -(0.25 * a) + 3.0 - c);
+    };
+
+    do_poly(3.25, 1.5, 2.0);
+    do_poly(-3.25, 0.125, 7.75);
+    do_poly(0.0, 0.0, 0.0);
+    do_poly(100.5, -20.25, -0.5);
+}
+
+#[test]
+fn encrypting_out_of_range_fixed_fails() {
+    #[fhe_program(scheme = "bfv")]
+    fn id(a: CipherFixed) -> CipherFixed {
+        a
+    }
+
+    let app = Compiler::new().fhe_program(id).compile().unwrap();
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, _) = runtime.generate_keys().unwrap();
+
+    assert!(runtime
+        .encrypt(Fixed16::from(65536.0), &public_key)
+        .is_err());
+    assert!(runtime
+        .encrypt(Fixed16::from(65535.75), &public_key)
+        .is_ok());
+}
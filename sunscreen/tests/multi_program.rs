@@ -113,3 +113,54 @@ fn cant_compile_multiple_programs_with_same_name() {
         }
     };
 }
+
+#[test]
+fn can_run_serialized_fhe_application() {
+    #[fhe_program(scheme = "bfv")]
+    fn add(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a + b
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn mul(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        a * b
+    }
+
+    let app = Compiler::new()
+        .fhe_program(add)
+        .fhe_program(mul)
+        .compile()
+        .unwrap();
+
+    let bytes = app.fhe_application().unwrap().to_bytes().unwrap();
+    let fhe_app = FheApplication::from_bytes(&bytes).unwrap();
+
+    assert_eq!(fhe_app.params(), app.params());
+
+    let runtime = Runtime::new_for_application(&fhe_app).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(-5), &public_key).unwrap();
+
+    let sum = runtime
+        .run(
+            fhe_app.get_fhe_program(add).unwrap(),
+            vec![a.clone(), b.clone()],
+            &public_key,
+        )
+        .unwrap();
+    let product = runtime
+        .run(
+            fhe_app.get_fhe_program(mul).unwrap(),
+            vec![a, b],
+            &public_key,
+        )
+        .unwrap();
+
+    let sum: Signed = runtime.decrypt(&sum[0], &private_key).unwrap();
+    let product: Signed = runtime.decrypt(&product[0], &private_key).unwrap();
+
+    assert_eq!(sum, (-2).into());
+    assert_eq!(product, (-15).into());
+}
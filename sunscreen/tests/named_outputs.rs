@@ -0,0 +1,77 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, FheProgramOutputs, Runtime, RuntimeError,
+};
+
+#[derive(FheProgramOutputs)]
+struct Stats {
+    sum: Cipher<Signed>,
+    product: Cipher<Signed>,
+}
+
+#[fhe_program(scheme = "bfv")]
+fn stats(a: Cipher<Signed>, b: Cipher<Signed>) -> Stats {
+    StatsNode {
+        sum: a + b,
+        product: a * b,
+    }
+}
+
+#[fhe_program(scheme = "bfv")]
+fn sum(a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+    a + b
+}
+
+#[test]
+fn can_run_named_outputs() {
+    assert_eq!(Stats::NAMES, ["sum", "product"]);
+
+    let app = Compiler::new()
+        .fhe_program(stats)
+        .fhe_program(sum)
+        .compile()
+        .unwrap();
+
+    assert_eq!(
+        app.get_fhe_program(stats)
+            .unwrap()
+            .metadata
+            .signature
+            .return_names,
+        ["sum", "product"]
+    );
+    assert!(app
+        .get_fhe_program(sum)
+        .unwrap()
+        .metadata
+        .signature
+        .return_names
+        .is_empty());
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(6), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(-4), &public_key).unwrap();
+
+    let result = runtime
+        .run_named::<Stats, _>(
+            app.get_fhe_program(stats).unwrap(),
+            vec![a.clone(), b.clone()],
+            &public_key,
+        )
+        .unwrap();
+
+    let s: Signed = runtime.decrypt(&result.sum, &private_key).unwrap();
+    let p: Signed = runtime.decrypt(&result.product, &private_key).unwrap();
+
+    assert_eq!(s, 2.into());
+    assert_eq!(p, (-24).into());
+
+    assert!(matches!(
+        runtime.run_named::<Stats, _>(app.get_fhe_program(sum).unwrap(), vec![a, b], &public_key),
+        Err(RuntimeError::TypeMismatch(_))
+    ));
+}
@@ -0,0 +1,49 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, EstimateNoiseBudget, FheProgramInput, PlainModulusConstraint, Runtime,
+};
+
+#[test]
+fn estimated_noise_budget_is_a_lower_bound() {
+    #[fhe_program(scheme = "bfv")]
+    fn shallow_and_deep(a: Cipher<Signed>, b: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+        (a + b, a * b * a * b)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(shallow_and_deep)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(shallow_and_deep).unwrap();
+    let report = program.estimate_noise_budget();
+
+    assert_eq!(report.output_budgets.len(), 2);
+    assert!(report.output_budgets[1] < report.output_budgets[0]);
+    assert!(report.min_budget > 0.);
+    assert!(report.min_budget_node.is_some());
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let args: Vec<FheProgramInput> = vec![
+        runtime
+            .encrypt(Signed::from(3), &public_key)
+            .unwrap()
+            .into(),
+        runtime
+            .encrypt(Signed::from(-2), &public_key)
+            .unwrap()
+            .into(),
+    ];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    for (c, estimate) in result.iter().zip(report.output_budgets.iter()) {
+        let measured = runtime.measure_noise_budget(c, &private_key).unwrap();
+
+        assert!(measured as f64 >= *estimate);
+    }
+}
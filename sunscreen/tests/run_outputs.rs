@@ -0,0 +1,133 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher},
+    Compiler, FheProgramInput, PlainModulusConstraint, Runtime, RuntimeError,
+};
+
+#[test]
+fn run_outputs_only_evaluates_selected_outputs() {
+    #[fhe_program(scheme = "bfv")]
+    fn sum_and_product(
+        a: Cipher<Signed>,
+        b: Cipher<Signed>,
+    ) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+        (a + b, a * b, a - b)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(sum_and_product)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (mut public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(7), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+
+    let args = || -> Vec<FheProgramInput> { vec![a.clone().into(), b.clone().into()] };
+
+    let program = app.get_fhe_program(sum_and_product).unwrap();
+
+    let result = runtime
+        .run_outputs(program, args(), &public_key, &[2, 1, 2])
+        .unwrap();
+
+    let decrypt = |c| -> i64 { runtime.decrypt::<Signed>(c, &private_key).unwrap().into() };
+
+    assert_eq!(result.len(), 3);
+    assert_eq!(decrypt(&result[0]), 10);
+    assert_eq!(decrypt(&result[1]), -21);
+    assert_eq!(decrypt(&result[2]), 10);
+
+    // Without relinearization keys, the multiplication fails. Computing only
+    // the sum and difference succeeds, so we never evaluated it.
+    public_key.relin_key = None;
+
+    assert_eq!(
+        runtime.run(program, args(), &public_key).err(),
+        Some(RuntimeError::MissingRelinearizationKeys)
+    );
+
+    let result = runtime
+        .run_outputs(program, args(), &public_key, &[0, 2])
+        .unwrap();
+
+    assert_eq!(decrypt(&result[0]), 4);
+    assert_eq!(decrypt(&result[1]), 10);
+
+    assert_eq!(
+        runtime
+            .run_outputs(program, args(), &public_key, &[1])
+            .err(),
+        Some(RuntimeError::MissingRelinearizationKeys)
+    );
+
+    assert_eq!(
+        runtime
+            .run_outputs(program, args(), &public_key, &[3])
+            .err(),
+        Some(RuntimeError::OutputIndexOutOfRange(3))
+    );
+}
+
+#[test]
+fn run_streaming_yields_each_output() {
+    #[fhe_program(scheme = "bfv")]
+    fn powers(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+        let a_2 = a * a;
+        let a_3 = a_2 * a;
+
+        (a_2, a_3, a_3 * a_2)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(powers)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(100_000))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (mut public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(-5), &public_key).unwrap();
+
+    let program = app.get_fhe_program(powers).unwrap();
+
+    let decrypt = |c| -> i64 { runtime.decrypt::<Signed>(c, &private_key).unwrap().into() };
+
+    let mut stream = runtime
+        .run_streaming(program, vec![a.clone()], &public_key)
+        .unwrap();
+
+    assert_eq!(stream.next().map(|c| decrypt(&c.unwrap())), Some(25));
+    assert_eq!(stream.next().map(|c| decrypt(&c.unwrap())), Some(-125));
+    assert_eq!(stream.next().map(|c| decrypt(&c.unwrap())), Some(-3125));
+    assert!(stream.next().is_none());
+
+    let streamed = runtime
+        .run_streaming(program, vec![a.clone()], &public_key)
+        .unwrap()
+        .map(|c| decrypt(&c.unwrap()))
+        .collect::<Vec<_>>();
+
+    let ran = runtime
+        .run(program, vec![a.clone()], &public_key)
+        .unwrap()
+        .iter()
+        .map(decrypt)
+        .collect::<Vec<_>>();
+
+    assert_eq!(streamed, ran);
+
+    // Missing keys are reported before anything runs.
+    public_key.relin_key = None;
+
+    assert_eq!(
+        runtime.run_streaming(program, vec![a], &public_key).err(),
+        Some(RuntimeError::MissingRelinearizationKeys)
+    );
+}
@@ -0,0 +1,61 @@
+use sunscreen::{
+    fhe_program,
+    types::{bfv::Signed, Cipher, TryIntoPlaintext},
+    Compiler, FheProgramInput, Runtime, RuntimeError,
+};
+
+#[test]
+fn can_run_with_encoded_plaintexts() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul_add(a: Cipher<Signed>, b: Signed) -> Cipher<Signed> {
+        a * b + b
+    }
+
+    let app = Compiler::new().fhe_program(mul_add).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let program = app.get_fhe_program(mul_add).unwrap();
+
+    let b = Signed::from(3)
+        .try_into_plaintext(runtime.params())
+        .unwrap();
+
+    for x in [-2, 5, 11] {
+        let a = runtime.encrypt(Signed::from(x), &public_key).unwrap();
+
+        let args: Vec<FheProgramInput> = vec![a.into(), b.clone().into()];
+
+        let result = runtime.run_raw(program, args, &public_key).unwrap();
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, (3 * x + 3).into());
+    }
+
+    // run also accepts encoded plaintexts.
+    let a = runtime.encrypt(Signed::from(4), &public_key).unwrap();
+    let args: Vec<FheProgramInput> = vec![a.clone().into(), b.into()];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 15.into());
+
+    // run_raw doesn't encode plaintexts.
+    let args: Vec<FheProgramInput> = vec![a.clone().into(), Signed::from(3).into()];
+
+    assert_eq!(
+        runtime.run_raw(program, args, &public_key).err(),
+        Some(RuntimeError::UnencodedPlaintext(1))
+    );
+
+    // Too few arguments.
+    let args: Vec<FheProgramInput> = vec![a.into()];
+
+    assert_eq!(
+        runtime.run_raw(program, args, &public_key).err(),
+        Some(RuntimeError::ArgumentCountMismatch(2, 1))
+    );
+}
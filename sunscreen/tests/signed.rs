@@ -1,7 +1,10 @@
 use sunscreen::{
     fhe_program,
-    types::{bfv::Signed, Cipher},
-    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+    types::{
+        bfv::{select, Signed},
+        Cipher, Pow,
+    },
+    Compiler, Error, FheProgramInput, PlainModulusConstraint, Runtime,
 };
 
 use std::ops::*;
@@ -513,7 +516,190 @@ fn can_mul_literal_cipher() {
     assert_eq!(c, mul_fn(-4, a));
 }
 
+#[test]
+fn can_mul_add() {
+    #[fhe_program(scheme = "bfv")]
+    fn mul_add(
+        a: Cipher<Signed>,
+        b: Cipher<Signed>,
+        c: Cipher<Signed>,
+        d: Signed,
+    ) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+        (a.mul_add(b, c), a * b + c, a.mul_add(d, 7))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mul_add)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Signed::from(15);
+    let a_c = runtime.encrypt(a, &public_key).unwrap();
+    let b = Signed::from(-5);
+    let b_c = runtime.encrypt(b, &public_key).unwrap();
+    let c = Signed::from(3);
+    let c_c = runtime.encrypt(c, &public_key).unwrap();
+    let d = Signed::from(4);
+
+    let args: Vec<FheProgramInput> = vec![a_c.into(), b_c.into(), c_c.into(), d.into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(mul_add).unwrap(), args, &public_key)
+        .unwrap();
+
+    let fused: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+    let explicit: Signed = runtime.decrypt(&result[1], &private_key).unwrap();
+    let plain: Signed = runtime.decrypt(&result[2], &private_key).unwrap();
+
+    assert_eq!(fused, Signed::from(-72));
+    assert_eq!(fused, explicit);
+    assert_eq!(plain, Signed::from(67));
+}
+
+#[test]
+fn can_select() {
+    #[fhe_program(scheme = "bfv")]
+    fn mux(cond: Cipher<Signed>, a: Cipher<Signed>, b: Cipher<Signed>) -> Cipher<Signed> {
+        select(cond, a, b)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mux)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(15), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(-5), &public_key).unwrap();
+
+    for (cond, expected) in [(1, 15), (0, -5)] {
+        let cond = runtime.encrypt(Signed::from(cond), &public_key).unwrap();
+
+        let args: Vec<FheProgramInput> = vec![cond.into(), a.clone().into(), b.clone().into()];
+
+        let result = runtime
+            .run(app.get_fhe_program(mux).unwrap(), args, &public_key)
+            .unwrap();
+
+        let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+        assert_eq!(c, Signed::from(expected));
+    }
+}
+
 #[test]
 fn can_create_default() {
     assert_eq!(Into::<i64>::into(Signed::default()), 0);
 }
+
+#[test]
+fn can_pow_cipher() {
+    fn pow_impl<T>(a: T) -> (T, T, T)
+    where
+        T: Pow<Output = T> + Copy,
+    {
+        (a.pow(1), a.pow(5), a.pow(8))
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn pow(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>, Cipher<Signed>) {
+        pow_impl(a)
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn pow_7(a: Cipher<Signed>) -> Cipher<Signed> {
+        a.pow(7)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(pow)
+        .fhe_program(pow_7)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(100_000))
+        .compile()
+        .unwrap();
+
+    // Repeated squaring computes a^7 in ceil(log2(7)) levels.
+    assert_eq!(
+        app.get_fhe_program(pow_7)
+            .unwrap()
+            .multiplicative_depth(false),
+        3
+    );
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Signed::from(-3);
+
+    let run = |program: &str| -> Vec<Signed> {
+        let args: Vec<FheProgramInput> = vec![runtime.encrypt(a, &public_key).unwrap().into()];
+
+        runtime
+            .run(app.get_fhe_program(program).unwrap(), args, &public_key)
+            .unwrap()
+            .iter()
+            .map(|c| runtime.decrypt(c, &private_key).unwrap())
+            .collect()
+    };
+
+    let (a_1, a_5, a_8) = pow_impl(a);
+
+    assert_eq!(run("pow"), vec![a_1, a_5, a_8]);
+    assert_eq!(a_8, Signed::from(6561));
+    assert_eq!(run("pow_7"), vec![Signed::from(-2187)]);
+}
+
+#[test]
+fn can_pow_0() {
+    #[fhe_program(scheme = "bfv")]
+    fn pow_0(a: Cipher<Signed>) -> (Cipher<Signed>, Cipher<Signed>) {
+        (a.pow(2) * 2 - a.pow(1) * 3 + a.pow(0) * 5, a.pow(0) - a)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(pow_0)
+        .plain_modulus_constraint(PlainModulusConstraint::Raw(500))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = runtime.encrypt(Signed::from(-3), &public_key).unwrap();
+
+    let result = runtime
+        .run(app.get_fhe_program(pow_0).unwrap(), vec![a], &public_key)
+        .unwrap()
+        .iter()
+        .map(|c| runtime.decrypt(c, &private_key).unwrap())
+        .collect::<Vec<Signed>>();
+
+    assert_eq!(result, vec![Signed::from(32), Signed::from(4)]);
+}
+
+#[test]
+fn pow_0_output_fails_to_compile() {
+    #[fhe_program(scheme = "bfv")]
+    fn pow_0(a: Cipher<Signed>) -> Cipher<Signed> {
+        a.pow(0)
+    }
+
+    assert!(matches!(
+        Compiler::new().fhe_program(pow_0).compile(),
+        Err(Error::Unsupported(_))
+    ));
+}
@@ -1,9 +1,13 @@
 use sunscreen::{
     fhe_program,
-    types::{bfv::Batched, Cipher, SwapRows},
-    Compiler, FheProgramInput, PlainModulusConstraint, Runtime,
+    types::{
+        bfv::Batched, Cipher, DivByConstant, Rotate, SwapRows, TryFromPlaintext, TryIntoPlaintext,
+    },
+    Compiler, Error, FheProgramInput, PlainModulusConstraint, Runtime,
 };
 
+use sunscreen_fhe_program::Operation;
+
 use std::ops::*;
 
 #[test]
@@ -132,6 +136,57 @@ fn can_rotate_right_cipher() {
     assert_eq!(c, shr_impl(a, 1));
 }
 
+#[test]
+fn can_dot_product_with_rotate() {
+    fn dot_impl<T>(a: T, b: T) -> T
+    where
+        T: Copy + Add<Output = T> + Mul<Output = T> + Rotate<Output = T> + SwapRows<Output = T>,
+    {
+        let mut x = a * b;
+
+        for i in [2, 1] {
+            x = x + x.rotate_left(i);
+        }
+
+        x + x.swap_rows()
+    }
+
+    #[fhe_program(scheme = "bfv")]
+    fn dot(a: Cipher<Batched<4>>, b: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        dot_impl(a, b)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(dot)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let b = Batched::<4>::try_from([vec![-1, 2, -3, 4], vec![5, -6, 7, -8]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![
+        runtime.encrypt(a, &public_key).unwrap().into(),
+        runtime.encrypt(b, &public_key).unwrap().into(),
+    ];
+
+    let result = runtime
+        .run(app.get_fhe_program(dot).unwrap(), args, &public_key)
+        .unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    // (-1 + 4 - 9 + 16) + (25 - 36 + 49 - 64) = -16 in every lane.
+    assert_eq!(c, dot_impl(a, b));
+    assert_eq!(c, [[-16; 4]; 2].into());
+    assert_eq!(a.rotate_right(1), a >> 1);
+}
+
 #[test]
 fn can_add_cipher_cipher() {
     fn add_impl<T>(a: T, b: T) -> T
@@ -301,3 +356,300 @@ fn can_neg_cipher_cipher() {
 
     assert_eq!(c, neg_impl(a));
 }
+
+#[test]
+fn can_average_with_div_by_constant() {
+    #[fhe_program(scheme = "bfv")]
+    fn average(a: Cipher<Batched<4>>, b: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        (a + b).div_by_constant(2)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(average)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let b = Batched::<4>::try_from([vec![3, -2, 5, -10], vec![5, 10, -11, 20]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![
+        runtime.encrypt(a, &public_key).unwrap().into(),
+        runtime.encrypt(b, &public_key).unwrap().into(),
+    ];
+
+    let result = runtime
+        .run(app.get_fhe_program(average).unwrap(), args, &public_key)
+        .unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, [[2, 0, 4, -3], [5, 8, -2, 14]].into());
+}
+
+#[test]
+fn div_by_zero_fails_to_compile() {
+    #[fhe_program(scheme = "bfv")]
+    fn div_zero(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.div_by_constant(0)
+    }
+
+    let result = Compiler::new()
+        .fhe_program(div_zero)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile();
+
+    assert!(matches!(result, Err(Error::NotInvertible { value: 0, .. })));
+}
+
+#[test]
+fn can_shift_lanes_arithmetically() {
+    #[fhe_program(scheme = "bfv")]
+    fn scale(a: Cipher<Batched<4>>) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        (a.shl(2).shl(1), a.shl(3).shr(2))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(scale)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    // Each chain of shifts folds into a single plaintext multiplication.
+    let multiplications = app
+        .get_fhe_program(scale)
+        .unwrap()
+        .fhe_program_fn
+        .graph
+        .node_weights()
+        .filter(|n| matches!(n.operation, Operation::MultiplyPlaintext))
+        .count();
+
+    assert_eq!(multiplications, 2);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, -2, 3, 4], vec![5, 6, -7, 0]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![runtime.encrypt(a, &public_key).unwrap().into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(scale).unwrap(), args, &public_key)
+        .unwrap();
+
+    let shifted: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+    let rescaled: Batched<4> = runtime.decrypt(&result[1], &private_key).unwrap();
+
+    assert_eq!(shifted, [[8, -16, 24, 32], [40, 48, -56, 0]].into());
+    assert_eq!(rescaled, [[2, -4, 6, 8], [10, 12, -14, 0]].into());
+}
+
+#[test]
+fn can_rotate_and_swap_plaintext() {
+    #[fhe_program(scheme = "bfv")]
+    fn mask(a: Cipher<Batched<4>>, m: Batched<4>) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        (a * (m << 1).swap_rows(), a + m.rotate_right(2))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mask)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    // Plaintext rotations don't need Galois keys.
+    assert!(app
+        .get_fhe_program(mask)
+        .unwrap()
+        .metadata
+        .required_keys
+        .is_empty());
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let m = Batched::<4>::try_from([vec![1, 0, 0, 2], vec![0, 3, 0, 0]]).unwrap();
+
+    let args: Vec<FheProgramInput> =
+        vec![runtime.encrypt(a, &public_key).unwrap().into(), m.into()];
+
+    let result = runtime
+        .run(app.get_fhe_program(mask).unwrap(), args, &public_key)
+        .unwrap();
+
+    let masked: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+    let sum: Batched<4> = runtime.decrypt(&result[1], &private_key).unwrap();
+
+    assert_eq!(masked, a * (m << 1).swap_rows());
+    assert_eq!(masked, [[3, 0, 0, 0], [0, 0, 14, 8]].into());
+    assert_eq!(sum, a + (m >> 2));
+    assert_eq!(sum, [[1, 4, 4, 4], [5, 6, 7, 11]].into());
+}
+
+#[test]
+fn can_run_with_keys_for_required_rotations() {
+    #[fhe_program(scheme = "bfv")]
+    fn rotate(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        ((a << 1) >> 2).swap_rows()
+    }
+
+    let app = Compiler::new()
+        .fhe_program(rotate)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(rotate).unwrap();
+
+    assert_eq!(program.required_rotations(), vec![-2, 0, 1]);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys_for(&[program]).unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![runtime.encrypt(a, &public_key).unwrap().into()];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, ((a << 1) >> 2).swap_rows());
+}
+
+#[test]
+fn rotation_wider_than_row_fails_to_compile() {
+    #[fhe_program(scheme = "bfv")]
+    fn rotate(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a << 1_000_000
+    }
+
+    let result = Compiler::new()
+        .fhe_program(rotate)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile();
+
+    assert!(matches!(
+        result,
+        Err(Error::RotationOutOfRange {
+            amount: 1_000_000,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn run_plaintext_matches_encrypted_run() {
+    #[fhe_program(scheme = "bfv")]
+    fn mix(
+        a: Cipher<Batched<4>>,
+        b: Cipher<Batched<4>>,
+        m: Batched<4>,
+    ) -> (Cipher<Batched<4>>, Cipher<Batched<4>>) {
+        ((a << 1) * b.swap_rows() - b + m, -(a >> 3) + (b * m))
+    }
+
+    let app = Compiler::new()
+        .fhe_program(mix)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(mix).unwrap();
+    let params = app.params();
+
+    let runtime = Runtime::new_fhe(params).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+    let b = Batched::<4>::try_from([vec![-1, 2, -3, 4], vec![0, 9, 1, -2]]).unwrap();
+    let m = Batched::<4>::try_from([vec![1, 0, 0, 2], vec![0, 3, 0, 0]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![
+        runtime.encrypt(a, &public_key).unwrap().into(),
+        runtime.encrypt(b, &public_key).unwrap().into(),
+        m.into(),
+    ];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    let plain_result = program
+        .run_plaintext(&[
+            a.try_into_plaintext(params).unwrap(),
+            b.try_into_plaintext(params).unwrap(),
+            m.try_into_plaintext(params).unwrap(),
+        ])
+        .unwrap();
+
+    assert_eq!(plain_result.len(), 2);
+
+    for (c, p) in result.iter().zip(&plain_result) {
+        let decrypted: Batched<4> = runtime.decrypt(c, &private_key).unwrap();
+        let evaluated = Batched::<4>::try_from_plaintext(p, params).unwrap();
+
+        assert_eq!(decrypted, evaluated);
+    }
+
+    assert_eq!(
+        Batched::<4>::try_from_plaintext(&plain_result[0], params).unwrap(),
+        (a << 1) * b.swap_rows() - b + m
+    );
+}
+
+#[test]
+fn can_rotate_sub_cipher() {
+    #[fhe_program(scheme = "bfv")]
+    fn diff(a: Cipher<Batched<4>>) -> Cipher<Batched<4>> {
+        a.rotate_sub(1)
+    }
+
+    let app = Compiler::new()
+        .fhe_program(diff)
+        .additional_noise_budget(5)
+        .plain_modulus_constraint(PlainModulusConstraint::BatchingMinimum(0))
+        .compile()
+        .unwrap();
+
+    let program = app.get_fhe_program(diff).unwrap();
+
+    assert_eq!(program.required_rotations(), vec![1]);
+
+    let ops = program
+        .fhe_program_fn
+        .graph
+        .node_weights()
+        .filter(|n| matches!(n.operation, Operation::ShiftLeft | Operation::Sub))
+        .count();
+
+    assert_eq!(ops, 2);
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys_for(&[program]).unwrap();
+
+    let a = Batched::<4>::try_from([vec![1, 2, 3, 4], vec![5, 6, 7, 8]]).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![runtime.encrypt(a, &public_key).unwrap().into()];
+
+    let result = runtime.run(program, args, &public_key).unwrap();
+
+    let c: Batched<4> = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, a - (a << 1));
+    assert_eq!(c, [[-1, -1, -1, 3], [-1, -1, -1, 3]].into());
+}
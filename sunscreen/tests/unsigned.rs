@@ -71,10 +71,13 @@ macro_rules! op_proptest {
 
                     proptest!(ProptestConfig::with_cases(20), |(lhs in [ANY; 4], rhs in [ANY; 4])| {
 
+                        // Unsigned can't decode negative differences, so put
+                        // the larger operand first. Add and mul commute.
+                        let (a, b) = (U256::from_words(lhs), U256::from_words(rhs));
+                        let (a, b) = if a >= b { (a, b) } else { (b, a) };
+
                         // Test both operands as ciphertexts
-                        let a = U256::from_words(lhs);
                         let a_c = rt.encrypt(Unsigned256::from(a), pk).unwrap();
-                        let b = U256::from_words(rhs);
                         let b_c = rt.encrypt(Unsigned256::from(b), pk).unwrap();
                         let args: Vec<FheProgramInput> = vec![a_c.clone().into(), b_c.clone().into()];
 
@@ -142,11 +145,27 @@ where
 }
 
 #[test]
-fn underflow_wraps_properly() {
-    // U256
-    run_with(U256::ZERO, U256::ONE, U256::wrapping_sub, sub, sub_plain);
-    // U64
-    run_with(U64::ZERO, U64::ONE, U64::wrapping_sub, sub, sub_plain);
+fn underflow_fails_to_decrypt() {
+    let FheApp { app, rt, pk, sk } = &*FHE_APP;
+
+    let a = Unsigned256::from(U256::ZERO);
+    let b = Unsigned256::from(U256::ONE);
+
+    let a_c = rt.encrypt(a, pk).unwrap();
+    let b_c = rt.encrypt(b, pk).unwrap();
+
+    let args: Vec<FheProgramInput> = vec![a_c.clone().into(), b_c.into()];
+    let result = rt.run(app.get_fhe_program(sub).unwrap(), args, pk).unwrap();
+
+    assert!(rt.decrypt::<Unsigned256>(&result[0], sk).is_err());
+
+    // Same test but subtracting plaintext
+    let args_mixed: Vec<FheProgramInput> = vec![a_c.into(), b.into()];
+    let result_mixed = rt
+        .run(app.get_fhe_program(sub_plain).unwrap(), args_mixed, pk)
+        .unwrap();
+
+    assert!(rt.decrypt::<Unsigned256>(&result_mixed[0], sk).is_err());
 }
 
 #[test]
@@ -0,0 +1,77 @@
+use sunscreen::{
+    fhe_program,
+    types::{
+        bfv::{Signed, Unsigned64},
+        Cipher, TypeName,
+    },
+    Compiler, FheProgramInput, Runtime, RuntimeError,
+};
+
+#[test]
+fn run_rejects_mismatched_inputs() {
+    #[fhe_program(scheme = "bfv")]
+    fn add_plain(a: Cipher<Signed>, b: Signed) -> Cipher<Signed> {
+        a + b
+    }
+
+    let app = Compiler::new().fhe_program(add_plain).compile().unwrap();
+
+    let runtime = Runtime::new_fhe(app.params()).unwrap();
+
+    let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+    let program = app.get_fhe_program(add_plain).unwrap();
+
+    let a = runtime.encrypt(Signed::from(3), &public_key).unwrap();
+    let b = runtime.encrypt(Signed::from(4), &public_key).unwrap();
+
+    let valid: Vec<FheProgramInput> = vec![a.clone().into(), Signed::from(4).into()];
+
+    program.validate_inputs(&valid).unwrap();
+
+    let result = runtime.run(program, valid, &public_key).unwrap();
+    let c: Signed = runtime.decrypt(&result[0], &private_key).unwrap();
+
+    assert_eq!(c, 7.into());
+
+    // Too few arguments.
+    let args: Vec<FheProgramInput> = vec![a.clone().into()];
+
+    assert_eq!(
+        program.validate_inputs(&args),
+        Err(RuntimeError::ArgumentCountMismatch(2, 1))
+    );
+    assert_eq!(
+        runtime.run(program, args, &public_key).err(),
+        Some(RuntimeError::ArgumentCountMismatch(2, 1))
+    );
+
+    // A ciphertext where the program expects a plaintext.
+    let args: Vec<FheProgramInput> = vec![a.clone().into(), b.into()];
+
+    let err = runtime.run(program, args, &public_key).unwrap_err();
+
+    assert_eq!(
+        err,
+        RuntimeError::argument_type_mismatch(
+            1,
+            &Signed::type_name(),
+            &Cipher::<Signed>::type_name()
+        )
+    );
+    assert!(err.to_string().starts_with(
+        "Argument 1 should be a plaintext of type sunscreen::types::bfv::signed::Signed"
+    ));
+
+    // The right kind, but the wrong type.
+    let args: Vec<FheProgramInput> = vec![a.into(), Unsigned64::from(4).into()];
+
+    assert_eq!(
+        runtime.run(program, args, &public_key).err(),
+        Some(RuntimeError::argument_type_mismatch(
+            1,
+            &Signed::type_name(),
+            &Unsigned64::type_name()
+        ))
+    );
+}
@@ -1,4 +1,9 @@
-use sunscreen::{types::zkp::Field, zkp_program, Compiler, Runtime};
+use sunscreen::{
+    render_compiled_zkp_program,
+    types::zkp::Field,
+    zkp::{render_zkp_program, ZkpProgramFn},
+    zkp_program, Compiler, Runtime,
+};
 use sunscreen_runtime::{TypeNameInstance, ZkpProgramInput};
 use sunscreen_zkp_backend::{bulletproofs::BulletproofsBackend, FieldSpec, ZkpBackend};
 
@@ -218,3 +223,209 @@ fn builder_methods_work() {
         .verify()
         .unwrap();
 }
+
+#[test]
+fn gadget_public_outputs_are_checked_by_verifier() {
+    use petgraph::stable_graph::NodeIndex;
+    use sunscreen::{
+        invoke_gadget,
+        zkp::{with_zkp_ctx, ZkpContextOps},
+    };
+    use sunscreen_zkp_backend::{BigInt, Gadget, Result as ZkpResult};
+
+    /// A toy (insecure) hash that applies 3 rounds of `x -> x^3 + x + 7`.
+    struct ToyHash;
+
+    impl ToyHash {
+        fn hash(x: u64) -> u64 {
+            (0..3).fold(x as u128, |x, _| x * x * x + x + 7) as u64
+        }
+    }
+
+    impl Gadget for ToyHash {
+        fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> ZkpResult<Vec<BigInt>> {
+            Ok(vec![])
+        }
+
+        fn gen_circuit(
+            &self,
+            gadget_inputs: &[NodeIndex],
+            _hidden_inputs: &[NodeIndex],
+        ) -> Vec<NodeIndex> {
+            with_zkp_ctx(|ctx| {
+                let seven = ctx.add_constant(&BigInt::from(7u32));
+
+                let digest = (0..3).fold(gadget_inputs[0], |x, _| {
+                    let x_2 = ctx.add_multiplication(x, x);
+                    let x_3 = ctx.add_multiplication(x_2, x);
+                    let x_3_plus_x = ctx.add_addition(x_3, x);
+
+                    ctx.add_addition(x_3_plus_x, seven)
+                });
+
+                vec![digest]
+            })
+        }
+
+        fn gadget_input_count(&self) -> usize {
+            1
+        }
+
+        fn hidden_input_count(&self) -> usize {
+            0
+        }
+
+        fn public_outputs(&self) -> Vec<usize> {
+            vec![0]
+        }
+    }
+
+    #[zkp_program]
+    fn knows_preimage<F: FieldSpec>(preimage: Field<F>, #[public] digest: Field<F>) {
+        let outputs = invoke_gadget(ToyHash, preimage.ids);
+
+        assert_eq!(outputs.public_outputs(), outputs.to_vec());
+
+        outputs.bind_public(digest.ids);
+    }
+
+    let app = Compiler::new()
+        .zkp_backend::<BulletproofsBackend>()
+        .zkp_program(knows_preimage)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+
+    let program = app.get_zkp_program(knows_preimage).unwrap();
+
+    let preimage = BPField::from(3u64);
+    let digest = BPField::from(ToyHash::hash(3));
+    let wrong_digest = BPField::from(ToyHash::hash(4));
+
+    let proof = runtime
+        .prove(program, vec![preimage], vec![digest], vec![])
+        .unwrap();
+
+    runtime
+        .verify(program, &proof, vec![digest], vec![])
+        .unwrap();
+
+    assert!(runtime
+        .verify(program, &proof, vec![wrong_digest], vec![])
+        .is_err());
+
+    assert!(runtime
+        .prove(program, vec![preimage], vec![wrong_digest], vec![])
+        .is_err());
+}
+
+#[test]
+fn can_use_linear_combination_gadget() {
+    use sunscreen::{
+        invoke_gadget,
+        types::zkp::{BigInt, LinearCombinationGadget},
+    };
+
+    #[zkp_program]
+    fn weighted_sum<F: FieldSpec>(a: Field<F>, b: Field<F>, #[public] target: Field<F>) {
+        let weights = vec![BigInt::from(2u64), BigInt::from(3u64)];
+
+        invoke_gadget(
+            LinearCombinationGadget { weights },
+            &[a.ids[0], b.ids[0], target.ids[0]],
+        );
+    }
+
+    let app = Compiler::new()
+        .zkp_backend::<BulletproofsBackend>()
+        .zkp_program(weighted_sum)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+    let program = app.get_zkp_program(weighted_sum).unwrap();
+
+    let inputs = vec![BPField::from(5u8), BPField::from(7u8)];
+
+    let proof = runtime
+        .prove(program, inputs.clone(), vec![BPField::from(31u8)], vec![])
+        .unwrap();
+
+    runtime
+        .verify(program, &proof, vec![BPField::from(31u8)], vec![])
+        .unwrap();
+
+    assert!(runtime
+        .prove(program, inputs, vec![BPField::from(30u8)], vec![])
+        .is_err());
+}
+
+#[test]
+fn can_use_bounded_int_gadget() {
+    use sunscreen::{
+        invoke_gadget,
+        types::zkp::{BoundedIntGadget, ProgramNode},
+    };
+
+    #[zkp_program]
+    fn bounded<F: FieldSpec>(x: Field<F>) {
+        let value = invoke_gadget(BoundedIntGadget { bits: 4 }, x.ids);
+
+        ProgramNode::<Field<F>>::new(&value).constrain_eq(x);
+    }
+
+    let app = Compiler::new()
+        .zkp_backend::<BulletproofsBackend>()
+        .zkp_program(bounded)
+        .compile()
+        .unwrap();
+
+    let runtime = Runtime::new_zkp(BulletproofsBackend::new()).unwrap();
+    let program = app.get_zkp_program(bounded).unwrap();
+
+    let proof = runtime
+        .prove(program, vec![BPField::from(-8i64)], vec![], vec![])
+        .unwrap();
+
+    runtime.verify(program, &proof, vec![], vec![]).unwrap();
+
+    assert!(runtime
+        .prove(program, vec![BPField::from(8i64)], vec![], vec![])
+        .is_err());
+}
+
+#[test]
+fn can_render_zkp_program_to_dot() {
+    #[zkp_program]
+    fn add_mul<F: FieldSpec>(b: Field<F>, #[public] c: Field<F>, #[constant] a: Field<F>) {
+        let x = a * b + c;
+
+        x.constrain_eq(Field::from(42u32))
+    }
+
+    let frontend =
+        ZkpProgramFn::<<BulletproofsBackend as ZkpBackend>::Field>::build(&add_mul, ()).unwrap();
+
+    let app = Compiler::new()
+        .zkp_backend::<BulletproofsBackend>()
+        .zkp_program(add_mul)
+        .compile()
+        .unwrap();
+
+    let compiled = &app.get_zkp_program(add_mul).unwrap().zkp_program_fn;
+
+    for dot in [
+        render_zkp_program(&frontend),
+        render_compiled_zkp_program(compiled),
+    ] {
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("PrivateInput(0)\" shape=invhouse style=filled fillcolor=lightcoral"));
+        assert!(dot.contains("PublicInput(0)\" shape=invhouse style=filled fillcolor=lightblue"));
+        assert!(dot.contains("ConstantInput(0)\" shape=invhouse style=filled fillcolor=lightgrey"));
+        assert!(dot.contains("Constraint("));
+        assert!(dot.contains("shape=house style=filled fillcolor=gold"));
+        assert!(dot.contains("Mul\" shape=ellipse]"));
+        assert!(dot.contains("[ label=\"Left\"]"));
+    }
+}
@@ -19,13 +19,60 @@ use sunscreen_fhe_program::FheProgram;
 
 use transforms::transform_intermediate_representation;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/**
+ * Controls where backend compilation inserts relinearizations after
+ * ciphertext multiplications.
+ *
+ * # Remarks
+ * Multiplying two ciphertexts yields a larger ciphertext that must be
+ * relinearized before it's multiplied again, rotated or output. Additions,
+ * subtractions, negations and plaintext multiplications accept larger
+ * ciphertexts, so relinearization can be deferred past them.
+ */
+pub enum RelinearizationStrategy {
+    /**
+     * Relinearize immediately after every ciphertext multiplication.
+     */
+    Eager,
+
+    /**
+     * Relinearize only where an unrelinearized value feeds an operation
+     * that needs a relinearized ciphertext. E.g. a sum of products
+     * relinearizes once rather than once per product, but the additions
+     * operate on larger ciphertexts.
+     */
+    Lazy,
+
+    /**
+     * Use [`Lazy`](Self::Lazy) relinearization for a program when it
+     * inserts fewer relinearizations than [`Eager`](Self::Eager), and
+     * eager relinearization otherwise.
+     */
+    Auto,
+}
+
+impl Default for RelinearizationStrategy {
+    fn default() -> Self {
+        Self::Eager
+    }
+}
+
 /**
  * Clones the given [`FheProgram`] and compiles it.
  */
 pub fn compile(ir: &FheProgram) -> FheProgram {
+    compile_with(ir, RelinearizationStrategy::default())
+}
+
+/**
+ * Clones the given [`FheProgram`] and compiles it, inserting
+ * relinearizations according to `strategy`.
+ */
+pub fn compile_with(ir: &FheProgram, strategy: RelinearizationStrategy) -> FheProgram {
     let mut clone = ir.clone();
 
-    transform_intermediate_representation(&mut clone);
+    transform_intermediate_representation(&mut clone, strategy);
 
     clone
 }
@@ -33,8 +80,16 @@ pub fn compile(ir: &FheProgram) -> FheProgram {
 /**
  * Consumes the given [`FheProgram`] and compiles it.
  */
-pub fn compile_inplace(mut ir: FheProgram) -> FheProgram {
-    transform_intermediate_representation(&mut ir);
+pub fn compile_inplace(ir: FheProgram) -> FheProgram {
+    compile_inplace_with(ir, RelinearizationStrategy::default())
+}
+
+/**
+ * Consumes the given [`FheProgram`] and compiles it, inserting
+ * relinearizations according to `strategy`.
+ */
+pub fn compile_inplace_with(mut ir: FheProgram, strategy: RelinearizationStrategy) -> FheProgram {
+    transform_intermediate_representation(&mut ir, strategy);
 
     ir
 }
@@ -373,6 +373,7 @@ impl MeasuredModel {
                 &evaluator,
                 &relin_keys.as_ref(),
                 &galois_keys.as_ref(),
+                params,
             )
         }?;
 
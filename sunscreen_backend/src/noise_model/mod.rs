@@ -1,14 +1,17 @@
 use crossbeam::atomic::AtomicCell;
+use petgraph::stable_graph::NodeIndex;
 use sunscreen_compiler_common::GraphQuery;
 use sunscreen_fhe_program::{FheProgram, Literal, Operation::*};
 use sunscreen_runtime::traverse;
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 mod canonical_embedding_norm;
 mod measured_model;
+mod report;
 pub use canonical_embedding_norm::*;
 pub use measured_model::*;
+pub use report::*;
 
 /**
  * The standard deviation of the Gaussian noise introduced when encrypting
@@ -36,9 +39,35 @@ pub const NOISE_MAX: f64 = NOISE_STD_DEV * NOISE_NUM_STD_DEVIATIONS;
  * validate before using this function to ascertain this.
  */
 pub fn predict_noise(model: &(dyn NoiseModel + Sync), fhe_program: &FheProgram) -> Vec<f64> {
-    let mut noise_levels: Vec<AtomicCell<f64>> = Vec::with_capacity(fhe_program.graph.node_count());
+    let noise = predict_node_noise(model, fhe_program);
 
-    for _ in 0..fhe_program.graph.node_count() {
+    fhe_program
+        .graph
+        .node_indices()
+        .filter_map(|node_id| match fhe_program.graph[node_id].operation {
+            OutputCiphertext => Some(noise[&node_id]),
+            _ => None,
+        })
+        .collect()
+}
+
+/**
+ * Returns the predicted noise level of every node in the given
+ * [`FheProgram`], keyed by node index. Plaintext nodes have zero noise.
+ *
+ * # Panic
+ * Panics if the FHE program is not well formed. You should call
+ * validate before using this function to ascertain this.
+ */
+pub fn predict_node_noise(
+    model: &(dyn NoiseModel + Sync),
+    fhe_program: &FheProgram,
+) -> BTreeMap<NodeIndex, f64> {
+    // Removing nodes leaves holes in the graph's indices, so size this by the
+    // index bound rather than the node count.
+    let mut noise_levels: Vec<AtomicCell<f64>> = Vec::with_capacity(fhe_program.graph.node_bound());
+
+    for _ in 0..fhe_program.graph.node_bound() {
         noise_levels.push(AtomicCell::new(0.));
     }
 
@@ -118,6 +147,7 @@ pub fn predict_noise(model: &(dyn NoiseModel + Sync), fhe_program: &FheProgram)
                     model.output(output_id, noise_levels[x.index()].load())
                 }
                 Literal(_) => 0.0,
+                ShiftLeftPlaintext | ShiftRightPlaintext | SwapRowsPlaintext => 0.0,
                 ShiftLeft => {
                     let (left, right) = query.get_binary_operands(node_id).unwrap();
 
@@ -159,13 +189,10 @@ pub fn predict_noise(model: &(dyn NoiseModel + Sync), fhe_program: &FheProgram)
     )
     .unwrap(); // No errors returned, so unwrap is safe.
 
-    noise_levels
-        .iter()
-        .zip(fhe_program.graph.node_indices())
-        .filter_map(|(x, node_id)| match fhe_program.graph[node_id].operation {
-            OutputCiphertext => Some(x.load()),
-            _ => None,
-        })
+    fhe_program
+        .graph
+        .node_indices()
+        .map(|node_id| (node_id, noise_levels[node_id.index()].load()))
         .collect()
 }
 
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use petgraph::stable_graph::NodeIndex;
+use sunscreen_fhe_program::{FheProgram, Operation, OutputType, OutputTypeTrait};
+use sunscreen_runtime::{CompiledFheProgram, Params};
+
+use super::{noise_to_noise_budget, predict_node_noise, CanonicalEmbeddingNormModel};
+
+#[derive(Debug, Clone, PartialEq)]
+/**
+ * The predicted noise budgets of an FHE program's ciphertexts. See
+ * [`EstimateNoiseBudget`].
+ */
+pub struct NoiseReport {
+    /**
+     * The predicted noise budget in bits of each node that produces a
+     * ciphertext, keyed by its index in the program's graph. Nodes that
+     * produce plaintexts have no entry.
+     */
+    pub node_budgets: BTreeMap<NodeIndex, f64>,
+
+    /**
+     * The predicted noise budget in bits of each output, in order.
+     */
+    pub output_budgets: Vec<f64>,
+
+    /**
+     * The smallest predicted noise budget of any ciphertext in bits, or
+     * [`f64::INFINITY`] if the program has none.
+     */
+    pub min_budget: f64,
+
+    /**
+     * The node whose ciphertext has the smallest predicted noise budget.
+     */
+    pub min_budget_node: Option<NodeIndex>,
+}
+
+/**
+ * Statically estimates how much noise budget an FHE program consumes.
+ */
+pub trait EstimateNoiseBudget {
+    /**
+     * Predicts the worst-case noise budget of every ciphertext the program
+     * computes without running it.
+     *
+     * # Remarks
+     * Noise is tracked with the [`CanonicalEmbeddingNormModel`], which
+     * bounds noise growth for each operation using only the scheme
+     * parameters. Since these are upper bounds, the measured budgets are
+     * usually a number of bits larger. A ciphertext whose budget reaches
+     * zero may fail to decrypt.
+     *
+     * As this requires no keys or encryption, it's cheap enough to run
+     * before deploying a program or when tuning its parameters.
+     *
+     * # Panics
+     * If the program's parameters are invalid (e.g. they have no
+     * coefficient modulus), which can't happen for a compiled program.
+     */
+    fn estimate_noise_budget(&self) -> NoiseReport;
+}
+
+impl NoiseReport {
+    /**
+     * Predicts the noise budgets of `program` under `params`. See
+     * [`EstimateNoiseBudget::estimate_noise_budget`].
+     *
+     * # Panics
+     * If `params` are invalid (e.g. they have no coefficient modulus).
+     */
+    pub fn new(program: &FheProgram, params: &Params) -> Self {
+        let model = CanonicalEmbeddingNormModel::new(params).expect("Parameters should be valid");
+
+        let noise = predict_node_noise(&model, program);
+
+        let mut node_budgets = BTreeMap::new();
+        let mut output_budgets = vec![];
+        let mut min_budget = f64::INFINITY;
+        let mut min_budget_node = None;
+
+        for node_id in program.graph.node_indices() {
+            if program.graph[node_id].output_type() == OutputType::Plaintext {
+                continue;
+            }
+
+            let budget = noise_to_noise_budget(noise[&node_id]);
+
+            if matches!(
+                program.graph[node_id].operation,
+                Operation::OutputCiphertext
+            ) {
+                output_budgets.push(budget);
+            }
+
+            if budget < min_budget {
+                min_budget = budget;
+                min_budget_node = Some(node_id);
+            }
+
+            node_budgets.insert(node_id, budget);
+        }
+
+        NoiseReport {
+            node_budgets,
+            output_budgets,
+            min_budget,
+            min_budget_node,
+        }
+    }
+}
+
+impl EstimateNoiseBudget for CompiledFheProgram {
+    fn estimate_noise_budget(&self) -> NoiseReport {
+        NoiseReport::new(&self.fhe_program_fn, &self.metadata.params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seal_fhe::{CoefficientModulus, SecurityLevel};
+    use sunscreen_fhe_program::{FheProgramTrait, SchemeType};
+    use sunscreen_runtime::{CallSignature, FheProgramMetadata};
+
+    fn compile(ir: FheProgram) -> CompiledFheProgram {
+        let params = Params {
+            lattice_dimension: 8192,
+            plain_modulus: 1024,
+            coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        CompiledFheProgram {
+            fhe_program_fn: ir,
+            metadata: FheProgramMetadata {
+                params,
+                signature: CallSignature {
+                    arguments: vec![],
+                    returns: vec![],
+                    num_ciphertexts: vec![],
+                    return_names: vec![],
+                },
+                required_keys: vec![],
+                required_rotations: vec![],
+                annotations: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn deeper_outputs_have_less_budget() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let sum = ir.add_add(a, b);
+        ir.add_output_ciphertext(sum);
+
+        let mul = ir.add_multiply(a, b);
+        let relin = ir.add_relinearize(mul);
+        let mul_2 = ir.add_multiply(relin, a);
+        let relin_2 = ir.add_relinearize(mul_2);
+        ir.add_output_ciphertext(relin_2);
+
+        let program = compile(ir);
+
+        let report = program.estimate_noise_budget();
+
+        assert_eq!(report.node_budgets.len(), 9);
+        assert_eq!(report.output_budgets.len(), 2);
+        assert!(report.output_budgets[1] < report.output_budgets[0]);
+        assert!(report.min_budget > 0.);
+        assert_eq!(report.min_budget, report.output_budgets[1]);
+        assert_eq!(report.min_budget_node, Some(mul_2));
+    }
+
+    #[test]
+    fn budgets_are_keyed_by_node_index() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let unused = ir.add_input_ciphertext(0);
+        let a = ir.add_input_ciphertext(1);
+        let b = ir.add_input_plaintext(2);
+        let product = ir.add_multiply_plaintext(a, b);
+        let output = ir.add_output_ciphertext(product);
+
+        // Leaves a hole at the start of the graph's indices.
+        ir.graph.0.remove_node(unused);
+
+        let report = compile(ir).estimate_noise_budget();
+
+        assert_eq!(report.node_budgets.len(), 3);
+        assert!(!report.node_budgets.contains_key(&unused));
+        assert!(!report.node_budgets.contains_key(&b));
+        assert_eq!(report.node_budgets[&output], report.output_budgets[0]);
+        assert!(report.node_budgets[&a] > report.node_budgets[&product]);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet};
 use std::convert::Infallible;
 
 use sunscreen_compiler_common::{
@@ -10,7 +11,7 @@ use sunscreen_fhe_program::{
     Operation::{self, *},
 };
 
-use petgraph::{stable_graph::NodeIndex, visit::EdgeRef, Direction};
+use petgraph::{algo::toposort, stable_graph::NodeIndex, visit::EdgeRef, Direction};
 
 type FheGraphQuery<'a> = GraphQuery<'a, NodeInfo<Operation>, EdgeInfo>;
 
@@ -60,6 +61,67 @@ pub fn apply_insert_relinearizations(ir: &mut FheProgram) {
     .unwrap();
 }
 
+/**
+ * Defers relinearization until an unrelinearized value, i.e. a product
+ * of ciphertexts or a sum, difference, negation or plaintext product
+ * involving one, feeds an operation that needs a relinearized ciphertext.
+ * Each such value is relinearized at most once, no matter how many
+ * operations consume it. Returns the number of relinearizations inserted.
+ *
+ * # Remarks
+ * Summing products this way relinearizes the sum once rather than each
+ * product, at the cost of additions on larger ciphertexts.
+ */
+pub fn apply_insert_relinearizations_lazy(ir: &mut FheProgram) -> usize {
+    let graph = &mut ir.graph.0;
+
+    let order = toposort(&*graph, None).expect("FHE program should be acyclic");
+
+    let mut unrelinearized = HashSet::new();
+    let mut pending = vec![];
+
+    for id in order {
+        let unrelinearized_inputs = graph
+            .edges_directed(id, Direction::Incoming)
+            .filter(|e| unrelinearized.contains(&e.source()))
+            .map(|e| (e.id(), e.source(), e.target(), *e.weight()))
+            .collect::<Vec<_>>();
+
+        match graph[id].operation {
+            Multiply => {
+                pending.extend(unrelinearized_inputs);
+                unrelinearized.insert(id);
+            }
+            Add | AddPlaintext | Sub | SubPlaintext | Negate | MultiplyPlaintext => {
+                if !unrelinearized_inputs.is_empty() {
+                    unrelinearized.insert(id);
+                }
+            }
+            Relinearize => {}
+            _ => pending.extend(unrelinearized_inputs),
+        }
+    }
+
+    let mut relin_nodes = HashMap::new();
+
+    for (edge, source, target, operand_type) in pending {
+        let relin_node = *relin_nodes.entry(source).or_insert_with(|| {
+            let relin_node = graph.add_node(NodeInfo {
+                operation: Operation::Relinearize,
+            });
+
+            graph.add_edge(source, relin_node, EdgeInfo::Unary);
+
+            relin_node
+        });
+
+        graph.remove_edge(edge);
+        graph.add_edge(relin_node, target, operand_type);
+    }
+
+    relin_nodes.len()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,4 +201,69 @@ mod tests {
             .neighbors_directed(relin_nodes[0], Direction::Outgoing)
             .all(|i| { matches!(query.get_node(i).unwrap().operation, Operation::Add) }),);
     }
+
+    fn create_sum_of_products_dag() -> FheProgram {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let ab = ir.add_multiply(a, b);
+        let bb = ir.add_multiply(b, b);
+        let sum = ir.add_add(ab, bb);
+        let neg = ir.add_negate(sum);
+        ir.add_output_ciphertext(neg);
+        ir.add_output_ciphertext(sum);
+
+        ir
+    }
+
+    #[test]
+    fn lazily_relinearizes_sums_of_products() {
+        let mut ir = create_sum_of_products_dag();
+
+        assert_eq!(apply_insert_relinearizations_lazy(&mut ir), 2);
+        ir.validate().unwrap();
+
+        let query = GraphQuery::new(&ir.graph.0);
+
+        let relin_inputs = ir
+            .graph
+            .node_indices()
+            .filter(|i| matches!(ir.graph[*i].operation, Operation::Relinearize))
+            .map(|i| query.get_unary_operand(i).unwrap())
+            .map(|i| ir.graph[i].operation.clone())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(
+            relin_inputs,
+            HashSet::from([Operation::Add, Operation::Negate])
+        );
+
+        // Every output reads a relinearized ciphertext.
+        assert!(ir
+            .graph
+            .node_indices()
+            .filter(|i| matches!(ir.graph[*i].operation, Operation::OutputCiphertext))
+            .all(|i| matches!(
+                ir.graph[query.get_unary_operand(i).unwrap()].operation,
+                Operation::Relinearize
+            )));
+    }
+
+    #[test]
+    fn lazily_relinearizes_before_multiply() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let aa = ir.add_multiply(a, a);
+        let aaa = ir.add_multiply(aa, a);
+        ir.add_output_ciphertext(aaa);
+
+        assert_eq!(apply_insert_relinearizations_lazy(&mut ir), 2);
+
+        let query = GraphQuery::new(&ir.graph.0);
+        let (left, _) = query.get_binary_operands(aaa).unwrap();
+
+        assert!(matches!(ir.graph[left].operation, Operation::Relinearize));
+    }
 }
@@ -1,12 +1,39 @@
 mod insert_relinearizations;
 
 use petgraph::stable_graph::NodeIndex;
-use sunscreen_fhe_program::{FheProgram, FheProgramTrait};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation};
 
-use insert_relinearizations::apply_insert_relinearizations;
+use insert_relinearizations::{apply_insert_relinearizations, apply_insert_relinearizations_lazy};
 
-pub fn transform_intermediate_representation(ir: &mut FheProgram) {
-    apply_insert_relinearizations(ir);
+use crate::RelinearizationStrategy;
+
+pub fn transform_intermediate_representation(
+    ir: &mut FheProgram,
+    strategy: RelinearizationStrategy,
+) {
+    match strategy {
+        RelinearizationStrategy::Eager => apply_insert_relinearizations(ir),
+        RelinearizationStrategy::Lazy => {
+            apply_insert_relinearizations_lazy(ir);
+        }
+        RelinearizationStrategy::Auto => {
+            let multiplies = ir
+                .graph
+                .node_weights()
+                .filter(|n| matches!(n.operation, Operation::Multiply))
+                .count();
+
+            let mut lazy = ir.clone();
+
+            // Ties go to eager relinearization, which keeps ciphertexts
+            // small for the operations in between.
+            if apply_insert_relinearizations_lazy(&mut lazy) < multiplies {
+                *ir = lazy;
+            } else {
+                apply_insert_relinearizations(ir);
+            }
+        }
+    }
 
     // Dead code elimination.
     *ir = ir.prune(&ir.get_outputs().collect::<Vec<NodeIndex>>());
@@ -78,7 +78,7 @@ pub trait Operation: Clone + Debug + Hash + PartialEq + Eq {
 /**
  * A type which represents the fully qualified name and version of a datatype.
  */
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Type {
     /**
      * The fully qualified name of the type (including crate name)
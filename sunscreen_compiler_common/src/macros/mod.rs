@@ -330,6 +330,7 @@ pub fn emit_signature(args: &[Type], return_types: &[Type]) -> TokenStream2 {
             arguments: vec![#(#arg_get_types)*],
             returns: vec![#(#return_type_names)*],
             num_ciphertexts: vec![#(#return_type_sizes)*],
+            return_names: vec![],
         }
     }
 }
@@ -3,9 +3,11 @@ use crate::{
     internals::attr::{FheProgramAttrs, Scheme},
 };
 use proc_macro2::{Span, TokenStream};
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
 use sunscreen_compiler_common::macros::{extract_fn_arguments, ExtractFnArgumentsError, FnArgInfo};
-use syn::{parse_macro_input, spanned::Spanned, Error, Ident, ItemFn, Result, Type};
+use syn::{
+    parse_macro_input, spanned::Spanned, Attribute, Error, Ident, ItemFn, Meta, Result, Type,
+};
 
 pub fn fhe_program_impl(
     metadata: proc_macro::TokenStream,
@@ -19,6 +21,10 @@ pub fn fhe_program_impl(
     }
 }
 
+fn is_constant_attr(attr: &Attribute) -> bool {
+    matches!(&attr.meta, Meta::Path(p) if p.is_ident("constant"))
+}
+
 struct FheProgram<'a> {
     // The function passed to the proc macro
     item_fn: &'a ItemFn,
@@ -63,11 +69,21 @@ impl<'a> FheProgram<'a> {
                 })
                 .and_then(|v| {
                     for arg in &v {
-                        if !arg.0.is_empty() {
-                            return Err(Error::new(
-                                arg.0[0].span(),
-                                "FHE program arguments do not support attributes.",
-                            ));
+                        match &arg.0[..] {
+                            [] => {}
+                            [attr] if is_constant_attr(attr) => {}
+                            [attr] => {
+                                return Err(Error::new(
+                                    attr.span(),
+                                    "FHE program arguments only support #[constant].",
+                                ));
+                            }
+                            [_, attr, ..] => {
+                                return Err(Error::new(
+                                    attr.span(),
+                                    "FHE program arguments may only have one attribute.",
+                                ));
+                            }
                         }
                     }
                     Ok(v)
@@ -95,11 +111,22 @@ impl<'a> FheProgram<'a> {
         })
     }
 
-    // The sunscreen::CallSignature value
+    // The arguments bound at compile time, whose attributes were validated
+    // to be `#[constant]` in `new`.
+    fn constants(&self) -> impl Iterator<Item = (usize, &FnArgInfo<'a>)> {
+        self.unwrapped_inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, (attrs, _, _))| !attrs.is_empty())
+    }
+
+    // The sunscreen::CallSignature value. Constants aren't runtime inputs,
+    // so they're omitted.
     fn signature(&self) -> TokenStream {
         let argument_types = self
             .unwrapped_inputs
             .iter()
+            .filter(|(attrs, _, _)| attrs.is_empty())
             .map(|(_, t, _)| (**t).clone())
             .collect::<Vec<Type>>();
         emit_signature(&argument_types, &self.return_types)
@@ -120,13 +147,44 @@ impl<'a> FheProgram<'a> {
 
     // Variable declarations like (but not exactly):
     // `__c_0: FheProgramNode<Cipher<Signed>> = FheProgramNode::input()`
+    //
+    // Input ids are their node indices, so every input gets declared before
+    // any constant's literal nodes.
     fn fhe_arg_var_decl(&self) -> Vec<TokenStream> {
-        self.unwrapped_inputs
+        let inputs = self
+            .unwrapped_inputs
             .iter()
             .enumerate()
+            .filter(|(_, t)| t.0.is_empty())
             .map(|(i, t)| {
                 let var_name = format!("__c_{}", i);
                 create_fhe_program_node(&var_name, t.1)
+            });
+
+        let constants = self.constants().map(|(i, t)| {
+            let var_name = format!("__c_{}", i);
+            let plaintext = Ident::new(&format!("__k_{}", i), Span::call_site());
+            create_fhe_program_constant(&var_name, t.1, &plaintext)
+        });
+
+        inputs.chain(constants).collect()
+    }
+
+    // Encodes each constant's bound value under the build's params, e.g.
+    // `let __k_1 = self.constant_k.try_into_plaintext(params)?`
+    fn constant_encodings(&self) -> Vec<TokenStream> {
+        self.constants()
+            .map(|(i, (_, _, name))| {
+                let plaintext = Ident::new(&format!("__k_{}", i), Span::call_site());
+                let field = format_ident!("constant_{}", name);
+                let name_literal = name.to_string();
+
+                quote! {
+                    let #plaintext = match &self.#field {
+                        Some(v) => v.try_into_plaintext(params)?,
+                        None => return Err(Error::unbound_constant(#name_literal)),
+                    };
+                }
             })
             .collect()
     }
@@ -196,11 +254,46 @@ impl<'a> FheProgram<'a> {
 
         let fhe_program_name_literal = format!("{}", fhe_program_name);
 
+        let constant_encodings = self.constant_encodings();
+        let constant_fields = self
+            .constants()
+            .map(|(_, (_, _, name))| format_ident!("constant_{}", name))
+            .collect::<Vec<_>>();
+        let constant_types = self
+            .constants()
+            .map(|(_, (_, ty, _))| *ty)
+            .collect::<Vec<_>>();
+        let constant_names = self
+            .constants()
+            .map(|(_, (_, _, name))| *name)
+            .collect::<Vec<_>>();
+        let constant_setters = self
+            .constants()
+            .map(|(_, (_, _, name))| format_ident!("with_{}", name))
+            .collect::<Vec<_>>();
+        let constant_setter_docs = self.constants().map(|(_, (_, _, name))| {
+            format!(
+                "Binds the `#[constant]` argument `{}`, which `{}` treats as a literal when compiled.",
+                name, fhe_program_name
+            )
+        }).collect::<Vec<_>>();
+
         quote! {
             #[allow(non_camel_case_types)]
             #[derive(Clone)]
             #vis struct #fhe_program_struct_name {
-                chain_count: usize
+                chain_count: usize,
+                #(#constant_fields: Option<#constant_types>,)*
+            }
+
+            impl #fhe_program_struct_name {
+                #(
+                    #[doc = #constant_setter_docs]
+                    #vis fn #constant_setters(mut self, #constant_names: #constant_types) -> Self {
+                        self.#constant_fields = Some(#constant_names);
+                        self
+                    }
+                )*
             }
 
             impl sunscreen::FheProgramFn for #fhe_program_struct_name {
@@ -208,12 +301,14 @@ impl<'a> FheProgram<'a> {
                 fn build(&self, params: &sunscreen::Params) -> sunscreen::Result<sunscreen::fhe::FheFrontendCompilation> {
                     use std::cell::RefCell;
                     use std::mem::transmute;
-                    use sunscreen::{fhe::{CURRENT_FHE_CTX, FheContext}, Error, INDEX_ARENA, Result, Params, SchemeType, Value, types::{intern::{FheProgramNode, Input, Output, Coerce}, NumCiphertexts, Type, TypeName, SwapRows, LaneCount, TypeNameInstance}};
+                    use sunscreen::{fhe::{CURRENT_FHE_CTX, FheContext}, Error, INDEX_ARENA, Result, Params, SchemeType, Value, types::{intern::{FheProgramNode, Input, Constant, Output, Coerce}, NumCiphertexts, Type, TypeName, SwapRows, LaneCount, TypeNameInstance, TryIntoPlaintext}};
 
                     if SchemeType::Bfv != params.scheme_type {
                         return Err(Error::IncorrectScheme)
                     }
 
+                    #(#constant_encodings)*
+
                     let mut context = FheContext::new(params.clone());
 
                     CURRENT_FHE_CTX.with(|ctx| {
@@ -248,6 +343,7 @@ impl<'a> FheProgram<'a> {
                                     allocator.borrow_mut().reset()
                                 });
                                 ctx.swap(&RefCell::new(None));
+                                sunscreen::fhe::take_build_error();
                                 std::panic::resume_unwind(err)
                             }
                         };
@@ -258,6 +354,10 @@ impl<'a> FheProgram<'a> {
                         ctx.swap(&RefCell::new(None));
                     });
 
+                    if let Some(e) = sunscreen::fhe::take_build_error() {
+                        return Err(e);
+                    }
+
                     Ok(context.graph)
                 }
 
@@ -291,7 +391,8 @@ impl<'a> FheProgram<'a> {
 
             #[allow(non_upper_case_globals)]
             #vis const #fhe_program_name: #fhe_program_struct_name = #fhe_program_struct_name {
-                chain_count: #chain_count
+                chain_count: #chain_count,
+                #(#constant_fields: None,)*
             };
         }
     }
@@ -347,4 +448,36 @@ mod test {
 
         assert!(FheProgram::new(&attempt_fn, attrs).is_err())
     }
+
+    #[test]
+    fn allows_constant_arguments() {
+        let attrs = FheProgramAttrs {
+            scheme: Scheme::Bfv,
+            chain_count: 1,
+        };
+        let attempt_fn = parse_quote! {
+            fn scale(a: Cipher<Signed>, #[constant] k: Signed) -> Cipher<Signed> {
+                a * k
+            }
+        };
+
+        let program = FheProgram::new(&attempt_fn, attrs).unwrap();
+
+        assert_eq!(program.constants().map(|(i, _)| i).collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn disallows_other_argument_attributes() {
+        let attrs = FheProgramAttrs {
+            scheme: Scheme::Bfv,
+            chain_count: 1,
+        };
+        let attempt_fn = parse_quote! {
+            fn scale(a: Cipher<Signed>, #[public] k: Signed) -> Cipher<Signed> {
+                a * k
+            }
+        };
+
+        assert!(FheProgram::new(&attempt_fn, attrs).is_err())
+    }
 }
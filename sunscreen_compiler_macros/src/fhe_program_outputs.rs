@@ -0,0 +1,163 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, LitStr, Result, Type,
+};
+
+pub fn derive_fhe_program_outputs(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match derive_fhe_program_outputs_inner(input) {
+        Ok(t) => t.into(),
+        Err(e) => e.into_compile_error().into(),
+    }
+}
+
+fn derive_fhe_program_outputs_inner(input: DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let vis = &input.vis;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "FHE program outputs do not support generics.",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(f) => &f.named,
+            _ => {
+                return Err(Error::new(
+                    s.fields.span(),
+                    "FHE program outputs must have named fields.",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                Span::call_site(),
+                "FHE program outputs must be a struct.",
+            ))
+        }
+    };
+
+    for f in fields {
+        if !matches!(f.ty, Type::Path(_)) {
+            return Err(Error::new(
+                f.ty.span(),
+                "Each FHE program output field must be a named FHE type.",
+            ));
+        }
+    }
+
+    let field_vis = fields.iter().map(|f| &f.vis).collect::<Vec<_>>();
+    let field_names = fields
+        .iter()
+        .map(|f| f.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let field_types = fields.iter().map(|f| &f.ty).collect::<Vec<_>>();
+    let field_name_literals = field_names
+        .iter()
+        .map(|f| LitStr::new(&f.to_string(), f.span()))
+        .collect::<Vec<_>>();
+
+    let node_name = format_ident!("{}Node", name);
+    let ciphertexts_name = format_ident!("{}Ciphertexts", name);
+
+    let name_contents = LitStr::new(&format!("{{}}::{name}"), name.span());
+    let node_doc = format!("The values an FHE program computes for each field of [`{name}`].");
+    let ciphertexts_doc =
+        format!("The ciphertext an FHE program returned for each field of [`{name}`].");
+
+    Ok(quote! {
+        impl sunscreen::types::TypeName for #name {
+            fn type_name() -> sunscreen::types::Type {
+                let version = env!("CARGO_PKG_VERSION");
+
+                sunscreen::types::Type {
+                    name: format!(#name_contents, module_path!()),
+                    version: sunscreen::types::Version::parse(version).expect("Crate version is not a valid semver"),
+                    is_encrypted: true,
+                }
+            }
+        }
+
+        impl sunscreen::types::NumCiphertexts for #name {
+            const NUM_CIPHERTEXTS: usize = 0 #(+ <#field_types as sunscreen::types::NumCiphertexts>::NUM_CIPHERTEXTS)*;
+
+            const FIELD_NAMES: &'static [&'static str] = &[#(#field_name_literals),*];
+        }
+
+        #[doc = #node_doc]
+        #[derive(Clone, Copy)]
+        #vis struct #node_name {
+            #(#field_vis #field_names: sunscreen::types::intern::FheProgramNode<#field_types>,)*
+        }
+
+        impl sunscreen::types::intern::Coerce<sunscreen::types::intern::FheProgramNode<#name>> for #node_name {
+            fn coerce(self) -> sunscreen::types::intern::FheProgramNode<#name> {
+                let mut ids = vec![];
+
+                #(ids.extend_from_slice(self.#field_names.ids);)*
+
+                sunscreen::types::intern::FheProgramNode::new(&ids)
+            }
+        }
+
+        #[doc = #ciphertexts_doc]
+        #[derive(Debug, Clone)]
+        #vis struct #ciphertexts_name {
+            #(#field_vis #field_names: sunscreen::Ciphertext,)*
+        }
+
+        impl sunscreen::FheProgramOutputs for #name {
+            type Ciphertexts = #ciphertexts_name;
+
+            const NAMES: &'static [&'static str] = <Self as sunscreen::types::NumCiphertexts>::FIELD_NAMES;
+
+            fn from_ciphertext(ciphertext: sunscreen::Ciphertext) -> std::result::Result<#ciphertexts_name, sunscreen::RuntimeError> {
+                use sunscreen::types::{NumCiphertexts, TypeName};
+
+                let mut fields = ciphertext
+                    .split(
+                        &Self::type_name(),
+                        &[#((<#field_types as TypeName>::type_name(), <#field_types as NumCiphertexts>::NUM_CIPHERTEXTS)),*],
+                    )?
+                    .into_iter();
+
+                Ok(#ciphertexts_name {
+                    #(#field_names: fields.next().unwrap(),)*
+                })
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+
+    use super::*;
+
+    #[test]
+    fn derives_for_named_struct() {
+        let input = parse_quote! {
+            struct Stats {
+                sum: Cipher<Signed>,
+                product: Cipher<Signed>,
+            }
+        };
+
+        assert!(derive_fhe_program_outputs_inner(input).is_ok());
+    }
+
+    #[test]
+    fn disallows_tuple_structs() {
+        let input = parse_quote! {
+            struct Stats(Cipher<Signed>, Cipher<Signed>);
+        };
+
+        assert!(derive_fhe_program_outputs_inner(input).is_err());
+    }
+}
@@ -53,6 +53,32 @@ pub fn create_fhe_program_node(var_name: &str, arg_type: &Type) -> TokenStream2
     }
 }
 
+/**
+ * Emits code to make an FHE program node for a `#[constant]` argument
+ * of type T from its already encoded plaintext.
+ */
+pub fn create_fhe_program_constant(
+    var_name: &str,
+    arg_type: &Type,
+    plaintext: &Ident,
+) -> TokenStream2 {
+    let mapped_type = match map_fhe_type(arg_type) {
+        Ok(v) => v,
+        Err(MapFheTypeError::IllegalType(s)) => {
+            return quote_spanned! {
+                s => compile_error!("FHE program arguments must ")
+            };
+        }
+    };
+
+    let var_name = format_ident!("{}", var_name);
+
+    quote_spanned! {arg_type.span() =>
+        { struct _AssertConstant where #mapped_type: Constant; }
+        let #var_name: #mapped_type = <#mapped_type as Constant>::constant(&#plaintext);
+    }
+}
+
 #[derive(Debug)]
 pub enum ExtractReturnTypesError {
     IllegalType(Span),
@@ -193,6 +219,18 @@ pub fn emit_signature(args: &[Type], return_types: &[Type]) -> TokenStream2 {
         }
     });
 
+    // Only a lone return value can be a named output struct.
+    let return_names = if return_types.len() == 1 {
+        quote! {
+            <R0 as sunscreen::types::NumCiphertexts>::FIELD_NAMES
+                .iter()
+                .map(|x| x.to_string())
+                .collect()
+        }
+    } else {
+        quote! { vec![] }
+    };
+
     quote! {
         use sunscreen::types::TypeName;
 
@@ -203,6 +241,7 @@ pub fn emit_signature(args: &[Type], return_types: &[Type]) -> TokenStream2 {
             arguments: vec![#(#arg_get_types)*],
             returns: vec![#(#return_type_names)*],
             num_ciphertexts: vec![#(#return_type_sizes)*],
+            return_names: #return_names,
         }
     }
 }
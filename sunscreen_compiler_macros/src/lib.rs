@@ -8,6 +8,7 @@ extern crate proc_macro;
 
 mod error;
 mod fhe_program;
+mod fhe_program_outputs;
 mod fhe_program_transforms;
 mod internals;
 mod type_name;
@@ -21,6 +22,38 @@ pub fn derive_typename(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
     type_name::derive_typename(input)
 }
 
+#[proc_macro_derive(FheProgramOutputs)]
+/**
+ * Allows you to `#[derive(FheProgramOutputs)]` on a struct with named
+ * fields of FHE types, so an [`fhe_program`](macro@fhe_program) can return
+ * its values by name.
+ *
+ * # Remarks
+ * For a struct `Foo`, this generates a `FooNode` struct, which FHE programs
+ * returning `Foo` construct from the values they compute, and a
+ * `FooCiphertexts` struct, which `Runtime::run_named` returns with each
+ * field's ciphertext.
+ *
+ * # Examples
+ * ```rust,ignore
+ * # use sunscreen::{fhe_program, types::{bfv::Signed, Cipher}, FheProgramOutputs};
+ *
+ * #[derive(FheProgramOutputs)]
+ * struct Stats {
+ *   sum: Cipher<Signed>,
+ *   product: Cipher<Signed>,
+ * }
+ *
+ * #[fhe_program(scheme = "bfv")]
+ * fn stats(a: Cipher<Signed>, b: Cipher<Signed>) -> Stats {
+ *   StatsNode { sum: a + b, product: a * b }
+ * }
+ * ```
+ */
+pub fn derive_fhe_program_outputs(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fhe_program_outputs::derive_fhe_program_outputs(input)
+}
+
 #[proc_macro_attribute]
 /**
  * Specifies a function to be an [`fhe_program`](macro@fhe_program). An [`fhe_program`](macro@fhe_program) has any number of inputs that impl the
@@ -59,6 +92,26 @@ pub fn derive_typename(input: proc_macro::TokenStream) -> proc_macro::TokenStrea
  *   (a + b, b + c)
  * }
  * ```
+ *
+ * # Constant arguments
+ * Annotating an unencrypted argument with `#[constant]` makes it a
+ * compile-time constant rather than an input. Its value gets bound with the
+ * generated `with_<name>` method before compiling and appears in the graph as
+ * a literal, so the compiler can specialize on it. Constants don't appear in
+ * the program's call signature, so you don't pass them when running it.
+ *
+ * ```rust,ignore
+ * # use sunscreen::{fhe_program, types::{bfv::Signed, Cipher}, Compiler};
+ *
+ * #[fhe_program(scheme = "bfv")]
+ * fn scale(a: Cipher<Signed>, #[constant] k: Signed) -> Cipher<Signed> {
+ *   a * k
+ * }
+ *
+ * let app = Compiler::new()
+ *   .fhe_program(scale.with_k(Signed::from(3)))
+ *   .compile()?;
+ * ```
  */
 pub fn fhe_program(
     metadata: proc_macro::TokenStream,
@@ -15,7 +15,8 @@ use petgraph::{
     algo::tred::*,
     graph::{Graph, NodeIndex},
     stable_graph::StableGraph,
-    visit::IntoNeighbors,
+    visit::{EdgeRef, IntoNeighbors},
+    Direction,
 };
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +27,7 @@ pub use seal_fhe::SecurityLevel;
 
 use sunscreen_compiler_common::{CompilationResult, Context, EdgeInfo, NodeInfo};
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 #[derive(Debug, Clone, Copy, Serialize, Hash, Deserialize, PartialEq, Eq)]
 /**
@@ -147,6 +148,9 @@ impl OutputTypeTrait for NodeInfo<Operation> {
         match self.operation {
             Operation::InputPlaintext(_) => OutputType::Plaintext,
             Operation::Literal(_) => OutputType::Plaintext,
+            Operation::ShiftLeftPlaintext
+            | Operation::ShiftRightPlaintext
+            | Operation::SwapRowsPlaintext => OutputType::Plaintext,
             _ => OutputType::Ciphertext,
         }
     }
@@ -232,6 +236,21 @@ pub trait FheProgramTrait {
      */
     fn append_rotate_right(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex;
 
+    /**
+     * Appends an operation that rotates plaintext `x` left by the literal node at `y` places.
+     */
+    fn add_rotate_left_plaintext(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex;
+
+    /**
+     * Appends an operation that rotates plaintext `x` right by the literal node at `y` places.
+     */
+    fn add_rotate_right_plaintext(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex;
+
+    /**
+     * Appends an operation that swaps the rows of plaintext `x`.
+     */
+    fn add_swap_rows_plaintext(&mut self, x: NodeIndex) -> NodeIndex;
+
     /**
      * Returns the node indices of output ciphertexts
      */
@@ -267,6 +286,20 @@ pub trait FheProgramTrait {
      * operations.
      */
     fn requires_galois_keys(&self) -> bool;
+
+    /**
+     * Returns the distinct rotation steps this FHE program performs on
+     * ciphertexts, sorted in ascending order.
+     *
+     * # Remarks
+     * A left rotation by `k` places is step `k` and a right rotation is
+     * step `-k`. Row swaps are step 0. These match the steps SEAL accepts
+     * when generating Galois keys for specific rotations.
+     *
+     * Rotations whose amount isn't a `u64` literal are skipped, as they
+     * aren't valid programs.
+     */
+    fn required_rotations(&self) -> Vec<i64>;
 }
 
 impl FheProgramTrait for FheProgram {
@@ -318,6 +351,18 @@ impl FheProgramTrait for FheProgram {
         self.add_binary_operation(Operation::ShiftRight, x, y)
     }
 
+    fn add_rotate_left_plaintext(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex {
+        self.add_binary_operation(Operation::ShiftLeftPlaintext, x, y)
+    }
+
+    fn add_rotate_right_plaintext(&mut self, x: NodeIndex, y: NodeIndex) -> NodeIndex {
+        self.add_binary_operation(Operation::ShiftRightPlaintext, x, y)
+    }
+
+    fn add_swap_rows_plaintext(&mut self, x: NodeIndex) -> NodeIndex {
+        self.add_unary_operation(Operation::SwapRowsPlaintext, x)
+    }
+
     fn get_outputs(&self) -> Box<dyn Iterator<Item = NodeIndex> + '_> {
         Box::new(
             self.graph
@@ -345,8 +390,17 @@ impl FheProgramTrait for FheProgram {
 
         let mut visit: Vec<NodeIndex> = vec![];
 
+        // Converting to a Graph removes any holes in the StableGraph,
+        // shifting subsequent nodes' indices down.
+        let compact_ids = self
+            .graph
+            .node_indices()
+            .enumerate()
+            .map(|(compact_id, id)| (id, compact_id))
+            .collect::<HashMap<_, _>>();
+
         for n in nodes {
-            let mapped_id = revmap[n.index()];
+            let mapped_id = revmap[compact_ids[n]];
             visit.push(mapped_id);
             closure_set.insert(mapped_id);
         }
@@ -409,6 +463,31 @@ impl FheProgramTrait for FheProgram {
             )
         })
     }
+
+    fn required_rotations(&self) -> Vec<i64> {
+        let amount = |node: NodeIndex| {
+            self.graph
+                .edges_directed(node, Direction::Incoming)
+                .find(|e| e.weight().is_right())
+                .and_then(|e| match self.graph[e.source()].operation {
+                    Operation::Literal(Literal::U64(x)) => Some(x as i64),
+                    _ => None,
+                })
+        };
+
+        let rotations = self
+            .graph
+            .node_indices()
+            .filter_map(|n| match self.graph[n].operation {
+                Operation::ShiftLeft => amount(n),
+                Operation::ShiftRight => amount(n).map(|x| -x),
+                Operation::SwapRows => Some(0),
+                _ => None,
+            })
+            .collect::<BTreeSet<_>>();
+
+        rotations.into_iter().collect()
+    }
 }
 
 #[cfg(test)]
@@ -474,6 +553,28 @@ mod tests {
         assert!(eq(&pruned, &expected_ir));
     }
 
+    #[test]
+    fn can_prune_graph_with_holes() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct = ir.add_input_ciphertext(0);
+        let rem = ir.add_input_ciphertext(1);
+        let l1 = ir.add_input_literal(Literal::from(7u64));
+        let add = ir.add_add(ct, l1);
+        let l2 = ir.add_input_literal(Literal::from(5u64));
+        ir.add_multiply(add, l2);
+        ir.graph.0.remove_node(rem);
+
+        let pruned = ir.prune(&[add]);
+
+        let mut expected_ir = FheProgram::new(SchemeType::Bfv);
+        let ct = expected_ir.add_input_ciphertext(0);
+        let l1 = expected_ir.add_input_literal(Literal::from(7u64));
+        expected_ir.add_add(ct, l1);
+
+        assert!(eq(&pruned, &expected_ir));
+    }
+
     #[test]
     fn can_prune_with_multiple_nodes() {
         let mut ir = FheProgram::new(SchemeType::Bfv);
@@ -525,6 +626,30 @@ mod tests {
         assert!(eq(&pruned, &expected_ir));
     }
 
+    #[test]
+    fn can_get_required_rotations() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct = ir.add_input_ciphertext(0);
+        let three = ir.add_input_literal(Literal::U64(3));
+        let five = ir.add_input_literal(Literal::U64(5));
+
+        let left = ir.add_rotate_left(ct, three);
+        let left_2 = ir.add_rotate_left(left, three);
+        let right = ir.append_rotate_right(left_2, five);
+        let swap = ir.add_unary_operation(Operation::SwapRows, right);
+        ir.add_output_ciphertext(swap);
+
+        assert_eq!(ir.required_rotations(), vec![-5, 0, 3]);
+
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let ct = ir.add_input_ciphertext(0);
+        ir.add_output_ciphertext(ct);
+
+        assert!(ir.required_rotations().is_empty());
+    }
+
     #[test]
     fn can_roundtrip_scheme_type() {
         let schemes = [SchemeType::Bfv];
@@ -91,6 +91,50 @@ pub enum Operation {
      * Represents a ciphertext output for the FHE program.
      */
     OutputCiphertext,
+
+    /**
+     * Rotate each row in a plaintext Batched type to the left.
+     */
+    ShiftLeftPlaintext,
+
+    /**
+     * Rotate each row in a plaintext Batched type to the right.
+     */
+    ShiftRightPlaintext,
+
+    /**
+     * Swap the rows of a plaintext Batched type.
+     */
+    SwapRowsPlaintext,
+}
+
+impl Operation {
+    /**
+     * The name of this operation's variant, without any associated data
+     * (e.g. an input's argument index or a literal's value).
+     */
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ShiftLeft => "ShiftLeft",
+            Self::ShiftRight => "ShiftRight",
+            Self::SwapRows => "SwapRows",
+            Self::Relinearize => "Relinearize",
+            Self::Multiply => "Multiply",
+            Self::MultiplyPlaintext => "MultiplyPlaintext",
+            Self::Add => "Add",
+            Self::AddPlaintext => "AddPlaintext",
+            Self::Negate => "Negate",
+            Self::Sub => "Sub",
+            Self::SubPlaintext => "SubPlaintext",
+            Self::InputCiphertext(_) => "InputCiphertext",
+            Self::InputPlaintext(_) => "InputPlaintext",
+            Self::Literal(_) => "Literal",
+            Self::OutputCiphertext => "OutputCiphertext",
+            Self::ShiftLeftPlaintext => "ShiftLeftPlaintext",
+            Self::ShiftRightPlaintext => "ShiftRightPlaintext",
+            Self::SwapRowsPlaintext => "SwapRowsPlaintext",
+        }
+    }
 }
 
 impl Display for Operation {
@@ -111,6 +155,8 @@ impl OperationTrait for Operation {
                 | Self::SubPlaintext
                 | Self::ShiftLeft
                 | Self::ShiftRight
+                | Self::ShiftLeftPlaintext
+                | Self::ShiftRightPlaintext
         )
     }
 
@@ -124,7 +170,11 @@ impl OperationTrait for Operation {
     fn is_unary(&self) -> bool {
         matches!(
             self,
-            Self::Negate | Self::Relinearize | Self::SwapRows | Self::OutputCiphertext
+            Self::Negate
+                | Self::Relinearize
+                | Self::SwapRows
+                | Self::SwapRowsPlaintext
+                | Self::OutputCiphertext
         )
     }
 
@@ -73,6 +73,15 @@ pub(crate) fn validate_nodes(ir: &FheProgram) -> Vec<IRError> {
             Relinearize => Some(validate_unary_op_has_correct_operands(ir, i)),
             Literal(_) => None,
             SwapRows => None,
+            ShiftLeftPlaintext | ShiftRightPlaintext => {
+                Some(validate_binary_op_has_correct_operands(
+                    ir,
+                    i,
+                    OutputType::Plaintext,
+                    OutputType::Plaintext,
+                ))
+            }
+            SwapRowsPlaintext => Some(validate_unary_op_has_correct_operands(ir, i)),
         };
 
         if let Some(node_errors) = node_errors {
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+use crate::{CompiledFheProgram, Error, Params, Result};
+
+#[derive(Clone, Serialize, Deserialize)]
+/**
+ * A set of named [`CompiledFheProgram`]s that share the same [`Params`],
+ * packaged as a single artifact.
+ *
+ * # Remarks
+ * Use [`to_bytes`](Self::to_bytes) to ship the whole application and
+ * [`from_bytes`](Self::from_bytes) to load it back, then create a
+ * runtime for it with
+ * [`Runtime::new_for_application`](crate::Runtime::new_for_application).
+ */
+pub struct FheApplication {
+    params: Params,
+    programs: BTreeMap<String, CompiledFheProgram>,
+}
+
+impl FheApplication {
+    /**
+     * Bundles the given named programs with their shared `params`.
+     *
+     * # Remarks
+     * Returns [`Error::ParameterMismatch`] if any program was compiled
+     * with parameters other than `params`.
+     */
+    pub fn new<I, N>(params: Params, programs: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (N, CompiledFheProgram)>,
+        N: Into<String>,
+    {
+        let app = Self {
+            params,
+            programs: programs
+                .into_iter()
+                .map(|(name, program)| (name.into(), program))
+                .collect(),
+        };
+
+        app.validate()?;
+
+        Ok(app)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self
+            .programs
+            .values()
+            .any(|p| p.metadata.params != self.params)
+        {
+            return Err(Error::ParameterMismatch);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * The [`Params`] shared by every program in this application.
+     */
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /**
+     * Gets the [`CompiledFheProgram`] with the given name or [`None`] if
+     * not present.
+     */
+    pub fn get_fhe_program<N>(&self, name: N) -> Option<&CompiledFheProgram>
+    where
+        N: AsRef<str>,
+    {
+        self.programs.get(name.as_ref())
+    }
+
+    /**
+     * Returns an iterator over the programs in this application, sorted
+     * by name.
+     */
+    pub fn get_fhe_programs(&self) -> impl Iterator<Item = (&String, &CompiledFheProgram)> {
+        self.programs.iter()
+    }
+
+    /**
+     * Serialize this application into bytes.
+     *
+     * # Remarks
+     * This function internally uses bincode for serialization.
+     */
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(&self)?)
+    }
+
+    /**
+     * Deserialize an application from bytes.
+     *
+     * # Remarks
+     * This function internally uses bincode for serialization. Returns
+     * [`Error::ParameterMismatch`] if the programs don't all agree with
+     * the application's [`Params`].
+     */
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let app: Self = bincode::deserialize(data)?;
+
+        app.validate()?;
+
+        Ok(app)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CallSignature, FheProgramMetadata};
+    use seal_fhe::SecurityLevel;
+    use sunscreen_fhe_program::{FheProgram, FheProgramTrait, SchemeType};
+
+    fn params(plain_modulus: u64) -> Params {
+        Params {
+            lattice_dimension: 4096,
+            plain_modulus,
+            coeff_modulus: vec![1, 2, 3, 4],
+            security_level: SecurityLevel::TC128,
+            scheme_type: SchemeType::Bfv,
+        }
+    }
+
+    fn program(params: Params) -> CompiledFheProgram {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let sum = ir.add_add(a, b);
+        ir.add_output_ciphertext(sum);
+
+        CompiledFheProgram {
+            fhe_program_fn: ir,
+            metadata: FheProgramMetadata {
+                params,
+                signature: CallSignature {
+                    arguments: vec![],
+                    returns: vec![],
+                    num_ciphertexts: vec![],
+                    return_names: vec![],
+                },
+                required_keys: vec![],
+                required_rotations: vec![],
+                annotations: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_application() {
+        let app = FheApplication::new(
+            params(64),
+            [("add", program(params(64))), ("add_2", program(params(64)))],
+        )
+        .unwrap();
+
+        let app_2 = FheApplication::from_bytes(&app.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(app_2.params(), &params(64));
+        assert_eq!(
+            app_2.get_fhe_programs().map(|(n, _)| n).collect::<Vec<_>>(),
+            ["add", "add_2"]
+        );
+        assert!(app_2.get_fhe_program("mul").is_none());
+    }
+
+    #[test]
+    fn rejects_mismatched_params() {
+        assert!(matches!(
+            FheApplication::new(
+                params(64),
+                [
+                    ("add", program(params(64))),
+                    ("add_2", program(params(128)))
+                ],
+            ),
+            Err(Error::ParameterMismatch)
+        ));
+
+        let mut app = FheApplication::new(params(64), [("add", program(params(64)))]).unwrap();
+        app.params = params(128);
+
+        assert!(matches!(
+            FheApplication::from_bytes(&app.to_bytes().unwrap()),
+            Err(Error::ParameterMismatch)
+        ));
+    }
+}
@@ -1,6 +1,6 @@
 use static_assertions::const_assert;
 
-use crate::Type;
+use crate::{Params, Type};
 use sunscreen_zkp_backend::Error as ZkpError;
 
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
@@ -58,6 +58,34 @@ pub enum Error {
     #[error("Type mismatch, expected {:#?} found {:#?}", self.unwrap_type_mismatch_data().0, self.unwrap_type_mismatch_data().1)]
     TypeMismatch(Box<(Type, Type)>),
 
+    /**
+     * The wrong number of arguments were passed to an FHE program. Contains
+     * the expected and actual number of arguments.
+     */
+    #[error("Expected {0} arguments, got {1}")]
+    ArgumentCountMismatch(usize, usize),
+
+    /**
+     * An argument passed to an FHE program doesn't match the type or
+     * ciphertext/plaintext kind in its call signature. Contains the
+     * argument's position and its expected and actual types.
+     */
+    #[error(
+        "Argument {} should be a {}, but got a {}",
+        self.unwrap_argument_type_mismatch_data().0,
+        describe_argument(&self.unwrap_argument_type_mismatch_data().1),
+        describe_argument(&self.unwrap_argument_type_mismatch_data().2)
+    )]
+    ArgumentTypeMismatch(Box<(usize, Type, Type)>),
+
+    /**
+     * An argument passed to [`run_raw`](crate::GenericRuntime::run_raw)
+     * is a plaintext that hasn't been encoded. Contains the argument's
+     * position.
+     */
+    #[error("Argument {0} is a plaintext that hasn't been encoded")]
+    UnencodedPlaintext(usize),
+
     /**
      * The vector indicating the number of ciphertexts in the return types isn't the same length
      * as the signature's return type. Running valid FHE programs created by the Sunscreen compiler
@@ -66,6 +94,13 @@ pub enum Error {
     #[error("Data returned from FHE program doesn't match return signature")]
     ReturnTypeMetadataError,
 
+    /**
+     * An output index passed to [`run_outputs`](crate::GenericRuntime::run_outputs)
+     * exceeds the number of values the FHE program returns.
+     */
+    #[error("Output index {0} is out of range")]
+    OutputIndexOutOfRange(usize),
+
     /**
      * Decryption failed because the cipher text had too much noise.
      */
@@ -128,6 +163,20 @@ pub enum Error {
     #[error("Builder error: {0}")]
     BuilderError(#[from] crate::builder::BuilderError),
 
+    /**
+     * An error occurred when decoding a plaintext.
+     */
+    #[error("Decode error: {0}")]
+    DecodeError(#[from] DecodeError),
+
+    /**
+     * Refreshing ciphertexts with
+     * [`refresh`](crate::GenericRuntime::refresh) isn't supported under the
+     * contained parameters.
+     */
+    #[error("Refreshing ciphertexts isn't supported for parameters {0:?}")]
+    RefreshUnsupported(Box<Params>),
+
     /// Error when proving or verifying a linked proof.
     #[cfg(feature = "linkedproofs")]
     #[error("Linked proof error: {0}")]
@@ -141,6 +190,19 @@ pub enum Error {
 
 const_assert!(std::mem::size_of::<Error>() <= 24);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+/**
+ * An error that can occur when decoding a plaintext into a value.
+ */
+pub enum DecodeError {
+    /**
+     * The plaintext likely overflowed the plaintext modulus during
+     * computation, so its decoded value would be wrong.
+     */
+    #[error("The plaintext may have overflowed the plaintext modulus")]
+    PossibleOverflow,
+}
+
 impl Error {
     /**
      * Create an [`Error::ArgumentMismatch`].
@@ -156,6 +218,13 @@ impl Error {
         Self::TypeMismatch(Box::new((expected.clone(), actual.clone())))
     }
 
+    /**
+     * Create an [`Error::ArgumentTypeMismatch`].
+     */
+    pub fn argument_type_mismatch(index: usize, expected: &Type, actual: &Type) -> Self {
+        Self::ArgumentTypeMismatch(Box::new((index, expected.clone(), actual.clone())))
+    }
+
     /**
      * Create an [`Error::FheTypeError`].
      */
@@ -163,6 +232,13 @@ impl Error {
         Self::FheTypeError(Box::new(msg.to_owned()))
     }
 
+    /**
+     * Create an [`Error::RefreshUnsupported`].
+     */
+    pub fn refresh_unsupported(params: &Params) -> Self {
+        Self::RefreshUnsupported(Box::new(params.clone()))
+    }
+
     fn unwrap_argument_mismatch_data(&self) -> &(Vec<Type>, Vec<Type>) {
         match self {
             Self::ArgumentMismatch(d) => d,
@@ -176,6 +252,23 @@ impl Error {
             _ => panic!("Not a type mismatch"),
         }
     }
+
+    fn unwrap_argument_type_mismatch_data(&self) -> &(usize, Type, Type) {
+        match self {
+            Self::ArgumentTypeMismatch(d) => d,
+            _ => panic!("Not an argument type mismatch"),
+        }
+    }
+}
+
+fn describe_argument(ty: &Type) -> String {
+    let kind = if ty.is_encrypted {
+        "ciphertext"
+    } else {
+        "plaintext"
+    };
+
+    format!("{kind} of type {} (version {})", ty.name, ty.version)
 }
 
 impl From<bincode::Error> for Error {
@@ -0,0 +1,174 @@
+use seal_fhe::{Plaintext as SealPlaintext, Result as SealResult};
+
+use crate::Params;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * A permutation of the lanes of a batch encoded plaintext.
+ */
+pub enum PlaintextRotation {
+    /**
+     * Rotate each row the given number of places to the left.
+     */
+    Left(u64),
+
+    /**
+     * Rotate each row the given number of places to the right.
+     */
+    Right(u64),
+
+    /**
+     * Swap the two rows.
+     */
+    SwapRows,
+}
+
+impl PlaintextRotation {
+    /**
+     * Returns the Galois element that performs this rotation under the
+     * given lattice dimension. These match the elements SEAL uses when
+     * rotating ciphertexts.
+     */
+    fn galois_element(&self, lattice_dimension: u64) -> u64 {
+        let m = 2 * lattice_dimension;
+        let row_len = lattice_dimension / 2;
+
+        let steps = match self {
+            Self::Left(x) => x % row_len,
+            Self::Right(x) => (row_len - x % row_len) % row_len,
+            Self::SwapRows => return m - 1,
+        };
+
+        (0..steps).fold(1, |elt, _| (elt * 3) % m)
+    }
+}
+
+/**
+ * Permutes the lanes of a batch encoded plaintext without encrypting it.
+ *
+ * # Remarks
+ * Rotating a ciphertext applies a Galois automorphism `x -> x^g` to its
+ * polynomials followed by a key switch. Plaintexts need no key switch, so
+ * we apply the automorphism directly to the coefficients, which maps the
+ * `i`th coefficient to `x^(i * g)` and negates it modulo the plaintext
+ * modulus if the exponent wraps past the lattice dimension. Decoding the
+ * result yields the same lanes as decrypting the equivalent ciphertext
+ * rotation.
+ *
+ * This requires no keys and costs `O(n)` operations.
+ */
+pub fn rotate_plaintext(
+    plaintext: &SealPlaintext,
+    rotation: PlaintextRotation,
+    params: &Params,
+) -> SealResult<SealPlaintext> {
+    let n = params.lattice_dimension;
+
+    let coefficients = (0..u64::min(plaintext.len() as u64, n))
+        .map(|i| plaintext.get_coefficient(i as usize))
+        .collect::<Vec<_>>();
+
+    let rotated = rotate_coefficients(&coefficients, rotation, params);
+
+    let mut result = SealPlaintext::new()?;
+    result.resize(n as usize);
+
+    for (i, c) in rotated.into_iter().enumerate() {
+        result.set_coefficient(i, c);
+    }
+
+    Ok(result)
+}
+
+/**
+ * Applies the Galois automorphism for `rotation` to the coefficients of a
+ * plaintext polynomial, as [`rotate_plaintext`] does. Returns exactly
+ * `params.lattice_dimension` coefficients.
+ */
+pub(crate) fn rotate_coefficients(
+    coefficients: &[u64],
+    rotation: PlaintextRotation,
+    params: &Params,
+) -> Vec<u64> {
+    let n = params.lattice_dimension;
+    let t = params.plain_modulus;
+    let g = rotation.galois_element(n);
+
+    let mut result = vec![0; n as usize];
+
+    for (i, c) in coefficients.iter().take(n as usize).enumerate() {
+        let j = (i as u64 * g) % (2 * n);
+
+        if j < n {
+            result[j as usize] = *c;
+        } else {
+            result[(j - n) as usize] = (t - c) % t;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seal_fhe::{
+        BFVEncoder, BfvEncryptionParametersBuilder, CoefficientModulus, Context, PlainModulus,
+        SecurityLevel,
+    };
+    use sunscreen_fhe_program::SchemeType;
+
+    #[test]
+    fn rotating_plaintext_permutes_lanes() {
+        let n = 4096;
+
+        let plain_modulus = PlainModulus::batching(n, 20).unwrap();
+        let coeff_modulus = CoefficientModulus::bfv_default(n, SecurityLevel::TC128).unwrap();
+
+        let seal_params = BfvEncryptionParametersBuilder::new()
+            .set_poly_modulus_degree(n)
+            .set_coefficient_modulus(coeff_modulus.clone())
+            .set_plain_modulus(plain_modulus.clone())
+            .build()
+            .unwrap();
+
+        let context = Context::new(&seal_params, false, SecurityLevel::TC128).unwrap();
+        let encoder = BFVEncoder::new(&context).unwrap();
+
+        let params = Params {
+            lattice_dimension: n,
+            coeff_modulus: coeff_modulus.iter().map(|x| x.value()).collect(),
+            plain_modulus: plain_modulus.value(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let row_len = n as usize / 2;
+        let data = (0..n as i64).map(|x| x - 100).collect::<Vec<_>>();
+        let plaintext = encoder.encode_signed(&data).unwrap();
+
+        let rotate = |rotation| {
+            let p = rotate_plaintext(&plaintext, rotation, &params).unwrap();
+            encoder.decode_signed(&p).unwrap()
+        };
+
+        let expected =
+            |f: &dyn Fn(usize) -> usize| (0..n as usize).map(|i| data[f(i)]).collect::<Vec<_>>();
+
+        let row_offset = |i: usize| i / row_len * row_len;
+
+        assert_eq!(
+            rotate(PlaintextRotation::Left(3)),
+            expected(&|i| row_offset(i) + (i + 3) % row_len)
+        );
+        assert_eq!(
+            rotate(PlaintextRotation::Right(3)),
+            expected(&|i| row_offset(i) + (i + row_len - 3) % row_len)
+        );
+        assert_eq!(
+            rotate(PlaintextRotation::SwapRows),
+            expected(&|i| (i + row_len) % n as usize)
+        );
+        assert_eq!(rotate(PlaintextRotation::Left(0)), data);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::serialization::WithContext;
+use crate::{Params, RequiredKeys};
 
 use seal_fhe::{
     GaloisKeys, PublicKey as SealPublicKey, RelinearizationKeys, SecretKey as SealSecretKey,
@@ -52,6 +53,122 @@ pub struct PublicKey {
  */
 pub struct PrivateKey(pub(crate) WithContext<SealSecretKey>);
 
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+/**
+ * The keys used to [`refresh`](crate::GenericRuntime::refresh) ciphertexts,
+ * restoring their noise budget.
+ *
+ * # Remarks
+ * Create these with
+ * [`generate_bootstrap_keys`](crate::GenericRuntime::generate_bootstrap_keys).
+ * Like the keys in a [`PublicKey`], they may be freely shared.
+ */
+pub struct BootstrapKeys {
+    pub(crate) params: Params,
+}
+
+impl BootstrapKeys {
+    /**
+     * The parameters these keys were generated for.
+     */
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/**
+ * Estimated sizes, in bytes, of the keys
+ * [`generate_keys`](crate::GenericRuntime::generate_keys) creates for a
+ * set of [`Params`]. See
+ * [`estimate_key_sizes`](crate::GenericRuntime::estimate_key_sizes).
+ *
+ * # Remarks
+ * These are the sizes of the keys' polynomials, which dominate both their
+ * memory footprint and their serialized size. Serialization compresses
+ * keys, so serialized keys are usually somewhat smaller.
+ */
+pub struct KeySizeReport {
+    /**
+     * The size of the public key used for encryption.
+     */
+    pub public_key: usize,
+
+    /**
+     * The size of the private key.
+     */
+    pub private_key: usize,
+
+    /**
+     * The size of the relinearization keys, or 0 if the parameters don't
+     * support them.
+     */
+    pub relin_keys: usize,
+
+    /**
+     * The size of the Galois keys that support every rotation, or 0 if the
+     * parameters don't support them.
+     */
+    pub galois_keys: usize,
+
+    /**
+     * The size of the Galois key for each rotation step when generating
+     * keys for only some steps (see
+     * [`generate_keys_for`](crate::GenericRuntime::generate_keys_for)).
+     */
+    pub galois_key_per_rotation: usize,
+}
+
+impl KeySizeReport {
+    /**
+     * Computes the key sizes for the given parameters.
+     *
+     * # Remarks
+     * Each key is made of polynomials with `lattice_dimension` 8-byte
+     * coefficients for each modulus in `coeff_modulus`. The public key
+     * has 2 such polynomials and the private key 1. Relinearization keys
+     * and the key for each Galois element hold a public key for each
+     * modulus except the last (special) one. Galois keys for every
+     * rotation need `2 * log2(lattice_dimension) - 1` such elements.
+     */
+    pub fn new(params: &Params) -> Self {
+        let n = params.lattice_dimension as usize;
+        let k = params.coeff_modulus.len();
+
+        let poly = n * k * std::mem::size_of::<u64>();
+        let public_key = 2 * poly;
+        let switch_key = k.saturating_sub(1) * public_key;
+        let galois_elements = 2 * n.max(2).ilog2() as usize - 1;
+
+        Self {
+            public_key,
+            private_key: poly,
+            relin_keys: switch_key,
+            galois_keys: galois_elements * switch_key,
+            galois_key_per_rotation: switch_key,
+        }
+    }
+
+    /**
+     * The total size of the public key and the `required_keys` an FHE
+     * program needs, e.g. from its
+     * [`FheProgramMetadata`](crate::FheProgramMetadata).
+     */
+    pub fn total(&self, required_keys: &[RequiredKeys]) -> usize {
+        let mut total = self.public_key;
+
+        if required_keys.contains(&RequiredKeys::Relin) {
+            total += self.relin_keys;
+        }
+
+        if required_keys.contains(&RequiredKeys::Galois) {
+            total += self.galois_keys;
+        }
+
+        total
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,4 +305,58 @@ mod tests {
         );
         assert_eq!(private_key.0.as_bytes(), private_2.0.as_bytes());
     }
+
+    #[test]
+    fn can_estimate_key_sizes() {
+        let params = Params {
+            lattice_dimension: 8192,
+            security_level: SecurityLevel::TC128,
+            plain_modulus: PlainModulus::batching(8192, 20).unwrap().value(),
+            scheme_type: SchemeType::Bfv,
+            coeff_modulus: CoefficientModulus::bfv_default(8192, SecurityLevel::TC128)
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+        };
+
+        let runtime = Runtime::new_fhe(&params).unwrap();
+
+        let report = runtime.estimate_key_sizes();
+
+        assert_eq!(report, KeySizeReport::new(&params));
+        assert_eq!(report.galois_keys, 25 * report.galois_key_per_rotation);
+        assert_eq!(
+            report.total(&[RequiredKeys::Relin]),
+            report.public_key + report.relin_keys
+        );
+
+        let (public_key, private_key) = runtime.generate_keys().unwrap();
+
+        // Serialization compresses keys a bit.
+        let is_close = |estimate: usize, actual: usize| actual <= estimate && estimate < 2 * actual;
+
+        assert!(is_close(
+            report.public_key,
+            public_key.public_key.data.as_bytes().unwrap().len()
+        ));
+        assert!(is_close(
+            report.private_key,
+            private_key.0.data.as_bytes().unwrap().len()
+        ));
+        assert!(is_close(
+            report.relin_keys,
+            public_key.relin_key.unwrap().data.as_bytes().unwrap().len()
+        ));
+        assert!(is_close(
+            report.galois_keys,
+            public_key
+                .galois_key
+                .unwrap()
+                .data
+                .as_bytes()
+                .unwrap()
+                .len()
+        ));
+    }
 }
@@ -3,9 +3,11 @@
 
 //! This crate contains the types and functions for executing a Sunscreen FHE or ZKP program.
 
+mod application;
 mod array;
 mod builder;
 mod error;
+mod galois;
 mod keys;
 #[cfg(feature = "linkedproofs")]
 mod linked;
@@ -14,14 +16,19 @@ mod run;
 mod runtime;
 mod serialization;
 
-use std::sync::Arc;
+use std::{
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
 use seal_fhe::{Ciphertext as SealCiphertext, Plaintext as SealPlaintext};
 use serde::{Deserialize, Serialize};
 use sunscreen_zkp_backend::BigInt;
 
+pub use application::*;
 pub use builder::*;
 pub use error::*;
+pub use galois::*;
 pub use keys::*;
 #[cfg(feature = "linkedproofs")]
 pub use linked::*;
@@ -98,6 +105,72 @@ impl InnerPlaintext {
             Self::Seal(d) => Ok(d),
         }
     }
+
+    /**
+     * Returns the polynomial coefficients of each plaintext inside this
+     * wrapper, starting with the constant term.
+     *
+     * # Remarks
+     * SEAL owns the memory backing its plaintexts, so this copies the
+     * coefficients out rather than borrowing them. A plaintext may store
+     * fewer coefficients than the lattice dimension, in which case the
+     * missing higher degree coefficients are zero.
+     */
+    pub fn coefficients(&self) -> Vec<Vec<u64>> {
+        match self {
+            Self::Seal(d) => d
+                .iter()
+                .map(|p| {
+                    (0..p.data.len())
+                        .map(|i| p.data.get_coefficient(i))
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    /**
+     * Creates a wrapper holding a single plaintext polynomial with the
+     * given coefficients, starting with the constant term. Useful for
+     * implementing custom encodings.
+     *
+     * # Remarks
+     * Returns [`Error::FheTypeError`] if there are more coefficients than
+     * the lattice dimension or any coefficient isn't less than the
+     * plaintext modulus.
+     */
+    pub fn from_coefficients(coeffs: &[u64], params: &Params) -> Result<Self> {
+        if coeffs.len() as u64 > params.lattice_dimension {
+            return Err(Error::fhe_type_error(&format!(
+                "Plaintext has {} coefficients, but the lattice dimension is {}",
+                coeffs.len(),
+                params.lattice_dimension
+            )));
+        }
+
+        if let Some((i, c)) = coeffs
+            .iter()
+            .enumerate()
+            .find(|(_, c)| **c >= params.plain_modulus)
+        {
+            return Err(Error::fhe_type_error(&format!(
+                "Coefficient {} is {}, which isn't less than the plaintext modulus {}",
+                i, c, params.plain_modulus
+            )));
+        }
+
+        let mut data = SealPlaintext::new()?;
+        data.resize(coeffs.len());
+
+        for (i, c) in coeffs.iter().enumerate() {
+            data.set_coefficient(i, *c);
+        }
+
+        Ok(Self::Seal(vec![WithContext {
+            params: params.clone(),
+            data,
+        }]))
+    }
 }
 
 #[derive(Clone)]
@@ -152,6 +225,52 @@ impl Plaintext {
     pub fn inner_as_seal_plaintext(&self) -> Result<&[WithContext<SealPlaintext>]> {
         self.inner.as_seal_plaintext()
     }
+
+    /**
+     * The parameters and coefficients of each inner plaintext polynomial
+     * with trailing zero coefficients removed, which uniquely determine
+     * the encoded value.
+     */
+    fn canonical_polynomials(&self) -> Vec<(&Params, Vec<u64>)> {
+        let params = match &self.inner {
+            InnerPlaintext::Seal(d) => d.iter().map(|p| &p.params),
+        };
+
+        params
+            .zip(self.inner.coefficients())
+            .map(|(params, mut coeffs)| {
+                let len = coeffs.iter().rposition(|c| *c != 0).map_or(0, |i| i + 1);
+                coeffs.truncate(len);
+
+                (params, coeffs)
+            })
+            .collect()
+    }
+}
+
+/**
+ * Plaintexts are equal if they have the same data type and encode the same
+ * polynomials under the same parameters.
+ *
+ * # Remarks
+ * Unlike comparing serialized bytes, this ignores how many coefficients
+ * the backend happens to store, so e.g. a plaintext with trailing zero
+ * coefficients equals one without them.
+ */
+impl PartialEq for Plaintext {
+    fn eq(&self, other: &Self) -> bool {
+        self.data_type == other.data_type
+            && self.canonical_polynomials() == other.canonical_polynomials()
+    }
+}
+
+impl Eq for Plaintext {}
+
+impl Hash for Plaintext {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data_type.hash(state);
+        self.canonical_polynomials().hash(state);
+    }
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -203,6 +322,145 @@ impl Ciphertext {
     pub fn inner_as_seal_ciphertext(&self) -> Result<&[WithContext<SealCiphertext>]> {
         self.inner.as_seal_ciphertext()
     }
+
+    /**
+     * Returns the number of backend ciphertexts this ciphertext encloses,
+     * which matches its data type's
+     * [`NUM_CIPHERTEXTS`](crate::NumCiphertexts::NUM_CIPHERTEXTS).
+     */
+    pub fn inner_len(&self) -> usize {
+        match &self.inner {
+            InnerCiphertext::Seal(c) => c.len(),
+        }
+    }
+
+    /**
+     * Describes this ciphertext's data type and the layout of each
+     * backend ciphertext it encloses, without exposing their contents.
+     */
+    pub fn shape(&self) -> CiphertextShape {
+        let components = match &self.inner {
+            InnerCiphertext::Seal(c) => c
+                .iter()
+                .map(|c| CiphertextComponent::Seal {
+                    num_polynomials: c.data.num_polynomials(),
+                    coeff_modulus_size: c.data.coeff_modulus_size(),
+                })
+                .collect(),
+        };
+
+        CiphertextShape {
+            data_type: self.data_type.clone(),
+            components,
+        }
+    }
+
+    /**
+     * Splits this ciphertext of type `expected` into consecutive
+     * ciphertexts with the given types and number of enclosed ciphertexts.
+     *
+     * # Remarks
+     * [`FheProgramOutputs`] implementations use this to unpack a struct an
+     * FHE program returns into its fields.
+     *
+     * Returns [`Error::TypeMismatch`] if this ciphertext's data type isn't
+     * `expected` and [`Error::IncorrectCiphertextCount`] if the counts don't
+     * sum to the number of ciphertexts this encloses.
+     */
+    pub fn split(self, expected: &Type, fields: &[(Type, usize)]) -> Result<Vec<Ciphertext>> {
+        if self.data_type != *expected {
+            return Err(Error::type_mismatch(expected, &self.data_type));
+        }
+
+        match self.inner {
+            InnerCiphertext::Seal(c) => {
+                if c.len() != fields.iter().map(|(_, count)| count).sum::<usize>() {
+                    return Err(Error::IncorrectCiphertextCount);
+                }
+
+                let mut c = c.into_iter();
+
+                Ok(fields
+                    .iter()
+                    .map(|(data_type, count)| Ciphertext {
+                        data_type: data_type.clone(),
+                        inner: InnerCiphertext::Seal(c.by_ref().take(*count).collect()),
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * The layout of a [`Ciphertext`]. See [`Ciphertext::shape`].
+ */
+pub struct CiphertextShape {
+    /**
+     * The data type the ciphertext encrypts.
+     */
+    pub data_type: Type,
+
+    /**
+     * A description of each enclosed backend ciphertext, in order.
+     */
+    pub components: Vec<CiphertextComponent>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/**
+ * Describes one backend ciphertext inside a [`Ciphertext`].
+ */
+pub enum CiphertextComponent {
+    /**
+     * A SEAL ciphertext.
+     */
+    Seal {
+        /**
+         * The number of polynomials. Fresh ciphertexts have 2 and
+         * multiplying without relinearizing adds more.
+         */
+        num_polynomials: u64,
+
+        /**
+         * The number of coefficient moduli remaining after any modulus
+         * switching.
+         */
+        coeff_modulus_size: u64,
+    },
+}
+
+/**
+ * A struct whose named fields an FHE program returns. Rather than
+ * implementing this yourself, `#[derive(FheProgramOutputs)]` on a struct
+ * whose fields are FHE types (e.g. `Cipher<Signed>`).
+ *
+ * # Remarks
+ * The derive generates two companion structs with the same fields. An FHE
+ * program returning `Foo` constructs a `FooNode` from the values it
+ * computes, and [`GenericRuntime::run_named`] returns a `FooCiphertexts`
+ * holding each field's [`Ciphertext`], so consumers access results by
+ * name rather than position.
+ */
+pub trait FheProgramOutputs: TypeName {
+    /**
+     * The struct holding the ciphertext of each field.
+     */
+    type Ciphertexts;
+
+    /**
+     * The name of each field, in declaration order.
+     */
+    const NAMES: &'static [&'static str];
+
+    /**
+     * Unpacks the ciphertext an FHE program returns for this type into
+     * its fields.
+     *
+     * Returns [`Error::TypeMismatch`] if `ciphertext` isn't of this type.
+     */
+    fn from_ciphertext(ciphertext: Ciphertext) -> Result<Self::Ciphertexts>;
 }
 
 /**
@@ -224,6 +482,12 @@ pub enum FheProgramInput {
      * The argument is a plaintext.
      */
     Plaintext(Box<dyn FheProgramInputTrait>),
+
+    /**
+     * The argument is an already encoded plaintext, which is passed to the
+     * FHE program without encoding it again.
+     */
+    EncodedPlaintext(Plaintext),
 }
 
 /**
@@ -260,6 +524,7 @@ impl TypeNameInstance for FheProgramInput {
         match self {
             Self::Ciphertext(c) => c.data_type.clone(),
             Self::Plaintext(p) => p.type_name_instance(),
+            Self::EncodedPlaintext(p) => p.data_type.clone(),
         }
     }
 }
@@ -270,6 +535,12 @@ impl From<Ciphertext> for FheProgramInput {
     }
 }
 
+impl From<Plaintext> for FheProgramInput {
+    fn from(val: Plaintext) -> Self {
+        Self::EncodedPlaintext(val)
+    }
+}
+
 impl<T> From<T> for FheProgramInput
 where
     T: FheProgramInputTrait + 'static,
@@ -339,6 +610,12 @@ pub trait NumCiphertexts {
      * The number of ciphertexts this type decomposes into.
      */
     const NUM_CIPHERTEXTS: usize;
+
+    /**
+     * The name of each field if this type derives [`FheProgramOutputs`],
+     * empty otherwise.
+     */
+    const FIELD_NAMES: &'static [&'static str] = &[];
 }
 
 /**
@@ -371,3 +648,101 @@ impl TypeNameInstance for Type {
         self.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use seal_fhe::SecurityLevel;
+    use sunscreen_fhe_program::SchemeType;
+
+    #[test]
+    fn can_round_trip_plaintext_coefficients() {
+        let params = Params {
+            lattice_dimension: 8,
+            coeff_modulus: vec![],
+            plain_modulus: 17,
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let coeffs = [1, 0, 16, 3];
+        let p = InnerPlaintext::from_coefficients(&coeffs, &params).unwrap();
+
+        assert_eq!(p.coefficients(), vec![coeffs.to_vec()]);
+        assert_eq!(p.as_seal_plaintext().unwrap()[0].params, params);
+
+        assert!(matches!(
+            InnerPlaintext::from_coefficients(&[0; 9], &params),
+            Err(Error::FheTypeError(_))
+        ));
+        assert!(matches!(
+            InnerPlaintext::from_coefficients(&[1, 17], &params),
+            Err(Error::FheTypeError(_))
+        ));
+    }
+
+    #[test]
+    fn plaintext_equality_ignores_trailing_zeros() {
+        let params = Params {
+            lattice_dimension: 8,
+            coeff_modulus: vec![],
+            plain_modulus: 17,
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::TC128,
+        };
+
+        let data_type = Type {
+            name: "Test".to_owned(),
+            version: semver::Version::new(1, 0, 0),
+            is_encrypted: false,
+        };
+
+        let plaintext = |coeffs: &[u64], params: &Params, data_type: &Type| Plaintext {
+            data_type: data_type.clone(),
+            inner: InnerPlaintext::from_coefficients(coeffs, params).unwrap(),
+        };
+
+        let hash = |p: &Plaintext| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            p.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let a = plaintext(&[1, 0, 16], &params, &data_type);
+        let b = plaintext(&[1, 0, 16, 0, 0], &params, &data_type);
+
+        // The backend plaintexts differ, but they encode the same value.
+        assert_ne!(a.inner, b.inner);
+        assert_eq!(a, b);
+        assert_eq!(hash(&a), hash(&b));
+
+        assert_eq!(
+            plaintext(&[], &params, &data_type),
+            plaintext(&[0, 0], &params, &data_type)
+        );
+
+        assert_ne!(a, plaintext(&[1, 0, 15], &params, &data_type));
+        assert_ne!(
+            a,
+            plaintext(
+                &[1, 0, 16],
+                &params,
+                &Type {
+                    is_encrypted: true,
+                    ..data_type.clone()
+                }
+            )
+        );
+        assert_ne!(
+            a,
+            plaintext(
+                &[1, 0, 16],
+                &Params {
+                    plain_modulus: 19,
+                    ..params.clone()
+                },
+                &data_type
+            )
+        );
+    }
+}
@@ -1,12 +1,19 @@
+use petgraph::{algo::toposort, Direction};
 use rlp::encode_list;
 use seal_fhe::SecurityLevel;
 pub use semver::Version;
 use serde::{Deserialize, Serialize};
 use sunscreen_compiler_common::Type;
-use sunscreen_fhe_program::{FheProgram, SchemeType};
+use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Operation, SchemeType};
 use sunscreen_zkp_backend::CompiledZkpProgram as ZkpProgram;
 
-use crate::{Error, Result};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::{
+    run_program_plaintext, Error, FheProgramInput, InnerPlaintext, Plaintext, Result,
+    TypeNameInstance, WithContext,
+};
 
 /**
  * Indicates the type signature of an FHE or ZKP program.
@@ -43,6 +50,18 @@ pub struct CallSignature {
      */
     // TODO This field is specific to FHE; should we segment the types here? CallSignature<Fhe|Zkp> ?
     pub num_ciphertexts: Vec<usize>,
+
+    /**
+     * The field names of the program's return value.
+     *
+     * # Remarks
+     * When the program returns a single value whose type derives
+     * [`FheProgramOutputs`](crate::FheProgramOutputs), this contains that
+     * type's [`NAMES`](crate::FheProgramOutputs::NAMES) in declaration order.
+     * Otherwise, this is empty.
+     */
+    #[serde(default)]
+    pub return_names: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -63,6 +82,13 @@ pub enum RequiredKeys {
      * The FHE program performs an operation that requires the public encryption key.
      */
     PublicKey,
+
+    /**
+     * The FHE program's outputs must be refreshed with
+     * [`refresh`](crate::GenericRuntime::refresh), which requires
+     * [`BootstrapKeys`](crate::BootstrapKeys).
+     */
+    Bootstrap,
 }
 
 #[derive(Debug, Clone, Serialize, Hash, Deserialize, PartialEq, Eq)]
@@ -193,6 +219,31 @@ pub struct FheProgramMetadata {
      * The set of keys required to run the FHE program.
      */
     pub required_keys: Vec<RequiredKeys>,
+
+    /**
+     * The distinct rotation steps the FHE program performs, sorted in
+     * ascending order. See
+     * [`CompiledFheProgram::required_rotations`].
+     */
+    #[serde(default)]
+    pub required_rotations: Vec<i64>,
+
+    /**
+     * Free-form key-value pairs attached when compiling, e.g. a build ID
+     * or source commit, so deployed programs can be traced to their
+     * source.
+     *
+     * # Remarks
+     * Annotations have no effect on compilation or execution. They're
+     * ordered by key, so the same annotations always serialize to the
+     * same bytes.
+     *
+     * Self-describing formats such as JSON read metadata serialized before
+     * this field existed as having no annotations. Formats that don't
+     * record field names, such as bincode, can't read such metadata.
+     */
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -212,6 +263,362 @@ pub struct CompiledFheProgram {
     pub metadata: FheProgramMetadata,
 }
 
+impl CompiledFheProgram {
+    /**
+     * Checks the number, type and ciphertext/plaintext kind of each input
+     * match this FHE program's call signature.
+     *
+     * # Remarks
+     * [`run`](crate::GenericRuntime::run) and its variants call this before
+     * running the program, so you only need to call it to check inputs
+     * ahead of time.
+     *
+     * Returns [`Error::ArgumentCountMismatch`] if the wrong number of inputs
+     * are given or [`Error::ArgumentTypeMismatch`] naming the first input
+     * that doesn't match its argument.
+     */
+    pub fn validate_inputs(&self, inputs: &[FheProgramInput]) -> Result<()> {
+        let expected = &self.metadata.signature.arguments;
+
+        if inputs.len() != expected.len() {
+            return Err(Error::ArgumentCountMismatch(expected.len(), inputs.len()));
+        }
+
+        for (i, (input, expected)) in inputs.iter().zip(expected).enumerate() {
+            let actual = input.type_name_instance();
+
+            if actual != *expected {
+                return Err(Error::argument_type_mismatch(i, expected, &actual));
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Runs this FHE program in the clear on the given plaintexts, returning
+     * what decrypting each value [`run`](crate::GenericRuntime::run)
+     * returns would yield.
+     *
+     * # Remarks
+     * Each input is the encoded plaintext of the corresponding argument,
+     * whether that argument is encrypted or not (e.g. from
+     * [`TryIntoPlaintext::try_into_plaintext`](crate::TryIntoPlaintext::try_into_plaintext)
+     * under this program's [`Params`]). The program's operations are
+     * interpreted directly over the plaintext polynomials (see
+     * [`run_program_plaintext`](crate::run_program_plaintext)), with
+     * rotations and row swaps permuting batched lanes exactly as they do
+     * on ciphertexts. This requires no keys and involves no noise, so it
+     * serves as a fast oracle for testing a program's logic; it can't
+     * detect a program exhausting its noise budget.
+     *
+     * Each returned plaintext has the unencrypted type of the
+     * corresponding return value, so decode it with
+     * [`TryFromPlaintext::try_from_plaintext`](crate::TryFromPlaintext::try_from_plaintext).
+     *
+     * Returns [`Error::ArgumentCountMismatch`] or
+     * [`Error::ArgumentTypeMismatch`] if the inputs don't match the
+     * program's call signature and [`Error::IncorrectCiphertextCount`] if
+     * they don't contain as many plaintexts as the program has inputs.
+     */
+    pub fn run_plaintext(&self, inputs: &[Plaintext]) -> Result<Vec<Plaintext>> {
+        self.fhe_program_fn.validate()?;
+
+        let signature = &self.metadata.signature;
+
+        if signature.num_ciphertexts.len() != signature.returns.len() {
+            return Err(Error::ReturnTypeMetadataError);
+        }
+
+        if inputs.len() != signature.arguments.len() {
+            return Err(Error::ArgumentCountMismatch(
+                signature.arguments.len(),
+                inputs.len(),
+            ));
+        }
+
+        let mut raw_inputs = vec![];
+
+        for (i, (input, expected)) in inputs.iter().zip(&signature.arguments).enumerate() {
+            let actual = Type {
+                is_encrypted: expected.is_encrypted,
+                ..input.data_type.clone()
+            };
+
+            if actual != *expected {
+                return Err(Error::argument_type_mismatch(i, expected, &actual));
+            }
+
+            match &input.inner {
+                InnerPlaintext::Seal(p) => raw_inputs.extend(p.iter().map(|p| p.data.clone())),
+            }
+        }
+
+        let expected_inputs = self
+            .fhe_program_fn
+            .graph
+            .node_weights()
+            .filter_map(|n| match n.operation {
+                Operation::InputCiphertext(id) | Operation::InputPlaintext(id) => Some(id + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        if raw_inputs.len() != expected_inputs {
+            return Err(Error::IncorrectCiphertextCount);
+        }
+
+        let params = &self.metadata.params;
+
+        let mut raw_outputs =
+            run_program_plaintext(&self.fhe_program_fn, &raw_inputs, params)?.into_iter();
+
+        if raw_outputs.len() != signature.num_ciphertexts.iter().sum::<usize>() {
+            return Err(Error::ReturnTypeMetadataError);
+        }
+
+        Ok(signature
+            .returns
+            .iter()
+            .zip(&signature.num_ciphertexts)
+            .map(|(data_type, count)| Plaintext {
+                data_type: Type {
+                    is_encrypted: false,
+                    ..data_type.clone()
+                },
+                inner: InnerPlaintext::Seal(
+                    raw_outputs
+                        .by_ref()
+                        .take(*count)
+                        .map(|data| WithContext {
+                            params: params.clone(),
+                            data,
+                        })
+                        .collect(),
+                ),
+            })
+            .collect())
+    }
+
+    /**
+     * Returns the distinct rotation steps this FHE program performs on
+     * ciphertexts, sorted in ascending order.
+     *
+     * # Remarks
+     * A left rotation by `k` places is step `k`, a right rotation is step
+     * `-k` and row swaps are step 0. The compiler records these when
+     * compiling the program and checks each fits in a batched row.
+     *
+     * [`generate_keys_for`](crate::GenericRuntime::generate_keys_for)
+     * creates Galois keys for only these steps.
+     */
+    pub fn required_rotations(&self) -> Vec<i64> {
+        self.metadata.required_rotations.clone()
+    }
+
+    /**
+     * Returns the multiplicative depth of this FHE program, i.e. the
+     * largest number of multiplications along any path from an input to
+     * an output.
+     *
+     * # Remarks
+     * Ciphertext-ciphertext multiplications always count towards depth.
+     * Plaintext-ciphertext multiplications grow noise far less, so they
+     * only count when `count_plaintext_multiplies` is true.
+     *
+     * # Panics
+     * If the program's graph contains a cycle, which can't happen for a
+     * compiled program.
+     */
+    pub fn multiplicative_depth(&self, count_plaintext_multiplies: bool) -> usize {
+        let graph = &self.fhe_program_fn.graph;
+
+        let order = toposort(&graph.0, None).expect("FHE program should be acyclic");
+
+        let mut depths = vec![0; graph.node_bound()];
+        let mut max_depth = 0;
+
+        for node in order {
+            let cost = match graph[node].operation {
+                Operation::Multiply => 1,
+                Operation::MultiplyPlaintext if count_plaintext_multiplies => 1,
+                _ => 0,
+            };
+
+            let depth = graph
+                .neighbors_directed(node, Direction::Incoming)
+                .map(|p| depths[p.index()])
+                .max()
+                .unwrap_or(0)
+                + cost;
+
+            depths[node.index()] = depth;
+            max_depth = max_depth.max(depth);
+        }
+
+        max_depth
+    }
+
+    /**
+     * Returns a static estimate of this FHE program's relative runtime
+     * cost using the default [`CostModel`].
+     *
+     * # Remarks
+     * See [`estimated_cost_with`](Self::estimated_cost_with).
+     */
+    pub fn estimated_cost(&self) -> f64 {
+        self.estimated_cost_with(&CostModel::default())
+    }
+
+    /**
+     * Returns a static estimate of this FHE program's relative runtime
+     * cost, summing `model`'s weight for each operation and scaling by
+     * the ciphertext size implied by the program's [`Params`].
+     *
+     * # Remarks
+     * The scale factor is the number of RNS coefficients in a ciphertext
+     * polynomial, i.e. the lattice dimension times the number of
+     * coefficient moduli. The result only has meaning relative to other
+     * estimates made with the same model.
+     */
+    pub fn estimated_cost_with(&self, model: &CostModel) -> f64 {
+        let params = &self.metadata.params;
+        let scale = params.lattice_dimension as f64 * params.coeff_modulus.len() as f64;
+
+        let weight = self
+            .fhe_program_fn
+            .graph
+            .node_weights()
+            .map(|n| model.weight(&n.operation))
+            .sum::<f64>();
+
+        weight * scale
+    }
+}
+
+impl Display for CompiledFheProgram {
+    /**
+     * Writes a short summary of this FHE program: its scheme, parameters,
+     * argument and return counts, node counts by operation and
+     * multiplicative depth (not counting plaintext multiplications).
+     */
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let params = &self.metadata.params;
+        let signature = &self.metadata.signature;
+        let graph = &self.fhe_program_fn.graph;
+
+        let mut op_counts = BTreeMap::new();
+
+        for node in graph.node_weights() {
+            *op_counts.entry(node.operation.name()).or_insert(0usize) += 1;
+        }
+
+        let op_counts = op_counts
+            .iter()
+            .map(|(op, count)| format!("{op}: {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(f, "FHE program ({:?})", params.scheme_type)?;
+        writeln!(
+            f,
+            "  params: lattice_dimension={}, plain_modulus={}, coeff_modulus={:?}, security_level={:?}",
+            params.lattice_dimension,
+            params.plain_modulus,
+            params.coeff_modulus,
+            params.security_level
+        )?;
+        writeln!(
+            f,
+            "  arguments: {}, returns: {}",
+            signature.arguments.len(),
+            signature.returns.len()
+        )?;
+        writeln!(f, "  nodes: {} ({op_counts})", graph.node_count())?;
+        write!(
+            f,
+            "  multiplicative depth: {}",
+            self.multiplicative_depth(false)
+        )
+    }
+}
+
+/**
+ * Per-operation weights used to estimate an FHE program's relative
+ * runtime cost. See [`CompiledFheProgram::estimated_cost_with`].
+ *
+ * # Remarks
+ * The defaults make ciphertext multiplication and relinearization
+ * dominant, rotations next and additions cheap. Inputs, literals,
+ * outputs and operations on plaintexts are free.
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostModel {
+    /**
+     * The weight of a ciphertext-ciphertext multiplication.
+     */
+    pub multiply: f64,
+
+    /**
+     * The weight of a relinearization.
+     */
+    pub relinearize: f64,
+
+    /**
+     * The weight of a row rotation or row swap on a ciphertext.
+     */
+    pub rotate: f64,
+
+    /**
+     * The weight of a ciphertext-plaintext multiplication.
+     */
+    pub multiply_plaintext: f64,
+
+    /**
+     * The weight of an addition, subtraction or negation.
+     */
+    pub add: f64,
+}
+
+impl Default for CostModel {
+    fn default() -> Self {
+        Self {
+            multiply: 10.0,
+            relinearize: 10.0,
+            rotate: 8.0,
+            multiply_plaintext: 2.0,
+            add: 1.0,
+        }
+    }
+}
+
+impl CostModel {
+    /**
+     * Returns this model's weight for the given operation.
+     */
+    pub fn weight(&self, operation: &Operation) -> f64 {
+        match operation {
+            Operation::Multiply => self.multiply,
+            Operation::Relinearize => self.relinearize,
+            Operation::ShiftLeft | Operation::ShiftRight | Operation::SwapRows => self.rotate,
+            Operation::MultiplyPlaintext => self.multiply_plaintext,
+            Operation::Add
+            | Operation::AddPlaintext
+            | Operation::Sub
+            | Operation::SubPlaintext
+            | Operation::Negate => self.add,
+            Operation::InputCiphertext(_)
+            | Operation::InputPlaintext(_)
+            | Operation::Literal(_)
+            | Operation::OutputCiphertext
+            | Operation::ShiftLeftPlaintext
+            | Operation::ShiftRightPlaintext
+            | Operation::SwapRowsPlaintext => 0.0,
+        }
+    }
+}
+
 /// A serializable list of metadata for a ZKP program.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZkpProgramMetadata {
@@ -268,4 +675,179 @@ mod tests {
         assert_eq!(deserialized.name, typename.name);
         assert_eq!(deserialized.version, typename.version);
     }
+
+    #[test]
+    fn can_compute_multiplicative_depth() {
+        use sunscreen_fhe_program::FheProgramTrait;
+
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let p = ir.add_input_plaintext(0);
+
+        // a * b * a, with a relinearization and addition that don't add depth.
+        let mul = ir.add_multiply(a, b);
+        let relin = ir.add_relinearize(mul);
+        let mul_2 = ir.add_multiply(relin, a);
+        let sum = ir.add_add(mul_2, b);
+        ir.add_output_ciphertext(sum);
+
+        // (a * p) * p on a separate path.
+        let mul_p = ir.add_multiply_plaintext(a, p);
+        let mul_p_2 = ir.add_multiply_plaintext(mul_p, p);
+        let mul_p_3 = ir.add_multiply_plaintext(mul_p_2, p);
+        ir.add_output_ciphertext(mul_p_3);
+
+        let program = CompiledFheProgram {
+            fhe_program_fn: ir,
+            metadata: FheProgramMetadata {
+                params: Params {
+                    lattice_dimension: 4096,
+                    plain_modulus: 64,
+                    coeff_modulus: vec![1, 2, 3, 4],
+                    security_level: SecurityLevel::TC128,
+                    scheme_type: SchemeType::Bfv,
+                },
+                signature: CallSignature {
+                    arguments: vec![],
+                    returns: vec![],
+                    num_ciphertexts: vec![],
+                    return_names: vec![],
+                },
+                required_keys: vec![],
+                required_rotations: vec![],
+                annotations: BTreeMap::new(),
+            },
+        };
+
+        assert_eq!(program.multiplicative_depth(false), 2);
+        assert_eq!(program.multiplicative_depth(true), 3);
+    }
+
+    #[test]
+    fn can_display_compiled_fhe_program_summary() {
+        use sunscreen_fhe_program::FheProgramTrait;
+
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        let relin = ir.add_relinearize(mul);
+        ir.add_output_ciphertext(relin);
+
+        let type_name = Type {
+            name: "foo::Bar".to_owned(),
+            version: Version::new(1, 0, 0),
+            is_encrypted: true,
+        };
+
+        let program = CompiledFheProgram {
+            fhe_program_fn: ir,
+            metadata: FheProgramMetadata {
+                params: Params {
+                    lattice_dimension: 4096,
+                    plain_modulus: 64,
+                    coeff_modulus: vec![1, 2, 3, 4],
+                    security_level: SecurityLevel::TC128,
+                    scheme_type: SchemeType::Bfv,
+                },
+                signature: CallSignature {
+                    arguments: vec![type_name.clone(), type_name.clone()],
+                    returns: vec![type_name],
+                    num_ciphertexts: vec![1],
+                    return_names: vec![],
+                },
+                required_keys: vec![RequiredKeys::Relin],
+                required_rotations: vec![],
+                annotations: BTreeMap::new(),
+            },
+        };
+
+        assert_eq!(
+            program.to_string(),
+            "FHE program (Bfv)\n\
+             \x20 params: lattice_dimension=4096, plain_modulus=64, coeff_modulus=[1, 2, 3, 4], security_level=TC128\n\
+             \x20 arguments: 2, returns: 1\n\
+             \x20 nodes: 5 (InputCiphertext: 2, Multiply: 1, OutputCiphertext: 1, Relinearize: 1)\n\
+             \x20 multiplicative depth: 1"
+        );
+    }
+
+    #[test]
+    fn can_estimate_cost() {
+        use sunscreen_fhe_program::FheProgramTrait;
+
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_ciphertext(1);
+        let mul = ir.add_multiply(a, b);
+        let relin = ir.add_relinearize(mul);
+        let sum = ir.add_add(relin, a);
+        ir.add_output_ciphertext(sum);
+
+        let program = CompiledFheProgram {
+            fhe_program_fn: ir,
+            metadata: FheProgramMetadata {
+                params: Params {
+                    lattice_dimension: 4096,
+                    plain_modulus: 64,
+                    coeff_modulus: vec![1, 2, 3, 4],
+                    security_level: SecurityLevel::TC128,
+                    scheme_type: SchemeType::Bfv,
+                },
+                signature: CallSignature {
+                    arguments: vec![],
+                    returns: vec![],
+                    num_ciphertexts: vec![],
+                    return_names: vec![],
+                },
+                required_keys: vec![],
+                required_rotations: vec![],
+                annotations: BTreeMap::new(),
+            },
+        };
+
+        assert_eq!(program.estimated_cost(), 21.0 * 4096.0 * 4.0);
+
+        let model = CostModel {
+            multiply: 1.0,
+            relinearize: 0.0,
+            add: 0.5,
+            ..CostModel::default()
+        };
+
+        assert_eq!(program.estimated_cost_with(&model), 1.5 * 4096.0 * 4.0);
+    }
+
+    #[test]
+    fn json_metadata_without_annotations_has_none() {
+        let metadata = FheProgramMetadata {
+            params: Params {
+                lattice_dimension: 4096,
+                plain_modulus: 64,
+                coeff_modulus: vec![1, 2, 3, 4],
+                security_level: SecurityLevel::TC128,
+                scheme_type: SchemeType::Bfv,
+            },
+            signature: CallSignature {
+                arguments: vec![],
+                returns: vec![],
+                num_ciphertexts: vec![],
+                return_names: vec![],
+            },
+            required_keys: vec![],
+            required_rotations: vec![],
+            annotations: BTreeMap::from([("build".to_owned(), "1234".to_owned())]),
+        };
+
+        let mut json = serde_json::to_value(&metadata).unwrap();
+        json.as_object_mut().unwrap().remove("annotations");
+
+        let metadata: FheProgramMetadata = serde_json::from_value(json).unwrap();
+
+        assert!(metadata.annotations.is_empty());
+    }
 }
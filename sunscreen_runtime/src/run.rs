@@ -1,10 +1,13 @@
-use crate::{InnerPlaintext, SealData};
+use crate::{
+    galois::rotate_coefficients, rotate_plaintext, InnerPlaintext, Params, PlaintextRotation,
+    SealData,
+};
 use static_assertions::const_assert;
 use sunscreen_compiler_common::{GraphQuery, GraphQueryError};
 use sunscreen_fhe_program::{FheProgram, FheProgramTrait, Literal, Operation::*};
 
 use crossbeam::atomic::AtomicCell;
-use petgraph::{stable_graph::NodeIndex, Direction};
+use petgraph::{algo::toposort, stable_graph::NodeIndex, Direction};
 
 use std::borrow::Cow;
 #[cfg(target_arch = "wasm32")]
@@ -81,6 +84,289 @@ impl From<SealError> for FheProgramRunFailure {
     }
 }
 
+fn get_data(
+    data: &[AtomicCell<Option<Arc<SealData>>>],
+    index: usize,
+) -> Result<&Arc<SealData>, FheProgramRunFailure> {
+    let data = data.get(index).ok_or(FheProgramRunFailure::MissingData)?;
+
+    // This is correct so long as the IR program is indeed a DAG executed in topological order
+    // Since for a given edge (x,y), x executes before y, the operand data that y needs
+    // from x will exist.
+    let val = unsafe { data.as_ptr().as_ref().unwrap() };
+
+    match val {
+        Some(v) => Ok(v),
+        None => Err(FheProgramRunFailure::MissingData),
+    }
+}
+
+fn get_ciphertext(
+    data: &[AtomicCell<Option<Arc<SealData>>>],
+    index: usize,
+) -> Result<&Ciphertext, FheProgramRunFailure> {
+    let val = get_data(data, index)?.as_ref();
+
+    match val {
+        SealData::Ciphertext(ref c) => Ok(c),
+        _ => Err(FheProgramRunFailure::ExpectedCiphertext),
+    }
+}
+
+fn get_plaintext(
+    data: &[AtomicCell<Option<Arc<SealData>>>],
+    index: usize,
+) -> Result<&Plaintext, FheProgramRunFailure> {
+    let val = get_data(data, index)?.as_ref();
+
+    match val {
+        SealData::Plaintext(ref c) => Ok(c),
+        _ => Err(FheProgramRunFailure::ExpectedPlaintext),
+    }
+}
+
+/**
+ * The state needed to run a single node of an [`FheProgram`].
+ */
+struct NodeRunner<'a, E> {
+    ir: &'a FheProgram,
+    inputs: &'a [Arc<SealData>],
+    evaluator: &'a E,
+    relin_keys: &'a Option<&'a RelinearizationKeys>,
+    galois_keys: &'a Option<&'a GaloisKeys>,
+    params: &'a Params,
+}
+
+impl<E: Evaluator> NodeRunner<'_, E> {
+    /**
+     * Runs the node at `index`, storing its result in `data`.
+     *
+     * # Safety
+     * The node's operands must have already run.
+     */
+    unsafe fn run_node(
+        &self,
+        index: NodeIndex,
+        data: &[AtomicCell<Option<Arc<SealData>>>],
+    ) -> Result<(), FheProgramRunFailure> {
+        let Self {
+            ir,
+            inputs,
+            evaluator,
+            relin_keys,
+            galois_keys,
+            params,
+        } = self;
+
+        let node = &ir.graph[index];
+        let query = GraphQuery::new(&ir.graph.0);
+
+        match &node.operation {
+            InputCiphertext(id) => {
+                data[index.index()].store(Some(inputs[*id].clone()));
+            }
+            InputPlaintext(id) => {
+                data[index.index()].store(Some(inputs[*id].clone()));
+            }
+            ShiftLeft => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = match ir.graph[right].operation {
+                    Literal(Literal::U64(v)) => v as i32,
+                    _ => panic!(
+                        "Illegal right operand for ShiftLeft: {:#?}",
+                        ir.graph[right].operation
+                    ),
+                };
+
+                let c = evaluator.rotate_rows(
+                    a,
+                    b,
+                    galois_keys
+                        .as_ref()
+                        .ok_or(FheProgramRunFailure::MissingGaloisKeys)?,
+                )?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            ShiftRight => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = match ir.graph[right].operation {
+                    Literal(Literal::U64(v)) => v as i32,
+                    _ => panic!(
+                        "Illegal right operand for ShiftLeft: {:#?}",
+                        ir.graph[right].operation
+                    ),
+                };
+
+                let c = evaluator.rotate_rows(
+                    a,
+                    -b,
+                    galois_keys
+                        .as_ref()
+                        .ok_or(FheProgramRunFailure::MissingGaloisKeys)?,
+                )?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            ShiftLeftPlaintext | ShiftRightPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_plaintext(&data, left.index())?;
+                let b = match ir.graph[right].operation {
+                    Literal(Literal::U64(v)) => v,
+                    _ => panic!(
+                        "Illegal right operand for {}: {:#?}",
+                        node.operation, ir.graph[right].operation
+                    ),
+                };
+
+                let rotation = match node.operation {
+                    ShiftLeftPlaintext => PlaintextRotation::Left(b),
+                    _ => PlaintextRotation::Right(b),
+                };
+
+                let c = rotate_plaintext(a, rotation, params)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            SwapRowsPlaintext => {
+                let input = query.get_unary_operand(index)?;
+
+                let x = get_plaintext(&data, input.index())?;
+
+                let y = rotate_plaintext(x, PlaintextRotation::SwapRows, params)?;
+
+                data[index.index()].store(Some(Arc::new(y.into())));
+            }
+            Add => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_ciphertext(&data, right.index())?;
+
+                let c = evaluator.add(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            AddPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_plaintext(&data, right.index())?;
+
+                let c = evaluator.add_plain(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            Multiply => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_ciphertext(&data, right.index())?;
+
+                let c = evaluator.multiply(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            MultiplyPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_plaintext(&data, right.index())?;
+
+                let c = evaluator.multiply_plain(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            SwapRows => {
+                let galois_keys = galois_keys
+                    .as_ref()
+                    .ok_or(FheProgramRunFailure::MissingGaloisKeys)?;
+
+                let input = query.get_unary_operand(index)?;
+
+                let x = get_ciphertext(&data, input.index())?;
+
+                let y = evaluator.rotate_columns(x, galois_keys)?;
+
+                data[index.index()].store(Some(Arc::new(y.into())));
+            }
+            Relinearize => {
+                let relin_keys = relin_keys
+                    .as_ref()
+                    .ok_or(FheProgramRunFailure::MissingRelinearizationKeys)?;
+
+                let input = query.get_unary_operand(index)?;
+
+                let a = get_ciphertext(&data, input.index())?;
+
+                let c = evaluator.relinearize(a, relin_keys)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            Negate => {
+                let x_id = query.get_unary_operand(index)?;
+
+                let x = get_ciphertext(&data, x_id.index())?;
+
+                let y = evaluator.negate(x)?;
+
+                data[index.index()].store(Some(Arc::new(y.into())));
+            }
+            Sub => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_ciphertext(&data, right.index())?;
+
+                let c = evaluator.sub(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            SubPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get_ciphertext(&data, left.index())?;
+                let b = get_plaintext(&data, right.index())?;
+
+                let c = evaluator.sub_plain(a, b)?;
+
+                data[index.index()].store(Some(Arc::new(c.into())));
+            }
+            Literal(x) => {
+                if let Literal::Plaintext(p) = x {
+                    let p = InnerPlaintext::from_bytes(p)
+                        .map_err(|_| FheProgramRunFailure::MalformedPlaintext)?;
+
+                    match p {
+                        InnerPlaintext::Seal(p) => {
+                            // Plaintext literals should always have exactly one plaintext.
+                            if p.len() != 1 {
+                                return Err(FheProgramRunFailure::MalformedPlaintext);
+                            }
+
+                            data[index.index()].store(Some(Arc::new(p[0].data.clone().into())))
+                        }
+                    };
+                }
+            }
+            OutputCiphertext => {
+                let input = query.get_unary_operand(index)?;
+
+                let a = get_data(&data, input.index())?;
+
+                data[index.index()].store(Some(a.clone()));
+            }
+        };
+
+        Ok(())
+    }
+}
+
 /**
  * You probably should instead use [`Runtime::run()`](crate::Runtime::run).
  *
@@ -93,6 +379,9 @@ impl From<SealError> for FheProgramRunFailure {
  * The input and outputs of this method are vectors containing [`seal_fhe::Ciphertext`] values, not the
  * high-level [`Ciphertext`] types. You must first unpack them from the high-level types.
  *
+ * `params` must be the parameters under which the inputs were created. Plaintext rotations
+ * use them to permute plaintext coefficients.
+ *
  * # Safety
  * Calling this method on a malformed [`FheProgram`] may
  * result in panics, non-termination, or undefined behavior.
@@ -103,48 +392,8 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
     evaluator: &E,
     relin_keys: &Option<&RelinearizationKeys>,
     galois_keys: &Option<&GaloisKeys>,
+    params: &Params,
 ) -> Result<Vec<Ciphertext>, FheProgramRunFailure> {
-    fn get_data(
-        data: &[AtomicCell<Option<Arc<SealData>>>],
-        index: usize,
-    ) -> Result<&Arc<SealData>, FheProgramRunFailure> {
-        let data = data.get(index).ok_or(FheProgramRunFailure::MissingData)?;
-
-        // This is correct so long as the IR program is indeed a DAG executed in topological order
-        // Since for a given edge (x,y), x executes before y, the operand data that y needs
-        // from x will exist.
-        let val = unsafe { data.as_ptr().as_ref().unwrap() };
-
-        match val {
-            Some(v) => Ok(v),
-            None => Err(FheProgramRunFailure::MissingData),
-        }
-    }
-
-    fn get_ciphertext(
-        data: &[AtomicCell<Option<Arc<SealData>>>],
-        index: usize,
-    ) -> Result<&Ciphertext, FheProgramRunFailure> {
-        let val = get_data(data, index)?.as_ref();
-
-        match val {
-            SealData::Ciphertext(ref c) => Ok(c),
-            _ => Err(FheProgramRunFailure::ExpectedCiphertext),
-        }
-    }
-
-    fn get_plaintext(
-        data: &[AtomicCell<Option<Arc<SealData>>>],
-        index: usize,
-    ) -> Result<&Plaintext, FheProgramRunFailure> {
-        let val = get_data(data, index)?.as_ref();
-
-        match val {
-            SealData::Plaintext(ref c) => Ok(c),
-            _ => Err(FheProgramRunFailure::ExpectedPlaintext),
-        }
-    }
-
     let mut data: Vec<AtomicCell<Option<Arc<SealData>>>> =
         Vec::with_capacity(ir.graph.node_count());
 
@@ -157,203 +406,385 @@ pub unsafe fn run_program_unchecked<E: Evaluator + Sync + Send>(
         data.push(AtomicCell::new(None));
     }
 
+    let runner = NodeRunner {
+        ir,
+        inputs: &inputs,
+        evaluator,
+        relin_keys,
+        galois_keys,
+        params,
+    };
+
     traverse(
         ir,
         |index| {
-            let node = &ir.graph[index];
-            let query = GraphQuery::new(&ir.graph.0);
+            // Nodes run in topological order, so their operands' data
+            // exists.
+            runner.run_node(index, &data)
+        },
+        None,
+    )?;
 
-            match &node.operation {
-                InputCiphertext(id) => {
-                    data[index.index()].store(Some(inputs[*id].clone()));
-                }
-                InputPlaintext(id) => {
-                    data[index.index()].store(Some(inputs[*id].clone()));
-                }
-                ShiftLeft => {
-                    let (left, right) = query.get_binary_operands(index)?;
-
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = match ir.graph[right].operation {
-                        Literal(Literal::U64(v)) => v as i32,
-                        _ => panic!(
-                            "Illegal right operand for ShiftLeft: {:#?}",
-                            ir.graph[right].operation
-                        ),
-                    };
+    // Attempt to copy ciphertexts to our output vector.
+    let output = ir
+        .graph
+        .node_indices()
+        .filter_map(|id| match ir.graph[id].operation {
+            OutputCiphertext => Some(get_ciphertext(&data, id.index())),
+            _ => None,
+        })
+        .collect::<Result<Vec<&Ciphertext>, FheProgramRunFailure>>()?
+        .drain(0..)
+        .map(|c| c.to_owned())
+        .collect();
 
-                    let c = evaluator.rotate_rows(
-                        a,
-                        b,
-                        galois_keys
-                            .as_ref()
-                            .ok_or(FheProgramRunFailure::MissingGaloisKeys)?,
-                    )?;
+    Ok(output)
+}
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                ShiftRight => {
-                    let (left, right) = query.get_binary_operands(index)?;
-
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = match ir.graph[right].operation {
-                        Literal(Literal::U64(v)) => v as i32,
-                        _ => panic!(
-                            "Illegal right operand for ShiftLeft: {:#?}",
-                            ir.graph[right].operation
-                        ),
-                    };
+/**
+ * An iterator that lazily runs an [`FheProgram`]. See
+ * [`run_program_streaming_unchecked`].
+ */
+pub struct ProgramStream<'a, E> {
+    ir: &'a FheProgram,
+    inputs: Vec<Arc<SealData>>,
+    evaluator: E,
+    relin_keys: Option<&'a RelinearizationKeys>,
+    galois_keys: Option<&'a GaloisKeys>,
+    params: &'a Params,
+    data: Vec<AtomicCell<Option<Arc<SealData>>>>,
+    has_run: Vec<bool>,
+    topo_position: Vec<usize>,
+    outputs: Vec<NodeIndex>,
+    last_uses: Vec<Vec<NodeIndex>>,
+    next_output: usize,
+}
 
-                    let c = evaluator.rotate_rows(
-                        a,
-                        -b,
-                        galois_keys
-                            .as_ref()
-                            .ok_or(FheProgramRunFailure::MissingGaloisKeys)?,
-                    )?;
+/**
+ * You probably should instead use
+ * [`Runtime::run_streaming()`](crate::Runtime::run_streaming).
+ *
+ * Returns an iterator that yields the ciphertext of each of the given
+ * [`FheProgram`]'s outputs in order, running only the operations each
+ * output needs when it's requested. As with [`run_program_unchecked`],
+ * this performs no validation.
+ *
+ * # Remarks
+ * Intermediate results shared between outputs are computed once and kept
+ * until the last output that needs them is yielded, after which they're
+ * freed. This bounds memory by the intermediate results live at any one
+ * time rather than every output of the program.
+ *
+ * Unlike [`run_program_unchecked`], operations run sequentially on the
+ * calling thread.
+ *
+ * After an output fails, the iterator yields nothing further.
+ *
+ * # Safety
+ * Running a malformed [`FheProgram`] may result in panics,
+ * non-termination, or undefined behavior.
+ */
+pub unsafe fn run_program_streaming_unchecked<'a, E: Evaluator>(
+    ir: &'a FheProgram,
+    inputs: &[SealData],
+    evaluator: E,
+    relin_keys: Option<&'a RelinearizationKeys>,
+    galois_keys: Option<&'a GaloisKeys>,
+    params: &'a Params,
+) -> ProgramStream<'a, E> {
+    let node_count = ir.graph.node_count();
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                Add => {
-                    let (left, right) = query.get_binary_operands(index)?;
+    let topo = toposort(&ir.graph.0, None).expect("FHE program should be acyclic");
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_ciphertext(&data, right.index())?;
+    let mut topo_position = vec![0; node_count];
 
-                    let c = evaluator.add(a, b)?;
+    for (i, n) in topo.iter().enumerate() {
+        topo_position[n.index()] = i;
+    }
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                AddPlaintext => {
-                    let (left, right) = query.get_binary_operands(index)?;
+    let outputs = ir
+        .graph
+        .node_indices()
+        .filter(|n| matches!(ir.graph[*n].operation, OutputCiphertext))
+        .collect::<Vec<_>>();
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_plaintext(&data, right.index())?;
+    // Find the last output each node feeds by walking backwards from the
+    // outputs.
+    let mut last_use: Vec<Option<usize>> = vec![None; node_count];
 
-                    let c = evaluator.add_plain(a, b)?;
+    for (i, o) in outputs.iter().enumerate() {
+        last_use[o.index()] = Some(i);
+    }
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                Multiply => {
-                    let (left, right) = query.get_binary_operands(index)?;
+    for n in topo.iter().rev() {
+        let consumers_last_use = ir
+            .graph
+            .neighbors_directed(*n, Direction::Outgoing)
+            .filter_map(|c| last_use[c.index()])
+            .max();
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_ciphertext(&data, right.index())?;
+        last_use[n.index()] = last_use[n.index()].max(consumers_last_use);
+    }
 
-                    let c = evaluator.multiply(a, b)?;
+    let mut last_uses = vec![vec![]; outputs.len()];
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                MultiplyPlaintext => {
-                    let (left, right) = query.get_binary_operands(index)?;
+    for n in ir.graph.node_indices() {
+        if let Some(i) = last_use[n.index()] {
+            last_uses[i].push(n);
+        }
+    }
+
+    ProgramStream {
+        ir,
+        inputs: inputs.iter().map(|v| Arc::new(v.clone())).collect(),
+        evaluator,
+        relin_keys,
+        galois_keys,
+        params,
+        data: (0..node_count).map(|_| AtomicCell::new(None)).collect(),
+        has_run: vec![false; node_count],
+        topo_position,
+        outputs,
+        last_uses,
+        next_output: 0,
+    }
+}
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_plaintext(&data, right.index())?;
+impl<E: Evaluator> Iterator for ProgramStream<'_, E> {
+    type Item = Result<Ciphertext, FheProgramRunFailure>;
 
-                    let c = evaluator.multiply_plain(a, b)?;
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.next_output;
+        let output = *self.outputs.get(i)?;
+        self.next_output += 1;
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                SwapRows => {
-                    let galois_keys = galois_keys
-                        .as_ref()
-                        .ok_or(FheProgramRunFailure::MissingGaloisKeys)?;
+        // Find the operations this output needs that haven't run yet.
+        let mut pending = vec![];
+        let mut visit = vec![output];
 
-                    let input = query.get_unary_operand(index)?;
+        while let Some(n) = visit.pop() {
+            if self.has_run[n.index()] {
+                continue;
+            }
 
-                    let x = get_ciphertext(&data, input.index())?;
+            self.has_run[n.index()] = true;
+            pending.push(n);
+            visit.extend(self.ir.graph.neighbors_directed(n, Direction::Incoming));
+        }
 
-                    let y = evaluator.rotate_columns(x, galois_keys)?;
+        pending.sort_by_key(|n| self.topo_position[n.index()]);
 
-                    data[index.index()].store(Some(Arc::new(y.into())));
-                }
-                Relinearize => {
-                    let relin_keys = relin_keys
-                        .as_ref()
-                        .ok_or(FheProgramRunFailure::MissingRelinearizationKeys)?;
+        let runner = NodeRunner {
+            ir: self.ir,
+            inputs: &self.inputs,
+            evaluator: &self.evaluator,
+            relin_keys: &self.relin_keys,
+            galois_keys: &self.galois_keys,
+            params: self.params,
+        };
 
-                    let input = query.get_unary_operand(index)?;
+        for n in pending {
+            // Running pending nodes in topological order ensures each
+            // node's operands exist. The caller of
+            // run_program_streaming_unchecked guaranteed the program is
+            // well-formed.
+            if let Err(e) = unsafe { runner.run_node(n, &self.data) } {
+                self.next_output = self.outputs.len();
+                return Some(Err(e));
+            }
+        }
 
-                    let a = get_ciphertext(&data, input.index())?;
+        let result = get_ciphertext(&self.data, output.index()).cloned();
 
-                    let c = evaluator.relinearize(a, relin_keys)?;
+        for n in &self.last_uses[i] {
+            self.data[n.index()].store(None);
+        }
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                Negate => {
-                    let x_id = query.get_unary_operand(index)?;
+        Some(result)
+    }
 
-                    let x = get_ciphertext(&data, x_id.index())?;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.outputs.len() - self.next_output;
 
-                    let y = evaluator.negate(x)?;
+        (remaining, Some(remaining))
+    }
+}
 
-                    data[index.index()].store(Some(Arc::new(y.into())));
-                }
-                Sub => {
-                    let (left, right) = query.get_binary_operands(index)?;
+/**
+ * Runs the given [`FheProgram`] in the clear over plaintext polynomials.
+ *
+ * # Remarks
+ * Each of `inputs` is the plaintext an encrypted input would decrypt to,
+ * and each returned plaintext is what decrypting the corresponding output
+ * of [`run_program_unchecked`] yields, provided its noise budget isn't
+ * exhausted. Additions, subtractions and negations operate coefficient-wise
+ * modulo the plaintext modulus, multiplications are negacyclic convolutions
+ * in `Z_t[x] / (x^n + 1)` and rotations apply the same Galois automorphisms
+ * as [`rotate_plaintext`], so batched lanes move exactly as they would
+ * under encryption. Relinearization does nothing.
+ *
+ * Unlike [`run_program_unchecked`], this needs no keys and runs
+ * sequentially on the calling thread. Multiplication costs `O(n^2)`
+ * operations in the lattice dimension `n`.
+ *
+ * The program should be valid (see
+ * [`validate()`](sunscreen_fhe_program::FheProgramTrait::validate())).
+ * Returns [`FheProgramRunFailure::MissingData`] if the program references
+ * an input beyond `inputs`.
+ */
+pub fn run_program_plaintext(
+    ir: &FheProgram,
+    inputs: &[Plaintext],
+    params: &Params,
+) -> Result<Vec<Plaintext>, FheProgramRunFailure> {
+    let n = params.lattice_dimension as usize;
+    let t = params.plain_modulus;
+
+    let coefficients = |p: &Plaintext| {
+        let mut c = (0..usize::min(p.len(), n))
+            .map(|i| p.get_coefficient(i) % t)
+            .collect::<Vec<_>>();
+
+        c.resize(n, 0);
+        c
+    };
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_ciphertext(&data, right.index())?;
+    let inputs = inputs.iter().map(coefficients).collect::<Vec<_>>();
 
-                    let c = evaluator.sub(a, b)?;
+    let mut data: Vec<Option<Vec<u64>>> = vec![None; ir.graph.node_count()];
+    let query = GraphQuery::new(&ir.graph.0);
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                SubPlaintext => {
-                    let (left, right) = query.get_binary_operands(index)?;
+    let topo = toposort(&ir.graph.0, None).expect("FHE program should be acyclic");
 
-                    let a = get_ciphertext(&data, left.index())?;
-                    let b = get_plaintext(&data, right.index())?;
+    fn get(data: &[Option<Vec<u64>>], index: NodeIndex) -> Result<&[u64], FheProgramRunFailure> {
+        data.get(index.index())
+            .and_then(|x| x.as_deref())
+            .ok_or(FheProgramRunFailure::MissingData)
+    }
 
-                    let c = evaluator.sub_plain(a, b)?;
+    let get_shift = |index: NodeIndex| match ir.graph[index].operation {
+        Literal(Literal::U64(v)) => v,
+        _ => panic!(
+            "Illegal right operand for a rotation: {:#?}",
+            ir.graph[index].operation
+        ),
+    };
 
-                    data[index.index()].store(Some(Arc::new(c.into())));
-                }
-                Literal(x) => {
-                    if let Literal::Plaintext(p) = x {
-                        let p = InnerPlaintext::from_bytes(p)
-                            .map_err(|_| FheProgramRunFailure::MalformedPlaintext)?;
-
-                        match p {
-                            InnerPlaintext::Seal(p) => {
-                                // Plaintext literals should always have exactly one plaintext.
-                                if p.len() != 1 {
-                                    return Err(FheProgramRunFailure::MalformedPlaintext);
-                                }
-
-                                data[index.index()].store(Some(Arc::new(p[0].data.clone().into())))
-                            }
-                        };
+    for index in topo {
+        let result = match &ir.graph[index].operation {
+            InputCiphertext(id) | InputPlaintext(id) => inputs
+                .get(*id)
+                .cloned()
+                .ok_or(FheProgramRunFailure::MissingData)?,
+            ShiftLeft | ShiftLeftPlaintext | ShiftRight | ShiftRightPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get(&data, left)?;
+                let b = get_shift(right);
+
+                let rotation = match ir.graph[index].operation {
+                    ShiftLeft | ShiftLeftPlaintext => PlaintextRotation::Left(b),
+                    _ => PlaintextRotation::Right(b),
+                };
+
+                rotate_coefficients(a, rotation, params)
+            }
+            SwapRows | SwapRowsPlaintext => {
+                let input = query.get_unary_operand(index)?;
+
+                rotate_coefficients(get(&data, input)?, PlaintextRotation::SwapRows, params)
+            }
+            Add | AddPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get(&data, left)?;
+                let b = get(&data, right)?;
+
+                a.iter().zip(b).map(|(a, b)| (a + b) % t).collect()
+            }
+            Sub | SubPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get(&data, left)?;
+                let b = get(&data, right)?;
+
+                a.iter().zip(b).map(|(a, b)| (a + t - b) % t).collect()
+            }
+            Negate => {
+                let input = query.get_unary_operand(index)?;
+
+                get(&data, input)?.iter().map(|a| (t - a) % t).collect()
+            }
+            Multiply | MultiplyPlaintext => {
+                let (left, right) = query.get_binary_operands(index)?;
+
+                let a = get(&data, left)?;
+                let b = get(&data, right)?;
+
+                let mut c = vec![0u64; n];
+
+                for (i, x) in a.iter().enumerate().filter(|(_, x)| **x != 0) {
+                    for (j, y) in b.iter().enumerate().filter(|(_, y)| **y != 0) {
+                        let x = ((*x as u128 * *y as u128) % t as u128) as u64;
+
+                        // x^n = -1, so terms that wrap past the lattice
+                        // dimension get negated.
+                        let k = i + j;
+
+                        if k < n {
+                            c[k] = (c[k] + x) % t;
+                        } else {
+                            c[k - n] = (c[k - n] + t - x) % t;
+                        }
                     }
                 }
-                OutputCiphertext => {
-                    let input = query.get_unary_operand(index)?;
 
-                    let a = get_data(&data, input.index())?;
+                c
+            }
+            Relinearize | OutputCiphertext => {
+                let input = query.get_unary_operand(index)?;
 
-                    data[index.index()].store(Some(a.clone()));
+                get(&data, input)?.to_vec()
+            }
+            Literal(Literal::Plaintext(p)) => {
+                let p = InnerPlaintext::from_bytes(p)
+                    .map_err(|_| FheProgramRunFailure::MalformedPlaintext)?;
+
+                match p {
+                    InnerPlaintext::Seal(p) => {
+                        // Plaintext literals should always have exactly one plaintext.
+                        if p.len() != 1 {
+                            return Err(FheProgramRunFailure::MalformedPlaintext);
+                        }
+
+                        coefficients(&p[0].data)
+                    }
                 }
-            };
+            }
+            // Other literals (e.g. shift amounts) are read by the nodes
+            // that consume them.
+            Literal(_) => continue,
+        };
 
-            Ok(())
-        },
-        None,
-    )?;
+        data[index.index()] = Some(result);
+    }
 
-    // Attempt to copy ciphertexts to our output vector.
-    let output = ir
-        .graph
+    ir.graph
         .node_indices()
-        .filter_map(|id| match ir.graph[id].operation {
-            OutputCiphertext => Some(get_ciphertext(&data, id.index())),
-            _ => None,
-        })
-        .collect::<Result<Vec<&Ciphertext>, FheProgramRunFailure>>()?
-        .drain(0..)
-        .map(|c| c.to_owned())
-        .collect();
+        .filter(|id| matches!(ir.graph[*id].operation, OutputCiphertext))
+        .map(|id| {
+            let c = get(&data, id)?;
 
-    Ok(output)
+            let mut p = Plaintext::new()?;
+            p.resize(n);
+
+            for (i, c) in c.iter().enumerate() {
+                p.set_coefficient(i, *c);
+            }
+
+            Ok(p)
+        })
+        .collect()
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -385,25 +816,32 @@ where
 
     let ir = ir.as_ref();
 
-    // Initialize the number of incomplete dependencies.
-    let deps = ir
-        .graph
-        .node_indices()
-        .map(|n| AtomicUsize::new(ir.graph.neighbors_directed(n, Direction::Incoming).count()))
+    // Initialize the number of incomplete dependencies. Removed nodes leave
+    // holes in the graph's indices, so this is indexed by node index rather
+    // than position.
+    let deps = (0..ir.graph.node_bound())
+        .map(|_| AtomicUsize::new(0))
         .collect::<Vec<AtomicUsize>>();
 
+    for n in ir.graph.node_indices() {
+        deps[n.index()].store(
+            ir.graph.neighbors_directed(n, Direction::Incoming).count(),
+            Ordering::Relaxed,
+        );
+    }
+
     // We must eagerly evaluate the iterator (i.e. collect) since
     // the dependency counts will be changing during iteration. Lazy
     // iteration causes a race condition between the filter_map closer
     // evaluating and the deps counts being decremented, potentially
     // resulting in nodes being run more than once.
-    let initial_ready = deps
-        .iter()
-        .enumerate()
-        .filter_map(|(id, count)| {
-            if count.load(Ordering::Relaxed) == 0 {
-                log::trace!("parallel_traverse: Initial node {}", id);
-                Some(id)
+    let initial_ready = ir
+        .graph
+        .node_indices()
+        .filter_map(|id| {
+            if deps[id.index()].load(Ordering::Relaxed) == 0 {
+                log::trace!("parallel_traverse: Initial node {}", id.index());
+                Some(id.index())
             } else {
                 None
             }
@@ -498,21 +936,21 @@ where
         Cow::Borrowed(ir)
     };
 
-    // Initialize the number of incomplete dependencies.
-    let mut deps = ir
+    // Initialize the number of incomplete dependencies. Removed nodes leave
+    // holes in the graph's indices, so this is indexed by node index rather
+    // than position.
+    let mut deps = vec![0; ir.graph.node_bound()];
+
+    for n in ir.graph.node_indices() {
+        deps[n.index()] = ir.graph.neighbors_directed(n, Direction::Incoming).count();
+    }
+
+    let initial_ready = ir
         .graph
         .node_indices()
-        .map(|n| ir.graph.neighbors_directed(n, Direction::Incoming).count())
-        .collect::<Vec<usize>>();
-
-    let initial_ready = deps.iter().enumerate().filter_map(|(id, count)| {
-        if *count == 0 {
-            log::trace!("traverse: Initial node {}", id);
-            Some(NodeIndex::from(id as u32))
-        } else {
-            None
-        }
-    });
+        .filter(|id| deps[id.index()] == 0)
+        .inspect(|id| log::trace!("traverse: Initial node {}", id.index()))
+        .collect::<Vec<_>>();
 
     let mut ready_nodes = VecDeque::new();
 
@@ -549,6 +987,20 @@ mod tests {
     use seal_fhe::*;
     use sunscreen_fhe_program::SchemeType;
 
+    fn params(degree: u64) -> Params {
+        Params {
+            lattice_dimension: degree,
+            plain_modulus: PlainModulus::batching(degree, 17).unwrap().value(),
+            coeff_modulus: CoefficientModulus::bfv_default(degree, SecurityLevel::default())
+                .unwrap()
+                .iter()
+                .map(|x| x.value())
+                .collect(),
+            scheme_type: SchemeType::Bfv,
+            security_level: SecurityLevel::default(),
+        }
+    }
+
     fn setup_scheme(
         degree: u64,
     ) -> (
@@ -618,8 +1070,15 @@ mod tests {
         let ct_1 = encryptor.encrypt(&pt_1).unwrap();
 
         let output = unsafe {
-            run_program_unchecked(&ir, &[ct_0.into(), ct_1.into()], &evaluator, &None, &None)
-                .unwrap()
+            run_program_unchecked(
+                &ir,
+                &[ct_0.into(), ct_1.into()],
+                &evaluator,
+                &None,
+                &None,
+                &params(degree),
+            )
+            .unwrap()
         };
 
         assert_eq!(output.len(), 1);
@@ -665,6 +1124,7 @@ mod tests {
                 &evaluator,
                 &Some(&relin_keys),
                 &None,
+                &params(degree),
             )
             .unwrap()
         };
@@ -713,6 +1173,7 @@ mod tests {
                 &evaluator,
                 &Some(&relin_keys),
                 &None,
+                &params(degree),
             )
             .unwrap()
         };
@@ -776,6 +1237,7 @@ mod tests {
                 &evaluator,
                 &Some(&relin_keys),
                 &None,
+                &params(degree),
             )
             .unwrap()
         };
@@ -816,8 +1278,15 @@ mod tests {
         let ct_0 = encryptor.encrypt(&pt_0).unwrap();
 
         let output = unsafe {
-            run_program_unchecked(&ir, &[ct_0.into()], &evaluator, &None, &Some(&galois_keys))
-                .unwrap()
+            run_program_unchecked(
+                &ir,
+                &[ct_0.into()],
+                &evaluator,
+                &None,
+                &Some(&galois_keys),
+                &params(degree),
+            )
+            .unwrap()
         };
 
         assert_eq!(output.len(), 1);
@@ -861,8 +1330,15 @@ mod tests {
         let ct_0 = encryptor.encrypt(&pt_0).unwrap();
 
         let output = unsafe {
-            run_program_unchecked(&ir, &[ct_0.into()], &evaluator, &None, &Some(&galois_keys))
-                .unwrap()
+            run_program_unchecked(
+                &ir,
+                &[ct_0.into()],
+                &evaluator,
+                &None,
+                &Some(&galois_keys),
+                &params(degree),
+            )
+            .unwrap()
         };
 
         assert_eq!(output.len(), 1);
@@ -879,4 +1355,58 @@ mod tests {
 
         assert_eq!(encoder.decode_unsigned(&o_p).unwrap(), expected);
     }
+
+    #[test]
+    fn can_rotate_plaintext() {
+        let mut ir = FheProgram::new(SchemeType::Bfv);
+
+        let a = ir.add_input_ciphertext(0);
+        let b = ir.add_input_plaintext(1);
+        let l = ir.add_input_literal(Literal::U64(3));
+
+        let b_rot = ir.add_rotate_left_plaintext(b, l);
+        let b_swap = ir.add_swap_rows_plaintext(b_rot);
+        let res = ir.add_multiply_plaintext(a, b_swap);
+
+        ir.add_output_ciphertext(res);
+
+        let degree = 4096;
+
+        let (_keygen, context, _public_key, _private_key, encryptor, decryptor, evaluator) =
+            setup_scheme(degree);
+
+        let encoder = BFVEncoder::new(&context).unwrap();
+
+        let a = vec![2; degree as usize];
+        let b: Vec<u64> = (0..degree).collect();
+
+        let ct_0 = encryptor
+            .encrypt(&encoder.encode_unsigned(&a).unwrap())
+            .unwrap();
+        let pt_1 = encoder.encode_unsigned(&b).unwrap();
+
+        // Plaintext rotations don't need Galois keys.
+        let output = unsafe {
+            run_program_unchecked(
+                &ir,
+                &[ct_0.into(), pt_1.into()],
+                &evaluator,
+                &None,
+                &None,
+                &params(degree),
+            )
+            .unwrap()
+        };
+
+        let o_p = decryptor.decrypt(&output[0]).unwrap();
+
+        let mut expected = (degree / 2 + 3..degree).collect::<Vec<u64>>();
+        expected.append(&mut vec![degree / 2, degree / 2 + 1, degree / 2 + 2]);
+        expected.append(&mut (3..degree / 2).collect::<Vec<u64>>());
+        expected.append(&mut vec![0, 1, 2]);
+
+        let expected = expected.iter().map(|x| 2 * x).collect::<Vec<u64>>();
+
+        assert_eq!(encoder.decode_unsigned(&o_p).unwrap(), expected);
+    }
 }
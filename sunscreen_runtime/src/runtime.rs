@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::time::Instant;
 
@@ -9,18 +11,21 @@ use crate::ProofBuilder;
 use crate::VerificationBuilder;
 use crate::ZkpProgramInput;
 use crate::{
-    run_program_unchecked, serialization::WithContext, Ciphertext, FheProgramInput,
-    InnerCiphertext, InnerPlaintext, Plaintext, PrivateKey, PublicKey, SealCiphertext, SealData,
+    run_program_streaming_unchecked, run_program_unchecked, serialization::WithContext,
+    BootstrapKeys, Ciphertext, FheApplication, FheProgramInput, FheProgramOutputs, InnerCiphertext,
+    InnerPlaintext, KeySizeReport, Plaintext, PrivateKey, PublicKey, SealCiphertext, SealData,
     SealPlaintext, TryFromPlaintext, TryIntoPlaintext, TypeNameInstance,
 };
 
 use log::trace;
+use sunscreen_fhe_program::FheProgram;
 use sunscreen_fhe_program::FheProgramTrait;
+use sunscreen_fhe_program::Operation;
 use sunscreen_fhe_program::SchemeType;
 
 use seal_fhe::{
     AsymmetricComponents, BFVEvaluator, BfvEncryptionParametersBuilder, Context as SealContext,
-    Decryptor, Encryptor, KeyGenerator, Modulus, SymmetricComponents,
+    Decryptor, Encryptor, Evaluator, KeyGenerator, Modulus, SymmetricComponents,
 };
 
 pub use sunscreen_compiler_common::{Type, TypeName};
@@ -233,6 +238,178 @@ where
         }
     }
 
+    /**
+     * Switches each ciphertext enclosed in `c` to the next modulus in the
+     * coefficient modulus chain, dropping its last prime.
+     *
+     * # Remarks
+     * The returned ciphertext encrypts the same value as `c`, but is smaller
+     * and subsequent operations on it are faster. Switching costs noise
+     * budget; a fresh ciphertext loses roughly as many bits as the dropped
+     * prime, while a ciphertext whose noise has already grown loses far
+     * less. Use [`measure_noise_budget`](Self::measure_noise_budget) to
+     * decide whether you can afford to switch.
+     *
+     * Ciphertexts at different levels of the modulus chain can't be combined,
+     * so this is most useful for shrinking results before decryption or
+     * transport rather than for inputs to [`run`](Self::run).
+     *
+     * Returns [`Error::ParameterMismatch`] if `c` wasn't encrypted under this
+     * runtime's parameters and [`Error::SealError`] if `c` is already at the
+     * last level of the modulus chain (e.g. when the parameters only have a
+     * single coefficient modulus).
+     */
+    pub fn mod_switch(&self, c: &Ciphertext) -> Result<Ciphertext> {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        match (&fhe_data.context, &c.inner) {
+            (Context::Seal(ctx), InnerCiphertext::Seal(ciphertexts)) => {
+                if ciphertexts.iter().any(|c| c.params != fhe_data.params) {
+                    return Err(Error::ParameterMismatch);
+                }
+
+                let evaluator = BFVEvaluator::new(ctx)?;
+
+                let ciphertexts = ciphertexts
+                    .iter()
+                    .map(|c| {
+                        Ok(WithContext {
+                            params: c.params.clone(),
+                            data: evaluator.mod_switch_to_next(&c.data)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                Ok(Ciphertext {
+                    data_type: c.data_type.clone(),
+                    inner: InnerCiphertext::Seal(ciphertexts),
+                })
+            }
+        }
+    }
+
+    /**
+     * Adds `other` to `acc` in place, without running an FHE program.
+     *
+     * # Remarks
+     * This adds each ciphertext enclosed in `other` to the corresponding
+     * one in `acc` using SEAL's in-place addition, so folding many
+     * ciphertexts into an accumulator doesn't allocate a new ciphertext per
+     * addition. Only types that enclose a single ciphertext (e.g. `Signed`,
+     * `Unsigned`, `Fractional` and `Batched`) are supported, since adding
+     * their ciphertexts adds the values they encrypt. This isn't true of
+     * types enclosing several ciphertexts (e.g. `Rational`, whose sum
+     * cross-multiplies numerators and denominators).
+     *
+     * Both operands must be encrypted under this runtime's [`Params`] and
+     * at the same level of the modulus chain (see
+     * [`mod_switch`](Self::mod_switch)).
+     *
+     * Returns [`Error::ParameterMismatch`] if either operand uses other
+     * parameters, [`Error::TypeMismatch`] if their data types differ,
+     * [`Error::FheTypeError`] if their type doesn't enclose exactly one
+     * ciphertext and [`Error::SealError`] if they're at different levels.
+     */
+    pub fn add_assign(&self, acc: &mut Ciphertext, other: &Ciphertext) -> Result<()> {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        if acc.data_type != other.data_type {
+            return Err(Error::type_mismatch(&acc.data_type, &other.data_type));
+        }
+
+        if acc.inner_len() != 1 {
+            return Err(Error::fhe_type_error(&format!(
+                "add_assign requires a type enclosing a single ciphertext, but {} encloses {}",
+                acc.data_type.name,
+                acc.inner_len()
+            )));
+        }
+
+        match (&fhe_data.context, &mut acc.inner, &other.inner) {
+            (Context::Seal(ctx), InnerCiphertext::Seal(acc), InnerCiphertext::Seal(other)) => {
+                if acc
+                    .iter()
+                    .chain(other.iter())
+                    .any(|c| c.params != fhe_data.params)
+                {
+                    return Err(Error::ParameterMismatch);
+                }
+
+                if acc.len() != other.len() {
+                    return Err(Error::IncorrectCiphertextCount);
+                }
+
+                let evaluator = BFVEvaluator::new(ctx)?;
+
+                for (a, b) in acc.iter_mut().zip(other) {
+                    evaluator.add_inplace(&mut a.data, &b.data)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /**
+     * Refreshes `c`, returning a ciphertext that encrypts the same value
+     * with a restored noise budget.
+     *
+     * # Remarks
+     * Long-running computations can refresh intermediate results rather than
+     * choosing parameters large enough for the whole computation. Refreshing
+     * bootstraps each ciphertext enclosed in `c`, which is by far the most
+     * expensive FHE operation: expect each refresh to take orders of
+     * magnitude longer than running a program that multiplies `c`, and
+     * prefer larger parameters when the computation's depth is known
+     * ahead of time. Use [`measure_noise_budget`](Self::measure_noise_budget)
+     * to refresh only when needed.
+     *
+     * No BFV parameters currently support refreshing, since SEAL
+     * ciphertexts can't yet be converted to and from the LWE ciphertexts
+     * `sunscreen_tfhe` bootstraps. Programs that need more depth than their
+     * parameters allow should use larger parameters or have the private key
+     * holder decrypt and re-encrypt intermediate results.
+     *
+     * Returns [`Error::ParameterMismatch`] if `c` or `keys` weren't created
+     * under this runtime's parameters and [`Error::RefreshUnsupported`] if
+     * the parameters don't support refreshing.
+     */
+    pub fn refresh(&self, c: &Ciphertext, keys: &BootstrapKeys) -> Result<Ciphertext> {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        match (&fhe_data.context, &c.inner) {
+            (Context::Seal(_), InnerCiphertext::Seal(ciphertexts)) => {
+                if keys.params != fhe_data.params
+                    || ciphertexts.iter().any(|c| c.params != fhe_data.params)
+                {
+                    return Err(Error::ParameterMismatch);
+                }
+
+                Err(Error::refresh_unsupported(&fhe_data.params))
+            }
+        }
+    }
+
+    /**
+     * Generates the [`BootstrapKeys`] needed to [`refresh`](Self::refresh)
+     * ciphertexts encrypted under `private_key`.
+     *
+     * # Remarks
+     * Returns [`Error::ParameterMismatch`] if `private_key` wasn't generated
+     * under this runtime's parameters and [`Error::RefreshUnsupported`] if
+     * the parameters don't support refreshing, which is currently the case
+     * for all BFV parameters.
+     */
+    pub fn generate_bootstrap_keys(&self, private_key: &PrivateKey) -> Result<BootstrapKeys> {
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        if private_key.0.params != fhe_data.params {
+            return Err(Error::ParameterMismatch);
+        }
+
+        Err(Error::refresh_unsupported(&fhe_data.params))
+    }
+
     /**
      * Generates a tuple of public/private keys for the encapsulated scheme and parameters.
      *
@@ -245,13 +422,71 @@ where
      * See [`PublicKey`] for more information.
      */
     pub fn generate_keys(&self) -> Result<(PublicKey, PrivateKey)> {
+        self.generate_keys_internal(None)
+    }
+
+    /**
+     * Estimates the sizes of the keys [`generate_keys`](Self::generate_keys)
+     * creates for this runtime's parameters without generating them.
+     *
+     * # Remarks
+     * This is a closed-form computation from the lattice dimension and
+     * coefficient modulus count, so it's cheap enough to e.g. reject
+     * parameters whose keys would be too large before generating any.
+     * See [`KeySizeReport`] for more details.
+     */
+    pub fn estimate_key_sizes(&self) -> KeySizeReport {
+        KeySizeReport::new(self.params())
+    }
+
+    /**
+     * Generates a tuple of public/private keys that can run exactly the
+     * given FHE programs.
+     *
+     * # Remarks
+     * Unlike [`generate_keys`](Self::generate_keys), which creates Galois
+     * keys for every rotation, this only creates Galois keys for the
+     * [`required_rotations`](CompiledFheProgram::required_rotations) of
+     * `programs`, or none if no program rotates. This can make the public
+     * key much smaller, but running any other program that rotates with it
+     * fails.
+     */
+    pub fn generate_keys_for(
+        &self,
+        programs: &[&CompiledFheProgram],
+    ) -> Result<(PublicKey, PrivateKey)> {
+        let steps = programs
+            .iter()
+            .flat_map(|p| p.required_rotations())
+            .map(|x| x as i32)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        self.generate_keys_internal(Some(&steps))
+    }
+
+    /**
+     * Generates keys, with Galois keys for only the given rotation steps
+     * if `galois_steps` is `Some`.
+     */
+    fn generate_keys_internal(
+        &self,
+        galois_steps: Option<&[i32]>,
+    ) -> Result<(PublicKey, PrivateKey)> {
         let fhe_data = self.runtime_data.unwrap_fhe();
 
         let keys = match &fhe_data.context {
             Context::Seal(context) => {
                 let keygen = KeyGenerator::new(context)?;
 
-                let galois_keys = keygen.create_galois_keys().ok().map(|v| WithContext {
+                let galois_keys = match galois_steps {
+                    None => keygen.create_galois_keys().ok(),
+                    Some([]) => None,
+                    Some(steps) => Some(keygen.create_galois_keys_from_steps(steps)?),
+                };
+
+                let galois_keys = galois_keys.map(|v| WithContext {
                     params: fhe_data.params.clone(),
                     data: v,
                 });
@@ -310,94 +545,404 @@ where
     pub fn run<I>(
         &self,
         fhe_program: &CompiledFheProgram,
-        mut arguments: Vec<I>,
+        arguments: Vec<I>,
         public_key: &PublicKey,
     ) -> Result<Vec<Ciphertext>>
     where
         I: Into<FheProgramInput>,
     {
-        // We're going to call run_program_unchecked, which
-        // can result in undefined behavior, non-termination,
-        // or panics on malformed programs. Since this method is safe,
-        // it must guard against calling run_program_unchecked with
-        // inputs that result in undefined behavior.
-        fhe_program.fhe_program_fn.validate()?;
+        self.run_selected_outputs(fhe_program, arguments, public_key, None)
+    }
 
-        // Aside from FHE program correctness, check that the required keys are given.
-        if public_key.relin_key.is_none() && fhe_program.fhe_program_fn.requires_relin_keys() {
-            return Err(Error::MissingRelinearizationKeys);
+    /**
+     * Validates and runs the given FHE program, which returns a struct
+     * deriving [`FheProgramOutputs`], returning each of its fields'
+     * ciphertexts by name.
+     *
+     * # Remarks
+     * This runs the program as [`run`](Self::run) does and unpacks the
+     * single value it returns into `O::Ciphertexts`.
+     *
+     * Returns [`Error::ReturnTypeMetadataError`] if the program doesn't
+     * return exactly one value and [`Error::TypeMismatch`] if that value
+     * isn't an `O`.
+     */
+    pub fn run_named<O, I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+    ) -> Result<O::Ciphertexts>
+    where
+        O: FheProgramOutputs,
+        I: Into<FheProgramInput>,
+    {
+        let expected = O::type_name();
+
+        match &fhe_program.metadata.signature.returns[..] {
+            [r] if *r == expected => {}
+            [r] => return Err(Error::type_mismatch(&expected, r)),
+            _ => return Err(Error::ReturnTypeMetadataError),
         }
 
-        if public_key.galois_key.is_none() && fhe_program.fhe_program_fn.requires_galois_keys() {
-            return Err(Error::MissingGaloisKeys);
+        let mut result = self.run(fhe_program, arguments, public_key)?;
+
+        O::from_ciphertext(result.remove(0))
+    }
+
+    /**
+     * Validates and runs the given FHE program on ciphertexts and already
+     * encoded plaintexts.
+     *
+     * # Remarks
+     * Each argument must be a [`FheProgramInput::Ciphertext`] or a
+     * [`FheProgramInput::EncodedPlaintext`], such as a plaintext encoded
+     * once with [`TryIntoPlaintext::try_into_plaintext`] and reused across
+     * many runs. Since nothing gets encoded, this avoids redundant work when
+     * a plaintext argument is constant.
+     *
+     * This first validates the arguments against the call signature as
+     * [`run`](Self::run) does, returning
+     * [`Error::ArgumentCountMismatch`] or [`Error::ArgumentTypeMismatch`]
+     * on failure. It then returns [`Error::UnencodedPlaintext`] if any
+     * argument is a plaintext that hasn't been encoded, and
+     * [`Error::IncorrectCiphertextCount`] if the arguments don't contain
+     * exactly as many ciphertexts and plaintexts as the FHE program has
+     * inputs.
+     */
+    pub fn run_raw(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<FheProgramInput>,
+        public_key: &PublicKey,
+    ) -> Result<Vec<Ciphertext>> {
+        fhe_program.validate_inputs(&arguments)?;
+
+        let mut count = 0;
+
+        for (i, a) in arguments.iter().enumerate() {
+            count += match a {
+                FheProgramInput::Ciphertext(c) => match &c.inner {
+                    InnerCiphertext::Seal(c) => c.len(),
+                },
+                FheProgramInput::EncodedPlaintext(p) => match &p.inner {
+                    InnerPlaintext::Seal(p) => p.len(),
+                },
+                FheProgramInput::Plaintext(_) => return Err(Error::UnencodedPlaintext(i)),
+            };
         }
 
-        let mut arguments: Vec<FheProgramInput> = arguments.drain(0..).map(|a| a.into()).collect();
+        let expected = fhe_program
+            .fhe_program_fn
+            .graph
+            .node_weights()
+            .filter_map(|n| match n.operation {
+                Operation::InputCiphertext(id) | Operation::InputPlaintext(id) => Some(id + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
 
-        // Check the passed arguments' types match the signature.
-        Self::validate_arguments(&fhe_program.metadata.signature, &arguments)?;
+        if count != expected {
+            return Err(Error::IncorrectCiphertextCount);
+        }
+
+        self.run(fhe_program, arguments, public_key)
+    }
+
+    /**
+     * Validates and runs the given FHE program, computing only the values it
+     * returns at the given `outputs` indices.
+     *
+     * # Remarks
+     * This only evaluates the operations the selected return values depend
+     * on, skipping operations that exclusively feed other return values.
+     * As with [`run`](Self::run), subexpressions shared between selected
+     * return values are computed once. Relinearization and Galois keys are
+     * only required if the selected return values need them.
+     *
+     * The returned vector contains the selected return values in the order
+     * given by `outputs`.
+     *
+     * Returns [`Error::OutputIndexOutOfRange`] if any index in `outputs`
+     * is at least the number of values the FHE program returns.
+     */
+    pub fn run_outputs<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+        outputs: &[usize],
+    ) -> Result<Vec<Ciphertext>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        self.run_selected_outputs(fhe_program, arguments, public_key, Some(outputs))
+    }
+
+    /**
+     * Validates and runs the given FHE program, lazily computing each
+     * value it returns as the returned iterator is advanced.
+     *
+     * # Remarks
+     * Each return value is computed when requested, running only the
+     * operations it needs that haven't already run. Intermediate values
+     * shared between return values are computed once and freed after the
+     * last return value that needs them, so only the values in flight are
+     * held in memory rather than every return value. This makes it possible
+     * to e.g. send each result over the network before computing the next.
+     *
+     * The program, keys, and arguments are validated before this returns.
+     * Errors that occur while running an operation are yielded in place of
+     * the return value that needed it, after which the iterator ends.
+     *
+     * # Performance
+     * Operations run sequentially on the calling thread, whereas
+     * [`run`](Self::run) runs independent operations in parallel. Prefer
+     * [`run`](Self::run) unless memory is the constraint.
+     */
+    pub fn run_streaming<'a, I>(
+        &'a self,
+        fhe_program: &'a CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &'a PublicKey,
+    ) -> Result<impl Iterator<Item = Result<Ciphertext>> + 'a>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let fhe_program_fn = &fhe_program.fhe_program_fn;
+        fhe_program_fn.validate()?;
 
-        if fhe_program.metadata.signature.num_ciphertexts.len()
-            != fhe_program.metadata.signature.returns.len()
+        let signature = &fhe_program.metadata.signature;
+
+        if signature.num_ciphertexts.len() != signature.returns.len()
+            || fhe_program_fn.get_outputs().count()
+                != signature.num_ciphertexts.iter().sum::<usize>()
         {
             return Err(Error::ReturnTypeMetadataError);
         }
 
+        Self::check_required_keys(fhe_program_fn, public_key)?;
+
+        let inputs = self.prepare_inputs(fhe_program, arguments)?;
+
         let fhe_data = self.runtime_data.unwrap_fhe();
 
-        match &fhe_data.context {
+        let mut outputs = match &fhe_data.context {
             Context::Seal(context) => {
                 let evaluator = BFVEvaluator::new(context)?;
 
-                let mut inputs: Vec<SealData> = vec![];
+                // We validated the program above.
+                unsafe {
+                    run_program_streaming_unchecked(
+                        fhe_program_fn,
+                        &inputs,
+                        evaluator,
+                        public_key.relin_key.as_ref().map(|p| &p.data),
+                        public_key.galois_key.as_ref().map(|p| &p.data),
+                        &fhe_data.params,
+                    )
+                }
+            }
+        };
 
-                for i in arguments.drain(0..) {
-                    match i {
-                        FheProgramInput::Ciphertext(c) => match c.inner {
-                            InnerCiphertext::Seal(mut c) => {
-                                for j in c.drain(0..) {
-                                    inputs.push(SealData::Ciphertext(j.data));
-                                }
-                            }
-                        },
-                        FheProgramInput::Plaintext(p) => {
-                            let p = p.try_into_plaintext(&fhe_data.params)?;
-
-                            match p.inner {
-                                InnerPlaintext::Seal(mut p) => {
-                                    for j in p.drain(0..) {
-                                        inputs.push(SealData::Plaintext(j.data));
-                                    }
-                                }
+        let mut returns = signature.returns.iter().zip(&signature.num_ciphertexts);
+        let mut failed = false;
+
+        Ok(std::iter::from_fn(move || {
+            if failed {
+                return None;
+            }
+
+            let (data_type, count) = returns.next()?;
+
+            let ciphertexts = outputs
+                .by_ref()
+                .take(*count)
+                .map(|c| {
+                    c.map(|data| WithContext {
+                        params: fhe_data.params.clone(),
+                        data,
+                    })
+                })
+                .collect::<std::result::Result<Vec<_>, _>>();
+
+            match ciphertexts {
+                Ok(c) => Some(Ok(Ciphertext {
+                    data_type: data_type.clone(),
+                    inner: InnerCiphertext::Seal(c),
+                })),
+                Err(e) => {
+                    failed = true;
+                    Some(Err(e.into()))
+                }
+            }
+        }))
+    }
+
+    /**
+     * Returns an error if `public_key` lacks keys `fhe_program_fn` needs.
+     */
+    fn check_required_keys(fhe_program_fn: &FheProgram, public_key: &PublicKey) -> Result<()> {
+        if public_key.relin_key.is_none() && fhe_program_fn.requires_relin_keys() {
+            return Err(Error::MissingRelinearizationKeys);
+        }
+
+        if public_key.galois_key.is_none() && fhe_program_fn.requires_galois_keys() {
+            return Err(Error::MissingGaloisKeys);
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Checks `arguments` match `fhe_program`'s call signature and unpacks
+     * them into the values the backend operates on.
+     */
+    fn prepare_inputs<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        mut arguments: Vec<I>,
+    ) -> Result<Vec<SealData>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        let mut arguments: Vec<FheProgramInput> = arguments.drain(0..).map(|a| a.into()).collect();
+
+        // Check the passed arguments' types match the signature.
+        fhe_program.validate_inputs(&arguments)?;
+
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        let mut inputs: Vec<SealData> = vec![];
+
+        for i in arguments.drain(0..) {
+            match i {
+                FheProgramInput::Ciphertext(c) => match c.inner {
+                    InnerCiphertext::Seal(mut c) => {
+                        for j in c.drain(0..) {
+                            inputs.push(SealData::Ciphertext(j.data));
+                        }
+                    }
+                },
+                FheProgramInput::Plaintext(p) => {
+                    let p = p.try_into_plaintext(&fhe_data.params)?;
+
+                    match p.inner {
+                        InnerPlaintext::Seal(mut p) => {
+                            for j in p.drain(0..) {
+                                inputs.push(SealData::Plaintext(j.data));
                             }
                         }
                     }
                 }
+                FheProgramInput::EncodedPlaintext(p) => match p.inner {
+                    InnerPlaintext::Seal(mut p) => {
+                        for j in p.drain(0..) {
+                            inputs.push(SealData::Plaintext(j.data));
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(inputs)
+    }
+
+    fn run_selected_outputs<I>(
+        &self,
+        fhe_program: &CompiledFheProgram,
+        arguments: Vec<I>,
+        public_key: &PublicKey,
+        outputs: Option<&[usize]>,
+    ) -> Result<Vec<Ciphertext>>
+    where
+        I: Into<FheProgramInput>,
+    {
+        // We're going to call run_program_unchecked, which
+        // can result in undefined behavior, non-termination,
+        // or panics on malformed programs. Since this method is safe,
+        // it must guard against calling run_program_unchecked with
+        // inputs that result in undefined behavior.
+        fhe_program.fhe_program_fn.validate()?;
+
+        let signature = &fhe_program.metadata.signature;
+
+        if signature.num_ciphertexts.len() != signature.returns.len() {
+            return Err(Error::ReturnTypeMetadataError);
+        }
+
+        let (selected, fhe_program_fn) = match outputs {
+            Some(outputs) => {
+                if let Some(i) = outputs.iter().find(|i| **i >= signature.returns.len()) {
+                    return Err(Error::OutputIndexOutOfRange(*i));
+                }
+
+                let output_nodes = fhe_program.fhe_program_fn.get_outputs().collect::<Vec<_>>();
+
+                if output_nodes.len() != signature.num_ciphertexts.iter().sum::<usize>() {
+                    return Err(Error::ReturnTypeMetadataError);
+                }
+
+                // Each return value spans consecutive output nodes. Prune
+                // every node that doesn't feed a selected one.
+                let mut keep = vec![];
+                let mut offset = 0;
+
+                for (i, count) in signature.num_ciphertexts.iter().enumerate() {
+                    if outputs.contains(&i) {
+                        keep.extend_from_slice(&output_nodes[offset..offset + count]);
+                    }
+
+                    offset += count;
+                }
+
+                (
+                    outputs.to_owned(),
+                    Cow::Owned(fhe_program.fhe_program_fn.prune(&keep)),
+                )
+            }
+            None => (
+                (0..signature.returns.len()).collect::<Vec<_>>(),
+                Cow::Borrowed(&fhe_program.fhe_program_fn),
+            ),
+        };
+
+        // Aside from FHE program correctness, check that the required keys are given.
+        Self::check_required_keys(&fhe_program_fn, public_key)?;
+
+        let inputs = self.prepare_inputs(fhe_program, arguments)?;
+
+        let fhe_data = self.runtime_data.unwrap_fhe();
+
+        match &fhe_data.context {
+            Context::Seal(context) => {
+                let evaluator = BFVEvaluator::new(context)?;
 
                 let relin_key = public_key.relin_key.as_ref().map(|p| &p.data);
                 let galois_key = public_key.galois_key.as_ref().map(|p| &p.data);
 
                 let mut raw_ciphertexts = unsafe {
                     run_program_unchecked(
-                        &fhe_program.fhe_program_fn,
+                        &fhe_program_fn,
                         &inputs,
                         &evaluator,
                         &relin_key,
                         &galois_key,
+                        &fhe_data.params,
                     )
                 }?;
 
-                let mut packed_ciphertexts = vec![];
+                // Pruning preserves the order of the remaining output nodes.
+                let mut packed_ciphertexts = vec![None; signature.returns.len()];
 
-                for (i, ciphertext_count) in fhe_program
-                    .metadata
-                    .signature
-                    .num_ciphertexts
-                    .iter()
-                    .enumerate()
-                {
-                    packed_ciphertexts.push(Ciphertext {
-                        data_type: fhe_program.metadata.signature.returns[i].clone(),
+                for (i, ciphertext_count) in signature.num_ciphertexts.iter().enumerate() {
+                    if !selected.contains(&i) {
+                        continue;
+                    }
+
+                    packed_ciphertexts[i] = Some(Ciphertext {
+                        data_type: signature.returns[i].clone(),
                         inner: InnerCiphertext::Seal(
                             raw_ciphertexts
                                 .drain(0..*ciphertext_count)
@@ -410,7 +955,21 @@ where
                     });
                 }
 
-                Ok(packed_ciphertexts)
+                // Move each return value into its last requested position
+                // and clone it into any others.
+                Ok(selected
+                    .iter()
+                    .enumerate()
+                    .map(|(j, i)| {
+                        let ciphertext = if selected[j + 1..].contains(i) {
+                            packed_ciphertexts[*i].clone()
+                        } else {
+                            packed_ciphertexts[*i].take()
+                        };
+
+                        ciphertext.unwrap()
+                    })
+                    .collect())
             }
         }
     }
@@ -739,6 +1298,9 @@ where
 
     /**
      * Verify that the given `proof` satisfies the given `program`.
+     *
+     * # Remarks
+     * Verification fails unless the prover used the given public inputs.
      */
     pub fn verify<I>(
         &self,
@@ -882,6 +1444,14 @@ impl GenericRuntime<(), ()> {
         })
     }
 
+    /**
+     * Create a new Runtime supporting only FHE operations for running
+     * the programs in the given [`FheApplication`].
+     */
+    pub fn new_for_application(app: &FheApplication) -> Result<FheRuntime> {
+        Self::new_fhe(app.params())
+    }
+
     /**
      * Creates a new Runtime supporting only ZKP operations
      */
@@ -106,6 +106,15 @@ fn programmable_bootstrapping(c: &mut Criterion) {
                 evaluation::univariate_programmable_bootstrap(&ct, &lut, &bsk, lwe, glwe, bs_radix);
             });
         });
+
+        #[cfg(feature = "parallel")]
+        g.bench_function(format!("{name} (parallel)"), |b| {
+            b.iter(|| {
+                evaluation::univariate_programmable_bootstrap_parallel(
+                    &ct, &lut, &bsk, lwe, glwe, bs_radix,
+                );
+            });
+        });
     }
 
     let mut g = c.benchmark_group("Bootstrapping");
@@ -342,12 +351,53 @@ fn public_functional_keyswitching(c: &mut Criterion) {
     });
 }
 
+fn bulk_encryption(c: &mut Criterion) {
+    let sk = high_level::keygen::generate_binary_lwe_sk(&LWE_512_80);
+    let messages = (0..1024).map(|x| x % 2).collect::<Vec<u64>>();
+
+    c.bench_function("LWE encrypt 1024 (serial)", |b| {
+        b.iter(|| {
+            messages
+                .iter()
+                .map(|m| {
+                    high_level::encryption::encrypt_lwe_secret(
+                        *m,
+                        &sk,
+                        &LWE_512_80,
+                        PlaintextBits(1),
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+    });
+
+    c.bench_function("LWE encrypt 1024 (parallel)", |b| {
+        b.iter(|| {
+            high_level::encryption::encrypt_lwe_many(&messages, &sk, &LWE_512_80, PlaintextBits(1))
+        });
+    });
+
+    let pk = high_level::keygen::generate_lwe_pk(&sk, &LWE_512_80);
+
+    c.bench_function("LWE public key encrypt 1024 (serial)", |b| {
+        b.iter(|| {
+            messages
+                .iter()
+                .map(|m| {
+                    high_level::encryption::encrypt_lwe(*m, &pk, &LWE_512_80, PlaintextBits(1))
+                })
+                .collect::<Vec<_>>()
+        });
+    });
+}
+
 criterion_group!(
     benches,
     cmux,
     programmable_bootstrapping,
     circuit_bootstrapping,
     keygen,
-    public_functional_keyswitching
+    public_functional_keyswitching,
+    bulk_encryption
 );
 criterion_main!(benches);
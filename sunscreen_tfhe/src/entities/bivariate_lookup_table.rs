@@ -33,6 +33,13 @@ impl<S: TorusOps> OverlaySize for BivariateLookupTableRef<S> {
 impl<S: TorusOps> BivariateLookupTable<S> {
     /// Creates a [BivariateLookupTable] filled with the result of
     /// a function applied to every possible pair of plaintext inputs.
+    ///
+    /// # Panics
+    /// If `plaintext_bits` exceeds `carry_bits`, as the left input gets
+    /// shifted into the carry bits when packing the two inputs.
+    /// If the packed message (`plaintext_bits + carry_bits` bits) doesn't
+    /// fit in the polynomial degree of `glwe`.
+    /// If `map` returns a value that doesn't fit in `plaintext_bits`.
     pub fn trivial_from_fn<F>(
         map: F,
         glwe: &GlweDef,
@@ -74,6 +81,9 @@ impl<S: TorusOps> BivariateLookupTableRef<S> {
 
     /// Fills the [BivariateLookupTableRef] with the result of a bivariate
     /// function.
+    ///
+    /// # Panics
+    /// Under the same conditions as [`BivariateLookupTable::trivial_from_fn`].
     pub fn fill_trivial_from_fn<F: Fn(u64, u64) -> u64>(
         &mut self,
         map: F,
@@ -0,0 +1,235 @@
+use num::Complex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dst::OverlaySize,
+    entities::{
+        BootstrapKey, BootstrapKeyFft, BootstrapKeyFftRef, BootstrapKeyRef,
+        CircuitBootstrappingKeyswitchKeys, CircuitBootstrappingKeyswitchKeysRef,
+        PublicFunctionalKeyswitchKey, PublicFunctionalKeyswitchKeyRef,
+    },
+    Error, GlweDef, LweDef, RadixDecomposition, Result, TorusOps,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+/// The parameters a key was generated under. See [`KeyWithParams`].
+pub struct KeyParams {
+    /// The LWE parameters. For bootstrapping keys, these are the parameters of
+    /// the LWE secret key being bootstrapped. For keyswitch keys, these are the
+    /// parameters of the key being switched from.
+    pub lwe: LweDef,
+
+    /// The GLWE parameters. For bootstrapping keys, these are the parameters
+    /// of the GLWE secret key encrypting the bootstrapping key. For keyswitch
+    /// keys, these are the parameters of the key being switched to.
+    pub glwe: GlweDef,
+
+    /// The radix decomposition used in the key.
+    pub radix: RadixDecomposition,
+}
+
+/// A key whose layout is determined by a [`KeyParams`].
+pub trait ParameterizedKey {
+    /// The name of this key to report in errors.
+    const NAME: &'static str;
+
+    /// Whether this key's size matches what `params` requires.
+    fn matches_params(&self, params: &KeyParams) -> bool;
+}
+
+impl<S: TorusOps> ParameterizedKey for BootstrapKey<S> {
+    const NAME: &'static str = "bootstrapping key";
+
+    fn matches_params(&self, params: &KeyParams) -> bool {
+        self.as_slice().len()
+            == BootstrapKeyRef::<S>::size((params.lwe.dim, params.glwe.dim, params.radix.count))
+    }
+}
+
+impl ParameterizedKey for BootstrapKeyFft<Complex<f64>> {
+    const NAME: &'static str = "bootstrapping key";
+
+    fn matches_params(&self, params: &KeyParams) -> bool {
+        self.as_slice().len()
+            == BootstrapKeyFftRef::size((params.lwe.dim, params.glwe.dim, params.radix.count))
+    }
+}
+
+impl<S: TorusOps> ParameterizedKey for PublicFunctionalKeyswitchKey<S> {
+    const NAME: &'static str = "public functional keyswitch key";
+
+    fn matches_params(&self, params: &KeyParams) -> bool {
+        self.as_slice().len()
+            == PublicFunctionalKeyswitchKeyRef::<S>::size((
+                params.lwe.dim,
+                params.glwe.dim,
+                params.radix.count,
+            ))
+    }
+}
+
+impl<S: TorusOps> ParameterizedKey for CircuitBootstrappingKeyswitchKeys<S> {
+    const NAME: &'static str = "circuit bootstrapping keyswitch keys";
+
+    fn matches_params(&self, params: &KeyParams) -> bool {
+        self.as_slice().len()
+            == CircuitBootstrappingKeyswitchKeysRef::<S>::size((
+                params.lwe.dim,
+                params.glwe.dim,
+                params.radix.count,
+            ))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// A key bundled with the [`KeyParams`] it was generated under, so it can be
+/// persisted and later loaded with its parameters checked.
+///
+/// # Remarks
+/// Keys such as [`BootstrapKey`] serialize as just their coefficients, so
+/// deserializing one generated under different parameters silently produces
+/// garbage. Serialize a [`KeyWithParams`] instead and call
+/// [`into_key`](Self::into_key) with the parameters in use after
+/// deserializing it.
+///
+/// # Example
+/// ```
+/// use sunscreen_tfhe::{
+///   entities::{BootstrapKeyFft, KeyParams, KeyWithParams},
+///   high_level::{keygen, fft},
+///   params::{GLWE_1_1024_80, LWE_512_80, RadixCount, RadixDecomposition, RadixLog},
+/// };
+///
+/// let params = KeyParams {
+///     lwe: LWE_512_80,
+///     glwe: GLWE_1_1024_80,
+///     radix: RadixDecomposition {
+///         count: RadixCount(3),
+///         radix_log: RadixLog(4),
+///     },
+/// };
+///
+/// let lwe_sk = keygen::generate_binary_lwe_sk(&params.lwe);
+/// let glwe_sk = keygen::generate_binary_glwe_sk(&params.glwe);
+///
+/// let bsk = keygen::generate_bootstrapping_key(
+///     &lwe_sk, &glwe_sk, &params.lwe, &params.glwe, &params.radix
+/// );
+/// let bsk = fft::fft_bootstrap_key(&bsk, &params.lwe, &params.glwe, &params.radix);
+///
+/// // Persist the key with its parameters...
+/// let key = KeyWithParams::new(bsk, &params).unwrap();
+///
+/// // ...and check them when loading it.
+/// let bsk: BootstrapKeyFft<_> = key.into_key(&params).unwrap();
+/// ```
+pub struct KeyWithParams<K> {
+    params: KeyParams,
+    key: K,
+}
+
+impl<K: ParameterizedKey> KeyWithParams<K> {
+    /// Bundles `key` with the `params` it was generated under.
+    ///
+    /// # Errors
+    /// [`Error::KeyMismatch`] if `key`'s size doesn't match `params`.
+    pub fn new(key: K, params: &KeyParams) -> Result<Self> {
+        if !key.matches_params(params) {
+            return Err(Error::KeyMismatch(K::NAME));
+        }
+
+        Ok(Self {
+            params: *params,
+            key,
+        })
+    }
+
+    /// The parameters the key was generated under.
+    pub fn params(&self) -> &KeyParams {
+        &self.params
+    }
+
+    /// Returns the key, checking it was generated under `params`.
+    ///
+    /// # Errors
+    /// [`Error::ParameterMismatch`] if the key was generated under parameters
+    /// other than `params`.
+    /// [`Error::KeyMismatch`] if the key's size doesn't match `params` (e.g.
+    /// it was truncated or tampered with after serialization).
+    pub fn into_key(self, params: &KeyParams) -> Result<K> {
+        if self.params != *params {
+            return Err(Error::ParameterMismatch(K::NAME));
+        }
+
+        if !self.key.matches_params(params) {
+            return Err(Error::KeyMismatch(K::NAME));
+        }
+
+        Ok(self.key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        high_level::{fft, keygen, TEST_GLWE_DEF_1, TEST_LWE_DEF_1, TEST_LWE_DEF_2, TEST_RADIX},
+        RadixCount, RadixLog,
+    };
+
+    #[test]
+    fn can_roundtrip_key_with_params() {
+        let params = KeyParams {
+            lwe: TEST_LWE_DEF_1,
+            glwe: TEST_GLWE_DEF_1,
+            radix: TEST_RADIX,
+        };
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&params.lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&params.glwe);
+
+        let bsk = keygen::generate_bootstrapping_key(
+            &lwe_sk,
+            &glwe_sk,
+            &params.lwe,
+            &params.glwe,
+            &params.radix,
+        );
+        let bsk = fft::fft_bootstrap_key(&bsk, &params.lwe, &params.glwe, &params.radix);
+
+        let ser = bincode::serialize(&KeyWithParams::new(bsk.clone(), &params).unwrap()).unwrap();
+
+        let load = || bincode::deserialize::<KeyWithParams<BootstrapKeyFft<_>>>(&ser).unwrap();
+
+        assert_eq!(load().params(), &params);
+        assert_eq!(load().into_key(&params).unwrap().as_slice(), bsk.as_slice());
+
+        // Loading under other parameters fails.
+        let other_params = [
+            KeyParams {
+                lwe: TEST_LWE_DEF_2,
+                ..params
+            },
+            KeyParams {
+                radix: RadixDecomposition {
+                    count: RadixCount(4),
+                    radix_log: RadixLog(4),
+                },
+                ..params
+            },
+        ];
+
+        for other in other_params {
+            assert_eq!(
+                load().into_key(&other).unwrap_err(),
+                Error::ParameterMismatch("bootstrapping key")
+            );
+        }
+
+        // As does bundling a key with the wrong parameters.
+        assert_eq!(
+            KeyWithParams::new(bsk, &other_params[0]).unwrap_err(),
+            Error::KeyMismatch("bootstrapping key")
+        );
+    }
+}
@@ -141,7 +141,7 @@ where
 mod tests {
     use crate::{
         high_level::{encryption, keygen, TEST_LWE_DEF_1},
-        PlaintextBits,
+        PlaintextBits, LWE_512_80,
     };
 
     #[test]
@@ -168,4 +168,22 @@ mod tests {
         let ct = encryption::encrypt_lwe(5, &pk, &params, bits);
         assert_eq!(encryption::decrypt_lwe(&ct, &sk, &params, bits), 5);
     }
+
+    #[test]
+    fn public_key_encryption_noise_decrypts_under_real_params() {
+        // TEST_LWE_DEF_1 is nearly noiseless, so use real parameters to
+        // check the noise from summing encryptions of zero stays small.
+        let params = LWE_512_80;
+        let bits = PlaintextBits(4);
+
+        let sk = keygen::generate_binary_lwe_sk(&params);
+        let pk = keygen::generate_lwe_pk(&sk, &params);
+
+        for _ in 0..4 {
+            for msg in 0..(1 << bits.0) {
+                let ct = encryption::encrypt_lwe(msg, &pk, &params, bits);
+                assert_eq!(encryption::decrypt_lwe(&ct, &sk, &params, bits), msg);
+            }
+        }
+    }
 }
@@ -16,6 +16,9 @@ pub use circuit_bootstrapping_private_keyswitch_keys::*;
 mod bootstrap_key;
 pub use bootstrap_key::*;
 
+mod key_with_params;
+pub use key_with_params::*;
+
 mod univariate_lookup_table;
 pub use univariate_lookup_table::*;
 
@@ -8,6 +8,7 @@ use num::{Complex, Zero};
 use crate::{
     dst::{FromMutSlice, FromSlice, NoWrapper, OverlaySize},
     fft::negacyclic::get_fft,
+    macros::timed_scope,
     polynomial::{polynomial_add_assign, polynomial_external_mad, polynomial_sub_assign},
     scratch::allocate_scratch,
     FrequencyTransform, PolynomialDegree, ReinterpretAsSigned, ToF64, Torus, TorusOps,
@@ -245,6 +246,8 @@ where
 {
     /// Compute the FFT of the polynomial.
     pub fn fft(&self, out: &mut PolynomialFftRef<Complex<f64>>) {
+        timed_scope!(Fft);
+
         assert!(self.len().is_power_of_two());
         assert_eq!(self.len(), out.len() * 2);
 
@@ -3,6 +3,7 @@ use num::Complex;
 use crate::{
     dst::{NoWrapper, OverlaySize},
     fft::negacyclic::get_fft,
+    macros::timed_scope,
     scratch::allocate_scratch,
     simd, FrequencyTransform, FromF64, NumBits, PolynomialDegree,
 };
@@ -82,6 +83,8 @@ impl PolynomialFftRef<Complex<f64>> {
     where
         T: Clone + FromF64 + NumBits,
     {
+        timed_scope!(Ifft);
+
         assert!(self.len().is_power_of_two());
         assert_eq!(self.len() * 2, poly.len());
 
@@ -49,6 +49,36 @@ impl<S: TorusOps> UnivariateLookupTable<S> {
         lut
     }
 
+    /// Creates a trivially encrypted lookup table from the caller-provided
+    /// test polynomial `poly`.
+    ///
+    /// # Remarks
+    /// Unlike [`trivial_from_fn`](Self::trivial_from_fn), this uses `poly`
+    /// as is, so it can express lookup tables that don't follow the usual
+    /// redundant encoding (e.g. negacyclic functions). Blind rotation
+    /// rotates `poly` by the input's modulus-switched phase, so coefficient
+    /// `i` of `poly` is the result for phase `i`.
+    ///
+    /// # Panics
+    /// If `poly` doesn't have `glwe.dim.polynomial_degree` coefficients.
+    /// If `glwe` is invalid.
+    pub fn from_polynomial(poly: &PolynomialRef<Torus<S>>, glwe: &GlweDef) -> Self {
+        glwe.assert_valid();
+        assert_eq!(
+            poly.len(),
+            glwe.dim.polynomial_degree.0,
+            "Polynomial must have one coefficient per GLWE polynomial degree"
+        );
+
+        let mut lut = UnivariateLookupTable {
+            data: avec![Torus::zero(); UnivariateLookupTableRef::<S>::size(glwe.dim)],
+        };
+
+        trivially_encrypt_glwe_ciphertext(lut.glwe_mut(), poly, glwe);
+
+        lut
+    }
+
     /// Creates a trivially encrypted lookup table that computes multiple functions
     /// given by `maps`.
     ///
@@ -1,4 +1,27 @@
-#[derive(thiserror::Error)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+/// Errors that can occur in this crate.
 pub enum Error {
-    OutOfRange
-}
\ No newline at end of file
+    /// A value is out of range.
+    #[error("Value is out of range")]
+    OutOfRange,
+
+    /// A set of parameters is invalid.
+    #[error("The {0} parameters are invalid")]
+    InvalidParameters(&'static str),
+
+    /// A key's size doesn't match the parameters it was supposedly
+    /// generated under.
+    #[error("The {0} doesn't match its parameters")]
+    KeyMismatch(&'static str),
+
+    /// A key was generated under different parameters than the ones in use.
+    #[error("The {0} was generated under different parameters")]
+    ParameterMismatch(&'static str),
+
+    /// No known parameter set meets the requested constraints.
+    #[error("No parameter set supports the requested message size and security level")]
+    NoSuitableParameters,
+}
+
+/// A result type for this crate.
+pub type Result<T> = std::result::Result<T, Error>;
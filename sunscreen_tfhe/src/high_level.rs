@@ -1,8 +1,17 @@
 #![allow(dead_code)]
 
+use num::Complex;
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    rand::Stddev, GlweDef, GlweDimension, GlweSize, LweDef, LweDimension, PolynomialDegree,
-    RadixCount, RadixDecomposition, RadixLog,
+    dst::OverlaySize,
+    entities::{
+        BootstrapKeyFft, BootstrapKeyFftRef, CircuitBootstrappingKeyswitchKeys, GlweSecretKeyRef,
+        LweKeyswitchKeyRef, LweSecretKeyRef,
+    },
+    rand::Stddev,
+    Error, GlweDef, GlweDimension, GlweSize, LweDef, LweDimension, PolynomialDegree, RadixCount,
+    RadixDecomposition, RadixLog, Result,
 };
 
 #[doc(hidden)]
@@ -47,23 +56,189 @@ pub const TEST_LWE_DEF_3: LweDef = LweDef {
     std: Stddev(0.0),
 };
 
+/// Encrypted integers built from radix-decomposed LWE ciphertexts.
+pub mod integer;
+
+/// Operations over arrays of encrypted values.
+pub mod array;
+
+/// Check that a set of keys was generated under the given parameters and
+/// that the parameters fit together.
+///
+/// # Remarks
+/// `bsk` should bootstrap from `lwe_sk` to `glwe_sk` and `ksk` should
+/// keyswitch from `glwe_sk` (as an LWE key) back to `lwe_sk`, as created by
+/// [`keygen::generate_bootstrapping_key`] and [`keygen::generate_ksk`].
+/// Keys are plain arrays of torus elements that don't record their
+/// parameters, so combining keys generated under different parameters
+/// otherwise produces garbage rather than an error.
+///
+/// Returns [`Error::InvalidParameters`] if `lwe`, `glwe`, `pbs_radix`, or
+/// `ks_radix` are invalid and [`Error::KeyMismatch`] if a key's size
+/// disagrees with them.
+///
+/// This only checks sizes. It can't detect keys generated under the right
+/// parameters but from a different secret key.
+#[allow(clippy::too_many_arguments)]
+pub fn check_key_compatibility(
+    lwe_sk: &LweSecretKeyRef<u64>,
+    glwe_sk: &GlweSecretKeyRef<u64>,
+    bsk: &BootstrapKeyFftRef<Complex<f64>>,
+    ksk: &LweKeyswitchKeyRef<u64>,
+    lwe: &LweDef,
+    glwe: &GlweDef,
+    pbs_radix: &RadixDecomposition,
+    ks_radix: &RadixDecomposition,
+) -> Result<()> {
+    let radix_is_valid = |radix: &RadixDecomposition| {
+        radix.count.0 > 0
+            && radix.radix_log.0 > 0
+            && radix.count.0 * radix.radix_log.0 <= u64::BITS as usize
+    };
+
+    if lwe.dim.0 == 0 {
+        return Err(Error::InvalidParameters("LWE"));
+    }
+
+    let degree = glwe.dim.polynomial_degree.0;
+
+    if !degree.is_power_of_two() || glwe.dim.size.0 == 0 {
+        return Err(Error::InvalidParameters("GLWE"));
+    }
+
+    if !radix_is_valid(pbs_radix) {
+        return Err(Error::InvalidParameters("bootstrapping radix"));
+    }
+
+    if !radix_is_valid(ks_radix) {
+        return Err(Error::InvalidParameters("keyswitching radix"));
+    }
+
+    let check_size = |len: usize, expected: usize, key: &'static str| {
+        if len == expected {
+            Ok(())
+        } else {
+            Err(Error::KeyMismatch(key))
+        }
+    };
+
+    check_size(
+        lwe_sk.as_slice().len(),
+        LweSecretKeyRef::<u64>::size(lwe.dim),
+        "LWE secret key",
+    )?;
+
+    check_size(
+        glwe_sk.as_slice().len(),
+        GlweSecretKeyRef::<u64>::size(glwe.dim),
+        "GLWE secret key",
+    )?;
+
+    check_size(
+        bsk.as_slice().len(),
+        BootstrapKeyFftRef::<Complex<f64>>::size((lwe.dim, glwe.dim, pbs_radix.count)),
+        "bootstrapping key",
+    )?;
+
+    check_size(
+        ksk.as_slice().len(),
+        LweKeyswitchKeyRef::<u64>::size((glwe.dim.as_lwe_dimension(), lwe.dim, ks_radix.count)),
+        "keyswitch key",
+    )
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+/// The parameters of a
+/// [`keyswitch_and_bootstrap`](evaluation::keyswitch_and_bootstrap)
+/// operation.
+///
+/// # Remarks
+/// Inputs and outputs are encrypted under the GLWE key under `glwe`
+/// interpreted as an LWE key (see [`GlweDef::as_lwe_def`]). The keyswitch
+/// takes them down to the LWE key under `lwe` and the bootstrap brings them
+/// back up.
+pub struct KeyswitchBootstrapParams {
+    /// The parameters of the small LWE key inputs get keyswitched to.
+    pub lwe: LweDef,
+
+    /// The parameters of the GLWE key inputs get bootstrapped to.
+    pub glwe: GlweDef,
+
+    /// The radix decomposition used when keyswitching from `glwe` to `lwe`.
+    pub ks_radix: RadixDecomposition,
+
+    /// The radix decomposition used in programmable bootstrapping.
+    pub pbs_radix: RadixDecomposition,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+/// The parameters of a
+/// [`circuit_bootstrap`](evaluation::circuit_bootstrap) operation.
+///
+/// # Remarks
+/// Circuit bootstrapping involves 3 secret keys: the LWE key under `lwe_0`
+/// encrypting the input, the GLWE key under `glwe_2` the input gets
+/// bootstrapped to, and the GLWE key under `glwe_1` encrypting the resulting
+/// [`GgswCiphertext`](crate::entities::GgswCiphertext).
+pub struct CircuitBootstrapParams {
+    /// The parameters under which the input is encrypted.
+    pub lwe_0: LweDef,
+
+    /// The parameters of the GLWE key under which circuit bootstrapping
+    /// produces its output.
+    pub glwe_1: GlweDef,
+
+    /// The parameters of the GLWE key used internally during circuit
+    /// bootstrapping.
+    pub glwe_2: GlweDef,
+
+    /// The radix decomposition used in programmable bootstrapping.
+    pub pbs_radix: RadixDecomposition,
+
+    /// The radix decomposition of circuit bootstrapped
+    /// [`GgswCiphertext`](crate::entities::GgswCiphertext)s.
+    pub cbs_radix: RadixDecomposition,
+
+    /// The radix decomposition used in private functional keyswitching.
+    pub pfks_radix: RadixDecomposition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// The public keys needed to perform a
+/// [`circuit_bootstrap`](evaluation::circuit_bootstrap) operation.
+///
+/// # Remarks
+/// Use [`generate_circuit_bootstrap_keys`](keygen::generate_circuit_bootstrap_keys)
+/// to create one.
+pub struct CircuitBootstrapKeys {
+    /// A bootstrapping key from `lwe_0` to `glwe_2`.
+    pub bsk: BootstrapKeyFft<Complex<f64>>,
+
+    /// The keyswitch keys from `glwe_2` (as an LWE key) to `glwe_1`.
+    pub cbsksk: CircuitBootstrappingKeyswitchKeys<u64>,
+}
+
 /// TFHE functionality related to key generation.
 pub mod keygen {
     use crate::{
         entities::{
             BootstrapKey, CircuitBootstrappingKeyswitchKeys, GlweSecretKey, GlweSecretKeyRef,
             LweKeyswitchKey, LwePublicKey, LweSecretKey, LweSecretKeyRef,
+            PublicFunctionalKeyswitchKey,
         },
         ops::{
             bootstrapping::generate_bootstrap_key,
             keyswitch::{
                 lwe_keyswitch_key::generate_keyswitch_key_lwe,
                 private_functional_keyswitch::generate_circuit_bootstrapping_pfks_keys,
+                public_functional_keyswitch::generate_public_functional_keyswitch_key,
             },
         },
         GlweDef, LweDef, RadixDecomposition,
     };
 
+    use super::{CircuitBootstrapKeys, CircuitBootstrapParams};
+
     /// Generate a new binary [`LweSecretKey`] under the given LWE parameters.
     ///
     /// # Remarks
@@ -299,10 +474,97 @@ pub mod keygen {
 
         cbs_ksk
     }
+
+    /// Generate a [`PublicFunctionalKeyswitchKey`] for packing
+    /// [`LweCiphertext`](crate::entities::LweCiphertext)s encrypted under
+    /// `from_sk` into a [`GlweCiphertext`](crate::entities::GlweCiphertext)
+    /// encrypted under `to_sk` with
+    /// [`pack_lwes_into_glwe`](super::keyswitch::pack_lwes_into_glwe).
+    ///
+    /// # Remarks
+    /// The `from_lwe` and `to_glwe` parameters correspond to those used when
+    /// you generated `from_sk` and `to_sk`, respectively.
+    ///
+    /// # Panics
+    /// If `from_lwe`, `to_glwe`, or `radix` are invalid.
+    /// If `from_lwe` or `to_glwe` parameters don't correspond with `from_sk`
+    /// or `to_sk`, respectively.
+    ///
+    /// # Security
+    /// The returned key is public and sharing it does not compromise semantic
+    /// security. However, anyone who possesses `to_sk` can recover `from_sk`
+    /// using it.
+    pub fn generate_packing_ksk(
+        from_sk: &LweSecretKeyRef<u64>,
+        to_sk: &GlweSecretKeyRef<u64>,
+        from_lwe: &LweDef,
+        to_glwe: &GlweDef,
+        radix: &RadixDecomposition,
+    ) -> PublicFunctionalKeyswitchKey<u64> {
+        let mut ksk = PublicFunctionalKeyswitchKey::new(from_lwe, to_glwe, radix);
+
+        generate_public_functional_keyswitch_key(
+            &mut ksk, from_sk, to_sk, from_lwe, to_glwe, radix,
+        );
+
+        ksk
+    }
+
+    /// Generate the [`CircuitBootstrapKeys`] needed to
+    /// [`circuit_bootstrap`](super::evaluation::circuit_bootstrap) under
+    /// `params`.
+    ///
+    /// # Remarks
+    /// `sk_0`, `sk_1`, and `sk_2` must have been generated under
+    /// `params.lwe_0`, `params.glwe_1`, and `params.glwe_2` respectively.
+    ///
+    /// This generates the bootstrapping key with
+    /// [`generate_bootstrapping_key`] (and takes its Fourier transform) and
+    /// the keyswitch keys with [`generate_cbs_ksk`].
+    ///
+    /// # Panics
+    /// If any of the keys don't correspond with `params`.
+    /// If `params` is invalid.
+    ///
+    /// # Security
+    /// The returned keys are public, but anyone holding `sk_1` or `sk_2` can
+    /// use them to recover `sk_0`.
+    pub fn generate_circuit_bootstrap_keys(
+        sk_0: &LweSecretKeyRef<u64>,
+        sk_1: &GlweSecretKeyRef<u64>,
+        sk_2: &GlweSecretKeyRef<u64>,
+        params: &CircuitBootstrapParams,
+    ) -> CircuitBootstrapKeys {
+        let bsk = generate_bootstrapping_key(
+            sk_0,
+            sk_2,
+            &params.lwe_0,
+            &params.glwe_2,
+            &params.pbs_radix,
+        );
+        let bsk =
+            super::fft::fft_bootstrap_key(&bsk, &params.lwe_0, &params.glwe_2, &params.pbs_radix);
+
+        let cbsksk = generate_cbs_ksk(
+            sk_2.to_lwe_secret_key(),
+            sk_1,
+            &params.glwe_2.as_lwe_def(),
+            &params.glwe_1,
+            &params.pfks_radix,
+        );
+
+        CircuitBootstrapKeys { bsk, cbsksk }
+    }
 }
 
 /// TFHE functionality related to encryption.
 pub mod encryption {
+    #[cfg(any(test, feature = "seeded-rng"))]
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    #[cfg(any(test, feature = "seeded-rng"))]
+    use rayon::iter::{IndexedParallelIterator, IntoParallelIterator};
+    use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
     use crate::{
         entities::{
             GgswCiphertext, GgswCiphertextRef, GlweCiphertext, GlweCiphertextRef, GlweSecretKeyRef,
@@ -366,6 +628,89 @@ pub mod encryption {
         sk.encrypt(val, params, plaintext_bits)
     }
 
+    /// Create an [`LweCiphertext`] encryption of each value in `messages`
+    /// under [LweSecretKey](crate::entities::LweSecretKey) `sk`, encrypting
+    /// them in parallel.
+    ///
+    /// # Remarks
+    /// This is equivalent to calling [`encrypt_lwe_secret`] on each message,
+    /// but distributes the work over the rayon thread pool. The returned
+    /// ciphertexts are in the same order as `messages`.
+    ///
+    /// Each message draws its randomness from its worker thread's
+    /// [`thread_rng`](rand::thread_rng), so encryptions are independent of one
+    /// another. Since this RNG is seeded from OS entropy, results are not
+    /// reproducible across runs and the order in which messages get assigned
+    /// to threads has no bearing on security.
+    ///
+    /// `params` should be the same parameters used when generating the secret key.
+    /// `plaintext_bits` describes how many of the most-significant bits of the [`Torus`]
+    /// will contain each message. No message should exceed `2^plaintext_bits.0`.
+    ///
+    /// # Panics
+    /// If `params` is invalid.
+    /// If `params` don't correspond with `sk`.
+    pub fn encrypt_lwe_many(
+        messages: &[u64],
+        sk: &LweSecretKeyRef<u64>,
+        params: &LweDef,
+        plaintext_bits: PlaintextBits,
+    ) -> Vec<LweCiphertext<u64>> {
+        messages
+            .par_iter()
+            .map(|m| sk.encrypt(*m, params, plaintext_bits).0)
+            .collect()
+    }
+
+    /// Create an [`LweCiphertext`] encryption of each value in `messages`
+    /// under [LweSecretKey](crate::entities::LweSecretKey) `sk` in parallel,
+    /// drawing all randomness from `seed`.
+    ///
+    /// # Remarks
+    /// This behaves like [`encrypt_lwe_many`], except that each message's
+    /// randomness comes from its own [`StdRng`](rand::rngs::StdRng), whose
+    /// seed is derived from `seed` and the message's index. Encryptions are
+    /// still independent of one another, and calling this function twice with
+    /// the same `seed`, `messages`, `sk` and parameters produces identical
+    /// ciphertexts regardless of how rayon schedules the work. Results are
+    /// only guaranteed to be reproducible within the same version of this
+    /// crate; the seed derivation and sampling order may change between
+    /// releases.
+    ///
+    /// # Panics
+    /// If `params` is invalid.
+    /// If `params` don't correspond with `sk`.
+    ///
+    /// # Security
+    /// Anyone who knows `seed` can recompute the encryption randomness and
+    /// thus decrypt the returned ciphertexts. This function exists for tests
+    /// and reproducible benchmarks, so it's only available with the
+    /// `seeded-rng` feature; never enable it in production. Use
+    /// [`encrypt_lwe_many`] instead.
+    #[cfg(any(test, feature = "seeded-rng"))]
+    #[doc(hidden)]
+    pub fn encrypt_lwe_many_seeded(
+        messages: &[u64],
+        sk: &LweSecretKeyRef<u64>,
+        params: &LweDef,
+        plaintext_bits: PlaintextBits,
+        seed: [u8; 32],
+    ) -> Vec<LweCiphertext<u64>> {
+        let mut seeds = StdRng::from_seed(seed);
+        let seeds = messages
+            .iter()
+            .map(|_| seeds.gen::<[u8; 32]>())
+            .collect::<Vec<_>>();
+
+        messages
+            .par_iter()
+            .zip(seeds.into_par_iter())
+            .map(|(m, seed)| {
+                crate::rand::with_seeded_rng(seed, || sk.encrypt(*m, params, plaintext_bits).0)
+            })
+            .collect()
+    }
+
     /// Create an [LweCiphertext] encryption of `val` under the secret
     /// key that pairs with `pk`.
     ///
@@ -768,13 +1113,15 @@ pub mod evaluation {
 
     use crate::{
         entities::{
-            BootstrapKeyFft, BootstrapKeyFftRef, CircuitBootstrappingKeyswitchKeysRef,
-            GgswCiphertext, GgswCiphertextFftRef, GlweCiphertext, GlweCiphertextRef, LweCiphertext,
-            LweCiphertextRef, LweKeyswitchKeyRef, UnivariateLookupTableRef,
+            BivariateLookupTableRef, BootstrapKeyFft, GgswCiphertext, GgswCiphertextFftRef,
+            GlweCiphertext, GlweCiphertextRef, LweCiphertext, LweCiphertextRef, LweKeyswitchKeyRef,
+            UnivariateLookupTable, UnivariateLookupTableRef,
         },
-        GlweDef, LweDef, RadixDecomposition,
+        GlweDef, LweDef, PlaintextBits, RadixDecomposition,
     };
 
+    use super::{CircuitBootstrapKeys, CircuitBootstrapParams, KeyswitchBootstrapParams};
+
     /// Perform a multiplexing operation. When `b_fft` encrypts a zero polynomial,
     /// the resulting [`GlweCiphertext`] will the same message as `d_0`. When `b_fft`
     /// encrypts the 1 polynomial, the result will contain the same message as `d_1`.
@@ -844,7 +1191,102 @@ pub mod evaluation {
     ) -> LweCiphertext<u64> {
         let mut out = LweCiphertext::new(&glwe.as_lwe_def());
 
+        univariate_programmable_bootstrap_into(&mut out, input, lut, bsk, lwe, glwe, radix);
+
+        out
+    }
+
+    /// Like [`univariate_programmable_bootstrap`], but writes the result
+    /// into `out` instead of allocating it.
+    ///
+    /// # Remarks
+    /// Temporaries come from a per-thread pool, so after the first call on a
+    /// thread with given parameters, this doesn't allocate.
+    ///
+    /// # Panics
+    /// Under the same conditions as [`univariate_programmable_bootstrap`].
+    /// If `out` isn't valid under `glwe` interpreted as an [`LweDef`].
+    pub fn univariate_programmable_bootstrap_into(
+        out: &mut LweCiphertextRef<u64>,
+        input: &LweCiphertextRef<u64>,
+        lut: &UnivariateLookupTableRef<u64>,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        radix: &RadixDecomposition,
+    ) {
         crate::ops::bootstrapping::programmable_bootstrap_univariate(
+            out, input, lut, bsk, lwe, glwe, radix,
+        );
+    }
+
+    /// Keyswitch `input` down to `params.lwe`, then programmable bootstrap it
+    /// back up to `params.glwe`, applying the function defined by `lut`.
+    ///
+    /// # Remarks
+    /// `input` and the result are both encrypted under the GLWE secret key
+    /// for `params.glwe` interpreted as an
+    /// [`LweSecretKey`](crate::entities::LweSecretKey) (see
+    /// [`GlweSecretKeyRef::to_lwe_secret_key`](crate::entities::GlweSecretKeyRef::to_lwe_secret_key)),
+    /// so one can feed the result into further calls without keyswitching.
+    ///
+    /// `ksk` should keyswitch from `params.glwe` (as an LWE key) to
+    /// `params.lwe` under `params.ks_radix`, and `bsk` should bootstrap from
+    /// `params.lwe` to `params.glwe` under `params.pbs_radix`, as created by
+    /// [`generate_ksk`](super::keygen::generate_ksk) and
+    /// [`generate_bootstrapping_key`](super::keygen::generate_bootstrapping_key).
+    ///
+    /// # Panics
+    /// If any of `params` are invalid.
+    /// If `input` isn't valid under `params.glwe` as an LWE.
+    /// If `ksk`, `bsk`, or `lut` don't correspond to `params`.
+    pub fn keyswitch_and_bootstrap(
+        input: &LweCiphertextRef<u64>,
+        ksk: &LweKeyswitchKeyRef<u64>,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lut: &UnivariateLookupTableRef<u64>,
+        params: &KeyswitchBootstrapParams,
+    ) -> LweCiphertext<u64> {
+        let small = keyswitch_lwe_to_lwe(
+            input,
+            ksk,
+            &params.glwe.as_lwe_def(),
+            &params.lwe,
+            &params.ks_radix,
+        );
+
+        univariate_programmable_bootstrap(
+            &small,
+            lut,
+            bsk,
+            &params.lwe,
+            &params.glwe,
+            &params.pbs_radix,
+        )
+    }
+
+    #[cfg(feature = "parallel")]
+    /// Like [`univariate_programmable_bootstrap`], but parallelizes each
+    /// blind rotation step on the `rayon` thread pool. The result is
+    /// bit-identical.
+    ///
+    /// See
+    /// [`programmable_bootstrap_univariate_parallel`](crate::ops::bootstrapping::programmable_bootstrap_univariate_parallel)
+    /// for how the work is divided.
+    ///
+    /// # Panics
+    /// Under the same conditions as [`univariate_programmable_bootstrap`].
+    pub fn univariate_programmable_bootstrap_parallel(
+        input: &LweCiphertextRef<u64>,
+        lut: &UnivariateLookupTableRef<u64>,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        radix: &RadixDecomposition,
+    ) -> LweCiphertext<u64> {
+        let mut out = LweCiphertext::new(&glwe.as_lwe_def());
+
+        crate::ops::bootstrapping::programmable_bootstrap_univariate_parallel(
             &mut out, input, lut, bsk, lwe, glwe, radix,
         );
 
@@ -852,15 +1294,189 @@ pub mod evaluation {
     }
 
     #[allow(clippy::too_many_arguments)]
+    /// Perform a programmable bootstrapping operation that applies the
+    /// bivariate function defined by `lut` to the messages in `left` and
+    /// `right`, producing a new ciphertext with a fixed noise level.
+    ///
+    /// This packs the two inputs into one ciphertext by scaling `left` by
+    /// `2^plaintext_bits` and adding `right`, then bootstraps the packed
+    /// ciphertext. As such, `left` and `right` must be encrypted with
+    /// `plaintext_bits + carry_bits` bits of message space (with the message
+    /// in the low `plaintext_bits`), where `carry_bits` matches the value
+    /// passed to [`BivariateLookupTable::trivial_from_fn`](crate::entities::BivariateLookupTable::trivial_from_fn).
+    ///
+    /// The result is encrypted under the
+    /// [`LweSecretKey`](crate::entities::LweSecretKey) defined by `glwe`
+    /// interpreted as an [`LweDef`] with the same encoding as the inputs.
+    ///
+    /// # Remarks
+    /// `left` and `right` must be valid under the `lwe` parameters.
+    /// `lwe`, `glwe`, and `radix` parameters must be the same as those used when
+    /// first creating the `bsk`.
+    /// `lut` must be valid under `glwe` parameters and generated with the same
+    /// `plaintext_bits`.
+    ///
+    /// # Panics
+    /// If `lwe`, `glwe`, or `radix` parameters are invalid.
+    /// If `left` or `right` don't correspond to `lwe` parameters.
+    /// If `bsk` doesn't correspond to `lwe`, `glwe`, `radix` parameters.
+    /// If `lut` doesn't correspond to `glwe` parameters.
+    pub fn bivariate_programmable_bootstrap(
+        left: &LweCiphertextRef<u64>,
+        right: &LweCiphertextRef<u64>,
+        lut: &BivariateLookupTableRef<u64>,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        plaintext_bits: PlaintextBits,
+        radix: &RadixDecomposition,
+    ) -> LweCiphertext<u64> {
+        let mut out = LweCiphertext::new(&glwe.as_lwe_def());
+
+        crate::ops::bootstrapping::programmable_bootstrap_bivariate(
+            &mut out,
+            left,
+            right,
+            lut,
+            bsk,
+            lwe,
+            glwe,
+            plaintext_bits,
+            radix,
+        );
+
+        out
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    /// Evaluate each function in `maps` on the message in `input` with a
+    /// single blind rotation. Element `i` of the result encrypts
+    /// `maps[i](m)`.
+    ///
+    /// # Remarks
+    /// Rather than bootstrapping once per function, this performs a single
+    /// many-LUT bootstrap: the functions are interleaved in one
+    /// [`UnivariateLookupTable`] (see
+    /// [`trivivial_multifunctional`](UnivariateLookupTable::trivivial_multifunctional)),
+    /// blind rotated once, and each result is sample extracted from
+    /// consecutive coefficients. This costs about as much as one
+    /// [`univariate_programmable_bootstrap`] regardless of how many functions
+    /// you evaluate.
+    ///
+    /// Evaluating `maps.len()` functions consumes `ceil(log2(maps.len()))`
+    /// additional bits of precision during modulus switching. `input` must
+    /// encrypt a `plaintext_bits` message with a zero padding bit above it
+    /// (i.e. encrypt it with `plaintext_bits + 1` bits), and `glwe` must
+    /// satisfy
+    /// `polynomial_degree >= 2^(plaintext_bits + 1 + ceil(log2(maps.len())))`.
+    /// Parameters with little headroom beyond this bound will decrypt
+    /// incorrectly due to modulus switching noise.
+    ///
+    /// The outputs are encrypted under the [`GlweSecretKey`](crate::entities::GlweSecretKey)
+    /// under which `bsk` was generated reinterpreted as an
+    /// [`LweSecretKey`](crate::entities::LweSecretKey) and encode each result
+    /// with `plaintext_bits` bits of message. `lwe`, `glwe`, and `radix`
+    /// must be the parameters used to create `bsk`.
+    ///
+    /// # Panics
+    /// If `maps` is empty.
+    /// If any function returns a value that doesn't fit in `plaintext_bits`.
+    /// If `input` isn't valid under `lwe`.
+    /// If `bsk` doesn't correspond to `lwe`, `glwe`, and `radix`.
+    /// If `glwe` can't fit `maps.len()` functions of a `plaintext_bits` message.
+    pub fn multivalue_programmable_bootstrap<F>(
+        input: &LweCiphertextRef<u64>,
+        maps: &[F],
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        radix: &RadixDecomposition,
+        plaintext_bits: PlaintextBits,
+    ) -> Vec<LweCiphertext<u64>>
+    where
+        F: Fn(u64) -> u64,
+    {
+        assert!(!maps.is_empty());
+
+        if maps.len() == 1 {
+            let lut = UnivariateLookupTable::trivial_from_fn(&maps[0], glwe, plaintext_bits);
+
+            return vec![univariate_programmable_bootstrap(
+                input, &lut, bsk, lwe, glwe, radix,
+            )];
+        }
+
+        let log_v = maps.len().next_power_of_two().ilog2();
+
+        assert!(
+            plaintext_bits.0 + 1 + log_v <= glwe.dim.polynomial_degree.0.ilog2(),
+            "glwe polynomial degree is too small to evaluate {} functions",
+            maps.len()
+        );
+
+        let lut = UnivariateLookupTable::trivivial_multifunctional(maps, glwe, plaintext_bits);
+
+        let mut glwe_out = GlweCiphertext::new(glwe);
+
+        crate::ops::bootstrapping::generalized_programmable_bootstrap(
+            &mut glwe_out,
+            input,
+            &lut,
+            bsk,
+            0,
+            log_v,
+            lwe,
+            glwe,
+            radix,
+        );
+
+        (0..maps.len())
+            .map(|i| sample_extract(&glwe_out, glwe, i))
+            .collect()
+    }
+
+    /// Decompose the message in `input` into its `n_bits` least-significant
+    /// bits, each in its own [`LweCiphertext`]. Element `i` of the result
+    /// encrypts bit `i` (i.e. `(m >> i) & 1`).
+    ///
+    /// # Remarks
+    /// This evaluates every bit-extraction function with a single
+    /// [`multivalue_programmable_bootstrap`], so `input`, `glwe`, and the
+    /// outputs have the same requirements as there, with `n_bits` functions.
+    ///
+    /// # Panics
+    /// If `n_bits` is zero or exceeds `plaintext_bits`.
+    /// If `input` isn't valid under `lwe`.
+    /// If `bsk` doesn't correspond to `lwe`, `glwe`, and `radix`.
+    /// If `glwe` can't fit `n_bits` functions of a `plaintext_bits` message.
+    pub fn bit_decompose(
+        input: &LweCiphertextRef<u64>,
+        n_bits: usize,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        radix: &RadixDecomposition,
+        plaintext_bits: PlaintextBits,
+    ) -> Vec<LweCiphertext<u64>> {
+        assert!(n_bits > 0);
+        assert!(n_bits <= plaintext_bits.0 as usize);
+
+        let maps = (0..n_bits)
+            .map(|i| move |x: u64| (x >> i) & 0x1)
+            .collect::<Vec<_>>();
+
+        multivalue_programmable_bootstrap(input, &maps, bsk, lwe, glwe, radix, plaintext_bits)
+    }
+
     /// Perform a circuit bootstrapping operation. Circuit bootstrapping takes
     /// `input` [LweCiphertext] encrypted under a [LweSecretKey](crate::entities::LweSecretKey)
-    /// constructed with `lwe_0` parameters and produces a [GgswCiphertext] encrypted
-    /// under a [GlweSecretKey](crate::entities::GlweSecretKey) constructed with `glwe_1`
-    /// parameters.
+    /// constructed with `params.lwe_0` parameters and produces a [GgswCiphertext] encrypted
+    /// under a [GlweSecretKey](crate::entities::GlweSecretKey) constructed with
+    /// `params.glwe_1` parameters.
     ///
-    /// See also [generate_bootstrapping_key](super::keygen::generate_bootstrapping_key) and
-    /// [generate_cbs_pfks](super::keygen::generate_cbs_ksk) for how to generate the required
-    /// keys.
+    /// See also
+    /// [generate_circuit_bootstrap_keys](super::keygen::generate_circuit_bootstrap_keys)
+    /// for how to generate `keys`.
     ///
     /// # Remarks
     /// Internally, circuit bootstrapping occurs in 2 steps. First we perform programmable
@@ -882,35 +1498,33 @@ pub mod evaluation {
     ///
     /// See [crate::ops::bootstrapping::circuit_bootstrap] for more details.
     ///
-    /// `pbs_radix` parameterizes the bootstrapping operation (step 1).
-    ///
-    /// `pfks_radix` parameterizes the PFKS operation (step 2). These should
-    ///
-    /// `cbs_radix` parameterizes the final decomposition of the resulting [`GgswCiphertext`]. This
-    /// should match the radix used when creating the
-    /// [CircuitBootstrappingKeyswitchKeys](crate::entities::CircuitBootstrappingKeyswitchKeys)
-    ///
+    /// `params.pbs_radix` parameterizes the bootstrapping operation (step 1) and
+    /// `params.pfks_radix` parameterizes the PFKS operation (step 2).
+    /// `params.cbs_radix` parameterizes the final decomposition of the resulting
+    /// [`GgswCiphertext`].
     ///
     /// # Panics
-    /// If `pbs_radix`, `cbs_radix`, `pfksk_radix`, `lwe_0`, `glwe_2`, or `glwe_1` are invalid.
-    /// If `pbs_radix`, `lwe_0`, `glwe_2` don't match the `radix`, `lwe`, `glwe` (respectively) used to generate `bsk`.
-    /// If `pfks_radix`, `glwe_2.as_lwe_def()`, `glwe_1` parameters don't match the `radix`, `from_lwe`, `to_lwe`
-    /// (respectively) used to generate `cbsksk`.
+    /// If `params` is invalid.
+    /// If `input` isn't valid under `params.lwe_0`.
+    /// If `keys` weren't generated under `params`.
     pub fn circuit_bootstrap(
         input: &LweCiphertextRef<u64>,
-        bsk: &BootstrapKeyFftRef<Complex<f64>>,
-        cbsksk: &CircuitBootstrappingKeyswitchKeysRef<u64>,
-        lwe_0: &LweDef,
-        glwe_1: &GlweDef,
-        glwe_2: &GlweDef,
-        pbs_radix: &RadixDecomposition,
-        cbs_radix: &RadixDecomposition,
-        pfks_radix: &RadixDecomposition,
+        keys: &CircuitBootstrapKeys,
+        params: &CircuitBootstrapParams,
     ) -> GgswCiphertext<u64> {
-        let mut out = GgswCiphertext::new(glwe_1, cbs_radix);
+        let mut out = GgswCiphertext::new(&params.glwe_1, &params.cbs_radix);
 
         crate::ops::bootstrapping::circuit_bootstrap(
-            &mut out, input, bsk, cbsksk, lwe_0, glwe_1, glwe_2, pbs_radix, cbs_radix, pfks_radix,
+            &mut out,
+            input,
+            &keys.bsk,
+            &keys.cbsksk,
+            &params.lwe_0,
+            &params.glwe_1,
+            &params.glwe_2,
+            &params.pbs_radix,
+            &params.cbs_radix,
+            &params.pfks_radix,
         );
 
         out
@@ -938,18 +1552,30 @@ pub mod evaluation {
         radix: &RadixDecomposition,
     ) -> LweCiphertext<u64> {
         let mut new_ct = LweCiphertext::new(to_lwe);
-        crate::ops::keyswitch::lwe_keyswitch::keyswitch_lwe_to_lwe(
-            &mut new_ct,
-            ct,
-            ksk,
-            from_lwe,
-            to_lwe,
-            radix,
-        );
+        keyswitch_lwe_to_lwe_into(&mut new_ct, ct, ksk, from_lwe, to_lwe, radix);
 
         new_ct
     }
 
+    /// Like [`keyswitch_lwe_to_lwe`], but writes the result into `out`
+    /// instead of allocating it. This doesn't allocate.
+    ///
+    /// # Panics
+    /// Under the same conditions as [`keyswitch_lwe_to_lwe`].
+    /// If `out` isn't valid under `to_lwe`.
+    pub fn keyswitch_lwe_to_lwe_into(
+        out: &mut LweCiphertextRef<u64>,
+        ct: &LweCiphertextRef<u64>,
+        ksk: &LweKeyswitchKeyRef<u64>,
+        from_lwe: &LweDef,
+        to_lwe: &LweDef,
+        radix: &RadixDecomposition,
+    ) {
+        crate::ops::keyswitch::lwe_keyswitch::keyswitch_lwe_to_lwe(
+            out, ct, ksk, from_lwe, to_lwe, radix,
+        );
+    }
+
     /// Create an [`LweCiphertext`] encrypting the `coeff_idx`-th coefficient in `ct`'s message.
     ///
     /// # Remarks
@@ -967,10 +1593,404 @@ pub mod evaluation {
         glwe_def: &GlweDef,
         coeff_idx: usize,
     ) -> LweCiphertext<u64> {
-        let mut result = LweCiphertext::new(&glwe_def.as_lwe_def());
+        crate::ops::sample_extract::sample_extract(ct, coeff_idx, glwe_def)
+    }
+}
 
-        crate::ops::ciphertext::sample_extract(&mut result, ct, coeff_idx, glwe_def);
+/// TFHE functionality related to key switching.
+pub mod keyswitch {
+    use crate::{
+        entities::{GlweCiphertext, LweCiphertext, PublicFunctionalKeyswitchKeyRef},
+        ops::keyswitch::public_functional_keyswitch::public_functional_keyswitch,
+        GlweDef, LweDef, RadixDecomposition,
+    };
+
+    /// Pack `cts` into a single [`GlweCiphertext`] whose `i`-th message
+    /// coefficient is the message in `cts[i]`. The remaining coefficients are
+    /// zero.
+    ///
+    /// # Remarks
+    /// `cts` must be encrypted under the `from_sk` used to generate `ksk`
+    /// with [`generate_packing_ksk`](super::keygen::generate_packing_ksk),
+    /// and the result is encrypted under its `to_sk`. The messages keep the
+    /// same encoding they had in `cts`.
+    ///
+    /// Packing lets one amortize subsequent GLWE operations over many
+    /// messages. It adds noise in proportion to the noise in `ksk`, the
+    /// dimension of `from_lwe`, and `radix`.
+    ///
+    /// # Panics
+    /// If `cts.len()` exceeds `to_glwe.dim.polynomial_degree.0`.
+    /// If any of `cts` aren't valid under `from_lwe`.
+    /// If `ksk` doesn't correspond with `from_lwe`, `to_glwe`, and `radix`.
+    pub fn pack_lwes_into_glwe(
+        cts: &[LweCiphertext<u64>],
+        ksk: &PublicFunctionalKeyswitchKeyRef<u64>,
+        from_lwe: &LweDef,
+        to_glwe: &GlweDef,
+        radix: &RadixDecomposition,
+    ) -> GlweCiphertext<u64> {
+        let degree = to_glwe.dim.polynomial_degree.0;
+
+        assert!(
+            cts.len() <= degree,
+            "Can't pack {} ciphertexts into a GLWE ciphertext with polynomial degree {degree}",
+            cts.len()
+        );
+
+        let inputs = cts.iter().map(|x| x.as_ref()).collect::<Vec<_>>();
+        let mut result = GlweCiphertext::new(to_glwe);
+
+        public_functional_keyswitch(
+            &mut result,
+            &inputs,
+            ksk,
+            |poly, vals| {
+                for (c, v) in poly.coeffs_mut().iter_mut().zip(vals) {
+                    *c = *v;
+                }
+            },
+            from_lwe,
+            to_glwe,
+            radix,
+        );
 
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entities::{BivariateLookupTable, Polynomial, UnivariateLookupTable},
+        CarryBits, PlaintextBits, GLWE_1_1024_80, GLWE_5_256_80, LWE_512_80,
+    };
+
+    #[test]
+    fn can_check_key_compatibility() {
+        let lwe = TEST_LWE_DEF_1;
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let bsk = keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, &lwe, &glwe, &radix);
+        let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+
+        let ksk = keygen::generate_ksk(
+            glwe_sk.to_lwe_secret_key(),
+            &lwe_sk,
+            &glwe.as_lwe_def(),
+            &lwe,
+            &radix,
+        );
+
+        check_key_compatibility(&lwe_sk, &glwe_sk, &bsk, &ksk, &lwe, &glwe, &radix, &radix)
+            .unwrap();
+
+        // Keys generated under different parameters.
+        let other_lwe_sk = keygen::generate_binary_lwe_sk(&TEST_LWE_DEF_2);
+        let other_glwe_sk = keygen::generate_binary_glwe_sk(&TEST_GLWE_DEF_2);
+
+        let other_ksk = keygen::generate_ksk(
+            glwe_sk.to_lwe_secret_key(),
+            &other_lwe_sk,
+            &glwe.as_lwe_def(),
+            &TEST_LWE_DEF_2,
+            &radix,
+        );
+
+        assert_eq!(
+            check_key_compatibility(
+                &other_lwe_sk,
+                &glwe_sk,
+                &bsk,
+                &ksk,
+                &lwe,
+                &glwe,
+                &radix,
+                &radix
+            ),
+            Err(Error::KeyMismatch("LWE secret key"))
+        );
+        assert_eq!(
+            check_key_compatibility(
+                &lwe_sk,
+                &other_glwe_sk,
+                &bsk,
+                &ksk,
+                &lwe,
+                &glwe,
+                &radix,
+                &radix
+            ),
+            Err(Error::KeyMismatch("GLWE secret key"))
+        );
+        assert_eq!(
+            check_key_compatibility(
+                &lwe_sk, &glwe_sk, &bsk, &other_ksk, &lwe, &glwe, &radix, &radix
+            ),
+            Err(Error::KeyMismatch("keyswitch key"))
+        );
+
+        // Keys generated with a different radix decomposition.
+        let other_radix = RadixDecomposition {
+            count: RadixCount(4),
+            radix_log: RadixLog(4),
+        };
+
+        assert_eq!(
+            check_key_compatibility(
+                &lwe_sk,
+                &glwe_sk,
+                &bsk,
+                &ksk,
+                &lwe,
+                &glwe,
+                &other_radix,
+                &radix
+            ),
+            Err(Error::KeyMismatch("bootstrapping key"))
+        );
+        assert_eq!(
+            check_key_compatibility(
+                &lwe_sk,
+                &glwe_sk,
+                &bsk,
+                &ksk,
+                &lwe,
+                &glwe,
+                &radix,
+                &other_radix
+            ),
+            Err(Error::KeyMismatch("keyswitch key"))
+        );
+
+        let invalid_radix = RadixDecomposition {
+            count: RadixCount(17),
+            radix_log: RadixLog(4),
+        };
+
+        assert_eq!(
+            check_key_compatibility(
+                &lwe_sk,
+                &glwe_sk,
+                &bsk,
+                &ksk,
+                &lwe,
+                &glwe,
+                &invalid_radix,
+                &radix
+            ),
+            Err(Error::InvalidParameters("bootstrapping radix"))
+        );
+    }
+
+    #[test]
+    fn can_bivariate_programmable_bootstrap() {
+        let lwe = TEST_LWE_DEF_1;
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+        let plaintext_bits = PlaintextBits(1);
+        let carry_bits = CarryBits(1);
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let bsk = keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, &lwe, &glwe, &radix);
+        let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+
+        let less_than = |x: u64, y: u64| (x < y) as u64;
+        let lut =
+            BivariateLookupTable::trivial_from_fn(less_than, &glwe, plaintext_bits, carry_bits);
+
+        for x in 0..2 {
+            for y in 0..2 {
+                // Inputs carry the plaintext, carry, and padding bits.
+                let encrypt = |m| {
+                    encryption::encrypt_lwe_secret(
+                        m,
+                        &lwe_sk,
+                        &lwe,
+                        PlaintextBits(plaintext_bits.0 + carry_bits.0 + 1),
+                    )
+                };
+
+                let result = evaluation::bivariate_programmable_bootstrap(
+                    &encrypt(x),
+                    &encrypt(y),
+                    &lut,
+                    &bsk,
+                    &lwe,
+                    &glwe,
+                    plaintext_bits,
+                    &radix,
+                );
+
+                let actual = encryption::decrypt_lwe_with_carry(
+                    &result,
+                    glwe_sk.to_lwe_secret_key(),
+                    &glwe.as_lwe_def(),
+                    plaintext_bits,
+                    carry_bits,
+                );
+
+                assert_eq!(actual, less_than(x, y));
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "must fit in the GLWE polynomial degree")]
+    fn bivariate_lut_must_fit_polynomial() {
+        // 4 plaintext and 4 carry bits need 256 coefficients, but
+        // TEST_GLWE_DEF_1 only has 128.
+        BivariateLookupTable::<u64>::trivial_from_fn(
+            |x, y| x & y,
+            &TEST_GLWE_DEF_1,
+            PlaintextBits(4),
+            CarryBits(4),
+        );
+    }
+
+    #[test]
+    fn can_keyswitch_and_bootstrap() {
+        let params = KeyswitchBootstrapParams {
+            lwe: TEST_LWE_DEF_1,
+            glwe: TEST_GLWE_DEF_1,
+            ks_radix: TEST_RADIX,
+            pbs_radix: TEST_RADIX,
+        };
+        let bits = PlaintextBits(2);
+        let big_lwe = params.glwe.as_lwe_def();
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&params.lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&params.glwe);
+        let big_lwe_sk = glwe_sk.to_lwe_secret_key();
+
+        let ksk =
+            keygen::generate_ksk(big_lwe_sk, &lwe_sk, &big_lwe, &params.lwe, &params.ks_radix);
+        let bsk = keygen::generate_bootstrapping_key(
+            &lwe_sk,
+            &glwe_sk,
+            &params.lwe,
+            &params.glwe,
+            &params.pbs_radix,
+        );
+        let bsk = fft::fft_bootstrap_key(&bsk, &params.lwe, &params.glwe, &params.pbs_radix);
+
+        let map = |x| (x + 1) % 4;
+        let lut = UnivariateLookupTable::trivial_from_fn(map, &params.glwe, bits);
+
+        for x in 0..4 {
+            // Inputs need a padding bit.
+            let ct =
+                encryption::encrypt_lwe_secret(x, big_lwe_sk, &big_lwe, PlaintextBits(bits.0 + 1));
+
+            let result = evaluation::keyswitch_and_bootstrap(&ct, &ksk, &bsk, &lut, &params);
+
+            assert_eq!(big_lwe_sk.decrypt(&result, &big_lwe, bits), map(x));
+        }
+    }
+
+    #[test]
+    fn can_pack_lwes_into_glwe() {
+        let lwe = TEST_LWE_DEF_1;
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+        let bits = PlaintextBits(4);
+        let degree = glwe.dim.polynomial_degree.0;
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let ksk = keygen::generate_packing_ksk(&lwe_sk, &glwe_sk, &lwe, &glwe, &radix);
+
+        let msgs = (0..degree as u64 / 2).map(|x| x % 16).collect::<Vec<_>>();
+        let cts = msgs
+            .iter()
+            .map(|m| encryption::encrypt_lwe_secret(*m, &lwe_sk, &lwe, bits))
+            .collect::<Vec<_>>();
+
+        let packed = keyswitch::pack_lwes_into_glwe(&cts, &ksk, &lwe, &glwe, &radix);
+        let actual = encryption::decrypt_glwe(&packed, &glwe_sk, &glwe, bits);
+
+        for (i, c) in actual.coeffs().iter().enumerate() {
+            assert_eq!(*c, msgs.get(i).copied().unwrap_or(0));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't pack")]
+    fn pack_lwes_into_glwe_checks_count() {
+        let lwe = TEST_LWE_DEF_1;
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let ksk = keygen::generate_packing_ksk(&lwe_sk, &glwe_sk, &lwe, &glwe, &radix);
+
+        let cts = vec![
+            encryption::trivial_lwe(0, &lwe, PlaintextBits(1));
+            glwe.dim.polynomial_degree.0 + 1
+        ];
+
+        keyswitch::pack_lwes_into_glwe(&cts, &ksk, &lwe, &glwe, &radix);
+    }
+
+    #[test]
+    fn can_circuit_bootstrap_with_key_bundle() {
+        let params = CircuitBootstrapParams {
+            lwe_0: LWE_512_80,
+            glwe_1: GLWE_1_1024_80,
+            glwe_2: GLWE_5_256_80,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(16),
+            },
+            cbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(5),
+            },
+            pfks_radix: RadixDecomposition {
+                count: RadixCount(3),
+                radix_log: RadixLog(11),
+            },
+        };
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let keys = keygen::generate_circuit_bootstrap_keys(&sk_0, &sk_1, &sk_2, &params);
+
+        let bits = PlaintextBits(4);
+        let degree = params.glwe_1.dim.polynomial_degree.0;
+
+        let constant = |x: u64| {
+            let mut coeffs = vec![0; degree];
+            coeffs[0] = x;
+
+            encryption::trivial_glwe(&Polynomial::new(&coeffs), &params.glwe_1, bits)
+        };
+
+        let d_0 = constant(5);
+        let d_1 = constant(11);
+
+        for val in [0, 1] {
+            let ct = encryption::encrypt_lwe_secret(val, &sk_0, &params.lwe_0, PlaintextBits(1));
+
+            let ggsw = evaluation::circuit_bootstrap(&ct, &keys, &params);
+            let ggsw = fft::fft_ggsw(&ggsw, &params.glwe_1, &params.cbs_radix);
+
+            let result = evaluation::cmux(&ggsw, &d_0, &d_1, &params.glwe_1, &params.cbs_radix);
+            let actual = encryption::decrypt_glwe(&result, &sk_1, &params.glwe_1, bits);
+
+            assert_eq!(actual.coeffs()[0], if val == 0 { 5 } else { 11 });
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use num::traits::WrappingNeg;
+
+use crate::{
+    entities::{
+        GgswCiphertext, GgswCiphertextFft, GlweCiphertext, LweCiphertext, LweCiphertextRef,
+    },
+    high_level::{
+        evaluation, fft,
+        integer::{RadixCiphertext, ServerKey},
+    },
+    ops::bootstrapping::circuit_bootstrap,
+    GlweDef,
+};
+
+/// Homomorphically read `table[index]`, where `index` is encrypted.
+///
+/// # Remarks
+/// Every entry in `table` must be encrypted under `server_key.params.glwe_1`'s
+/// [`GlweSecretKey`](crate::entities::GlweSecretKey) reinterpreted as an
+/// [`LweSecretKey`](crate::entities::LweSecretKey) (e.g. the output of a
+/// programmable bootstrap into `glwe_1`). The returned ciphertext is
+/// encrypted under this same key.
+///
+/// `index` must be encrypted under `server_key.params.lwe_0` with 1 bit
+/// per block. Each index bit is circuit bootstrapped into a
+/// [`GgswCiphertext`](crate::entities::GgswCiphertext) which then drives one
+/// level of a binary tree of [`cmux`](super::evaluation::cmux) operations
+/// over the table entries.
+///
+/// When `index >= table.len()`, the result is an encryption of an
+/// unspecified entry in `table`.
+///
+/// # Cost
+/// Selection performs `ceil(log2(table.len()))` circuit bootstraps, one per
+/// level of the cmux tree, and `table.len() - 1` cmux operations. Circuit
+/// bootstrapping dominates for small tables, while the cmux tree grows
+/// linearly with the table size. Index bits beyond the tree's depth are
+/// ignored.
+///
+/// # Panics
+/// If `table` is empty.
+/// If `index` doesn't use 1 bit per block.
+/// If `table.len()` exceeds `2^index.bits()`.
+/// If any entry in `table` isn't valid under `server_key.params.glwe_1`.
+pub fn select(
+    table: &[LweCiphertext<u64>],
+    index: &RadixCiphertext,
+    server_key: &ServerKey,
+) -> LweCiphertext<u64> {
+    let params = &server_key.params;
+
+    assert!(!table.is_empty());
+    assert_eq!(index.block_bits().0, 1);
+    assert!(index.bits() >= usize::BITS as usize || table.len() <= 0x1 << index.bits());
+
+    let glwe = &params.glwe_1;
+
+    let mut level = table
+        .iter()
+        .map(|x| lwe_to_glwe(x, glwe))
+        .collect::<Vec<_>>();
+
+    for bit in index.blocks() {
+        if level.len() == 1 {
+            break;
+        }
+
+        let sel = bootstrap_select_bit(bit, server_key);
+
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [d_0, d_1] => evaluation::cmux(&sel, d_0, d_1, glwe, &params.cbs_radix),
+                [d_0] => d_0.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+
+    evaluation::sample_extract(&level[0], glwe, 0)
+}
+
+fn bootstrap_select_bit(
+    bit: &LweCiphertextRef<u64>,
+    server_key: &ServerKey,
+) -> GgswCiphertextFft<num::Complex<f64>> {
+    let params = &server_key.params;
+
+    server_key.record_bootstrap();
+
+    let mut ggsw = GgswCiphertext::new(&params.glwe_1, &params.cbs_radix);
+
+    circuit_bootstrap(
+        &mut ggsw,
+        bit,
+        &server_key.bsk,
+        &server_key.cbsksk,
+        &params.lwe_0,
+        &params.glwe_1,
+        &params.glwe_2,
+        &params.pbs_radix,
+        &params.cbs_radix,
+        &params.pfks_radix,
+    );
+
+    fft::fft_ggsw(&ggsw, &params.glwe_1, &params.cbs_radix)
+}
+
+/// Embed an [`LweCiphertext`] under `glwe` reinterpreted as LWE into a
+/// [`GlweCiphertext`] whose constant coefficient encrypts the same message.
+/// This is the inverse of sample extracting coefficient 0; the remaining
+/// coefficients contain garbage.
+fn lwe_to_glwe(lwe: &LweCiphertextRef<u64>, glwe: &GlweDef) -> GlweCiphertext<u64> {
+    lwe.assert_valid(&glwe.as_lwe_def());
+
+    #[allow(non_snake_case)]
+    let N = glwe.dim.polynomial_degree.0;
+
+    let mut result = GlweCiphertext::new(glwe);
+    let (a_lwe, b_lwe) = lwe.a_b(&glwe.as_lwe_def());
+    let (a_glwe, b_glwe) = result.a_b_mut(glwe);
+
+    for (a_glwe_i, a_lwe_i) in a_glwe.zip(a_lwe.chunks(N)) {
+        let coeffs = a_glwe_i.coeffs_mut();
+
+        coeffs[0] = a_lwe_i[0];
+
+        for j in 1..N {
+            coeffs[N - j] = a_lwe_i[j].wrapping_neg();
+        }
+    }
+
+    b_glwe.coeffs_mut()[0] = *b_lwe;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        high_level::{integer::*, *},
+        PlaintextBits, RadixCount, RadixDecomposition, RadixLog, GLWE_1_1024_80, GLWE_5_256_80,
+        LWE_512_80,
+    };
+
+    use super::*;
+
+    #[test]
+    fn lwe_to_glwe_preserves_constant_coefficient() {
+        let sk = keygen::generate_binary_glwe_sk(&TEST_GLWE_DEF_1);
+        let lwe_def = TEST_GLWE_DEF_1.as_lwe_def();
+
+        for val in 0..16 {
+            let lwe = encryption::encrypt_lwe_secret(
+                val,
+                sk.to_lwe_secret_key(),
+                &lwe_def,
+                PlaintextBits(4),
+            );
+
+            let glwe = lwe_to_glwe(&lwe, &TEST_GLWE_DEF_1);
+            let actual = evaluation::sample_extract(&glwe, &TEST_GLWE_DEF_1, 0);
+
+            assert_eq!(lwe.as_slice(), actual.as_slice());
+        }
+    }
+
+    #[test]
+    fn can_select_by_encrypted_index() {
+        let params = ServerKeyParams {
+            lwe_0: LWE_512_80,
+            glwe_1: GLWE_1_1024_80,
+            glwe_2: GLWE_5_256_80,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(16),
+            },
+            cbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(5),
+            },
+            pfks_radix: RadixDecomposition {
+                count: RadixCount(3),
+                radix_log: RadixLog(11),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(4),
+                radix_log: RadixLog(4),
+            },
+        };
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        let bits = PlaintextBits(4);
+        let values = [3, 14, 7, 9];
+
+        let table = values
+            .iter()
+            .map(|x| {
+                encryption::encrypt_lwe_secret(
+                    *x,
+                    sk_1.to_lwe_secret_key(),
+                    &params.glwe_1.as_lwe_def(),
+                    bits,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (i, expected) in values.iter().enumerate() {
+            let index = encrypt_radix(i as u64, &sk_0, &params.lwe_0, PlaintextBits(1), 2);
+
+            let actual = select(&table, &index, &server_key);
+            let actual = encryption::decrypt_lwe(
+                &actual,
+                sk_1.to_lwe_secret_key(),
+                &params.glwe_1.as_lwe_def(),
+                bits,
+            );
+
+            assert_eq!(actual, *expected);
+        }
+
+        // One circuit bootstrap per index bit.
+        assert_eq!(server_key.bootstrap_count(), 2 * values.len() as u64);
+    }
+}
@@ -0,0 +1,1906 @@
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    sync::{
+        atomic::{self, AtomicU64},
+        Arc, RwLock,
+    },
+};
+
+use num::Complex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dst::FromMutSlice,
+    entities::{
+        BootstrapKeyFft, CircuitBootstrappingKeyswitchKeys, GlweSecretKeyRef, LweCiphertext,
+        LweCiphertextRef, LweKeyswitchKey, LweSecretKeyRef, UnivariateLookupTable,
+        UnivariateLookupTableRef,
+    },
+    high_level::{encryption, evaluation, fft, keygen},
+    ops::ciphertext::{add_lwe_inplace, scalar_mul_ciphertext_mad},
+    scratch::allocate_scratch_ref,
+    GlweDef, LweDef, PlaintextBits, RadixDecomposition, Torus,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An encrypted unsigned integer represented as a little-endian sequence of
+/// radix digits (blocks), each stored in its own [`LweCiphertext`].
+///
+/// # Remarks
+/// Each block encrypts `block_bits` bits of the integer, so a
+/// [`RadixCiphertext`] with `n` blocks represents an integer in
+/// `[0, 2^(n * block_bits))`. Block `0` holds the least-significant digit.
+///
+/// Blocks are encrypted under the level 0 [`LweDef`] (i.e. the `lwe_0`
+/// parameters in a [`ServerKeyParams`]).
+///
+/// By default, arithmetic wraps on overflow, as with Rust's `wrapping_*`
+/// integer methods. Use [`with_overflow`](Self::with_overflow) to saturate
+/// instead.
+pub struct RadixCiphertext {
+    blocks: Vec<LweCiphertext<u64>>,
+    block_bits: PlaintextBits,
+
+    #[serde(default)]
+    overflow: Overflow,
+}
+
+impl RadixCiphertext {
+    /// Create a [`RadixCiphertext`] from its `blocks`, least-significant block first.
+    ///
+    /// # Panics
+    /// If `blocks` is empty.
+    pub fn from_blocks(blocks: Vec<LweCiphertext<u64>>, block_bits: PlaintextBits) -> Self {
+        assert!(!blocks.is_empty());
+
+        Self {
+            blocks,
+            block_bits,
+            overflow: Overflow::default(),
+        }
+    }
+
+    /// Set how arithmetic on this integer behaves on overflow.
+    ///
+    /// # Remarks
+    /// Results inherit this setting, and both operands of a binary operation
+    /// must have the same setting.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// How arithmetic on this integer behaves on overflow.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// The [`LweCiphertext`] blocks in this integer, least-significant block first.
+    pub fn blocks(&self) -> &[LweCiphertext<u64>] {
+        &self.blocks
+    }
+
+    /// The number of message bits each block encrypts.
+    pub fn block_bits(&self) -> PlaintextBits {
+        self.block_bits
+    }
+
+    /// The total number of message bits this integer can represent.
+    pub fn bits(&self) -> usize {
+        self.blocks.len() * self.block_bits.0 as usize
+    }
+
+    /// Compute `self + rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow).
+    ///
+    /// # Remarks
+    /// Blocks have no room for carries, so this first re-encrypts each block
+    /// of both operands with the carry space of a [`SignedRadixCiphertext`]
+    /// block (3 programmable bootstraps per block). It then adds the blocks
+    /// and propagates carries using bivariate programmable bootstrapping and
+    /// keyswitching back to `lwe_0`, and finally re-encrypts the result's
+    /// blocks without carry space (1 programmable bootstrap per block).
+    ///
+    /// When saturating, the operands are extended by a zero block to
+    /// capture the carry out of the most-significant block, and the result
+    /// becomes the maximum value if it's set.
+    ///
+    /// Performs `9n - 1` programmable bootstraps when wrapping and `9n + 1`
+    /// when saturating, where `n` is the number of blocks.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn add(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        self.assert_compatible(rhs);
+
+        let max = self.digit_mask();
+
+        if self.overflow == Overflow::Wrapping {
+            let sum = self
+                .add_carry_space(0, server_key)
+                .add(&rhs.add_carry_space(0, server_key), server_key);
+
+            return self.strip_carry_space(&sum.blocks, None, server_key);
+        }
+
+        let sum = self
+            .add_carry_space(1, server_key)
+            .add(&rhs.add_carry_space(1, server_key), server_key);
+        let (carry, sum) = sum.blocks.split_last().unwrap();
+
+        self.strip_carry_space(sum, Some((carry, max)), server_key)
+    }
+
+    /// Compute `self - rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow). When saturating, results below zero
+    /// become zero.
+    ///
+    /// # Remarks
+    /// Works like [`add`](Self::add), computing `self + !rhs + 1` on the
+    /// re-encrypted blocks. When saturating, the extra block is nonzero
+    /// exactly when `self < rhs`.
+    ///
+    /// Performs `9n - 1` programmable bootstraps when wrapping and `9n + 1`
+    /// when saturating, where `n` is the number of blocks.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn sub(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        self.assert_compatible(rhs);
+
+        if self.overflow == Overflow::Wrapping {
+            let diff = self
+                .add_carry_space(0, server_key)
+                .sub(&rhs.add_carry_space(0, server_key), server_key);
+
+            return self.strip_carry_space(&diff.blocks, None, server_key);
+        }
+
+        let diff = self
+            .add_carry_space(1, server_key)
+            .sub(&rhs.add_carry_space(1, server_key), server_key);
+        let (borrow, diff) = diff.blocks.split_last().unwrap();
+
+        self.strip_carry_space(diff, Some((borrow, 0)), server_key)
+    }
+
+    /// Compute `self * rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow).
+    ///
+    /// # Remarks
+    /// Works like [`add`](Self::add), computing the schoolbook product of
+    /// the re-encrypted blocks as in [`SignedRadixCiphertext::mul`], whose
+    /// low `n * block_bits` bits are the same for unsigned operands. When
+    /// saturating, the operands are zero extended to `2n` blocks to compute
+    /// the full product, and the result becomes the maximum value if any
+    /// block in its upper half is nonzero.
+    ///
+    /// Performs `M(n) + 7n` programmable bootstraps when wrapping and
+    /// `M(2n) + 8n - 1` when saturating, where `n` is the number of blocks
+    /// and `M` is the cost of [`SignedRadixCiphertext::mul`] given in
+    /// [`ServerKey::bootstrap_count`].
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn mul(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        self.assert_compatible(rhs);
+
+        let n = self.blocks.len();
+
+        if self.overflow == Overflow::Wrapping {
+            let product = self
+                .add_carry_space(0, server_key)
+                .mul(&rhs.add_carry_space(0, server_key), server_key);
+
+            return self.strip_carry_space(&product.blocks, None, server_key);
+        }
+
+        let product = self
+            .add_carry_space(n, server_key)
+            .mul(&rhs.add_carry_space(n, server_key), server_key);
+        let (low, high) = product.blocks.split_at(n);
+
+        // Encrypts a nonzero digit if any block in `high` is nonzero.
+        let overflowed = high[1..].iter().fold(high[0].clone(), |acc, block| {
+            let packed = product.pack(&acc, block, server_key);
+
+            product.apply_lut(&packed, |x| (x != 0) as u64, server_key)
+        });
+
+        self.strip_carry_space(low, Some((&overflowed, self.digit_mask())), server_key)
+    }
+
+    /// Bootstrap each block, returning an encryption of the same integer
+    /// whose blocks carry only the noise of a fresh bootstrap.
+    ///
+    /// # Remarks
+    /// Homomorphically adding or scaling blocks (e.g. with
+    /// [`add_lwe_inplace`]) grows their noise. Once a block's noise
+    /// exceeds half a digit (`2^(64 - block_bits - 1)`), it decrypts
+    /// incorrectly, so refresh before then. Noise grows with the square root
+    /// of the number of additions of fresh blocks, so for a given parameter
+    /// set, refreshing after a fixed number of additions (found by
+    /// estimating or measuring the noise) suffices.
+    ///
+    /// Blocks have no room for carries, so a block's digit wraps modulo
+    /// `2^block_bits` when added to, and refreshing preserves the wrapped
+    /// digit. [`SignedRadixCiphertext`] operations already bootstrap every
+    /// block they return and don't need refreshing.
+    ///
+    /// Without a padding bit, a single negacyclic bootstrap can't map a
+    /// full-width digit, so each block takes two. The first extracts the
+    /// digit's most-significant bit, which we subtract to move the digit
+    /// into the lower half of the torus. The second maps the remaining
+    /// bits to a fresh encryption, to which we add the bit back. This
+    /// performs `2n` programmable bootstraps, where `n` is the number of
+    /// blocks.
+    ///
+    /// The result is encrypted under `lwe_0`.
+    ///
+    /// # Panics
+    /// If `server_key`'s `glwe_2` has a polynomial degree less than
+    /// `2^block_bits`.
+    pub fn refresh(&self, server_key: &ServerKey) -> Self {
+        let params = &server_key.params;
+        let lwe = &params.lwe_0;
+        let b = self.block_bits.0;
+
+        let half = Torus::from(0x1u64 << 63);
+        let quarter = Torus::from(0x1u64 << 62);
+
+        // Encrypts q/4 in the first half of the torus and -q/4 in the second.
+        // Unlike LUTs generated from a function, a constant LUT isn't rotated
+        // by half an entry, so its sign flips exactly at 0 and q/2.
+        let mut msb_lut =
+            UnivariateLookupTable::trivial_from_fn(|_| 0, &params.glwe_2, PlaintextBits(2));
+        msb_lut.fill_with_constant(1, &params.glwe_2, PlaintextBits(2));
+
+        // LUT inputs carry a padding bit, so each digit in the lower half of
+        // the torus spans two entries.
+        let low_lut =
+            UnivariateLookupTable::trivial_from_fn(|x| x >> 1, &params.glwe_2, self.block_bits);
+
+        let blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                // Offset by half a digit so noise in either direction keeps
+                // the digit on the same side of q/2.
+                let mut centered = block.clone();
+                *centered.b_mut(lwe) += Torus::from(0x1u64 << (64 - b - 1));
+
+                // Encrypts q/2 if the digit's most-significant bit is 0 and
+                // 0 otherwise.
+                let mut not_msb = bootstrap_with_lut(&centered, &msb_lut, server_key);
+                *not_msb.b_mut(lwe) += quarter;
+
+                // Subtract the most-significant bit and offset by a quarter
+                // digit to align the digit with its two LUT entries.
+                let mut low = block.clone();
+                add_lwe_inplace(&mut low, &not_msb, lwe);
+                *low.b_mut(lwe) += half + Torus::from(0x1u64 << (64 - b - 2));
+
+                let low = bootstrap_with_lut(&low, &low_lut, server_key);
+
+                let mut refreshed = low.as_ref() - not_msb.as_ref();
+                *refreshed.b_mut(lwe) += half;
+
+                refreshed
+            })
+            .collect();
+
+        self.with_blocks(blocks)
+    }
+
+    fn assert_compatible(&self, rhs: &Self) {
+        assert_eq!(self.blocks.len(), rhs.blocks.len());
+        assert_eq!(self.block_bits.0, rhs.block_bits.0);
+        assert_eq!(self.overflow, rhs.overflow);
+    }
+
+    fn with_blocks(&self, blocks: Vec<LweCiphertext<u64>>) -> Self {
+        Self::from_blocks(blocks, self.block_bits).with_overflow(self.overflow)
+    }
+
+    fn digit_mask(&self) -> u64 {
+        (0x1 << self.block_bits.0) - 1
+    }
+
+    /// Re-encrypt each block with the carry space of a
+    /// [`SignedRadixCiphertext`] block and append `extra_blocks` zero
+    /// blocks. The result wraps on overflow, which for the low
+    /// `n * block_bits` bits of a result is the same for signed and
+    /// unsigned operands. Performs 3 programmable bootstraps per block.
+    ///
+    /// # Remarks
+    /// As in [`refresh`](Self::refresh), a negacyclic bootstrap can't map a
+    /// full-width digit, so we bootstrap its most-significant bit and the
+    /// remaining bits separately. One constant LUT yields the most
+    /// significant bit as `q/2`, which we subtract to move the digit into
+    /// the lower half of the torus, and another yields it already scaled to
+    /// its place in the carry encoding. A third LUT maps the remaining bits
+    /// to the carry encoding, and we add the two parts.
+    fn add_carry_space(
+        &self,
+        extra_blocks: usize,
+        server_key: &ServerKey,
+    ) -> SignedRadixCiphertext {
+        let params = &server_key.params;
+        let lwe = &params.lwe_0;
+        let b = self.block_bits.0;
+
+        let half = Torus::from(0x1u64 << 63);
+        let quarter = Torus::from(0x1u64 << 62);
+        let half_digit = Torus::from(0x1u64 << (64 - b - 1));
+
+        let mut not_msb_lut =
+            UnivariateLookupTable::trivial_from_fn(|_| 0, &params.glwe_2, PlaintextBits(2));
+        not_msb_lut.fill_with_constant(1, &params.glwe_2, PlaintextBits(2));
+
+        // Encrypts ±2^(64 - b - 3), half of the most-significant bit's
+        // value in the carry encoding.
+        let msb_scale = Torus::from(0x1u64 << (64 - b - 3));
+        let mut msb_lut =
+            UnivariateLookupTable::trivial_from_fn(|_| 0, &params.glwe_2, PlaintextBits(2));
+        msb_lut.fill_with_constant(1, &params.glwe_2, PlaintextBits(b + 3));
+
+        // Each digit in the lower half of the torus spans `2^(b + 2)` entries
+        // of a LUT with the carry encoding's `2b + 1` bits.
+        let low_lut = UnivariateLookupTable::trivial_from_fn(
+            |x| x >> (b + 2),
+            &params.glwe_2,
+            signed_encoding_bits(self.block_bits),
+        );
+
+        let mut blocks = self
+            .blocks
+            .iter()
+            .map(|block| {
+                // Offset by half a digit so noise in either direction keeps
+                // the digit on the same side of q/2.
+                let mut centered = block.clone();
+                *centered.b_mut(lwe) += half_digit;
+
+                // Encrypts q/2 if the digit's most-significant bit is 0 and
+                // 0 otherwise.
+                let mut not_msb = bootstrap_with_lut(&centered, &not_msb_lut, server_key);
+                *not_msb.b_mut(lwe) += quarter;
+
+                // Encrypts 2^(b - 1) in the carry encoding if the digit's
+                // most-significant bit is 1 and 0 otherwise.
+                let mut msb = -bootstrap_with_lut(&centered, &msb_lut, server_key).as_ref();
+                *msb.b_mut(lwe) += msb_scale;
+
+                // Subtract the most-significant bit and center the digit in
+                // its LUT entries.
+                let mut low = block.clone();
+                add_lwe_inplace(&mut low, &not_msb, lwe);
+                *low.b_mut(lwe) += half + half_digit;
+
+                let mut digit = bootstrap_with_lut(&low, &low_lut, server_key);
+                add_lwe_inplace(&mut digit, &msb, lwe);
+
+                digit
+            })
+            .collect::<Vec<_>>();
+
+        blocks.resize(blocks.len() + extra_blocks, LweCiphertext::new(lwe));
+
+        SignedRadixCiphertext {
+            blocks,
+            block_bits: self.block_bits,
+            overflow: Overflow::Wrapping,
+        }
+    }
+
+    /// Re-encrypt `blocks`, which must use the carry encoding of a
+    /// [`SignedRadixCiphertext`] and have empty carries, without carry space.
+    /// If `saturate` is `Some((flag, digit))`, every block becomes `digit` when
+    /// `flag` encrypts a nonzero digit. Performs 1 programmable bootstrap
+    /// per block.
+    fn strip_carry_space(
+        &self,
+        blocks: &[LweCiphertext<u64>],
+        saturate: Option<(&LweCiphertext<u64>, u64)>,
+        server_key: &ServerKey,
+    ) -> Self {
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+
+        let engine = SignedRadixCiphertext {
+            blocks: vec![],
+            block_bits: self.block_bits,
+            overflow: Overflow::Wrapping,
+        };
+
+        // A digit `d` in the carry encoding's `2b + 1` bits lands in the top
+        // `b` bits when shifted left by `b + 1`.
+        let blocks = blocks
+            .iter()
+            .map(|block| match saturate {
+                Some((flag, digit)) => {
+                    let packed = engine.pack(flag, block, server_key);
+
+                    engine.apply_lut(
+                        &packed,
+                        |x| {
+                            let d = if x >> b != 0 { digit } else { x & mask };
+
+                            d << (b + 1)
+                        },
+                        server_key,
+                    )
+                }
+                None => engine.apply_lut(block, |x| (x & mask) << (b + 1), server_key),
+            })
+            .collect();
+
+        self.with_blocks(blocks)
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+/// The parameters under which a [`ServerKey`] was generated.
+///
+/// # Remarks
+/// These are the [`CircuitBootstrapParams`](super::CircuitBootstrapParams)
+/// plus the radix used to keyswitch back to `lwe_0`; see
+/// [`circuit_bootstrap`](super::evaluation::circuit_bootstrap) for the role
+/// of each level.
+pub struct ServerKeyParams {
+    /// The parameters under which [`RadixCiphertext`] blocks are encrypted.
+    pub lwe_0: LweDef,
+
+    /// The parameters of the GLWE key under which circuit bootstrapping
+    /// produces its output.
+    pub glwe_1: GlweDef,
+
+    /// The parameters of the GLWE key used internally during circuit
+    /// bootstrapping.
+    pub glwe_2: GlweDef,
+
+    /// The radix decomposition used in programmable bootstrapping.
+    pub pbs_radix: RadixDecomposition,
+
+    /// The radix decomposition of circuit bootstrapped
+    /// [`GgswCiphertext`](crate::entities::GgswCiphertext)s.
+    pub cbs_radix: RadixDecomposition,
+
+    /// The radix decomposition used in private functional keyswitching.
+    pub pfks_radix: RadixDecomposition,
+
+    /// The radix decomposition used when keyswitching programmable
+    /// bootstrapping results from `glwe_2` back to `lwe_0`.
+    pub ks_radix: RadixDecomposition,
+}
+
+/// The public keys needed to homomorphically compute on [`RadixCiphertext`]s.
+///
+/// # Remarks
+/// Use [`generate_server_key`] to create one.
+pub struct ServerKey {
+    /// A bootstrapping key from `lwe_0` to `glwe_2`.
+    pub bsk: BootstrapKeyFft<Complex<f64>>,
+
+    /// The keyswitch keys used during circuit bootstrapping.
+    pub cbsksk: CircuitBootstrappingKeyswitchKeys<u64>,
+
+    /// A keyswitch key from `glwe_2` (as an LWE key) to `lwe_0`, used to
+    /// return programmable bootstrapping results to `lwe_0`.
+    pub ksk: LweKeyswitchKey<u64>,
+
+    /// The parameters under which these keys were generated.
+    pub params: ServerKeyParams,
+
+    bootstraps: AtomicU64,
+
+    /// The carry propagation LUTs for each block size, built on first use.
+    carry_luts: RwLock<HashMap<u32, Arc<CarryLuts>>>,
+}
+
+/// The lookup tables [`SignedRadixCiphertext`] carry propagation applies
+/// to every block.
+struct CarryLuts {
+    /// Extracts a block's carry.
+    carry: UnivariateLookupTable<u64>,
+
+    /// Extracts a block's digit.
+    digit: UnivariateLookupTable<u64>,
+}
+
+impl ServerKey {
+    /// The number of bootstraps performed with this key since it was
+    /// generated or [`ServerKey::reset_bootstrap_count`] was last called.
+    ///
+    /// # Remarks
+    /// Bootstraps dominate the running time of the high-level APIs, so
+    /// this count serves as a parameter-independent cost measure for
+    /// comparing algorithms. A programmable bootstrap and a circuit
+    /// bootstrap each count once, as the latter performs a single
+    /// (multi-output) blind rotation.
+    ///
+    /// For an integer with `n` blocks, the operations bootstrap as follows:
+    ///
+    /// | Operation | Bootstraps |
+    /// |-----------|------------|
+    /// | [`SignedRadixCiphertext::add`] | `2n - 1` |
+    /// | [`SignedRadixCiphertext::sub`] | `2n - 1` |
+    /// | [`SignedRadixCiphertext::neg`] | `2n - 1` |
+    /// | [`SignedRadixCiphertext::compare`] | `2n - 1` |
+    /// | [`SignedRadixCiphertext::mul`] | `M(n) = Σ (k + 1) * (4(n - k) - 2)` over `k < n - 1`, plus `2n` |
+    /// | Saturating [`SignedRadixCiphertext::add`], [`sub`](SignedRadixCiphertext::sub), or [`neg`](SignedRadixCiphertext::neg) | `3n + 1` |
+    /// | Saturating [`SignedRadixCiphertext::mul`] | `M(2n) + 2n + 2` |
+    /// | [`RadixCiphertext::add`] or [`sub`](RadixCiphertext::sub) | `9n - 1` |
+    /// | [`RadixCiphertext::mul`] | `M(n) + 7n` |
+    /// | Saturating [`RadixCiphertext::add`] or [`sub`](RadixCiphertext::sub) | `9n + 1` |
+    /// | Saturating [`RadixCiphertext::mul`] | `M(2n) + 8n - 1` |
+    /// | [`RadixCiphertext::refresh`] | `2n` |
+    /// | [`select`](super::array::select) | `ceil(log2(table.len()))` |
+    ///
+    /// Since the counts depend only on the number of blocks, running a
+    /// computation under small, insecure parameters yields the count for
+    /// any parameter set.
+    ///
+    /// The count is shared by all threads using this key.
+    pub fn bootstrap_count(&self) -> u64 {
+        self.bootstraps.load(atomic::Ordering::Relaxed)
+    }
+
+    /// Reset [`ServerKey::bootstrap_count`] to zero.
+    pub fn reset_bootstrap_count(&self) {
+        self.bootstraps.store(0, atomic::Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_bootstrap(&self) {
+        self.bootstraps.fetch_add(1, atomic::Ordering::Relaxed);
+    }
+
+    /// The [`CarryLuts`] for blocks with `block_bits` bits, building them
+    /// the first time they're requested.
+    fn carry_luts(&self, block_bits: PlaintextBits) -> Arc<CarryLuts> {
+        if let Some(luts) = self.carry_luts.read().unwrap().get(&block_bits.0) {
+            return luts.clone();
+        }
+
+        let glwe = &self.params.glwe_2;
+        let b = block_bits.0;
+        let mask = (0x1 << b) - 1;
+
+        self.carry_luts
+            .write()
+            .unwrap()
+            .entry(b)
+            .or_insert_with(|| {
+                Arc::new(CarryLuts {
+                    carry: signed_block_lut(|x| x >> b, glwe, block_bits),
+                    digit: signed_block_lut(|x| x & mask, glwe, block_bits),
+                })
+            })
+            .clone()
+    }
+}
+
+/// Generate a [`ServerKey`] from the three secret keys used in circuit
+/// bootstrapping.
+///
+/// # Remarks
+/// `sk_0`, `sk_1`, and `sk_2` must have been generated under `params.lwe_0`,
+/// `params.glwe_1`, and `params.glwe_2` respectively.
+///
+/// # Panics
+/// If any of the keys don't correspond with `params`.
+/// If `params` is invalid.
+///
+/// # Security
+/// The returned key is public, but anyone holding `sk_1` or `sk_2` can use it to
+/// recover `sk_0`.
+pub fn generate_server_key(
+    sk_0: &LweSecretKeyRef<u64>,
+    sk_1: &GlweSecretKeyRef<u64>,
+    sk_2: &GlweSecretKeyRef<u64>,
+    params: &ServerKeyParams,
+) -> ServerKey {
+    let bsk = keygen::generate_bootstrapping_key(
+        sk_0,
+        sk_2,
+        &params.lwe_0,
+        &params.glwe_2,
+        &params.pbs_radix,
+    );
+    let bsk = fft::fft_bootstrap_key(&bsk, &params.lwe_0, &params.glwe_2, &params.pbs_radix);
+
+    let cbsksk = keygen::generate_cbs_ksk(
+        sk_2.to_lwe_secret_key(),
+        sk_1,
+        &params.glwe_2.as_lwe_def(),
+        &params.glwe_1,
+        &params.pfks_radix,
+    );
+
+    let ksk = keygen::generate_ksk(
+        sk_2.to_lwe_secret_key(),
+        sk_0,
+        &params.glwe_2.as_lwe_def(),
+        &params.lwe_0,
+        &params.ks_radix,
+    );
+
+    ServerKey {
+        bsk,
+        cbsksk,
+        ksk,
+        params: *params,
+        bootstraps: AtomicU64::new(0),
+        carry_luts: RwLock::new(HashMap::new()),
+    }
+}
+
+/// Encrypt `val` as a [`RadixCiphertext`] with `num_blocks` blocks of
+/// `block_bits` bits each under `sk`.
+///
+/// # Remarks
+/// `params` should be the parameters under which `sk` was generated.
+///
+/// # Panics
+/// If `val` doesn't fit in `num_blocks * block_bits` bits.
+/// If `num_blocks` is zero.
+/// If `params` doesn't correspond with `sk`.
+pub fn encrypt_radix(
+    val: u64,
+    sk: &LweSecretKeyRef<u64>,
+    params: &LweDef,
+    block_bits: PlaintextBits,
+    num_blocks: usize,
+) -> RadixCiphertext {
+    let total_bits = num_blocks * block_bits.0 as usize;
+    assert!(num_blocks > 0);
+    assert!(total_bits >= u64::BITS as usize || val < (0x1 << total_bits));
+
+    let mask = (0x1 << block_bits.0) - 1;
+
+    let blocks = (0..num_blocks)
+        .map(|i| {
+            let digit = val.checked_shr(i as u32 * block_bits.0).unwrap_or(0) & mask;
+
+            encryption::encrypt_lwe_secret(digit, sk, params, block_bits)
+        })
+        .collect();
+
+    RadixCiphertext::from_blocks(blocks, block_bits)
+}
+
+/// Decrypt a [`RadixCiphertext`] encrypted under `sk`.
+///
+/// # Remarks
+/// `params` should be the parameters under which `sk` was generated.
+///
+/// # Panics
+/// If `params` doesn't correspond with `sk` or `ct`.
+pub fn decrypt_radix(ct: &RadixCiphertext, sk: &LweSecretKeyRef<u64>, params: &LweDef) -> u64 {
+    ct.blocks()
+        .iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, block)| {
+            let digit = encryption::decrypt_lwe(block, sk, params, ct.block_bits());
+
+            acc | digit.checked_shl(i as u32 * ct.block_bits().0).unwrap_or(0)
+        })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An encrypted two's complement signed integer represented as a
+/// little-endian sequence of radix digits (blocks), each stored in its own
+/// [`LweCiphertext`].
+///
+/// # Remarks
+/// A [`SignedRadixCiphertext`] with `n` blocks of `block_bits` bits
+/// represents an integer in `[-2^(n * block_bits - 1), 2^(n * block_bits - 1))`
+/// as the `n * block_bits`-bit two's complement of its value. The sign bit
+/// is thus the most-significant bit of the last block. By default,
+/// arithmetic wraps on overflow, as with Rust's `wrapping_*` integer
+/// methods. Use [`with_overflow`](Self::with_overflow) to saturate instead.
+///
+/// Unlike [`RadixCiphertext`], each block reserves room for carries:
+/// a block's plaintext has `2 * block_bits + 1` bits, holding the digit in
+/// its low `block_bits` bits, `block_bits` carry bits above it, and a zero
+/// padding bit on top that programmable bootstrapping requires. Operations
+/// always return blocks with empty carries. Consequently, `glwe_2` in the
+/// [`ServerKeyParams`] must have a polynomial degree of at least
+/// `2^(2 * block_bits + 1)` and enough precision to decode
+/// `2 * block_bits + 1` bits.
+///
+/// Blocks are encrypted under the level 0 [`LweDef`] (i.e. the `lwe_0`
+/// parameters in a [`ServerKeyParams`]).
+pub struct SignedRadixCiphertext {
+    blocks: Vec<LweCiphertext<u64>>,
+    block_bits: PlaintextBits,
+
+    #[serde(default)]
+    overflow: Overflow,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// How [`RadixCiphertext`] and [`SignedRadixCiphertext`] arithmetic behaves
+/// when the result doesn't fit in the integer.
+pub enum Overflow {
+    #[default]
+    /// Wrap around the integer's range, as with Rust's `wrapping_*` integer
+    /// methods.
+    Wrapping,
+
+    /// Clamp to the integer's minimum or maximum value, as with Rust's
+    /// `saturating_*` integer methods.
+    ///
+    /// # Remarks
+    /// Detecting overflow costs extra programmable bootstraps; see
+    /// [`ServerKey::bootstrap_count`].
+    Saturating,
+}
+
+/// A saturation flag value replacing a result with the maximum value.
+/// Smaller flag values leave the result unchanged.
+const SATURATE_MAX: u64 = 2;
+
+/// A saturation flag value replacing a result with the minimum value.
+const SATURATE_MIN: u64 = 3;
+
+/// The number of plaintext bits in each block of a
+/// [`SignedRadixCiphertext`], including its carry and padding bits.
+fn signed_encoding_bits(block_bits: PlaintextBits) -> PlaintextBits {
+    PlaintextBits(2 * block_bits.0 + 1)
+}
+
+/// Create a LUT computing `f` on [`SignedRadixCiphertext`] blocks with
+/// `block_bits` bits. See [`SignedRadixCiphertext::apply_lut`] for the
+/// encoding.
+fn signed_block_lut<F>(
+    f: F,
+    glwe: &GlweDef,
+    block_bits: PlaintextBits,
+) -> UnivariateLookupTable<u64>
+where
+    F: Fn(u64) -> u64,
+{
+    UnivariateLookupTable::trivial_from_fn(|x| f(x / 2), glwe, signed_encoding_bits(block_bits))
+}
+
+/// Programmably bootstrap `ct` under `lwe_0` with `lut` and keyswitch the
+/// result back to `lwe_0`.
+fn bootstrap_with_lut(
+    ct: &LweCiphertextRef<u64>,
+    lut: &UnivariateLookupTableRef<u64>,
+    server_key: &ServerKey,
+) -> LweCiphertext<u64> {
+    let mut out = LweCiphertext::new(&server_key.params.lwe_0);
+
+    bootstrap_with_lut_into(&mut out, ct, lut, server_key);
+
+    out
+}
+
+/// Like [`bootstrap_with_lut`], but writes the result into `out`. This
+/// doesn't allocate after the first call on a thread.
+fn bootstrap_with_lut_into(
+    out: &mut LweCiphertextRef<u64>,
+    ct: &LweCiphertextRef<u64>,
+    lut: &UnivariateLookupTableRef<u64>,
+    server_key: &ServerKey,
+) {
+    let params = &server_key.params;
+    let extracted_lwe = params.glwe_2.as_lwe_def();
+
+    server_key.record_bootstrap();
+
+    allocate_scratch_ref!(result, LweCiphertextRef<u64>, (extracted_lwe.dim));
+
+    evaluation::univariate_programmable_bootstrap_into(
+        result,
+        ct,
+        lut,
+        &server_key.bsk,
+        &params.lwe_0,
+        &params.glwe_2,
+        &params.pbs_radix,
+    );
+
+    evaluation::keyswitch_lwe_to_lwe_into(
+        out,
+        result,
+        &server_key.ksk,
+        &extracted_lwe,
+        &params.lwe_0,
+        &params.ks_radix,
+    );
+}
+
+impl SignedRadixCiphertext {
+    /// Create a [`SignedRadixCiphertext`] from its `blocks`,
+    /// least-significant block first. Each block must encrypt a digit less
+    /// than `2^block_bits` encoded as described in
+    /// [`SignedRadixCiphertext`].
+    ///
+    /// # Panics
+    /// If `blocks` is empty.
+    /// If `block_bits` is less than 2.
+    pub fn from_blocks(blocks: Vec<LweCiphertext<u64>>, block_bits: PlaintextBits) -> Self {
+        assert!(!blocks.is_empty());
+        assert!(block_bits.0 >= 2);
+
+        Self {
+            blocks,
+            block_bits,
+            overflow: Overflow::default(),
+        }
+    }
+
+    /// Set how arithmetic on this integer behaves on overflow.
+    ///
+    /// # Remarks
+    /// Results inherit this setting, and both operands of a binary operation
+    /// must have the same setting.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// How arithmetic on this integer behaves on overflow.
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// The [`LweCiphertext`] blocks in this integer, least-significant block first.
+    pub fn blocks(&self) -> &[LweCiphertext<u64>] {
+        &self.blocks
+    }
+
+    /// The number of message bits each block encrypts.
+    pub fn block_bits(&self) -> PlaintextBits {
+        self.block_bits
+    }
+
+    /// The total number of message bits this integer can represent,
+    /// including the sign bit.
+    pub fn bits(&self) -> usize {
+        self.blocks.len() * self.block_bits.0 as usize
+    }
+
+    /// Compute `self + rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow).
+    ///
+    /// # Remarks
+    /// Performs `2n - 1` programmable bootstraps when wrapping and `3n + 1`
+    /// when saturating, where `n` is the number of blocks.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn add(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        let mut sum = self.clone();
+
+        self.add_into(rhs, &mut sum, server_key);
+
+        sum
+    }
+
+    /// Like [`add`](Self::add), but writes the result into `out`, reusing
+    /// its blocks.
+    ///
+    /// # Remarks
+    /// When `out` has as many blocks as `self` and wraps on overflow, this
+    /// doesn't allocate after the first call on a thread, so repeatedly
+    /// adding into the same `out` avoids allocation entirely. Saturating
+    /// still allocates temporaries for the overflow check.
+    ///
+    /// `out`'s previous value is ignored; it takes `self`'s block bits and
+    /// overflow behavior.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    /// If `out`'s blocks aren't valid under `lwe_0`.
+    pub fn add_into(&self, rhs: &Self, out: &mut Self, server_key: &ServerKey) {
+        self.assert_compatible(rhs);
+
+        let lwe = &server_key.params.lwe_0;
+
+        out.block_bits = self.block_bits;
+        out.overflow = self.overflow;
+        out.blocks
+            .resize_with(self.blocks.len(), || LweCiphertext::new(lwe));
+
+        for ((sum, a), b) in out.blocks.iter_mut().zip(&self.blocks).zip(&rhs.blocks) {
+            sum.clone_from_ref(a);
+            add_lwe_inplace(sum, b, lwe);
+        }
+
+        self.propagate_carries(&mut out.blocks, 0, server_key);
+
+        if self.overflow == Overflow::Saturating {
+            let sums = std::mem::take(&mut out.blocks);
+            out.blocks = self.saturate_sum(sums, rhs, false, server_key);
+        }
+    }
+
+    /// Compute `self - rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow).
+    ///
+    /// # Remarks
+    /// Computes `self + !rhs + 1` and performs `2n - 1` programmable
+    /// bootstraps when wrapping and `3n + 1` when saturating, where `n` is
+    /// the number of blocks.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn sub(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        self.assert_compatible(rhs);
+
+        let mask = self.digit_mask();
+
+        let mut diffs = self
+            .blocks
+            .iter()
+            .zip(rhs.blocks.iter())
+            .enumerate()
+            .map(|(i, (a, b))| {
+                // a + (mask - b), plus one in the least-significant block.
+                let mut diff = a.as_ref() - b.as_ref();
+                self.add_constant(&mut diff, mask + (i == 0) as u64, server_key);
+                diff
+            })
+            .collect::<Vec<_>>();
+
+        self.propagate_carries(&mut diffs, 0, server_key);
+
+        self.with_blocks(self.saturate_sum(diffs, rhs, true, server_key))
+    }
+
+    /// Compute `-self`, wrapping or saturating on overflow according to
+    /// [`overflow`](Self::overflow). When wrapping, the minimum value
+    /// negates to itself. When saturating, it negates to the maximum value.
+    ///
+    /// # Remarks
+    /// Computes `!self + 1` and performs `2n - 1` programmable bootstraps
+    /// when wrapping. When saturating, this computes `0 - self` and performs
+    /// `3n + 1`. `n` is the number of blocks.
+    pub fn neg(&self, server_key: &ServerKey) -> Self {
+        if self.overflow == Overflow::Saturating {
+            let zero = vec![LweCiphertext::new(&server_key.params.lwe_0); self.blocks.len()];
+
+            return self.with_blocks(zero).sub(self, server_key);
+        }
+
+        let mask = self.digit_mask();
+
+        let mut negated = self
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(i, a)| {
+                let mut neg = -a.as_ref();
+                self.add_constant(&mut neg, mask + (i == 0) as u64, server_key);
+                neg
+            })
+            .collect::<Vec<_>>();
+
+        self.propagate_carries(&mut negated, 0, server_key);
+
+        self.with_blocks(negated)
+    }
+
+    /// Compute `self * rhs`, wrapping or saturating on overflow according
+    /// to [`overflow`](Self::overflow).
+    ///
+    /// # Remarks
+    /// The low `n * block_bits` bits of a two's complement product don't
+    /// depend on the operands' signs, so this computes the unsigned
+    /// schoolbook product, discarding digits past the last block. Each
+    /// digit product is split into a low and high digit with one
+    /// programmable bootstrap each and accumulated with carry propagation,
+    /// so the cost grows cubically in the number of blocks.
+    ///
+    /// When saturating, this sign extends both operands to `2n` blocks to
+    /// compute the full product and then checks whether its upper half is
+    /// the sign extension of its lower half. This costs about 8 times as
+    /// much as wrapping multiplication.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks, block bits, or
+    /// overflow behaviors.
+    pub fn mul(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        self.assert_compatible(rhs);
+
+        if self.overflow == Overflow::Saturating {
+            return self.saturating_mul(rhs, server_key);
+        }
+
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+        let n = self.blocks.len();
+
+        let mut acc = vec![LweCiphertext::new(&server_key.params.lwe_0); n];
+
+        for (i, a) in self.blocks.iter().enumerate() {
+            for (j, c) in rhs.blocks.iter().take(n - i).enumerate() {
+                let packed = self.pack(a, c, server_key);
+
+                let lo = self.apply_lut(&packed, |x| ((x >> b) * (x & mask)) & mask, server_key);
+                acc = self.add_at(acc, &lo, i + j, server_key);
+
+                if i + j + 1 < n {
+                    let hi = self.apply_lut(&packed, |x| ((x >> b) * (x & mask)) >> b, server_key);
+                    acc = self.add_at(acc, &hi, i + j + 1, server_key);
+                }
+            }
+        }
+
+        self.with_blocks(acc)
+    }
+
+    /// Compare `self` with `rhs` as signed integers, returning an
+    /// [`LweCiphertext`] under `lwe_0` encrypting `0` if `self < rhs`, `1`
+    /// if `self == rhs`, and `2` if `self > rhs`. Use
+    /// [`decrypt_ordering`] to decrypt the result.
+    ///
+    /// # Remarks
+    /// Each block pair is compared with one programmable bootstrap, where
+    /// the most-significant pair is compared as signed digits. The block
+    /// results are then combined from least to most significant, with each
+    /// more significant block overriding the result unless its digits are
+    /// equal. This performs `2n - 1` programmable bootstraps, where `n` is
+    /// the number of blocks.
+    ///
+    /// # Panics
+    /// If `self` and `rhs` have different numbers of blocks or block bits.
+    pub fn compare(&self, rhs: &Self, server_key: &ServerKey) -> LweCiphertext<u64> {
+        self.assert_compatible(rhs);
+
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+        let n = self.blocks.len();
+
+        let to_signed = |x: u64| {
+            if x >> (b - 1) == 1 {
+                x as i64 - (1 << b)
+            } else {
+                x as i64
+            }
+        };
+
+        let cmp = |l: i64, r: i64| (l.cmp(&r) as i64 + 1) as u64;
+
+        let block_cmps = self
+            .blocks
+            .iter()
+            .zip(rhs.blocks.iter())
+            .enumerate()
+            .map(|(i, (l, r))| {
+                let packed = self.pack(l, r, server_key);
+
+                if i == n - 1 {
+                    self.apply_lut(
+                        &packed,
+                        |x| cmp(to_signed(x >> b), to_signed(x & mask)),
+                        server_key,
+                    )
+                } else {
+                    self.apply_lut(
+                        &packed,
+                        |x| cmp((x >> b) as i64, (x & mask) as i64),
+                        server_key,
+                    )
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let equal = cmp(0, 0);
+
+        block_cmps
+            .iter()
+            .skip(1)
+            .fold(block_cmps[0].clone(), |result, block_cmp| {
+                let packed = self.pack(block_cmp, &result, server_key);
+
+                self.apply_lut(
+                    &packed,
+                    |x| {
+                        if x >> b == equal {
+                            x & mask
+                        } else {
+                            x >> b
+                        }
+                    },
+                    server_key,
+                )
+            })
+    }
+
+    fn assert_compatible(&self, rhs: &Self) {
+        assert_eq!(self.blocks.len(), rhs.blocks.len());
+        assert_eq!(self.block_bits.0, rhs.block_bits.0);
+        assert_eq!(self.overflow, rhs.overflow);
+    }
+
+    fn with_blocks(&self, blocks: Vec<LweCiphertext<u64>>) -> Self {
+        Self::from_blocks(blocks, self.block_bits).with_overflow(self.overflow)
+    }
+
+    /// The most-significant bit of a digit.
+    fn msb(&self, x: u64) -> u64 {
+        (x >> (self.block_bits.0 - 1)) & 0x1
+    }
+
+    /// When saturating, clamp `sum`, the wrapped result of `self + rhs` (or
+    /// `self - rhs` if `subtract`), to the integer's range. Performs `n + 2`
+    /// programmable bootstraps when saturating and none otherwise.
+    ///
+    /// # Remarks
+    /// A sum overflows exactly when its operands have the same sign and the
+    /// wrapped result has the other sign. Subtracting adds `!rhs`, whose
+    /// sign is the opposite of `rhs`'s.
+    fn saturate_sum(
+        &self,
+        sum: Vec<LweCiphertext<u64>>,
+        rhs: &Self,
+        subtract: bool,
+        server_key: &ServerKey,
+    ) -> Vec<LweCiphertext<u64>> {
+        if self.overflow == Overflow::Wrapping {
+            return sum;
+        }
+
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+
+        // 1 if both operands are nonnegative, 2 if both are negative, and 0
+        // if their signs differ.
+        let packed = self.pack(self.top(), rhs.top(), server_key);
+        let signs = self.apply_lut(
+            &packed,
+            |x| {
+                let lhs_sign = self.msb(x >> b);
+                let rhs_sign = self.msb(x & mask) ^ subtract as u64;
+
+                if lhs_sign == rhs_sign {
+                    lhs_sign + 1
+                } else {
+                    0
+                }
+            },
+            server_key,
+        );
+
+        let packed = self.pack(&signs, &sum[sum.len() - 1], server_key);
+        let flag = self.apply_lut(
+            &packed,
+            |x| match (x >> b, self.msb(x & mask)) {
+                (1, 1) => SATURATE_MAX,
+                (2, 0) => SATURATE_MIN,
+                _ => 0,
+            },
+            server_key,
+        );
+
+        self.saturate(sum, &flag, server_key)
+    }
+
+    /// Compute `self * rhs`, saturating on overflow.
+    fn saturating_mul(&self, rhs: &Self, server_key: &ServerKey) -> Self {
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+        let n = self.blocks.len();
+
+        let sign_extend = |x: &Self| {
+            let sign = self.apply_lut(
+                x.top(),
+                |d| if self.msb(d) == 1 { mask } else { 0 },
+                server_key,
+            );
+
+            let mut blocks = x.blocks.clone();
+            blocks.resize(2 * n, sign);
+
+            Self::from_blocks(blocks, self.block_bits)
+        };
+
+        let product = sign_extend(self).mul(&sign_extend(rhs), server_key);
+        let (low, high) = product.blocks.split_at(n);
+
+        // The product fits when every block in `high` and the sign bit of
+        // `low` match the product's sign. We track the sign `s` and whether
+        // we've seen a mismatch as `s` while they match and
+        // `SATURATE_MAX + s` afterwards.
+        let packed = self.pack(&high[n - 1], &low[n - 1], server_key);
+        let state = self.apply_lut(
+            &packed,
+            |x| {
+                let sign = self.msb(x >> b);
+
+                if self.msb(x & mask) == sign {
+                    sign
+                } else {
+                    SATURATE_MAX + sign
+                }
+            },
+            server_key,
+        );
+
+        let flag = high[..n - 1].iter().fold(state, |state, block| {
+            let packed = self.pack(&state, block, server_key);
+
+            self.apply_lut(
+                &packed,
+                |x| {
+                    let state = x >> b;
+                    let extension = if state == 1 { mask } else { 0 };
+
+                    if state >= SATURATE_MAX || x & mask == extension {
+                        state
+                    } else {
+                        SATURATE_MAX + state
+                    }
+                },
+                server_key,
+            )
+        });
+
+        self.with_blocks(self.saturate(low.to_vec(), &flag, server_key))
+    }
+
+    /// Replace `blocks` with the maximum value if `flag` encrypts
+    /// [`SATURATE_MAX`] or the minimum value if it encrypts
+    /// [`SATURATE_MIN`]. Performs `n` programmable bootstraps.
+    fn saturate(
+        &self,
+        blocks: Vec<LweCiphertext<u64>>,
+        flag: &LweCiphertext<u64>,
+        server_key: &ServerKey,
+    ) -> Vec<LweCiphertext<u64>> {
+        let b = self.block_bits.0;
+        let mask = self.digit_mask();
+        let n = blocks.len();
+
+        blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let (max, min) = if i == n - 1 {
+                    (mask >> 1, 0x1 << (b - 1))
+                } else {
+                    (mask, 0)
+                };
+
+                let packed = self.pack(flag, block, server_key);
+
+                self.apply_lut(
+                    &packed,
+                    |x| match x >> b {
+                        SATURATE_MAX => max,
+                        SATURATE_MIN => min,
+                        _ => x & mask,
+                    },
+                    server_key,
+                )
+            })
+            .collect()
+    }
+
+    /// The most-significant block, which holds the sign bit.
+    fn top(&self) -> &LweCiphertext<u64> {
+        &self.blocks[self.blocks.len() - 1]
+    }
+
+    fn digit_mask(&self) -> u64 {
+        (0x1 << self.block_bits.0) - 1
+    }
+
+    /// Homomorphically add the plaintext `val` to `ct`.
+    fn add_constant(&self, ct: &mut LweCiphertext<u64>, val: u64, server_key: &ServerKey) {
+        let encoding_bits = signed_encoding_bits(self.block_bits);
+
+        *ct.b_mut(&server_key.params.lwe_0) += Torus::from(val << (64 - encoding_bits.0));
+    }
+
+    /// Compute `hi * 2^block_bits + lo`. When both inputs have empty carries,
+    /// the result fits in a block without overflowing into the padding bit.
+    fn pack(
+        &self,
+        hi: &LweCiphertext<u64>,
+        lo: &LweCiphertext<u64>,
+        server_key: &ServerKey,
+    ) -> LweCiphertext<u64> {
+        let lwe = &server_key.params.lwe_0;
+
+        let mut packed = lo.clone();
+        scalar_mul_ciphertext_mad(&mut packed, &(0x1 << self.block_bits.0), hi, lwe);
+
+        packed
+    }
+
+    /// Programmably bootstrap `ct` with `f` and keyswitch the result back
+    /// to `lwe_0`.
+    ///
+    /// # Remarks
+    /// Blocks encode messages at every other entry of a LUT with
+    /// `2 * block_bits + 1` bits, so the output retains the padding bit. We
+    /// shift the input by half an entry so noise in either direction lands
+    /// in one of the two entries mapping to the same message, which gives
+    /// the same noise tolerance as a standard LUT.
+    fn apply_lut<F>(
+        &self,
+        ct: &LweCiphertext<u64>,
+        f: F,
+        server_key: &ServerKey,
+    ) -> LweCiphertext<u64>
+    where
+        F: Fn(u64) -> u64,
+    {
+        let lut = signed_block_lut(f, &server_key.params.glwe_2, self.block_bits);
+
+        let mut out = LweCiphertext::new(&server_key.params.lwe_0);
+
+        self.apply_lut_into(&mut out, ct, &lut, server_key);
+
+        out
+    }
+
+    /// Like [`apply_lut`](Self::apply_lut), but with a prebuilt `lut` from
+    /// [`signed_block_lut`] and writing the result into `out`. This doesn't
+    /// allocate after the first call on a thread.
+    fn apply_lut_into(
+        &self,
+        out: &mut LweCiphertextRef<u64>,
+        ct: &LweCiphertextRef<u64>,
+        lut: &UnivariateLookupTableRef<u64>,
+        server_key: &ServerKey,
+    ) {
+        let lwe = &server_key.params.lwe_0;
+        let encoding_bits = signed_encoding_bits(self.block_bits);
+
+        allocate_scratch_ref!(input, LweCiphertextRef<u64>, (lwe.dim));
+        input.clone_from_ref(ct);
+        *input.b_mut(lwe) += Torus::from(0x1u64 << (64 - encoding_bits.0 - 2));
+
+        bootstrap_with_lut_into(out, input, lut, server_key);
+    }
+
+    /// Clear the carries of `blocks` from `start` onward, adding each block's
+    /// carry into the next and discarding the carry out of the last block.
+    /// Blocks before `start` must already have empty carries.
+    ///
+    /// # Remarks
+    /// This uses the [`ServerKey`]'s cached LUTs and scratch temporaries,
+    /// so it doesn't allocate after the first call on a thread.
+    fn propagate_carries(
+        &self,
+        blocks: &mut [LweCiphertext<u64>],
+        start: usize,
+        server_key: &ServerKey,
+    ) {
+        let lwe = &server_key.params.lwe_0;
+        let luts = server_key.carry_luts(self.block_bits);
+        let n = blocks.len();
+
+        allocate_scratch_ref!(carry, LweCiphertextRef<u64>, (lwe.dim));
+        allocate_scratch_ref!(input, LweCiphertextRef<u64>, (lwe.dim));
+
+        for (i, block) in blocks.iter_mut().enumerate().skip(start) {
+            if i > start {
+                add_lwe_inplace(block, carry, lwe);
+            }
+
+            input.clone_from_ref(block);
+
+            if i + 1 < n {
+                self.apply_lut_into(carry, input, &luts.carry, server_key);
+            }
+
+            self.apply_lut_into(block, input, &luts.digit, server_key);
+        }
+    }
+
+    /// Add the digit `ct` to block `index` of `blocks` and propagate carries.
+    fn add_at(
+        &self,
+        mut blocks: Vec<LweCiphertext<u64>>,
+        ct: &LweCiphertext<u64>,
+        index: usize,
+        server_key: &ServerKey,
+    ) -> Vec<LweCiphertext<u64>> {
+        add_lwe_inplace(&mut blocks[index], ct, &server_key.params.lwe_0);
+
+        self.propagate_carries(&mut blocks, index, server_key);
+
+        blocks
+    }
+}
+
+/// Encrypt `val` as a [`SignedRadixCiphertext`] with `num_blocks` blocks of
+/// `block_bits` bits each under `sk`.
+///
+/// # Remarks
+/// `params` should be the parameters under which `sk` was generated.
+///
+/// # Panics
+/// If `val` doesn't fit in a `num_blocks * block_bits`-bit two's complement
+/// integer.
+/// If `num_blocks` is zero, `block_bits` is less than 2, or the integer has
+/// more than 64 bits.
+/// If `params` doesn't correspond with `sk`.
+pub fn encrypt_signed_radix(
+    val: i64,
+    sk: &LweSecretKeyRef<u64>,
+    params: &LweDef,
+    block_bits: PlaintextBits,
+    num_blocks: usize,
+) -> SignedRadixCiphertext {
+    let total_bits = num_blocks * block_bits.0 as usize;
+    assert!(num_blocks > 0);
+    assert!(block_bits.0 >= 2);
+    assert!(total_bits <= i64::BITS as usize);
+
+    let shift = (i64::BITS as usize - total_bits) as u32;
+    assert_eq!(
+        (val << shift) >> shift,
+        val,
+        "{val} doesn't fit in {total_bits} bits"
+    );
+
+    let val = val as u64;
+    let mask = (0x1 << block_bits.0) - 1;
+
+    let blocks = (0..num_blocks)
+        .map(|i| {
+            let digit = (val >> (i as u32 * block_bits.0)) & mask;
+
+            encryption::encrypt_lwe_secret(digit, sk, params, signed_encoding_bits(block_bits))
+        })
+        .collect();
+
+    SignedRadixCiphertext::from_blocks(blocks, block_bits)
+}
+
+/// Decrypt a [`SignedRadixCiphertext`] encrypted under `sk`.
+///
+/// # Remarks
+/// `params` should be the parameters under which `sk` was generated.
+///
+/// # Panics
+/// If `params` doesn't correspond with `sk` or `ct`.
+pub fn decrypt_signed_radix(
+    ct: &SignedRadixCiphertext,
+    sk: &LweSecretKeyRef<u64>,
+    params: &LweDef,
+) -> i64 {
+    let block_bits = ct.block_bits();
+    let mask = (0x1 << block_bits.0) - 1;
+
+    let val = ct
+        .blocks()
+        .iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, block)| {
+            let digit =
+                encryption::decrypt_lwe(block, sk, params, signed_encoding_bits(block_bits)) & mask;
+
+            acc | (digit << (i as u32 * block_bits.0))
+        });
+
+    // Sign extend.
+    let shift = (i64::BITS as usize - ct.bits()) as u32;
+
+    ((val as i64) << shift) >> shift
+}
+
+/// Decrypt the result of [`SignedRadixCiphertext::compare`] encrypted
+/// under `sk`.
+///
+/// # Remarks
+/// `params` should be the parameters under which `sk` was generated, and
+/// `block_bits` should match those of the compared integers.
+///
+/// # Panics
+/// If `params` doesn't correspond with `sk` or `ct`.
+/// If `ct` doesn't encrypt a valid comparison result.
+pub fn decrypt_ordering(
+    ct: &LweCiphertext<u64>,
+    sk: &LweSecretKeyRef<u64>,
+    params: &LweDef,
+    block_bits: PlaintextBits,
+) -> Ordering {
+    match encryption::decrypt_lwe(ct, sk, params, signed_encoding_bits(block_bits)) {
+        0 => Ordering::Less,
+        1 => Ordering::Equal,
+        2 => Ordering::Greater,
+        x => panic!("{x} isn't a valid comparison result"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        high_level::*, PlaintextBits, RadixCount, RadixDecomposition, RadixLog, GLWE_1_1024_80,
+        LWE_512_80,
+    };
+
+    use super::*;
+
+    #[test]
+    fn can_encrypt_decrypt_radix() {
+        let sk = keygen::generate_binary_lwe_sk(&LWE_512_80);
+
+        for val in [0, 1, 37, 200, 255] {
+            let ct = encrypt_radix(val, &sk, &LWE_512_80, PlaintextBits(2), 4);
+
+            assert_eq!(ct.blocks().len(), 4);
+            assert_eq!(ct.bits(), 8);
+            assert_eq!(decrypt_radix(&ct, &sk, &LWE_512_80), val);
+        }
+    }
+
+    #[test]
+    fn can_encrypt_decrypt_signed_radix() {
+        let sk = keygen::generate_binary_lwe_sk(&LWE_512_80);
+
+        for val in [-128, -37, -1, 0, 1, 37, 127] {
+            let ct = encrypt_signed_radix(val, &sk, &LWE_512_80, PlaintextBits(2), 4);
+
+            assert_eq!(ct.bits(), 8);
+            assert_eq!(decrypt_signed_radix(&ct, &sk, &LWE_512_80), val);
+        }
+    }
+
+    fn test_server_key_params() -> ServerKeyParams {
+        ServerKeyParams {
+            lwe_0: TEST_LWE_DEF_1,
+            glwe_1: TEST_GLWE_DEF_1,
+            glwe_2: GLWE_1_1024_80,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(16),
+            },
+            cbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(5),
+            },
+            pfks_radix: RadixDecomposition {
+                count: RadixCount(3),
+                radix_log: RadixLog(11),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(4),
+                radix_log: RadixLog(4),
+            },
+        }
+    }
+
+    #[test]
+    fn can_compute_signed_radix() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        // 4-bit integers in [-8, 8).
+        let bits = PlaintextBits(2);
+        let wrap = |x: i64| (x << 60) >> 60;
+
+        let encrypt = |x| encrypt_signed_radix(x, &sk_0, &params.lwe_0, bits, 2);
+        let decrypt = |ct: &SignedRadixCiphertext| decrypt_signed_radix(ct, &sk_0, &params.lwe_0);
+
+        for (a, b) in [(3, 4), (7, 1), (-8, -1), (-3, 2), (5, -6), (-4, -4)] {
+            let ct_a = encrypt(a);
+            let ct_b = encrypt(b);
+
+            assert_eq!(decrypt(&ct_a.add(&ct_b, &server_key)), wrap(a + b));
+            assert_eq!(decrypt(&ct_a.sub(&ct_b, &server_key)), wrap(a - b));
+            assert_eq!(decrypt(&ct_a.mul(&ct_b, &server_key)), wrap(a * b));
+            assert_eq!(decrypt(&ct_a.neg(&server_key)), wrap(-a));
+
+            let ordering = ct_a.compare(&ct_b, &server_key);
+
+            assert_eq!(
+                decrypt_ordering(&ordering, &sk_0, &params.lwe_0, bits),
+                a.cmp(&b),
+                "{a} <=> {b}"
+            );
+        }
+    }
+
+    #[test]
+    fn can_compute_radix() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        // 4-bit integers in [0, 16).
+        let bits = PlaintextBits(2);
+        let n = 2;
+        let wrap = |x: u64| x % 16;
+
+        let encrypt = |x| encrypt_radix(x, &sk_0, &params.lwe_0, bits, n);
+        let decrypt = |ct: &RadixCiphertext| decrypt_radix(ct, &sk_0, &params.lwe_0);
+
+        for (a, b) in [(3, 4), (15, 1), (0, 1), (15, 15), (8, 2), (9, 6)] {
+            let ct_a = encrypt(a);
+            let ct_b = encrypt(b);
+
+            let sum = ct_a.add(&ct_b, &server_key);
+
+            assert_eq!(sum.overflow(), Overflow::Wrapping);
+            assert_eq!(decrypt(&sum), wrap(a + b), "{a} + {b}");
+            assert_eq!(
+                decrypt(&ct_a.sub(&ct_b, &server_key)),
+                wrap(a.wrapping_sub(b)),
+                "{a} - {b}"
+            );
+            assert_eq!(
+                decrypt(&ct_a.mul(&ct_b, &server_key)),
+                wrap(a * b),
+                "{a} * {b}"
+            );
+        }
+
+        server_key.reset_bootstrap_count();
+        encrypt(5).add(&encrypt(5), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 9 * n as u64 - 1);
+
+        // M(2) = 1 * 6 + 4
+        server_key.reset_bootstrap_count();
+        encrypt(5).mul(&encrypt(3), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 10 + 7 * n as u64);
+    }
+
+    #[test]
+    fn can_compute_saturating_radix() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        // 4-bit integers in [0, 16).
+        let bits = PlaintextBits(2);
+        let n = 2;
+
+        let encrypt =
+            |x| encrypt_radix(x, &sk_0, &params.lwe_0, bits, n).with_overflow(Overflow::Saturating);
+        let decrypt = |ct: &RadixCiphertext| decrypt_radix(ct, &sk_0, &params.lwe_0);
+
+        for (a, b) in [
+            (3, 4),
+            (15, 1),
+            (8, 8),
+            (0, 1),
+            (3, 5),
+            (5, 3),
+            (4, 4),
+            (15, 0),
+        ] {
+            let ct_a = encrypt(a);
+            let ct_b = encrypt(b);
+
+            let sum = ct_a.add(&ct_b, &server_key);
+
+            assert_eq!(sum.overflow(), Overflow::Saturating);
+            assert_eq!(decrypt(&sum), (a + b).min(15), "{a} + {b}");
+            assert_eq!(
+                decrypt(&ct_a.sub(&ct_b, &server_key)),
+                a.saturating_sub(b),
+                "{a} - {b}"
+            );
+            assert_eq!(
+                decrypt(&ct_a.mul(&ct_b, &server_key)),
+                (a * b).min(15),
+                "{a} * {b}"
+            );
+        }
+
+        server_key.reset_bootstrap_count();
+        encrypt(5).sub(&encrypt(7), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 9 * n as u64 + 1);
+
+        // M(4) = 1 * 14 + 2 * 10 + 3 * 6 + 8
+        server_key.reset_bootstrap_count();
+        encrypt(5).mul(&encrypt(3), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 60 + 8 * n as u64 - 1);
+    }
+
+    #[test]
+    fn can_compute_saturating_signed_radix() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        // 4-bit integers in [-8, 8).
+        let bits = PlaintextBits(2);
+        let n = 2;
+        let saturate = |x: i64| x.clamp(-8, 7);
+
+        let encrypt = |x| {
+            encrypt_signed_radix(x, &sk_0, &params.lwe_0, bits, n)
+                .with_overflow(Overflow::Saturating)
+        };
+        let decrypt = |ct: &SignedRadixCiphertext| decrypt_signed_radix(ct, &sk_0, &params.lwe_0);
+
+        for (a, b) in [(3, 4), (7, 1), (-8, -1), (-3, 2), (5, -6), (-8, 7), (3, 3)] {
+            let ct_a = encrypt(a);
+            let ct_b = encrypt(b);
+
+            let sum = ct_a.add(&ct_b, &server_key);
+
+            assert_eq!(sum.overflow(), Overflow::Saturating);
+            assert_eq!(decrypt(&sum), saturate(a + b), "{a} + {b}");
+            assert_eq!(
+                decrypt(&ct_a.sub(&ct_b, &server_key)),
+                saturate(a - b),
+                "{a} - {b}"
+            );
+            assert_eq!(
+                decrypt(&ct_a.mul(&ct_b, &server_key)),
+                saturate(a * b),
+                "{a} * {b}"
+            );
+            assert_eq!(decrypt(&ct_a.neg(&server_key)), saturate(-a), "-{a}");
+        }
+
+        server_key.reset_bootstrap_count();
+        encrypt(5).add(&encrypt(5), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 3 * n as u64 + 1);
+
+        // M(4) = 1 * 14 + 2 * 10 + 3 * 6 + 8
+        server_key.reset_bootstrap_count();
+        encrypt(5).mul(&encrypt(-3), &server_key);
+        assert_eq!(server_key.bootstrap_count(), 60 + 2 * n as u64 + 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cannot_mix_overflow_behaviors() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        let a = encrypt_signed_radix(1, &sk_0, &params.lwe_0, PlaintextBits(2), 2);
+        let b = a.clone().with_overflow(Overflow::Saturating);
+
+        a.add(&b, &server_key);
+    }
+
+    #[test]
+    fn bootstrap_count_matches_documented_costs() {
+        let params = test_server_key_params();
+
+        let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        let n = 3;
+        let encrypt = |x| encrypt_signed_radix(x, &sk_0, &params.lwe_0, PlaintextBits(2), n);
+
+        let a = encrypt(5);
+        let b = encrypt(-3);
+
+        assert_eq!(server_key.bootstrap_count(), 0);
+
+        let sum = a.add(&b, &server_key);
+        assert_eq!(server_key.bootstrap_count(), 2 * n as u64 - 1);
+
+        server_key.reset_bootstrap_count();
+        let diff = sum.sub(&b, &server_key).neg(&server_key);
+        assert_eq!(server_key.bootstrap_count(), 2 * (2 * n as u64 - 1));
+
+        server_key.reset_bootstrap_count();
+        diff.compare(&a, &server_key);
+        assert_eq!(server_key.bootstrap_count(), 2 * n as u64 - 1);
+
+        // 1 * 10 + 2 * 6 + 3 * 2
+        server_key.reset_bootstrap_count();
+        let prod = a.mul(&b, &server_key);
+        assert_eq!(server_key.bootstrap_count(), 28);
+
+        assert_eq!(decrypt_signed_radix(&prod, &sk_0, &params.lwe_0), -15);
+    }
+
+    #[test]
+    fn refresh_resets_radix_noise() {
+        let params = test_server_key_params();
+        let lwe = &params.lwe_0;
+
+        let sk_0 = keygen::generate_binary_lwe_sk(lwe);
+        let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+        let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+        let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+        let bits = PlaintextBits(2);
+        let third_digit = (0x1u64 << (64 - bits.0)) / 3;
+
+        // Add a third of a digit of noise in alternating directions to each
+        // block. One more addition of the same magnitude would corrupt it.
+        let noisy = |val| {
+            let ct = encrypt_radix(val, &sk_0, lwe, bits, 4);
+
+            let blocks = ct
+                .blocks()
+                .iter()
+                .enumerate()
+                .map(|(i, block)| {
+                    let mut block = block.clone();
+                    let noise = if i % 2 == 0 {
+                        third_digit
+                    } else {
+                        third_digit.wrapping_neg()
+                    };
+
+                    *block.b_mut(lwe) += Torus::from(noise);
+                    block
+                })
+                .collect();
+
+            RadixCiphertext::from_blocks(blocks, bits)
+        };
+
+        let double = |ct: &RadixCiphertext| {
+            let blocks = ct
+                .blocks()
+                .iter()
+                .map(|block| {
+                    let mut sum = block.clone();
+                    add_lwe_inplace(&mut sum, block, lwe);
+                    sum
+                })
+                .collect();
+
+            RadixCiphertext::from_blocks(blocks, bits)
+        };
+
+        // Digits 3, 2, 1, 0 exercise both halves of the torus.
+        for val in [0b00_01_10_11, 0b11_10_01_00, 0] {
+            let ct = noisy(val);
+
+            assert_eq!(decrypt_radix(&ct, &sk_0, lwe), val);
+
+            server_key.reset_bootstrap_count();
+            let refreshed = ct.refresh(&server_key);
+
+            assert_eq!(server_key.bootstrap_count(), 8);
+            assert_eq!(decrypt_radix(&refreshed, &sk_0, lwe), val);
+        }
+
+        // Doubling digits 1, 0, 1, 0 gives 2, 0, 2, 0.
+        let val = 0b01_00_01_00;
+        let expected = 0b10_00_10_00;
+
+        let ct = noisy(val);
+
+        assert_ne!(decrypt_radix(&double(&ct), &sk_0, lwe), expected);
+        assert_eq!(
+            decrypt_radix(&double(&ct.refresh(&server_key)), &sk_0, lwe),
+            expected
+        );
+    }
+}
@@ -1,4 +1,19 @@
 //! TFHE low-level library
+//!
+//! # WASM
+//! This crate builds for `wasm32-unknown-unknown` with the `wasm` feature,
+//! which sources randomness from the JavaScript host's
+//! `crypto.getRandomValues`. Keys, ciphertexts and lookup tables serialize
+//! with `serde` and never touch the filesystem. Without WASM threads,
+//! operations parallelized with `rayon` run on the calling thread.
+//!
+//! The `timing` feature reads `std::time::Instant`, which isn't available
+//! on `wasm32-unknown-unknown`.
+//!
+//! The `parallel` feature adds variants of some operations (e.g.
+//! [`programmable_bootstrap_univariate_parallel`](crate::ops::bootstrapping::programmable_bootstrap_univariate_parallel))
+//! that spread each step over the `rayon` thread pool while producing
+//! bit-identical results.
 
 #![deny(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links)]
@@ -6,6 +21,9 @@
 #[macro_use]
 mod dst;
 
+mod error;
+pub use error::*;
+
 /// The entities module contains the main data structures used in the library.
 pub mod entities;
 
@@ -30,6 +48,20 @@ mod scratch;
 /// objects as you would expect from a Rust API.
 pub mod high_level;
 
+/// Estimates of how TFHE operations grow ciphertext noise, and ciphertext
+/// wrappers that track these estimates.
+pub mod noise;
+
+/// Cumulative per-operation timing instrumentation. Enabled with the
+/// `timing` feature.
+///
+/// # Remarks
+/// Each instrumented operation reads the clock twice and performs two
+/// atomic adds, which measurably slows down small operations such as FFTs.
+/// Don't enable this feature in production builds.
+#[cfg(feature = "timing")]
+pub mod timing;
+
 /// Zero Knowledge proofs for TFHE.
 #[cfg(feature = "logproof")]
 pub mod zkp;
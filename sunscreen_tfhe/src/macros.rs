@@ -104,5 +104,17 @@ macro_rules! impl_unary_op {
     };
 }
 
+/// Times the remainder of the enclosing block under the given
+/// [`TimingCategory`](crate::timing::TimingCategory) when the `timing`
+/// feature is enabled. Expands to nothing otherwise.
+macro_rules! timed_scope {
+    ($category:ident) => {
+        #[cfg(feature = "timing")]
+        let _timing_scope =
+            crate::timing::TimingScope::new(crate::timing::TimingCategory::$category);
+    };
+}
+
 pub(crate) use impl_binary_op;
 pub(crate) use impl_unary_op;
+pub(crate) use timed_scope;
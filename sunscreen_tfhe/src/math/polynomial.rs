@@ -59,6 +59,37 @@ where
     }
 }
 
+/// Compute `c = a(X^k) mod (X^N + 1)`, where `N` is the polynomial degree.
+///
+/// # Remarks
+/// Coefficient `i` of `a` moves to position `i * k mod 2N`, negated when
+/// that position lies in `[N, 2N)` since `X^N = -1`. This is a permutation
+/// of coefficients (up to sign) when `k` is odd.
+///
+/// # Panics
+/// If `c` and `a` have different lengths.
+/// If `k` is even.
+pub fn polynomial_automorphism<S>(c: &mut PolynomialRef<S>, a: &PolynomialRef<S>, k: usize)
+where
+    S: Clone + Copy + num::traits::WrappingNeg,
+{
+    assert_eq!(c.len(), a.len());
+    assert!(k % 2 == 1, "Automorphism exponent must be odd");
+
+    let n = a.len();
+    let c = c.coeffs_mut();
+
+    for (i, a) in a.coeffs().iter().enumerate() {
+        let j = (i * k) % (2 * n);
+
+        if j < n {
+            c[j] = *a;
+        } else {
+            c[j - n] = a.wrapping_neg();
+        }
+    }
+}
+
 /// Compute `c = a + b` where a, b, and c are polynomials.
 pub fn polynomial_add<S>(c: &mut PolynomialRef<S>, a: &PolynomialRef<S>, b: &PolynomialRef<S>)
 where
@@ -0,0 +1,397 @@
+use num::Complex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    entities::{
+        BootstrapKeyFft, GgswCiphertextFftRef, GlweCiphertext, GlweCiphertextRef, GlweSecretKeyRef,
+        LweCiphertext, LweCiphertextRef, LweKeyswitchKeyRef, LweSecretKeyRef, PolynomialRef,
+        UnivariateLookupTableRef,
+    },
+    high_level::{encryption, evaluation},
+    rand::Stddev,
+    GlweDef, LweDef, PlaintextBits, RadixDecomposition,
+};
+
+/// The variance of the error in a fresh encryption whose noise has standard
+/// deviation `std`.
+pub fn fresh_variance(std: Stddev) -> f64 {
+    std.0 * std.0
+}
+
+/// The variance of the rounding error introduced by approximating a torus
+/// element with `radix`.
+fn decomposition_rounding_variance(radix: &RadixDecomposition) -> f64 {
+    let bits = (radix.radix_log.0 * radix.count.0) as i32;
+
+    2f64.powi(-2 * bits) / 12.0
+}
+
+/// The variance of a digit of a signed radix decomposition, which lies in
+/// `[-B/2, B/2)`.
+fn decomposition_digit_variance(radix: &RadixDecomposition) -> f64 {
+    let b = 2f64.powi(radix.radix_log.0 as i32);
+
+    b * b / 12.0
+}
+
+/// The variance an LWE keyswitch from `from_lwe` to `to_lwe` adds to its
+/// input.
+///
+/// # Remarks
+/// The first term comes from the noise in the keyswitch key (encrypted under
+/// `to_lwe.std`) scaled by the decomposed input. The second comes from
+/// rounding the input's mask to `radix.radix_log * radix.count` bits before
+/// decomposing it. Assumes a binary `from_lwe` secret key.
+pub fn keyswitch_variance(from_lwe: &LweDef, to_lwe: &LweDef, radix: &RadixDecomposition) -> f64 {
+    let n = from_lwe.dim.0 as f64;
+    let l = radix.count.0 as f64;
+
+    n * l * decomposition_digit_variance(radix) * fresh_variance(to_lwe.std)
+        + n / 2.0 * decomposition_rounding_variance(radix)
+}
+
+/// The variance an external product between a fresh GGSW ciphertext encrypting
+/// 0 or 1 and a GLWE ciphertext adds to the GLWE ciphertext. This is also the
+/// variance a [`cmux`](crate::high_level::evaluation::cmux) adds to the
+/// selected input.
+///
+/// # Remarks
+/// Assumes the GGSW ciphertext was encrypted under `glwe.std` and a binary
+/// GLWE secret key.
+pub fn external_product_variance(glwe: &GlweDef, radix: &RadixDecomposition) -> f64 {
+    let n = glwe.dim.polynomial_degree.0 as f64;
+    let k = glwe.dim.size.0 as f64;
+    let l = radix.count.0 as f64;
+
+    (k + 1.0) * l * n * decomposition_digit_variance(radix) * fresh_variance(glwe.std)
+        + (1.0 + k * n / 2.0) * decomposition_rounding_variance(radix)
+}
+
+/// The variance of the result of programmably bootstrapping a ciphertext
+/// under `lwe` to `glwe` with a bootstrapping key using `radix`.
+///
+/// # Remarks
+/// Blind rotation performs `n` cmuxes on a noiseless lookup table, where `n`
+/// is the LWE dimension, and sample extraction adds no noise. The result
+/// doesn't depend on the input's noise, though the input's noise plus
+/// [`modulus_switch_variance`] determines whether bootstrapping selects the
+/// correct lookup table entry.
+pub fn programmable_bootstrap_variance(
+    lwe: &LweDef,
+    glwe: &GlweDef,
+    radix: &RadixDecomposition,
+) -> f64 {
+    lwe.dim.0 as f64 * external_product_variance(glwe, radix)
+}
+
+/// The variance switching a ciphertext under `lwe` to `target_modulus` adds
+/// to its input, relative to the unit torus.
+///
+/// # Remarks
+/// Each component gets rounded to the nearest multiple of
+/// `1 / target_modulus`. Assumes a binary secret key. Programmable
+/// bootstrapping switches to `2N`, where `N` is the GLWE polynomial degree.
+pub fn modulus_switch_variance(lwe: &LweDef, target_modulus: u64) -> f64 {
+    let n = lwe.dim.0 as f64;
+    let step = 1.0 / target_modulus as f64;
+
+    (1.0 + n / 2.0) * step * step / 12.0
+}
+
+/// How many standard deviations of noise with the given `variance` fit
+/// before a message with `plaintext_bits` decodes incorrectly.
+///
+/// # Remarks
+/// Decoding rounds to the nearest multiple of `2^-plaintext_bits`, so errors
+/// up to half that decode correctly. Since the error is approximately
+/// Gaussian, a margin of `z` fails with probability about `erfc(z / sqrt(2))`;
+/// e.g. a margin of 5 fails with probability about `2^-21`.
+pub fn decoding_margin(variance: f64, plaintext_bits: PlaintextBits) -> f64 {
+    2f64.powi(-(plaintext_bits.0 as i32) - 1) / variance.sqrt()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// An [`LweCiphertext`] together with an estimate of the variance of its
+/// noise.
+///
+/// # Remarks
+/// Operations on this type update the estimate using the formulas in this
+/// module, so one can tell how close a computation gets to decryption
+/// failure without decrypting. The estimates assume binary secret keys and
+/// keys encrypted under the standard deviations in their parameters. They're
+/// averages, not bounds.
+pub struct NoisyLweCiphertext {
+    ct: LweCiphertext<u64>,
+    variance: f64,
+}
+
+impl NoisyLweCiphertext {
+    /// Pairs `ct` with a known noise `variance`.
+    pub fn new(ct: LweCiphertext<u64>, variance: f64) -> Self {
+        Self { ct, variance }
+    }
+
+    /// Encrypt `val` under `sk`, as with
+    /// [`encrypt_lwe_secret`](crate::high_level::encryption::encrypt_lwe_secret).
+    pub fn encrypt(
+        val: u64,
+        sk: &LweSecretKeyRef<u64>,
+        params: &LweDef,
+        plaintext_bits: PlaintextBits,
+    ) -> Self {
+        Self::new(
+            encryption::encrypt_lwe_secret(val, sk, params, plaintext_bits),
+            fresh_variance(params.std),
+        )
+    }
+
+    /// The underlying ciphertext.
+    pub fn ciphertext(&self) -> &LweCiphertextRef<u64> {
+        &self.ct
+    }
+
+    /// Returns the underlying ciphertext, discarding the estimate.
+    pub fn into_ciphertext(self) -> LweCiphertext<u64> {
+        self.ct
+    }
+
+    /// The estimated variance of this ciphertext's noise.
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    /// The estimated standard deviation of this ciphertext's noise, relative
+    /// to the unit torus.
+    pub fn estimated_noise_stddev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// See [`decoding_margin`].
+    pub fn decoding_margin(&self, plaintext_bits: PlaintextBits) -> f64 {
+        decoding_margin(self.variance, plaintext_bits)
+    }
+
+    /// Keyswitch this ciphertext as with
+    /// [`keyswitch_lwe_to_lwe`](crate::high_level::evaluation::keyswitch_lwe_to_lwe),
+    /// adding [`keyswitch_variance`] to the estimate.
+    pub fn keyswitch(
+        &self,
+        ksk: &LweKeyswitchKeyRef<u64>,
+        from_lwe: &LweDef,
+        to_lwe: &LweDef,
+        radix: &RadixDecomposition,
+    ) -> Self {
+        Self::new(
+            evaluation::keyswitch_lwe_to_lwe(&self.ct, ksk, from_lwe, to_lwe, radix),
+            self.variance + keyswitch_variance(from_lwe, to_lwe, radix),
+        )
+    }
+
+    /// Bootstrap this ciphertext as with
+    /// [`univariate_programmable_bootstrap`](crate::high_level::evaluation::univariate_programmable_bootstrap).
+    /// The result's estimate is [`programmable_bootstrap_variance`].
+    pub fn programmable_bootstrap(
+        &self,
+        lut: &UnivariateLookupTableRef<u64>,
+        bsk: &BootstrapKeyFft<Complex<f64>>,
+        lwe: &LweDef,
+        glwe: &GlweDef,
+        radix: &RadixDecomposition,
+    ) -> Self {
+        Self::new(
+            evaluation::univariate_programmable_bootstrap(&self.ct, lut, bsk, lwe, glwe, radix),
+            programmable_bootstrap_variance(lwe, glwe, radix),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A [`GlweCiphertext`] together with an estimate of the variance of the
+/// noise in each of its coefficients. See [`NoisyLweCiphertext`].
+pub struct NoisyGlweCiphertext {
+    ct: GlweCiphertext<u64>,
+    variance: f64,
+}
+
+impl NoisyGlweCiphertext {
+    /// Pairs `ct` with a known noise `variance`.
+    pub fn new(ct: GlweCiphertext<u64>, variance: f64) -> Self {
+        Self { ct, variance }
+    }
+
+    /// Encrypt `pt` under `sk`, as with
+    /// [`encrypt_glwe`](crate::high_level::encryption::encrypt_glwe).
+    pub fn encrypt(
+        pt: &PolynomialRef<u64>,
+        sk: &GlweSecretKeyRef<u64>,
+        params: &GlweDef,
+        plaintext_bits: PlaintextBits,
+    ) -> Self {
+        Self::new(
+            encryption::encrypt_glwe(pt, sk, params, plaintext_bits),
+            fresh_variance(params.std),
+        )
+    }
+
+    /// The underlying ciphertext.
+    pub fn ciphertext(&self) -> &GlweCiphertextRef<u64> {
+        &self.ct
+    }
+
+    /// Returns the underlying ciphertext, discarding the estimate.
+    pub fn into_ciphertext(self) -> GlweCiphertext<u64> {
+        self.ct
+    }
+
+    /// The estimated variance of this ciphertext's noise.
+    pub fn variance(&self) -> f64 {
+        self.variance
+    }
+
+    /// The estimated standard deviation of this ciphertext's noise, relative
+    /// to the unit torus.
+    pub fn estimated_noise_stddev(&self) -> f64 {
+        self.variance.sqrt()
+    }
+
+    /// See [`decoding_margin`].
+    pub fn decoding_margin(&self, plaintext_bits: PlaintextBits) -> f64 {
+        decoding_margin(self.variance, plaintext_bits)
+    }
+
+    /// Select `d_0` or `d_1` as with
+    /// [`cmux`](crate::high_level::evaluation::cmux). Since the estimate
+    /// can't depend on the encrypted selector, it's the larger of the
+    /// inputs' plus [`external_product_variance`].
+    pub fn cmux(
+        b_fft: &GgswCiphertextFftRef<Complex<f64>>,
+        d_0: &Self,
+        d_1: &Self,
+        params: &GlweDef,
+        radix: &RadixDecomposition,
+    ) -> Self {
+        Self::new(
+            evaluation::cmux(b_fft, &d_0.ct, &d_1.ct, params, radix),
+            d_0.variance.max(d_1.variance) + external_product_variance(params, radix),
+        )
+    }
+
+    /// Extract the coefficient at `index` as with
+    /// [`sample_extract`](crate::ops::sample_extract::sample_extract). Sample
+    /// extraction adds no noise.
+    pub fn sample_extract(&self, index: usize, params: &GlweDef) -> NoisyLweCiphertext {
+        NoisyLweCiphertext::new(
+            crate::ops::sample_extract::sample_extract(&self.ct, index, params),
+            self.variance,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entities::Polynomial,
+        high_level::{fft, keygen},
+        normalized_torus_distance, RadixCount, RadixLog, Torus, GLWE_1_1024_80, LWE_512_80,
+    };
+
+    use super::*;
+
+    fn measured_stddev(
+        cts: &[NoisyLweCiphertext],
+        sk: &LweSecretKeyRef<u64>,
+        params: &LweDef,
+        expected: Torus<u64>,
+    ) -> f64 {
+        let variance = cts
+            .iter()
+            .map(|ct| {
+                let actual = sk.decrypt_without_decode(ct.ciphertext(), params);
+                let e = normalized_torus_distance(&expected, &actual);
+
+                e * e
+            })
+            .sum::<f64>()
+            / cts.len() as f64;
+
+        variance.sqrt()
+    }
+
+    #[test]
+    fn keyswitch_estimate_matches_measured_noise() {
+        let glwe = GLWE_1_1024_80;
+        let from_lwe = glwe.as_lwe_def();
+        let to_lwe = LWE_512_80;
+        let radix = RadixDecomposition {
+            count: RadixCount(3),
+            radix_log: RadixLog(4),
+        };
+        let bits = PlaintextBits(4);
+
+        let from_sk = keygen::generate_binary_glwe_sk(&glwe);
+        let from_sk = from_sk.to_lwe_secret_key();
+        let to_sk = keygen::generate_binary_lwe_sk(&to_lwe);
+
+        let ksk = keygen::generate_ksk(from_sk, &to_sk, &from_lwe, &to_lwe, &radix);
+
+        let cts = (0..200)
+            .map(|_| {
+                NoisyLweCiphertext::encrypt(5, from_sk, &from_lwe, bits)
+                    .keyswitch(&ksk, &from_lwe, &to_lwe, &radix)
+            })
+            .collect::<Vec<_>>();
+
+        let estimate = cts[0].estimated_noise_stddev();
+        let measured = measured_stddev(&cts, &to_sk, &to_lwe, Torus::encode(5, bits));
+
+        assert!(
+            measured > estimate / 2.0 && measured < estimate * 2.0,
+            "measured={measured} estimate={estimate}"
+        );
+    }
+
+    #[test]
+    fn cmux_estimate_matches_measured_noise() {
+        let glwe = GLWE_1_1024_80;
+        let radix = RadixDecomposition {
+            count: RadixCount(2),
+            radix_log: RadixLog(16),
+        };
+        let bits = PlaintextBits(4);
+        let n = glwe.dim.polynomial_degree.0;
+
+        let sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let d_0 = NoisyGlweCiphertext::encrypt(&Polynomial::zero(n), &sk, &glwe, bits);
+        let d_1 = NoisyGlweCiphertext::encrypt(&Polynomial::new(&vec![3; n]), &sk, &glwe, bits);
+
+        let b = encryption::encrypt_ggsw(1, &sk, &glwe, &radix, PlaintextBits(1));
+        let b = fft::fft_ggsw(&b, &glwe, &radix);
+
+        let result = NoisyGlweCiphertext::cmux(&b, &d_0, &d_1, &glwe, &radix);
+
+        let cts = (0..n)
+            .map(|i| result.sample_extract(i, &glwe))
+            .collect::<Vec<_>>();
+
+        let estimate = result.estimated_noise_stddev();
+        let measured = measured_stddev(
+            &cts,
+            sk.to_lwe_secret_key(),
+            &glwe.as_lwe_def(),
+            Torus::encode(3, bits),
+        );
+
+        assert!(
+            measured > estimate / 2.0 && measured < estimate * 2.0,
+            "measured={measured} estimate={estimate}"
+        );
+    }
+
+    #[test]
+    fn can_compute_decoding_margin() {
+        let variance = 2f64.powi(-20);
+
+        assert_eq!(decoding_margin(variance, PlaintextBits(4)), 32.0);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    dst::FromMutSlice,
+    entities::{GlweCiphertextRef, GlweKeyswitchKeyRef, GlweSecretKeyRef},
+    math::polynomial::polynomial_automorphism,
+    ops::keyswitch::{
+        glwe_keyswitch::keyswitch_glwe_to_glwe, glwe_keyswitch_key::generate_keyswitch_key_glwe,
+    },
+    scratch::allocate_scratch_ref,
+    GlweDef, RadixDecomposition, TorusOps,
+};
+
+fn assert_valid_automorphism(k: usize, params: &GlweDef) {
+    let two_n = 2 * params.dim.polynomial_degree.0;
+
+    assert!(
+        k % 2 == 1 && k < two_n,
+        "Automorphism exponent {k} must be odd and less than 2N={two_n}"
+    );
+}
+
+/// Generate a key for applying the automorphism `X -> X^k` to GLWE
+/// ciphertexts encrypted under `sk`. For use with [`apply_automorphism`].
+///
+/// # Remarks
+/// The result is a keyswitch key from `sk(X^k)` back to `sk`. Each
+/// automorphism exponent `k` requires its own key.
+///
+/// # Panics
+/// If `k` isn't odd or is at least `2N`, where `N` is the polynomial degree.
+/// If `auto_ksk` or `sk` aren't valid for `params` and `radix`.
+pub fn generate_automorphism_key<S>(
+    auto_ksk: &mut GlweKeyswitchKeyRef<S>,
+    sk: &GlweSecretKeyRef<S>,
+    k: usize,
+    params: &GlweDef,
+    radix: &RadixDecomposition,
+) where
+    S: TorusOps,
+{
+    assert_valid_automorphism(k, params);
+    sk.assert_valid(params);
+
+    let mut automorphed_sk = sk.to_owned();
+
+    for (s_auto, s) in automorphed_sk.s_mut(params).zip(sk.s(params)) {
+        polynomial_automorphism(s_auto, s, k);
+    }
+
+    generate_keyswitch_key_glwe(auto_ksk, &automorphed_sk, sk, params, radix);
+}
+
+/// Apply the automorphism `X -> X^k` to the message encrypted in `input`,
+/// writing the result to `result` under the same key as `input`.
+///
+/// # Remarks
+/// Applying the automorphism to every polynomial in `input` yields an
+/// encryption of `m(X^k)` under `s(X^k)`, which we then keyswitch back to
+/// `s` using `auto_ksk` from [`generate_automorphism_key`].
+///
+/// `k` must be odd (equivalently, coprime to `2N` since `N` is a power of
+/// 2) and less than `2N`, where `N` is the polynomial degree. These are
+/// exactly the automorphisms of `Z[X]/(X^N + 1)`. Coefficient `i` of the
+/// message moves to `i * k mod 2N`, negated if it wraps past `N`.
+///
+/// The keyswitch adds noise in proportion to `radix`, as with
+/// [`keyswitch_glwe_to_glwe`].
+///
+/// # Panics
+/// If `k` isn't odd or is at least `2N`.
+/// If `result`, `input`, or `auto_ksk` aren't valid for `params` and `radix`.
+pub fn apply_automorphism<S>(
+    result: &mut GlweCiphertextRef<S>,
+    input: &GlweCiphertextRef<S>,
+    k: usize,
+    auto_ksk: &GlweKeyswitchKeyRef<S>,
+    params: &GlweDef,
+    radix: &RadixDecomposition,
+) where
+    S: TorusOps,
+{
+    assert_valid_automorphism(k, params);
+    result.assert_valid(params);
+    input.assert_valid(params);
+
+    allocate_scratch_ref!(automorphed, GlweCiphertextRef<S>, (params.dim));
+
+    let (a, b) = input.a_b(params);
+    let (a_auto, b_auto) = automorphed.a_b_mut(params);
+
+    for (a_auto, a) in a_auto.zip(a) {
+        polynomial_automorphism(a_auto, a, k);
+    }
+
+    polynomial_automorphism(b_auto, b, k);
+
+    keyswitch_glwe_to_glwe(result, automorphed, auto_ksk, params, radix);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        entities::{GlweCiphertext, GlweKeyswitchKey, Polynomial},
+        high_level::*,
+        PlaintextBits,
+    };
+
+    use super::*;
+
+    #[test]
+    fn can_apply_automorphism() {
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+        let bits = PlaintextBits(4);
+        let n = glwe.dim.polynomial_degree.0;
+
+        let sk = keygen::generate_binary_glwe_sk(&glwe);
+
+        let msg = (0..n as u64).map(|x| x % 7).collect::<Vec<_>>();
+        let ct = sk.encode_encrypt_glwe(&Polynomial::new(&msg), &glwe, bits);
+
+        for k in [1, 3, 5, 2 * n - 1] {
+            let mut ksk = GlweKeyswitchKey::new(&glwe, &radix);
+            generate_automorphism_key(&mut ksk, &sk, k, &glwe, &radix);
+
+            let mut result = GlweCiphertext::new(&glwe);
+            apply_automorphism(&mut result, &ct, k, &ksk, &glwe, &radix);
+
+            let actual = sk.decrypt_decode_glwe(&result, &glwe, bits);
+
+            let mut expected = vec![0u64; n];
+
+            for (i, m) in msg.iter().enumerate() {
+                let j = (i * k) % (2 * n);
+
+                if j < n {
+                    expected[j] = *m;
+                } else {
+                    expected[j - n] = (*m).wrapping_neg() % (1 << bits.0);
+                }
+            }
+
+            assert_eq!(actual.coeffs(), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn even_automorphism_panics() {
+        let glwe = TEST_GLWE_DEF_1;
+        let sk = keygen::generate_binary_glwe_sk(&glwe);
+        let mut ksk = GlweKeyswitchKey::new(&glwe, &TEST_RADIX);
+
+        generate_automorphism_key(&mut ksk, &sk, 2, &glwe, &TEST_RADIX);
+    }
+}
@@ -3,6 +3,7 @@ use num::Complex;
 use crate::{
     dst::FromMutSlice,
     entities::{BlindRotationShiftFftRef, GgswCiphertext, GlweCiphertextRef, GlweSecretKeyRef},
+    macros::timed_scope,
     ops::{encryption::encrypt_ggsw_ciphertext_scalar, fft_ops::cmux},
     scratch::allocate_scratch_ref,
     GlweDef, PlaintextBits, RadixDecomposition, TorusOps,
@@ -208,17 +209,20 @@ pub fn blind_rotation<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(BlindRotation);
+
     // Initialize with the unrotated message m
     output.clone_from_ref(ct);
     allocate_scratch_ref!(rotated_ct, GlweCiphertextRef<S>, (params.dim));
+    allocate_scratch_ref!(tmp, GlweCiphertextRef<S>, (params.dim));
 
     for (i, index_select) in blind_rotation_index.rows(params, radix).enumerate() {
         let rotation = 1 << i;
 
         rotate_glwe_negative_monomial_negacyclic(rotated_ct, output, rotation, params);
 
-        let tmp = output.to_owned();
-        cmux(output, &tmp, rotated_ct, index_select, params, radix);
+        tmp.clone_from_ref(output);
+        cmux(output, tmp, rotated_ct, index_select, params, radix);
     }
 }
 
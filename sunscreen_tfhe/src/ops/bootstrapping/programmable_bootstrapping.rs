@@ -4,10 +4,11 @@ use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIter
 use crate::{
     dst::FromMutSlice,
     entities::{
-        BivariateLookupTableRef, BootstrapKeyFftRef, BootstrapKeyRef, GlweCiphertextRef,
-        GlweSecretKeyRef, LweCiphertextRef, LweSecretKeyRef, Polynomial, PolynomialRef,
-        UnivariateLookupTableRef,
+        BivariateLookupTableRef, BootstrapKeyFftRef, BootstrapKeyRef, GgswCiphertextFftRef,
+        GlweCiphertextRef, GlweSecretKeyRef, LweCiphertextRef, LweSecretKeyRef, Polynomial,
+        PolynomialRef, UnivariateLookupTableRef,
     },
+    macros::timed_scope,
     ops::{
         bootstrapping::rotate_glwe_positive_monomial_negacyclic,
         ciphertext::{
@@ -21,6 +22,9 @@ use crate::{
     CarryBits, GlweDef, LweDef, PlaintextBits, RadixDecomposition, Torus, TorusOps,
 };
 
+#[cfg(feature = "parallel")]
+use crate::ops::fft_ops::cmux_parallel;
+
 use super::rotate_glwe_negative_monomial_negacyclic;
 
 /// Generate a bootstrap key from a LWE secret key to a GLWE secret key.
@@ -314,6 +318,48 @@ pub fn programmable_bootstrap_univariate<S>(
     sample_extract(output, glwe, 0, glwe_params);
 }
 
+#[cfg(feature = "parallel")]
+/// Like [`programmable_bootstrap_univariate`], but parallelizes each
+/// blind rotation step on the `rayon` thread pool.
+///
+/// # Remarks
+/// Each step of the blind rotation depends on the previous one, so the
+/// steps still run in order. Within each step, this decomposes the
+/// accumulator's polynomials, computes the external product for each
+/// output polynomial, and takes their inverse FFTs in parallel (see
+/// [`cmux_parallel`](crate::ops::fft_ops::cmux_parallel)). As such, the
+/// speedup grows with the GLWE size and the bootstrap key's radix count.
+///
+/// The result is bit-identical to [`programmable_bootstrap_univariate`].
+pub fn programmable_bootstrap_univariate_parallel<S>(
+    output: &mut LweCiphertextRef<S>,
+    input: &LweCiphertextRef<S>,
+    lut: &UnivariateLookupTableRef<S>,
+    bootstrap_key: &BootstrapKeyFftRef<Complex<f64>>,
+    lwe_params: &LweDef,
+    glwe_params: &GlweDef,
+    radix: &RadixDecomposition,
+) where
+    S: TorusOps,
+{
+    allocate_scratch_ref!(glwe, GlweCiphertextRef<S>, (glwe_params.dim));
+
+    blind_rotate_lut(
+        glwe,
+        input,
+        lut,
+        bootstrap_key,
+        0,
+        0,
+        lwe_params,
+        glwe_params,
+        radix,
+        cmux_parallel,
+    );
+
+    sample_extract(output, glwe, 0, glwe_params);
+}
+
 #[allow(clippy::too_many_arguments)]
 /// A generalized version of programmable bootstrapping.
 /// Computes a function `lut` of the encrypted `input`.
@@ -348,6 +394,47 @@ pub fn generalized_programmable_bootstrap<S>(
     radix: &RadixDecomposition,
 ) where
     S: TorusOps,
+{
+    blind_rotate_lut(
+        output,
+        input,
+        lut,
+        bootstrap_key,
+        log_chi,
+        log_v,
+        lwe_params,
+        glwe_params,
+        radix,
+        cmux,
+    );
+}
+
+/// The cmux used for each step of a blind rotation.
+type Cmux<S> = fn(
+    &mut GlweCiphertextRef<S>,
+    &GlweCiphertextRef<S>,
+    &GlweCiphertextRef<S>,
+    &GgswCiphertextFftRef<Complex<f64>>,
+    &GlweDef,
+    &RadixDecomposition,
+);
+
+#[allow(clippy::too_many_arguments)]
+/// Computes [`generalized_programmable_bootstrap`], using `cmux` for each
+/// step of the blind rotation.
+fn blind_rotate_lut<S>(
+    output: &mut GlweCiphertextRef<S>,
+    input: &LweCiphertextRef<S>,
+    lut: &UnivariateLookupTableRef<S>,
+    bootstrap_key: &BootstrapKeyFftRef<Complex<f64>>,
+    log_chi: u32,
+    log_v: u32,
+    lwe_params: &LweDef,
+    glwe_params: &GlweDef,
+    radix: &RadixDecomposition,
+    cmux: Cmux<S>,
+) where
+    S: TorusOps,
 {
     lwe_params.assert_valid();
     glwe_params.assert_valid();
@@ -368,8 +455,9 @@ pub fn generalized_programmable_bootstrap<S>(
     let two_n = degree.ilog2() + 1;
 
     // 1. Modulus switch the ciphertext to 2N.
-    let mut ct = input.to_owned();
-    lwe_ciphertext_modulus_switch(&mut ct, log_chi, log_v, two_n, lwe_params);
+    allocate_scratch_ref!(ct, LweCiphertextRef<S>, (lwe_params.dim));
+    ct.clone_from_ref(input);
+    lwe_ciphertext_modulus_switch(ct, log_chi, log_v, two_n, lwe_params);
 
     let (ct_a, ct_b) = ct.a_b(lwe_params);
 
@@ -387,11 +475,14 @@ pub fn generalized_programmable_bootstrap<S>(
     );
 
     allocate_scratch_ref!(rotated_ct, GlweCiphertextRef<S>, (glwe_params.dim));
+    allocate_scratch_ref!(tmp, GlweCiphertextRef<S>, (glwe_params.dim));
+
+    timed_scope!(BlindRotation);
 
     // Perform the cmux tree from the bootstrap key with the relation
     // V_n = V_{n-1} ^ X^{a_{n-1} s_{n-1}}
     for (a_i, index_select) in ct_a.iter().zip(bootstrap_key.rows(glwe_params, radix)) {
-        let tmp = output.to_owned();
+        tmp.clone_from_ref(output);
 
         // This operation performs a copy so the rotated_ct doesn't need to be
         // cleared.
@@ -402,7 +493,7 @@ pub fn generalized_programmable_bootstrap<S>(
             glwe_params,
         );
 
-        cmux(output, &tmp, rotated_ct, index_select, glwe_params, radix);
+        cmux(output, tmp, rotated_ct, index_select, glwe_params, radix);
     }
 }
 
@@ -440,6 +531,10 @@ pub(crate) fn generate_bivariate_lut<S, F>(
         plaintext_bits.0 <= carry_bits.0,
         "The number of plaintext bits must be less than or equal to the number of carry bits"
     );
+    assert!(
+        (1usize << (plaintext_bits.0 + carry_bits.0)) <= params.dim.polynomial_degree.0,
+        "The combined plaintext and carry bits must fit in the GLWE polynomial degree"
+    );
 
     let wrapped_func = |input: u64| bivariate_function(&map, input, plaintext_bits);
 
@@ -625,12 +720,14 @@ mod tests {
             BivariateLookupTable, BootstrapKey, BootstrapKeyFft, GlweCiphertext, LweCiphertext,
             LweKeyswitchKey, UnivariateLookupTable,
         },
-        high_level::{encryption, fft, keygen, TEST_GLWE_DEF_1, TEST_LWE_DEF_1, TEST_RADIX},
+        high_level::{
+            encryption, evaluation, fft, keygen, TEST_GLWE_DEF_1, TEST_LWE_DEF_1, TEST_RADIX,
+        },
         ops::{
             encryption::{decrypt_ggsw_ciphertext, encrypt_lwe_ciphertext},
             keyswitch::lwe_keyswitch_key::generate_keyswitch_key_lwe,
         },
-        RoundedDiv, GLWE_1_1024_80, LWE_512_80,
+        RadixCount, RadixLog, RoundedDiv, GLWE_1_1024_80, GLWE_1_2048_128, LWE_512_80,
     };
 
     use super::*;
@@ -671,6 +768,31 @@ mod tests {
         assert_eq!(expected, poly);
     }
 
+    #[test]
+    fn lut_from_polynomial_matches_from_fn() {
+        let bits = PlaintextBits(4);
+        let glwe = TEST_GLWE_DEF_1;
+        let map = |x| (x * 3) % 16;
+
+        let mut poly = Polynomial::<Torus<u64>>::zero(glwe.dim.polynomial_degree.0);
+        generate_lut(&mut poly, &[map], &glwe, bits);
+
+        let expected = UnivariateLookupTable::trivial_from_fn(map, &glwe, bits);
+        let actual = UnivariateLookupTable::from_polynomial(&poly, &glwe);
+
+        assert_eq!(actual.as_slice(), expected.as_slice());
+    }
+
+    #[test]
+    #[should_panic(expected = "one coefficient per GLWE polynomial degree")]
+    fn lut_from_polynomial_checks_length() {
+        let glwe = TEST_GLWE_DEF_1;
+
+        let poly = Polynomial::<Torus<u64>>::zero(glwe.dim.polynomial_degree.0 / 2);
+
+        UnivariateLookupTable::from_polynomial(&poly, &glwe);
+    }
+
     #[test]
     fn can_generate_bootstrap_key() {
         let lwe_params = TEST_LWE_DEF_1;
@@ -785,6 +907,41 @@ mod tests {
         bootstrap_helper(|x| (x + 3) % 8);
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn parallel_bootstrap_matches_serial() {
+        let bits = PlaintextBits(3);
+        let lwe = TEST_LWE_DEF_1;
+        let glwe = TEST_GLWE_DEF_1;
+        let radix = TEST_RADIX;
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+        let bsk = keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, &lwe, &glwe, &radix);
+        let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+
+        let map = |x| (x + 3) % 8;
+        let lut = UnivariateLookupTable::trivial_from_fn(map, &glwe, bits);
+
+        for msg in 0..(1 << bits.0) {
+            let ct = lwe_sk.encrypt(msg, &lwe, PlaintextBits(bits.0 + 1)).0;
+
+            let serial =
+                evaluation::univariate_programmable_bootstrap(&ct, &lut, &bsk, &lwe, &glwe, &radix);
+            let parallel = evaluation::univariate_programmable_bootstrap_parallel(
+                &ct, &lut, &bsk, &lwe, &glwe, &radix,
+            );
+
+            assert_eq!(serial.as_slice(), parallel.as_slice());
+            assert_eq!(
+                glwe_sk
+                    .to_lwe_secret_key()
+                    .decrypt(&parallel, &glwe.as_lwe_def(), bits),
+                map(msg)
+            );
+        }
+    }
+
     fn bivariate_bootstrap_helper(map: impl Fn(u64, u64) -> u64) {
         let lwe = TEST_LWE_DEF_1;
         let glwe = TEST_GLWE_DEF_1;
@@ -981,4 +1138,96 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn can_multivalue_bootstrap() {
+        let radix = &RadixDecomposition {
+            count: RadixCount(2),
+            radix_log: RadixLog(16),
+        };
+        let lwe = &LWE_512_80;
+        let glwe = &GLWE_1_1024_80;
+        let bits = PlaintextBits(2);
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(glwe);
+        let bs_key = keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, lwe, glwe, radix);
+        let bs_key = fft::fft_bootstrap_key(&bs_key, lwe, glwe, radix);
+
+        let maps: [fn(u64) -> u64; 3] = [|x| x, |x| (x + 1) % 4, |x| (x * x) % 4];
+
+        for n_maps in 1..=maps.len() {
+            for msg in 0..(1 << bits.0) {
+                // Add a padding bit
+                let input =
+                    encryption::encrypt_lwe_secret(msg, &lwe_sk, lwe, PlaintextBits(bits.0 + 1));
+
+                let outputs = evaluation::multivalue_programmable_bootstrap(
+                    &input,
+                    &maps[..n_maps],
+                    &bs_key,
+                    lwe,
+                    glwe,
+                    radix,
+                    bits,
+                );
+
+                assert_eq!(outputs.len(), n_maps);
+
+                for (output, map) in outputs.iter().zip(maps) {
+                    let actual = encryption::decrypt_lwe(
+                        output,
+                        glwe_sk.to_lwe_secret_key(),
+                        &glwe.as_lwe_def(),
+                        bits,
+                    );
+
+                    assert_eq!(actual, map(msg));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn can_bit_decompose() {
+        let radix = &RadixDecomposition {
+            count: RadixCount(2),
+            radix_log: RadixLog(16),
+        };
+        let lwe = &LWE_512_80;
+        // Packing 3 functions rounds the input to a quarter of the usual
+        // resolution, so 3 message bits plus padding need the extra
+        // headroom from modulus switching to a degree 2048 polynomial.
+        let glwe = &GLWE_1_2048_128;
+        let bits = PlaintextBits(3);
+
+        let lwe_sk = keygen::generate_binary_lwe_sk(lwe);
+        let glwe_sk = keygen::generate_binary_glwe_sk(glwe);
+        let bs_key = keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, lwe, glwe, radix);
+        let bs_key = fft::fft_bootstrap_key(&bs_key, lwe, glwe, radix);
+
+        for n_bits in [1, 2, 3] {
+            for msg in 0..(1 << bits.0) {
+                // Add a padding bit
+                let input =
+                    encryption::encrypt_lwe_secret(msg, &lwe_sk, lwe, PlaintextBits(bits.0 + 1));
+
+                let decomposed =
+                    evaluation::bit_decompose(&input, n_bits, &bs_key, lwe, glwe, radix, bits);
+
+                assert_eq!(decomposed.len(), n_bits);
+
+                for (i, bit) in decomposed.iter().enumerate() {
+                    let actual = encryption::decrypt_lwe(
+                        bit,
+                        glwe_sk.to_lwe_secret_key(),
+                        &glwe.as_lwe_def(),
+                        bits,
+                    );
+
+                    assert_eq!(actual, (msg >> i) & 0x1);
+                }
+            }
+        }
+    }
 }
@@ -3,6 +3,7 @@ use crate::{
     entities::{
         GgswCiphertextRef, GlweCiphertext, GlweCiphertextRef, LweCiphertextRef, PolynomialRef,
     },
+    macros::timed_scope,
     ops::ciphertext::decomposed_polynomial_glev_mad,
     polynomial::{
         polynomial_add, polynomial_external_mad, polynomial_negate, polynomial_scalar_mad,
@@ -36,8 +37,14 @@ pub fn sample_extract<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(SampleExtract);
+
     glwe.assert_valid(params);
-    assert!(h < params.dim.polynomial_degree.0);
+    assert!(
+        h < params.dim.polynomial_degree.0,
+        "Coefficient index {h} must be less than the polynomial degree {}",
+        params.dim.polynomial_degree.0
+    );
 
     // We are copying parts of the GLWE ciphertext out according to the following rule:
     // a_{N*i + j} =  a_{i, h - j} for 0 <= i < k, 0 <= j <= h
@@ -195,6 +202,8 @@ pub fn glwe_ggsw_mad<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(ExternalProduct);
+
     let (a_a, a_b) = a.a_b(glwe_def);
     let rows = b.rows(glwe_def, radix);
 
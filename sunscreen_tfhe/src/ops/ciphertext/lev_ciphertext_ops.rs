@@ -1,7 +1,9 @@
 use crate::{
-    entities::{LevCiphertextRef, LweCiphertextRef, Polynomial},
+    dst::FromMutSlice,
+    entities::{LevCiphertextRef, LweCiphertextRef, PolynomialRef},
     radix::PolynomialRadixIterator,
-    LweDef, TorusOps,
+    scratch::allocate_scratch_ref,
+    LweDef, PolynomialDegree, TorusOps,
 };
 
 use super::scalar_mul_ciphertext_mad;
@@ -24,7 +26,7 @@ pub fn decomposed_scalar_lev_mad<S>(
     S: TorusOps,
 {
     let b_lwe = b.lwe_ciphertexts(params);
-    let mut cur_radix: Polynomial<S> = Polynomial::zero(1);
+    allocate_scratch_ref!(cur_radix, PolynomialRef<S>, (PolynomialDegree(1)));
 
     // The decomposition of
     //     <Decomp^{beta, l}(gamma), GLEV>
@@ -34,7 +36,7 @@ pub fn decomposed_scalar_lev_mad<S>(
     // Note the reverse of the GLWE ciphertexts here! The decomposition iterator
     // returns the decomposed values in the opposite order.
     for b in b_lwe.rev() {
-        a.write_next(&mut cur_radix);
+        a.write_next(cur_radix);
         let radix = cur_radix.coeffs()[0];
 
         scalar_mul_ciphertext_mad(c, &radix, b, params);
@@ -1,4 +1,4 @@
-use crate::{entities::LweCiphertextRef, LweDef, Torus, TorusOps};
+use crate::{entities::LweCiphertextRef, macros::timed_scope, LweDef, Torus, TorusOps};
 
 /// Add the coefficients of a to the coefficients of c in place.
 pub fn add_lwe_inplace<S>(c: &mut LweCiphertextRef<S>, a: &LweCiphertextRef<S>, params: &LweDef)
@@ -103,6 +103,8 @@ pub fn lwe_ciphertext_modulus_switch<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(ModulusSwitch);
+
     let (c_a, c_b) = ct.a_b_mut(params);
 
     // We specifically want to zero out the MSBs instead of shifting them back
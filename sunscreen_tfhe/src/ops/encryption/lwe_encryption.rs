@@ -136,4 +136,47 @@ mod tests {
 
         assert_eq!(pt, 4);
     }
+
+    #[test]
+    fn can_encrypt_many_in_parallel() {
+        let params = TEST_LWE_DEF_1;
+        let bits = PlaintextBits(4);
+
+        let sk = keygen::generate_binary_lwe_sk(&params);
+
+        let messages = (0..1024).map(|x| x % 16).collect::<Vec<u64>>();
+
+        let cts = encryption::encrypt_lwe_many(&messages, &sk, &params, bits);
+
+        assert_eq!(cts.len(), messages.len());
+
+        for (ct, m) in cts.iter().zip(messages.iter()) {
+            assert_eq!(encryption::decrypt_lwe(ct, &sk, &params, bits), *m);
+        }
+    }
+
+    #[test]
+    fn seeded_encrypt_many_is_reproducible() {
+        let params = TEST_LWE_DEF_1;
+        let bits = PlaintextBits(4);
+
+        let sk = keygen::generate_binary_lwe_sk(&params);
+
+        let messages = (0..256).map(|x| x % 16).collect::<Vec<u64>>();
+
+        let a = encryption::encrypt_lwe_many_seeded(&messages, &sk, &params, bits, [7; 32]);
+        let b = encryption::encrypt_lwe_many_seeded(&messages, &sk, &params, bits, [7; 32]);
+        let c = encryption::encrypt_lwe_many_seeded(&messages, &sk, &params, bits, [8; 32]);
+
+        let a_data = a.iter().map(|x| x.as_slice().to_vec()).collect::<Vec<_>>();
+        let b_data = b.iter().map(|x| x.as_slice().to_vec()).collect::<Vec<_>>();
+        let c_data = c.iter().map(|x| x.as_slice().to_vec()).collect::<Vec<_>>();
+
+        assert_eq!(a_data, b_data);
+        assert_ne!(a_data, c_data);
+
+        for (ct, m) in a.iter().zip(messages.iter()) {
+            assert_eq!(encryption::decrypt_lwe(ct, &sk, &params, bits), *m);
+        }
+    }
 }
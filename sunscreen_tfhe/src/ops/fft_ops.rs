@@ -6,12 +6,16 @@ use crate::{
         GgswCiphertextFftRef, GlevCiphertextFftRef, GlweCiphertextFftRef, GlweCiphertextRef,
         PolynomialFftRef, PolynomialRef,
     },
+    macros::timed_scope,
     ops::ciphertext::{add_glwe_ciphertexts, sub_glwe_ciphertexts},
     radix::PolynomialRadixIterator,
     scratch::{allocate_scratch, allocate_scratch_ref},
     GlweDef, RadixDecomposition, TorusOps,
 };
 
+#[cfg(feature = "parallel")]
+use crate::{dst::FromSlice, Torus};
+
 /// Compute `c += a \[*] b`` where
 /// * `a` is a GLWE ciphertext
 /// * `b` is a GGSW cipheetext
@@ -25,6 +29,8 @@ pub fn glwe_ggsw_mad<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(ExternalProduct);
+
     let (a_a, a_b) = a.a_b(params);
     let rows = b_fft.rows(params, radix);
 
@@ -165,6 +171,137 @@ pub fn cmux<S>(
     add_glwe_ciphertexts(c, prod, d_0, params);
 }
 
+#[cfg(feature = "parallel")]
+/// Like [`glwe_ggsw_mad`], but decomposes the GLWE polynomials and
+/// accumulates each output polynomial in parallel on the `rayon` thread
+/// pool.
+///
+/// # Remarks
+/// Each output polynomial accumulates its products in the same order as
+/// [`glwe_ggsw_mad`], so the two produce bit-identical results.
+pub fn glwe_ggsw_mad_parallel<S>(
+    c_fft: &mut GlweCiphertextFftRef<Complex<f64>>,
+    a: &GlweCiphertextRef<S>,
+    b_fft: &GgswCiphertextFftRef<Complex<f64>>,
+    params: &GlweDef,
+    radix: &RadixDecomposition,
+) where
+    S: TorusOps,
+{
+    use num::Zero;
+    use rayon::{
+        iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator},
+        slice::ParallelSliceMut,
+    };
+
+    use crate::entities::PolynomialFft;
+
+    timed_scope!(ExternalProduct);
+
+    let degree = params.dim.polynomial_degree;
+    let fft_len = PolynomialFftRef::<Complex<f64>>::size(degree);
+
+    let (a_a, a_b) = a.a_b(params);
+    let a_then_b_glwe_polynomials = a_a.chain(std::iter::once(a_b)).collect::<Vec<_>>();
+    let rows = b_fft.rows(params, radix).collect::<Vec<_>>();
+
+    // Decompose each GLWE polynomial and take the FFT of each of its
+    // digits, least significant first.
+    let decomps = a_then_b_glwe_polynomials
+        .par_iter()
+        .map(|a_i| {
+            allocate_scratch_ref!(scratch, PolynomialRef<S>, (degree));
+            allocate_scratch_ref!(cur_radix, PolynomialRef<S>, (degree));
+
+            let mut decomp = PolynomialRadixIterator::new(a_i, scratch, radix);
+
+            (0..radix.count.0)
+                .map(|_| {
+                    let mut decomp_fft = PolynomialFft::new(&vec![Complex::zero(); fft_len]);
+
+                    decomp.write_next(cur_radix);
+                    cur_radix.fft(&mut decomp_fft);
+
+                    decomp_fft
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    // As in decomposed_polynomial_glev_mad, pair the digits with the GLEV's
+    // GLWE ciphertexts in reverse.
+    c_fft
+        .as_mut_slice()
+        .par_chunks_mut(fft_len)
+        .enumerate()
+        .for_each(|(i, c_i)| {
+            let c_i = PolynomialFftRef::from_mut_slice(c_i);
+
+            for (decomp, r) in decomps.iter().zip(rows.iter()) {
+                for (digit, b) in decomp.iter().zip(r.glwe_ciphertexts(params).rev()) {
+                    let (b_a, b_b) = b.a_b(params);
+                    let b_i = b_a.chain(std::iter::once(b_b)).nth(i).unwrap();
+
+                    c_i.multiply_add(b_i, digit);
+                }
+            }
+        });
+}
+
+#[cfg(feature = "parallel")]
+/// Like [`cmux`], but computes the external product with
+/// [`glwe_ggsw_mad_parallel`] and the inverse FFT of each polynomial in
+/// parallel. The result is bit-identical to [`cmux`].
+pub fn cmux_parallel<S>(
+    c: &mut GlweCiphertextRef<S>,
+    d_0: &GlweCiphertextRef<S>,
+    d_1: &GlweCiphertextRef<S>,
+    b_fft: &GgswCiphertextFftRef<Complex<f64>>,
+    params: &GlweDef,
+    radix: &RadixDecomposition,
+) where
+    S: TorusOps,
+{
+    use rayon::{
+        iter::{IndexedParallelIterator, ParallelIterator},
+        slice::{ParallelSlice, ParallelSliceMut},
+    };
+
+    params.assert_valid();
+    radix.assert_valid::<S>();
+    c.assert_valid(params);
+    d_0.assert_valid(params);
+    d_1.assert_valid(params);
+    b_fft.assert_valid(params, radix);
+
+    allocate_scratch_ref!(diff, GlweCiphertextRef<S>, (params.dim));
+
+    sub_glwe_ciphertexts(diff, d_1, d_0, params);
+
+    allocate_scratch_ref!(prod_fft, GlweCiphertextFftRef<Complex<f64>>, (params.dim));
+
+    prod_fft.clear();
+
+    glwe_ggsw_mad_parallel(prod_fft, diff, b_fft, params, radix);
+
+    allocate_scratch_ref!(prod, GlweCiphertextRef<S>, (params.dim));
+
+    let degree = params.dim.polynomial_degree;
+
+    prod_fft
+        .as_slice()
+        .par_chunks(PolynomialFftRef::<Complex<f64>>::size(degree))
+        .zip(
+            prod.as_mut_slice()
+                .par_chunks_mut(PolynomialRef::<Torus<S>>::size(degree)),
+        )
+        .for_each(|(fft, poly)| {
+            PolynomialFftRef::from_slice(fft).ifft(PolynomialRef::from_mut_slice(poly));
+        });
+
+    add_glwe_ciphertexts(c, prod, d_0, params);
+}
+
 #[cfg(test)]
 mod tests {
     use rand::{thread_rng, RngCore};
@@ -1,6 +1,7 @@
 use crate::{
     dst::{FromMutSlice, FromSlice},
-    entities::{LweCiphertext, LweCiphertextRef, LweKeyswitchKeyRef, PolynomialRef},
+    entities::{LweCiphertextRef, LweKeyswitchKeyRef, PolynomialRef},
+    macros::timed_scope,
     ops::{
         ciphertext::{decomposed_scalar_lev_mad, sub_lwe_ciphertexts},
         encryption::trivially_encrypt_lwe_ciphertext,
@@ -30,6 +31,8 @@ pub fn keyswitch_lwe_to_lwe<S>(
 ) where
     S: TorusOps,
 {
+    timed_scope!(Keyswitch);
+
     old_params.assert_valid();
     new_params.assert_valid();
     radix.assert_valid::<S>();
@@ -41,7 +44,8 @@ pub fn keyswitch_lwe_to_lwe<S>(
 
     let keyswitch_levs = keyswitch_key.rows(new_params, radix);
 
-    let mut a_i_decomp_sum = LweCiphertext::new(new_params);
+    allocate_scratch_ref!(a_i_decomp_sum, LweCiphertextRef<S>, (new_params.dim));
+    a_i_decomp_sum.clear();
 
     allocate_scratch_ref!(scratch, PolynomialRef<S>, (PolynomialDegree(1)));
 
@@ -50,15 +54,15 @@ pub fn keyswitch_lwe_to_lwe<S>(
         let decomp =
             PolynomialRadixIterator::new(PolynomialRef::from_slice(&[*a_i]), scratch, radix);
 
-        decomposed_scalar_lev_mad(&mut a_i_decomp_sum, decomp, lev_i, new_params);
+        decomposed_scalar_lev_mad(a_i_decomp_sum, decomp, lev_i, new_params);
     }
 
     // trivial_encrypt(ciphertext_b)
-    let mut trivial_b = LweCiphertext::new(new_params);
-    trivially_encrypt_lwe_ciphertext(&mut trivial_b, ciphertext_b, new_params);
+    allocate_scratch_ref!(trivial_b, LweCiphertextRef<S>, (new_params.dim));
+    trivially_encrypt_lwe_ciphertext(trivial_b, ciphertext_b, new_params);
 
     // output = trivial_encrypt(ciphertext_b) - sum_i(<decomp(ciphertext_a_i), glev_i>)
-    sub_lwe_ciphertexts(output, &trivial_b, &a_i_decomp_sum, new_params);
+    sub_lwe_ciphertexts(output, trivial_b, a_i_decomp_sum, new_params);
 }
 
 #[cfg(test)]
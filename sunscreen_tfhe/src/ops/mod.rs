@@ -17,3 +17,12 @@ pub mod ciphertext;
 
 /// Methods for encrypting and decrypting to various ciphertext types.
 pub mod encryption;
+
+/// Methods for applying automorphisms `X -> X^k` to GLWE ciphertexts.
+pub mod automorphism;
+
+/// Methods for switching ciphertexts to a smaller modulus.
+pub mod modulus_switch;
+
+/// Methods for extracting LWE ciphertexts from GLWE ciphertexts.
+pub mod sample_extract;
@@ -0,0 +1,117 @@
+use crate::{
+    entities::{LweCiphertext, LweCiphertextRef},
+    LweDef, Torus,
+};
+
+/// Switch `ct` from the native `2^64` modulus to `target_modulus`, returning
+/// the result as a new ciphertext.
+///
+/// # Remarks
+/// Each component `c` of `ct` (both the mask `a` and body `b`) is replaced by
+/// `round(c * target_modulus / 2^64) mod target_modulus`. The components of
+/// the result lie in `[0, target_modulus)` and should be interpreted modulo
+/// `target_modulus` rather than as `2^64` torus elements; decrypting the
+/// result requires computing `b - <a, s>` modulo `target_modulus`.
+///
+/// `target_modulus` need not be a power of 2. The rounding of each component
+/// adds at most `1/2` to the error, so under a binary secret key the switch
+/// adds at most `(n + 1) / 2` additional error (in units of the target
+/// modulus), where `n` is the LWE dimension.
+///
+/// # Panics
+/// If `target_modulus` is less than 2.
+/// If `ct` isn't valid for `lwe`.
+pub fn modulus_switch_lwe(
+    ct: &LweCiphertextRef<u64>,
+    target_modulus: u64,
+    lwe: &LweDef,
+) -> LweCiphertext<u64> {
+    assert!(
+        target_modulus >= 2,
+        "Target modulus {target_modulus} must be at least 2"
+    );
+    lwe.assert_valid();
+    ct.assert_valid(lwe);
+
+    let mut result = LweCiphertext::new(lwe);
+
+    let (a, b) = ct.a_b(lwe);
+    let (r_a, r_b) = result.a_b_mut(lwe);
+
+    for (r_a, a) in r_a.iter_mut().zip(a.iter()) {
+        *r_a = Torus::from(switch_component(a.inner(), target_modulus));
+    }
+
+    *r_b = Torus::from(switch_component(b.inner(), target_modulus));
+
+    result
+}
+
+#[inline(always)]
+fn switch_component(x: u64, target_modulus: u64) -> u64 {
+    let scaled = x as u128 * target_modulus as u128 + (1u128 << 63);
+
+    ((scaled >> 64) as u64) % target_modulus
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{high_level::*, PlaintextBits};
+
+    use super::*;
+
+    #[test]
+    fn can_switch_component() {
+        assert_eq!(switch_component(0, 4096), 0);
+        assert_eq!(switch_component(1 << 63, 4096), 2048);
+        assert_eq!(switch_component(u64::MAX, 4096), 0);
+        assert_eq!(switch_component(1 << 52, 4096), 1);
+        assert_eq!(switch_component((1 << 51) - 1, 4096), 0);
+        assert_eq!(switch_component(1 << 51, 4096), 1);
+    }
+
+    #[test]
+    fn can_modulus_switch_lwe() {
+        let lwe = TEST_LWE_DEF_1;
+        let bits = PlaintextBits(4);
+        let n = lwe.dim.0 as i128;
+
+        let sk = keygen::generate_binary_lwe_sk(&lwe);
+
+        for q in [4096u64, 3000] {
+            for msg in 0..(1 << bits.0) {
+                let ct = encryption::encrypt_lwe_secret(msg, &sk, &lwe, bits);
+
+                let switched = modulus_switch_lwe(&ct, q, &lwe);
+
+                let (a, b) = switched.a_b(&lwe);
+
+                assert!(a.iter().all(|x| x.inner() < q));
+                assert!(b.inner() < q);
+
+                let dot = a
+                    .iter()
+                    .zip(sk.s())
+                    .map(|(a, s)| a.inner() as i128 * *s as i128)
+                    .sum::<i128>();
+
+                let phase = (b.inner() as i128 - dot).rem_euclid(q as i128);
+
+                // Scale the phase up to avoid fractional deltas for
+                // non-power-of-2 moduli.
+                let expected = msg as i128 * q as i128;
+                let mut err = (phase << bits.0) - expected;
+                let wrap = (q as i128) << bits.0;
+                err = (err + wrap / 2).rem_euclid(wrap) - wrap / 2;
+
+                // Each rounded component contributes at most 1/2, plus
+                // a little slack for the encryption noise.
+                let bound = ((n + 1) / 2 + 1) << bits.0;
+                assert!(err.abs() <= bound, "q={q} msg={msg} err={err}");
+
+                let decoded = ((phase << bits.0) + q as i128 / 2) / q as i128;
+                assert_eq!(decoded as u64 % (1 << bits.0), msg);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{
+    entities::{GlweCiphertextRef, LweCiphertext},
+    GlweDef, TorusOps,
+};
+
+/// Extract the coefficient at `index` in the message encrypted by `glwe` as a
+/// new [`LweCiphertext`].
+///
+/// # Remarks
+/// The result has dimension `k * N` (see
+/// [`GlweDef::as_lwe_def`]) and is encrypted under `glwe`'s
+/// [`GlweSecretKey`](crate::entities::GlweSecretKey) reinterpreted as an
+/// [`LweSecretKey`](crate::entities::LweSecretKey) via
+/// [`GlweSecretKeyRef::to_lwe_secret_key`](crate::entities::GlweSecretKeyRef::to_lwe_secret_key).
+///
+/// Sample extraction adds no noise, so one can use it to turn the GLWE output
+/// of a blind rotation back into an LWE ciphertext when composing
+/// bootstrapping steps manually. To extract into an existing ciphertext,
+/// use [`crate::ops::ciphertext::sample_extract`].
+///
+/// # Panics
+/// If `index` isn't less than the polynomial degree `N`.
+/// If `glwe` isn't valid for `glwe_def`.
+pub fn sample_extract<S>(
+    glwe: &GlweCiphertextRef<S>,
+    index: usize,
+    glwe_def: &GlweDef,
+) -> LweCiphertext<S>
+where
+    S: TorusOps,
+{
+    let mut result = LweCiphertext::new(&glwe_def.as_lwe_def());
+
+    crate::ops::ciphertext::sample_extract(&mut result, glwe, index, glwe_def);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{entities::Polynomial, high_level::*, PlaintextBits};
+
+    use super::*;
+
+    #[test]
+    fn can_sample_extract() {
+        let bits = PlaintextBits(3);
+        let glwe = TEST_GLWE_DEF_1;
+        let n = glwe.dim.polynomial_degree.0;
+
+        let sk = keygen::generate_binary_glwe_sk(&glwe);
+        let lwe_sk = sk.to_lwe_secret_key();
+
+        let msg = Polynomial::new(&(0..n as u64).map(|x| x % 8).collect::<Vec<_>>());
+        let ct = sk.encode_encrypt_glwe(&msg, &glwe, bits);
+
+        for index in [0, 1, n / 2, n - 1] {
+            let lwe = sample_extract(&ct, index, &glwe);
+
+            let actual = lwe_sk.decrypt(&lwe, &glwe.as_lwe_def(), bits);
+
+            assert_eq!(actual, msg.coeffs()[index]);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn sample_extract_checks_index() {
+        let glwe = TEST_GLWE_DEF_1;
+
+        let sk = keygen::generate_binary_glwe_sk(&glwe);
+        let msg = Polynomial::zero(glwe.dim.polynomial_degree.0);
+        let ct = sk.encode_encrypt_glwe(&msg, &glwe, PlaintextBits(1));
+
+        sample_extract(&ct, glwe.dim.polynomial_degree.0, &glwe);
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::rand::Stddev;
-use crate::TorusOps;
+use crate::{noise, Error, Result, TorusOps};
 
 use sunscreen_math::security::lwe_std_to_security_level;
 
@@ -28,7 +28,7 @@ pub trait SecurityLevel {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The number of torus elements in the LWE lattice.
 pub struct LweDimension(pub usize);
@@ -40,7 +40,7 @@ impl LweDimension {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The degree of the modulus polynomial `(x^N+1)` in a GLWE instance.
 ///
@@ -50,7 +50,7 @@ impl LweDimension {
 /// are integers mod `q`.
 pub struct PolynomialDegree(pub usize);
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The number of polynomials in a GLWE instance.
 pub struct GlweSize(pub usize);
@@ -71,12 +71,12 @@ pub struct PlaintextBits(pub u32);
 /// The number of padding bits to include in an LWE ciphertext.
 pub struct CarryBits(pub u32);
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The number of digits to decompose a value into.
 pub struct RadixCount(pub usize);
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The number of bits in a digit output during base decomposition.
 pub struct RadixLog(pub usize);
@@ -96,7 +96,7 @@ impl PrivateFunctionalKeyswitchLweCount {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 /// The parameters defining how to do approximately perform base decomposition. I.e.
 /// decompose values into digits.
 ///
@@ -127,7 +127,7 @@ impl RadixDecomposition {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 /// A [`PolynomialDegree`] and [`GlweSize`] in a GLWE instance.
 pub struct GlweDimension {
     /// The degree of the polynomial in a GLWE instance.
@@ -153,7 +153,7 @@ impl GlweDimension {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 /// Parameters that define an LWE problem instance.
 ///
 /// # Security
@@ -181,7 +181,7 @@ impl SecurityLevel for LweDef {
     }
 }
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 /// Parameters that define a GLWE problem instance.
 ///
 /// # Security
@@ -250,6 +250,18 @@ pub const GLWE_1_2048_128: GlweDef = GlweDef {
     std: Stddev(0.00000000000000034667670193445625),
 };
 
+/// 128-bit secure parameters for an LWE instance with a dimension of 630.
+pub const LWE_630_128: LweDef = LweDef {
+    dim: LweDimension(630),
+    std: Stddev(0.000056687861446933204),
+};
+
+/// 128-bit secure parameters for an LWE instance with a dimension of 800.
+pub const LWE_800_128: LweDef = LweDef {
+    dim: LweDimension(800),
+    std: Stddev(0.0000025793364771358303),
+};
+
 /// 80-bit secure parameters for an LWE instance with a dimension of 512.
 pub const LWE_512_80: LweDef = LweDef {
     dim: LweDimension(512),
@@ -274,6 +286,182 @@ pub const GLWE_1_1024_80: GlweDef = GlweDef {
     std: Stddev(0.0000000000010900242107812643),
 };
 
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+/// Parameters for programmable bootstrapping from `lwe` to `glwe` followed by
+/// keyswitching back to `lwe`. See [`select_pbs_params`].
+pub struct PbsParams {
+    /// The parameters under which inputs to programmable bootstrapping are
+    /// encrypted.
+    pub lwe: LweDef,
+
+    /// The parameters of the GLWE key under which programmable bootstrapping
+    /// produces its output.
+    pub glwe: GlweDef,
+
+    /// The radix decomposition of the bootstrapping key.
+    pub pbs_radix: RadixDecomposition,
+
+    /// The radix decomposition of the keyswitch key from `glwe` (as an LWE
+    /// key) back to `lwe`.
+    pub ks_radix: RadixDecomposition,
+}
+
+impl PbsParams {
+    /// The estimated variance of the noise in a ciphertext entering
+    /// programmable bootstrapping after switching to modulus `2N`, where `N`
+    /// is the GLWE polynomial degree.
+    ///
+    /// # Remarks
+    /// Covers both fresh encryptions under `lwe` and the outputs of a
+    /// previous bootstrap and keyswitch, whichever is noisier. See
+    /// [`noise`](crate::noise) for the underlying estimates.
+    pub fn bootstrap_input_variance(&self) -> f64 {
+        let glwe_as_lwe = self.glwe.as_lwe_def();
+        let degree = self.glwe.dim.polynomial_degree.0 as u64;
+
+        let bootstrapped =
+            noise::programmable_bootstrap_variance(&self.lwe, &self.glwe, &self.pbs_radix)
+                + noise::keyswitch_variance(&glwe_as_lwe, &self.lwe, &self.ks_radix);
+
+        bootstrapped.max(noise::fresh_variance(self.lwe.std))
+            + noise::modulus_switch_variance(&self.lwe, 2 * degree)
+    }
+
+    /// The largest number of plaintext bits, including padding, for which
+    /// programmable bootstrapping selects the wrong lookup table entry with
+    /// probability below `2^-30`.
+    ///
+    /// # Remarks
+    /// Requires a [`decoding_margin`](crate::noise::decoding_margin) of at
+    /// least [`MIN_PBS_DECODING_MARGIN`] standard deviations for
+    /// [`bootstrap_input_variance`](Self::bootstrap_input_variance).
+    pub fn max_plaintext_bits(&self) -> PlaintextBits {
+        let variance = self.bootstrap_input_variance();
+
+        let mut bits = PlaintextBits(0);
+
+        while noise::decoding_margin(variance, PlaintextBits(bits.0 + 1)) >= MIN_PBS_DECODING_MARGIN
+        {
+            bits.0 += 1;
+        }
+
+        bits
+    }
+}
+
+/// The number of standard deviations of noise [`PbsParams::max_plaintext_bits`]
+/// leaves before a bootstrap selects the wrong lookup table entry. Gaussian
+/// noise exceeds 6.2 standard deviations with probability about `2^-30.7`.
+pub const MIN_PBS_DECODING_MARGIN: f64 = 6.2;
+
+/// A [`PbsParams`] along with the security level it was validated for.
+struct PbsParamsEntry {
+    security_level: usize,
+
+    params: PbsParams,
+}
+
+/// The known-good [`PbsParams`], ordered from least to most expensive.
+///
+/// Each entry supports messages up to its
+/// [`max_plaintext_bits`](PbsParams::max_plaintext_bits): 5, 6, 3 and 5
+/// bits respectively.
+const PBS_PARAMS: [PbsParamsEntry; 4] = [
+    PbsParamsEntry {
+        security_level: 80,
+        params: PbsParams {
+            lwe: LWE_512_80,
+            glwe: GLWE_1_1024_80,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(16),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(5),
+                radix_log: RadixLog(4),
+            },
+        },
+    },
+    PbsParamsEntry {
+        // A 2048 degree polynomial can't have less than 128-bit security.
+        security_level: 80,
+        params: PbsParams {
+            lwe: LWE_512_80,
+            glwe: GLWE_1_2048_128,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(1),
+                radix_log: RadixLog(20),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(5),
+                radix_log: RadixLog(3),
+            },
+        },
+    },
+    PbsParamsEntry {
+        security_level: 128,
+        params: PbsParams {
+            lwe: LWE_630_128,
+            glwe: GLWE_1_1024_128,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(2),
+                radix_log: RadixLog(8),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(6),
+                radix_log: RadixLog(2),
+            },
+        },
+    },
+    PbsParamsEntry {
+        security_level: 128,
+        params: PbsParams {
+            lwe: LWE_800_128,
+            glwe: GLWE_1_2048_128,
+            pbs_radix: RadixDecomposition {
+                count: RadixCount(1),
+                radix_log: RadixLog(23),
+            },
+            ks_radix: RadixDecomposition {
+                count: RadixCount(5),
+                radix_log: RadixLog(3),
+            },
+        },
+    },
+];
+
+/// Selects parameters for programmable bootstrapping functions of
+/// `message_bits` bit messages with `carry_bits` carry bits, at
+/// `security_level` bits of security or more.
+///
+/// # Remarks
+/// The parameters come from a table of known-good parameter sets. This
+/// returns the cheapest one that meets the requested security level and
+/// whose [`max_plaintext_bits`](PbsParams::max_plaintext_bits) leaves room
+/// for the message, carry, and one padding bit. Each bootstrap then fails
+/// with probability below `2^-30` under the noise estimates in
+/// [`noise`](crate::noise).
+///
+/// Inputs to bootstrapping must be encrypted with
+/// `message_bits + carry_bits + 1` plaintext bits to include the padding bit.
+///
+/// # Errors
+/// [`Error::NoSuitableParameters`] if no parameter set supports the message
+/// size at the requested security level.
+pub fn select_pbs_params(
+    message_bits: PlaintextBits,
+    carry_bits: CarryBits,
+    security_level: usize,
+) -> Result<PbsParams> {
+    let bits = message_bits.0 + carry_bits.0 + 1;
+
+    PBS_PARAMS
+        .iter()
+        .find(|x| x.security_level >= security_level && x.params.max_plaintext_bits().0 >= bits)
+        .map(|x| x.params)
+        .ok_or(Error::NoSuitableParameters)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -310,5 +498,121 @@ mod tests {
         let actual_glwe_std = lwe_security_level_to_std(1024, 80.0).unwrap();
         println!("GLWE 1 1024 80: {}", actual_glwe_std);
         GLWE_1_1024_80.assert_security_level(80);
+
+        let actual_lwe_std = lwe_security_level_to_std(630, 128.0).unwrap();
+        println!("LWE 630 128: {}", actual_lwe_std);
+        LWE_630_128.assert_security_level(128);
+
+        let actual_lwe_std = lwe_security_level_to_std(800, 128.0).unwrap();
+        println!("LWE 800 128: {}", actual_lwe_std);
+        LWE_800_128.assert_security_level(128);
+    }
+
+    #[test]
+    fn can_select_pbs_params() {
+        let select = |message_bits, carry_bits, security_level| {
+            select_pbs_params(
+                PlaintextBits(message_bits),
+                CarryBits(carry_bits),
+                security_level,
+            )
+        };
+
+        assert_eq!(select(2, 2, 80).unwrap(), PBS_PARAMS[0].params);
+        assert_eq!(select(4, 1, 80).unwrap(), PBS_PARAMS[1].params);
+        assert_eq!(select(1, 0, 100).unwrap(), PBS_PARAMS[2].params);
+        assert_eq!(select(2, 0, 128).unwrap(), PBS_PARAMS[2].params);
+        assert_eq!(select(4, 0, 128).unwrap(), PBS_PARAMS[3].params);
+
+        assert_eq!(select(5, 1, 80), Err(Error::NoSuitableParameters));
+        assert_eq!(select(5, 0, 128), Err(Error::NoSuitableParameters));
+        assert_eq!(select(1, 1, 192), Err(Error::NoSuitableParameters));
+    }
+
+    #[test]
+    fn pbs_params_meet_security_and_noise_bounds() {
+        let max_bits = PBS_PARAMS
+            .iter()
+            .map(|x| x.params.max_plaintext_bits().0)
+            .collect::<Vec<_>>();
+
+        assert_eq!(max_bits, [5, 6, 3, 5]);
+
+        for entry in &PBS_PARAMS {
+            let PbsParams { lwe, glwe, .. } = entry.params;
+            let bits = entry.params.max_plaintext_bits();
+
+            assert!(lwe.security_level() >= entry.security_level as f64 - 0.25);
+            assert!(glwe.security_level() >= entry.security_level as f64 - 0.25);
+
+            let variance = entry.params.bootstrap_input_variance();
+
+            assert!(noise::decoding_margin(variance, bits) >= MIN_PBS_DECODING_MARGIN);
+            assert!(
+                noise::decoding_margin(variance, PlaintextBits(bits.0 + 1))
+                    < MIN_PBS_DECODING_MARGIN
+            );
+        }
+    }
+
+    #[test]
+    fn selected_pbs_params_bootstrap_correctly() {
+        use crate::{
+            entities::UnivariateLookupTable,
+            high_level::{encryption, evaluation, fft, keygen},
+        };
+
+        for entry in &PBS_PARAMS {
+            // Exercise the widest message each entry supports, leaving the
+            // top bit for padding.
+            let bits = PlaintextBits(entry.params.max_plaintext_bits().0 - 1);
+            let modulus = 1 << bits.0;
+
+            let PbsParams {
+                lwe,
+                glwe,
+                pbs_radix,
+                ks_radix,
+            } = entry.params;
+
+            let lwe_sk = keygen::generate_binary_lwe_sk(&lwe);
+            let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+            let bsk =
+                keygen::generate_bootstrapping_key(&lwe_sk, &glwe_sk, &lwe, &glwe, &pbs_radix);
+            let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &pbs_radix);
+            let ksk = keygen::generate_ksk(
+                glwe_sk.to_lwe_secret_key(),
+                &lwe_sk,
+                &glwe.as_lwe_def(),
+                &lwe,
+                &ks_radix,
+            );
+
+            let lut = UnivariateLookupTable::trivial_from_fn(|x| (x + 1) % modulus, &glwe, bits);
+
+            // Bootstrapping is slow without optimizations, so check the
+            // edges and middle of the lookup table rather than every entry.
+            for msg in [0, 1, modulus / 2, modulus - 1] {
+                let ct =
+                    encryption::encrypt_lwe_secret(msg, &lwe_sk, &lwe, PlaintextBits(bits.0 + 1));
+
+                let ct = evaluation::univariate_programmable_bootstrap(
+                    &ct, &lut, &bsk, &lwe, &glwe, &pbs_radix,
+                );
+                let ct = evaluation::keyswitch_lwe_to_lwe(
+                    &ct,
+                    &ksk,
+                    &glwe.as_lwe_def(),
+                    &lwe,
+                    &ks_radix,
+                );
+
+                assert_eq!(
+                    encryption::decrypt_lwe(&ct, &lwe_sk, &lwe, bits),
+                    (msg + 1) % modulus
+                );
+            }
+        }
     }
 }
@@ -1,23 +1,75 @@
+#[cfg(any(test, feature = "seeded-rng"))]
+use std::cell::RefCell;
 use std::fmt::Debug;
 
+#[cfg(any(test, feature = "seeded-rng"))]
+use rand::{rngs::StdRng, SeedableRng};
 use rand::{thread_rng, Rng, RngCore};
 use rand_distr::Normal;
 use serde::{Deserialize, Serialize};
 
 use crate::math::{Torus, TorusOps};
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(transparent)]
 /// The standard deviation of a Gaussian distribution normalized over the torus
 /// `T_q`.
 pub struct Stddev(pub f64);
 
+/// Run `f` with [`thread_rng`].
+#[cfg(not(any(test, feature = "seeded-rng")))]
+fn with_rng<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+    f(&mut thread_rng())
+}
+
+#[cfg(any(test, feature = "seeded-rng"))]
+thread_local! {
+    static SEEDED_RNG: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with the seeded RNG installed by [`with_seeded_rng`] if there is
+/// one and [`thread_rng`] otherwise.
+#[cfg(any(test, feature = "seeded-rng"))]
+fn with_rng<R>(f: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+    SEEDED_RNG.with(|rng| match rng.borrow_mut().as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut thread_rng()),
+    })
+}
+
+/// Run `f` with every sample drawn on the current thread coming from a
+/// [`StdRng`] seeded with `seed`, then restore the previous RNG.
+///
+/// # Remarks
+/// Only available in tests and with the `seeded-rng` feature, so builds
+/// without it sample straight from [`thread_rng`].
+///
+/// # Security
+/// Randomness derived from a known seed is predictable. This exists so
+/// tests can reproduce ciphertexts and must not be used in production.
+#[cfg(any(test, feature = "seeded-rng"))]
+pub(crate) fn with_seeded_rng<R>(seed: [u8; 32], f: impl FnOnce() -> R) -> R {
+    // Restores the previous RNG even if `f` panics.
+    struct Restore(Option<StdRng>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let prev = self.0.take();
+            SEEDED_RNG.with(|rng| *rng.borrow_mut() = prev);
+        }
+    }
+
+    let _restore = Restore(SEEDED_RNG.with(|rng| rng.replace(Some(StdRng::from_seed(seed)))));
+
+    f()
+}
+
 /// Sample a random torus element from the a normal distribution
 /// with a mean of 0 and the given stddev
 pub fn normal_torus<S: TorusOps>(std: Stddev) -> Torus<S> {
     let dist = Normal::new(0., std.0).unwrap();
 
-    let e_0 = thread_rng().sample(dist);
+    let e_0 = with_rng(|rng| rng.sample(dist));
     let q = (S::BITS as f64).exp2();
 
     let e = f64::round(e_0 * q) as i64;
@@ -28,12 +80,12 @@ pub fn normal_torus<S: TorusOps>(std: Stddev) -> Torus<S> {
 
 /// Generate a random torus element uniformly
 pub fn uniform_torus<S: TorusOps>() -> Torus<S> {
-    Torus::from(S::from_u64(thread_rng().next_u64()))
+    Torus::from(S::from_u64(with_rng(|rng| rng.next_u64())))
 }
 
 /// Generate a random binary torus element
 pub fn binary<S: TorusOps>() -> S {
-    S::from_u64(thread_rng().next_u64() % 2)
+    S::from_u64(with_rng(|rng| rng.next_u64()) % 2)
 }
 
 #[cfg(test)]
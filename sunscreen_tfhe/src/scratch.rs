@@ -3,7 +3,6 @@ use num::{Complex, Float};
 use rustfft::FftNum;
 use std::{
     cell::RefCell,
-    collections::LinkedList,
     marker::PhantomData,
     mem::{align_of, size_of},
     rc::Rc,
@@ -66,7 +65,22 @@ unsafe impl<S> Pod for Torus<S> where S: TorusOps {}
 ///
 /// # Remarks
 /// The returned [`ScratchBuffer`] will be aligned to `align_of::<T>()` and
-/// have a length equal to count.
+/// have a length equal to count. Its contents are unspecified; callers must
+/// initialize it before reading.
+///
+/// Each thread owns an independent pool, so buffers never cross threads and
+/// no synchronization occurs. Allocation is reentrant: an operation holding
+/// scratch buffers may call other operations that allocate scratch, and
+/// buffers return to the pool when dropped in any order. Because the pool
+/// retains freed buffers, an operation's temporaries only come from the
+/// global allocator the first time it runs on a thread with given
+/// parameters or when it needs a larger buffer than previously seen.
+///
+/// This doesn't cover an operation's results. Functions that return owned
+/// values allocate them on every call; use the `_into` variants (e.g.
+/// [`univariate_programmable_bootstrap_into`](crate::high_level::evaluation::univariate_programmable_bootstrap_into)
+/// or `SignedRadixCiphertext::add_into`) to write into a caller-provided
+/// buffer instead.
 ///
 /// # Panics
 /// If `T` is a zero-sized type (e.g. `()`).
@@ -87,8 +101,11 @@ where
 /// An "allocator" designed for allocating scratch memory.
 ///
 /// # Remarks
-/// Internally, this data structure is a [`LinkedList`] of
-/// [`Vec`]s treated as a stack.
+/// Internally, this data structure is a [`Vec`] of free buffers treated
+/// as a stack. Pushing and popping from the stack doesn't allocate once
+/// its capacity has grown to the peak number of outstanding buffers, so
+/// after a warmup call, repeating the same operation doesn't touch the
+/// global allocator.
 ///
 /// [`Scratch`] is designed to provide a cache locality for
 /// temporary buffers by reusing allocations.
@@ -108,7 +125,7 @@ where
 /// Please note the lack of [`Sync`] and [`Send`] on this object. It is not sound to
 /// share these between threads.
 struct Scratch {
-    stack: Rc<RefCell<LinkedList<*mut Allocation>>>,
+    stack: Rc<RefCell<Vec<*mut Allocation>>>,
 }
 
 impl Scratch {
@@ -117,7 +134,7 @@ impl Scratch {
     /// using a thread_local allocator.
     fn new() -> Self {
         Self {
-            stack: Rc::new(RefCell::new(LinkedList::new())),
+            stack: Rc::new(RefCell::new(Vec::new())),
         }
     }
 
@@ -132,7 +149,7 @@ impl Scratch {
         let u8_len = count * size_of::<T>();
 
         let allocation = unsafe {
-            let allocation = self.stack.borrow_mut().pop_back();
+            let allocation = self.stack.borrow_mut().pop();
 
             if allocation.is_none() {
                 // If we don't have an existing allocation, make one
@@ -186,7 +203,7 @@ struct Allocation {
 /// [`Self::as_mut_slice`], as these obey standard lifetime rules.
 pub struct ScratchBuffer<'a, T> {
     allocation: *mut Allocation,
-    pool: Rc<RefCell<LinkedList<*mut Allocation>>>,
+    pool: Rc<RefCell<Vec<*mut Allocation>>>,
     requested_len: usize,
     _phantom: PhantomData<&'a T>,
 }
@@ -213,7 +230,7 @@ impl<T> ScratchBuffer<'_, T> {
 
 impl<T> Drop for ScratchBuffer<'_, T> {
     fn drop(&mut self) {
-        self.pool.borrow_mut().push_back(self.allocation);
+        self.pool.borrow_mut().push(self.allocation);
     }
 }
 
@@ -0,0 +1,171 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+/// A category of TFHE operation whose running time is tracked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TimingCategory {
+    /// Switching an LWE ciphertext's modulus to `2N` before blind rotation.
+    ModulusSwitch,
+
+    /// The cmux loop of a blind rotation or programmable bootstrap.
+    BlindRotation,
+
+    /// An external product between a GGSW and a GLWE ciphertext.
+    ExternalProduct,
+
+    /// A forward FFT of a polynomial.
+    Fft,
+
+    /// An inverse FFT of a polynomial.
+    Ifft,
+
+    /// Extracting an LWE ciphertext from a GLWE ciphertext.
+    SampleExtract,
+
+    /// An LWE to LWE keyswitch.
+    Keyswitch,
+}
+
+impl TimingCategory {
+    /// Every category, in the order they appear in a [`TimingReport`].
+    pub const ALL: [TimingCategory; 7] = [
+        TimingCategory::ModulusSwitch,
+        TimingCategory::BlindRotation,
+        TimingCategory::ExternalProduct,
+        TimingCategory::Fft,
+        TimingCategory::Ifft,
+        TimingCategory::SampleExtract,
+        TimingCategory::Keyswitch,
+    ];
+}
+
+struct Counter {
+    nanos: AtomicU64,
+    count: AtomicU64,
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const EMPTY_COUNTER: Counter = Counter {
+    nanos: AtomicU64::new(0),
+    count: AtomicU64::new(0),
+};
+
+static COUNTERS: [Counter; TimingCategory::ALL.len()] = [EMPTY_COUNTER; TimingCategory::ALL.len()];
+
+/// The cumulative time spent in a single [`TimingCategory`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperationTiming {
+    /// The total time spent across all invocations.
+    pub total: Duration,
+
+    /// The number of invocations.
+    pub count: u64,
+}
+
+/// A snapshot of the cumulative time spent in each [`TimingCategory`].
+/// See [`timing_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimingReport {
+    entries: Vec<(TimingCategory, OperationTiming)>,
+}
+
+impl TimingReport {
+    /// The timing for the given category. Categories that haven't run
+    /// return a zero timing.
+    pub fn get(&self, category: TimingCategory) -> OperationTiming {
+        self.entries
+            .iter()
+            .find(|(c, _)| *c == category)
+            .map(|(_, t)| *t)
+            .unwrap_or_default()
+    }
+
+    /// Iterate over the categories that ran at least once.
+    pub fn iter(&self) -> impl Iterator<Item = &(TimingCategory, OperationTiming)> {
+        self.entries.iter()
+    }
+}
+
+impl Display for TimingReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (category, timing) in &self.entries {
+            writeln!(
+                f,
+                "{:<16} {:>10} calls {:>14.3?}",
+                format!("{category:?}"),
+                timing.count,
+                timing.total
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the cumulative time spent in each [`TimingCategory`] across all
+/// threads since the program started or [`reset_timing`] was last called.
+///
+/// # Remarks
+/// Only categories that ran at least once appear in the report.
+///
+/// Categories nest; for example, [`TimingCategory::BlindRotation`] includes
+/// the time spent in its external products, which in turn include their
+/// FFTs. As such, the totals shouldn't be summed.
+pub fn timing_report() -> TimingReport {
+    let entries = TimingCategory::ALL
+        .iter()
+        .zip(COUNTERS.iter())
+        .filter_map(|(category, counter)| {
+            let count = counter.count.load(Ordering::Relaxed);
+
+            if count == 0 {
+                return None;
+            }
+
+            let timing = OperationTiming {
+                total: Duration::from_nanos(counter.nanos.load(Ordering::Relaxed)),
+                count,
+            };
+
+            Some((*category, timing))
+        })
+        .collect();
+
+    TimingReport { entries }
+}
+
+/// Clears all accumulated timings.
+pub fn reset_timing() {
+    for counter in COUNTERS.iter() {
+        counter.nanos.store(0, Ordering::Relaxed);
+        counter.count.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Records the time between its creation and drop under a category.
+pub(crate) struct TimingScope {
+    category: TimingCategory,
+    start: Instant,
+}
+
+impl TimingScope {
+    pub(crate) fn new(category: TimingCategory) -> Self {
+        Self {
+            category,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for TimingScope {
+    fn drop(&mut self) {
+        let counter = &COUNTERS[self.category as usize];
+        let nanos = self.start.elapsed().as_nanos() as u64;
+
+        counter.nanos.fetch_add(nanos, Ordering::Relaxed);
+        counter.count.fetch_add(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use sunscreen_tfhe::{
+    entities::{LweCiphertext, UnivariateLookupTable},
+    high_level::fft,
+    high_level::{
+        evaluation,
+        integer::{encrypt_signed_radix, generate_server_key, ServerKeyParams},
+        keygen, TEST_GLWE_DEF_1, TEST_LWE_DEF_1, TEST_RADIX,
+    },
+    ops::{
+        bootstrapping::programmable_bootstrap_univariate, ciphertext::add_lwe_inplace,
+        encryption::encrypt_lwe_ciphertext, keyswitch::lwe_keyswitch::keyswitch_lwe_to_lwe,
+    },
+    PlaintextBits, RadixCount, RadixDecomposition, RadixLog, Torus, GLWE_1_1024_80,
+};
+
+/// Counts allocations made by the current thread so the test harness's
+/// own threads don't perturb the result.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(|x| x.get())
+}
+
+#[test]
+fn add_and_bootstrap_dont_allocate_after_warmup() {
+    let bits = PlaintextBits(2);
+    let lwe = TEST_LWE_DEF_1;
+    let glwe = GLWE_1_1024_80;
+    let radix = TEST_RADIX;
+
+    let sk = keygen::generate_binary_lwe_sk(&lwe);
+    let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+    let bsk = keygen::generate_bootstrapping_key(&sk, &glwe_sk, &lwe, &glwe, &radix);
+    let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+    let ksk = keygen::generate_ksk(
+        glwe_sk.to_lwe_secret_key(),
+        &sk,
+        &glwe.as_lwe_def(),
+        &lwe,
+        &radix,
+    );
+
+    let lut = UnivariateLookupTable::trivial_from_fn(|x| (x + 1) % 4, &glwe, bits);
+
+    let mut a = LweCiphertext::new(&lwe);
+    let mut b = LweCiphertext::new(&lwe);
+    let encode = |x: u64| Torus::from(x << (64 - bits.0 - 1));
+    encrypt_lwe_ciphertext(&mut a, &sk, encode(1), &lwe);
+    encrypt_lwe_ciphertext(&mut b, &sk, encode(1), &lwe);
+
+    let mut bootstrapped = LweCiphertext::new(&glwe.as_lwe_def());
+    let mut switched = LweCiphertext::new(&lwe);
+
+    let mut run = || {
+        add_lwe_inplace(&mut a, &b, &lwe);
+        programmable_bootstrap_univariate(&mut bootstrapped, &a, &lut, &bsk, &lwe, &glwe, &radix);
+        keyswitch_lwe_to_lwe(
+            &mut switched,
+            &bootstrapped,
+            &ksk,
+            &glwe.as_lwe_def(),
+            &lwe,
+            &radix,
+        );
+    };
+
+    // Warm up the thread's scratch pool and FFT plans.
+    run();
+
+    let before = allocations();
+    run();
+    run();
+
+    assert_eq!(allocations(), before);
+}
+
+#[test]
+fn high_level_bootstrap_into_doesnt_allocate_after_warmup() {
+    let bits = PlaintextBits(2);
+    let lwe = TEST_LWE_DEF_1;
+    let glwe = GLWE_1_1024_80;
+    let radix = TEST_RADIX;
+
+    let sk = keygen::generate_binary_lwe_sk(&lwe);
+    let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+
+    let bsk = keygen::generate_bootstrapping_key(&sk, &glwe_sk, &lwe, &glwe, &radix);
+    let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+    let ksk = keygen::generate_ksk(
+        glwe_sk.to_lwe_secret_key(),
+        &sk,
+        &glwe.as_lwe_def(),
+        &lwe,
+        &radix,
+    );
+
+    let lut = UnivariateLookupTable::trivial_from_fn(|x| (x + 1) % 4, &glwe, bits);
+
+    let mut a = LweCiphertext::new(&lwe);
+    let encode = |x: u64| Torus::from(x << (64 - bits.0 - 1));
+    encrypt_lwe_ciphertext(&mut a, &sk, encode(1), &lwe);
+
+    let mut bootstrapped = LweCiphertext::new(&glwe.as_lwe_def());
+    let mut switched = LweCiphertext::new(&lwe);
+
+    let mut run = || {
+        evaluation::univariate_programmable_bootstrap_into(
+            &mut bootstrapped,
+            &a,
+            &lut,
+            &bsk,
+            &lwe,
+            &glwe,
+            &radix,
+        );
+        evaluation::keyswitch_lwe_to_lwe_into(
+            &mut switched,
+            &bootstrapped,
+            &ksk,
+            &glwe.as_lwe_def(),
+            &lwe,
+            &radix,
+        );
+    };
+
+    // Warm up the thread's scratch pool and FFT plans.
+    run();
+
+    let before = allocations();
+    run();
+    run();
+
+    assert_eq!(allocations(), before);
+}
+
+#[test]
+fn high_level_integer_add_into_doesnt_allocate_after_warmup() {
+    let params = ServerKeyParams {
+        lwe_0: TEST_LWE_DEF_1,
+        glwe_1: TEST_GLWE_DEF_1,
+        glwe_2: GLWE_1_1024_80,
+        pbs_radix: RadixDecomposition {
+            count: RadixCount(2),
+            radix_log: RadixLog(16),
+        },
+        cbs_radix: RadixDecomposition {
+            count: RadixCount(2),
+            radix_log: RadixLog(5),
+        },
+        pfks_radix: RadixDecomposition {
+            count: RadixCount(3),
+            radix_log: RadixLog(11),
+        },
+        ks_radix: RadixDecomposition {
+            count: RadixCount(4),
+            radix_log: RadixLog(4),
+        },
+    };
+
+    let sk_0 = keygen::generate_binary_lwe_sk(&params.lwe_0);
+    let sk_1 = keygen::generate_binary_glwe_sk(&params.glwe_1);
+    let sk_2 = keygen::generate_binary_glwe_sk(&params.glwe_2);
+
+    let server_key = generate_server_key(&sk_0, &sk_1, &sk_2, &params);
+
+    let a = encrypt_signed_radix(3, &sk_0, &params.lwe_0, PlaintextBits(2), 2);
+    let b = encrypt_signed_radix(-2, &sk_0, &params.lwe_0, PlaintextBits(2), 2);
+
+    let mut sum = a.clone();
+
+    // Warm up the thread's scratch pool, FFT plans, and the server key's
+    // carry LUTs.
+    a.add_into(&b, &mut sum, &server_key);
+
+    let before = allocations();
+    a.add_into(&b, &mut sum, &server_key);
+    a.add_into(&b, &mut sum, &server_key);
+
+    assert_eq!(allocations(), before);
+}
@@ -0,0 +1,63 @@
+use sunscreen_tfhe::{
+    entities::UnivariateLookupTable,
+    high_level::{encryption, evaluation, fft, keygen, TEST_LWE_DEF_1, TEST_RADIX},
+    timing::{reset_timing, timing_report, TimingCategory},
+    PlaintextBits, GLWE_1_1024_80,
+};
+
+#[test]
+fn timing_report_covers_bootstrap_categories() {
+    let bits = PlaintextBits(2);
+    let lwe = TEST_LWE_DEF_1;
+    let glwe = GLWE_1_1024_80;
+    let radix = TEST_RADIX;
+
+    let sk = keygen::generate_binary_lwe_sk(&lwe);
+    let glwe_sk = keygen::generate_binary_glwe_sk(&glwe);
+    let bsk = keygen::generate_bootstrapping_key(&sk, &glwe_sk, &lwe, &glwe, &radix);
+    let bsk = fft::fft_bootstrap_key(&bsk, &lwe, &glwe, &radix);
+    let ksk = keygen::generate_ksk(
+        glwe_sk.to_lwe_secret_key(),
+        &sk,
+        &glwe.as_lwe_def(),
+        &lwe,
+        &radix,
+    );
+
+    let lut = UnivariateLookupTable::trivial_from_fn(|x| (x + 1) % 4, &glwe, bits);
+    let ct = encryption::encrypt_lwe_secret(2, &sk, &lwe, bits);
+
+    reset_timing();
+
+    let res = evaluation::univariate_programmable_bootstrap(&ct, &lut, &bsk, &lwe, &glwe, &radix);
+    let res = evaluation::keyswitch_lwe_to_lwe(&res, &ksk, &glwe.as_lwe_def(), &lwe, &radix);
+
+    let report = timing_report();
+
+    for category in [
+        TimingCategory::ModulusSwitch,
+        TimingCategory::BlindRotation,
+        TimingCategory::ExternalProduct,
+        TimingCategory::Fft,
+        TimingCategory::Ifft,
+        TimingCategory::SampleExtract,
+        TimingCategory::Keyswitch,
+    ] {
+        assert!(
+            report.get(category).count > 0,
+            "{category:?} missing from report:\n{report}"
+        );
+    }
+
+    // One blind rotation performs one external product per LWE key bit.
+    assert_eq!(report.get(TimingCategory::BlindRotation).count, 1);
+    assert_eq!(
+        report.get(TimingCategory::ExternalProduct).count,
+        lwe.dim.0 as u64
+    );
+
+    assert_eq!(encryption::decrypt_lwe(&res, &sk, &lwe, bits), 3);
+
+    reset_timing();
+    assert_eq!(timing_report().iter().count(), 0);
+}
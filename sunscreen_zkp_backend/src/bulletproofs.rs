@@ -305,6 +305,7 @@ impl BulletproofsCircuit {
 
                     self.nodes[idx.index()] = Some(x.into());
                 }
+                Operation::Gadget(..) => {}
             }
 
             Ok::<(), Error>(())
@@ -328,6 +329,44 @@ impl BulletproofsBackend {
         Self
     }
 
+    /**
+     * Verify each of the given proofs for the given executable Sunscreen
+     * program, returning whether each one is valid.
+     *
+     * # Remarks
+     * Unlike calling [`verify`](ZkpBackend::verify) for each proof, this
+     * creates the Bulletproofs generators for `graph` only once and shares
+     * them across all the proofs. The underlying Bulletproofs library
+     * doesn't expose a batched R1CS verifier, so each proof still performs
+     * its own multiscalar multiplication.
+     *
+     * Proofs that aren't [`Proof::Bulletproofs`] are invalid.
+     */
+    pub fn verify_batch(
+        &self,
+        graph: &ExecutableZkpProgram,
+        proofs: &[Proof],
+    ) -> Result<Vec<bool>> {
+        let constraint_count = constraint_count(graph)?;
+
+        let (pedersen_gens, bulletproof_gens) =
+            BulletproofsCircuit::make_gens(2 * constraint_count);
+
+        let parameters = BulletproofVerifierParameters::new(pedersen_gens, bulletproof_gens, 0);
+
+        let results = proofs
+            .iter()
+            .map(|proof| {
+                let mut transcript = BulletproofsCircuit::make_base_transcript();
+
+                self.verify_with_parameters(graph, proof, &parameters, &mut transcript)
+                    .is_ok()
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     /// Generate a prover from a given circuit. The purpose of this is to be
     /// able to extract information from the prover without actually running it.
     fn prover_with_circuit<'g>(
@@ -784,10 +823,12 @@ impl crate::ZkpFrom<&Scalar> for BigInt {
 #[cfg(test)]
 mod tests {
     use crypto_bigint::U512;
+    use petgraph::Direction;
     use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
 
     use super::*;
     use crate::exec::Operation as BackendOperation;
+    use crate::{jit_prover_with_public, specialize_constants};
 
     #[test]
     fn can_convert_small_u512_to_scalar() {
@@ -922,4 +963,225 @@ mod tests {
 
         assert!(backend.verify(&graph, &proof).is_err());
     }
+
+    #[test]
+    fn can_roundtrip_proof_wire_format() {
+        let mut graph = ExecutableZkpProgram::new();
+
+        let input = graph.add_node(NodeInfo {
+            operation: BackendOperation::Input(0),
+        });
+        let constraint = graph.add_node(NodeInfo {
+            operation: BackendOperation::Constraint(BigInt::from_u32(42)),
+        });
+        graph.add_edge(input, constraint, EdgeInfo::Unordered);
+
+        let backend = BulletproofsBackend::new();
+
+        let proof = backend.prove(&graph, &[BigInt::from_u32(42)]).unwrap();
+        let wire = proof.to_wire();
+
+        assert_eq!(&wire[8..20], b"bulletproofs");
+
+        let proof = Proof::from_wire(&wire).unwrap();
+
+        assert!(matches!(proof, Proof::Bulletproofs(_)));
+        backend.verify(&graph, &proof).unwrap();
+    }
+
+    #[test]
+    fn can_verify_batch() {
+        let mut graph = ExecutableZkpProgram::new();
+
+        let in_0 = graph.add_node(NodeInfo {
+            operation: BackendOperation::Input(0),
+        });
+        let in_1 = graph.add_node(NodeInfo {
+            operation: BackendOperation::Input(1),
+        });
+        let mul = graph.add_node(NodeInfo {
+            operation: BackendOperation::Mul,
+        });
+        let constraint = graph.add_node(NodeInfo {
+            operation: BackendOperation::Constraint(BigInt::from_u32(42)),
+        });
+        graph.add_edge(in_0, mul, EdgeInfo::Left);
+        graph.add_edge(in_1, mul, EdgeInfo::Right);
+        graph.add_edge(mul, constraint, EdgeInfo::Unordered);
+
+        let backend = BulletproofsBackend::new();
+
+        let prove = |a: u32, b: u32| {
+            backend
+                .prove(&graph, &[BigInt::from_u32(a), BigInt::from_u32(b)])
+                .unwrap()
+        };
+
+        let proofs = [
+            prove(6, 7),
+            prove(6, 8),
+            prove(21, 2),
+            Proof::Custom {
+                name: "groth16".to_owned(),
+                data: vec![],
+            },
+        ];
+
+        let results = backend.verify_batch(&graph, &proofs).unwrap();
+
+        assert_eq!(results, vec![true, false, true, false]);
+        assert!(backend.verify_batch(&graph, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn verify_with_inputs_binds_public_inputs() {
+        // Prove the private input equals the public input.
+        let mut prog = CompiledZkpProgram::new();
+
+        let private = prog.add_node(NodeInfo::new(crate::Operation::PrivateInput(0)));
+        let public = prog.add_node(NodeInfo::new(crate::Operation::PublicInput(0)));
+        let sub = prog.add_node(NodeInfo::new(crate::Operation::Sub));
+        let constraint = prog.add_node(NodeInfo::new(crate::Operation::Constraint(
+            BigInt::from_u32(0),
+        )));
+
+        prog.add_edge(private, sub, EdgeInfo::Left);
+        prog.add_edge(public, sub, EdgeInfo::Right);
+        prog.add_edge(sub, constraint, EdgeInfo::Unordered);
+
+        let backend = BulletproofsBackend::new();
+
+        let x = [BigInt::from_u32(42)];
+        let y = [BigInt::from_u32(43)];
+
+        let graph = backend.jit_prover(&prog, &x, &x, &[]).unwrap();
+        let proof = backend.prove(&graph, &[x[0], x[0]]).unwrap();
+
+        backend.verify_with_inputs(&prog, &proof, &[], &x).unwrap();
+        assert!(backend.verify_with_inputs(&prog, &proof, &[], &y).is_err());
+        assert!(backend.verify_with_inputs(&prog, &proof, &[], &[]).is_err());
+    }
+
+    #[test]
+    fn can_reuse_specialized_constants() {
+        // Prove private * constant == public.
+        let mut prog = CompiledZkpProgram::new();
+
+        let private = prog.add_node(NodeInfo::new(crate::Operation::PrivateInput(0)));
+        let public = prog.add_node(NodeInfo::new(crate::Operation::PublicInput(0)));
+        let constant = prog.add_node(NodeInfo::new(crate::Operation::ConstantInput(0)));
+        let mul = prog.add_node(NodeInfo::new(crate::Operation::Mul));
+        let sub = prog.add_node(NodeInfo::new(crate::Operation::Sub));
+        let constraint = prog.add_node(NodeInfo::new(crate::Operation::Constraint(
+            BigInt::from_u32(0),
+        )));
+
+        prog.add_edge(private, mul, EdgeInfo::Left);
+        prog.add_edge(constant, mul, EdgeInfo::Right);
+        prog.add_edge(mul, sub, EdgeInfo::Left);
+        prog.add_edge(public, sub, EdgeInfo::Right);
+        prog.add_edge(sub, constraint, EdgeInfo::Unordered);
+
+        let backend = BulletproofsBackend::new();
+
+        let c = BigInt::from_u32(3);
+        let partial =
+            specialize_constants::<BulletproofsFieldSpec>(&prog, &[Scalar::try_from(c).unwrap()])
+                .unwrap();
+
+        assert!(!partial
+            .program()
+            .node_weights()
+            .any(|x| matches!(x.operation, crate::Operation::ConstantInput(_))));
+
+        for x in [1u32, 2, 5] {
+            let private = BigInt::from_u32(x);
+            let public = BigInt::from_u32(3 * x);
+
+            let graph = jit_prover_with_public::<BulletproofsFieldSpec>(
+                &partial,
+                &[Scalar::try_from(public).unwrap()],
+                &[Scalar::try_from(private).unwrap()],
+            )
+            .unwrap();
+
+            let proof = backend.prove(&graph, &[public, private]).unwrap();
+
+            backend
+                .verify_with_inputs(&prog, &proof, &[c], &[public])
+                .unwrap();
+        }
+
+        let result = jit_prover_with_public::<BulletproofsFieldSpec>(
+            &partial,
+            &[Scalar::try_from(BigInt::from_u32(4)).unwrap()],
+            &[Scalar::try_from(BigInt::from_u32(1)).unwrap()],
+        );
+
+        assert!(matches!(result, Err(Error::UnsatisfiableConstraint(_))));
+    }
+
+    #[test]
+    fn specialize_constants_folds_constant_subgraphs() {
+        // Prove private * (constant_0 + constant_1) == public.
+        let mut prog = CompiledZkpProgram::new();
+
+        let private = prog.add_node(NodeInfo::new(crate::Operation::PrivateInput(0)));
+        let public = prog.add_node(NodeInfo::new(crate::Operation::PublicInput(0)));
+        let c_0 = prog.add_node(NodeInfo::new(crate::Operation::ConstantInput(0)));
+        let c_1 = prog.add_node(NodeInfo::new(crate::Operation::ConstantInput(1)));
+        let add = prog.add_node(NodeInfo::new(crate::Operation::Add));
+        let mul = prog.add_node(NodeInfo::new(crate::Operation::Mul));
+        let sub = prog.add_node(NodeInfo::new(crate::Operation::Sub));
+        let constraint = prog.add_node(NodeInfo::new(crate::Operation::Constraint(
+            BigInt::from_u32(0),
+        )));
+
+        prog.add_edge(c_0, add, EdgeInfo::Left);
+        prog.add_edge(c_1, add, EdgeInfo::Right);
+        prog.add_edge(private, mul, EdgeInfo::Left);
+        prog.add_edge(add, mul, EdgeInfo::Right);
+        prog.add_edge(mul, sub, EdgeInfo::Left);
+        prog.add_edge(public, sub, EdgeInfo::Right);
+        prog.add_edge(sub, constraint, EdgeInfo::Unordered);
+
+        let backend = BulletproofsBackend::new();
+
+        let constants = [BigInt::from_u32(3), BigInt::from_u32(4)];
+        let partial = specialize_constants::<BulletproofsFieldSpec>(
+            &prog,
+            &constants.map(|c| Scalar::try_from(c).unwrap()),
+        )
+        .unwrap();
+
+        let folded = partial.program();
+
+        // Folded operands stay in place with no outgoing edges.
+        assert_eq!(folded.node_count(), prog.node_count());
+
+        for c in [c_0, c_1] {
+            assert_eq!(folded.edges_directed(c, Direction::Outgoing).count(), 0);
+        }
+
+        assert!(matches!(
+            folded[add].operation,
+            crate::Operation::Constant(x) if x == BigInt::from_u32(7)
+        ));
+
+        let private = BigInt::from_u32(5);
+        let public = BigInt::from_u32(35);
+
+        let graph = jit_prover_with_public::<BulletproofsFieldSpec>(
+            &partial,
+            &[Scalar::try_from(public).unwrap()],
+            &[Scalar::try_from(private).unwrap()],
+        )
+        .unwrap();
+
+        let proof = backend.prove(&graph, &[public, private]).unwrap();
+
+        backend
+            .verify_with_inputs(&prog, &proof, &constants, &[public])
+            .unwrap();
+    }
 }
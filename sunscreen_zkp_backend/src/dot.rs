@@ -0,0 +1,180 @@
+use petgraph::dot::{Config, Dot};
+use sunscreen_compiler_common::Render;
+
+use crate::{CompiledZkpProgram, Operation};
+
+impl Render for Operation {
+    fn render(&self) -> String {
+        format!("{:?}", self)
+    }
+}
+
+/**
+ * Renders a compiled ZKP program's graph in Graphviz's DOT format.
+ *
+ * # Remarks
+ * Each node is labeled with its index and [`Render`]ed operation, so
+ * gadget invocations show their [`debug_name`](crate::Gadget::debug_name).
+ * Inputs are drawn as inverted houses, constraints as houses and constants
+ * as boxes. Nodes are filled by kind:
+ *
+ * | Node           | Color      |
+ * |----------------|------------|
+ * | Private input  | lightcoral |
+ * | Public input   | lightblue  |
+ * | Constant input | lightgrey  |
+ * | Hidden input   | plum       |
+ * | Constraint     | gold       |
+ * | Gadget         | palegreen  |
+ *
+ * Each edge is labeled with the operand it provides to its destination
+ * node.
+ *
+ * You can view the result with e.g. `dot -Tsvg`.
+ */
+pub fn render_compiled_zkp_program(prog: &CompiledZkpProgram) -> String {
+    let data = Dot::with_attr_getters(
+        &prog.0,
+        &[Config::NodeNoLabel, Config::EdgeNoLabel],
+        &|_, e| format!("label=\"{}\"", e.weight().render()),
+        &|_, n| {
+            let (index, node) = n;
+            let op = &node.operation;
+
+            let shape = match op {
+                Operation::PrivateInput(_)
+                | Operation::PublicInput(_)
+                | Operation::ConstantInput(_)
+                | Operation::HiddenInput(_) => "invhouse",
+                Operation::Constraint(_) => "house",
+                Operation::Constant(_) => "box",
+                _ => "ellipse",
+            };
+
+            let color = match op {
+                Operation::PrivateInput(_) => Some("lightcoral"),
+                Operation::PublicInput(_) => Some("lightblue"),
+                Operation::ConstantInput(_) => Some("lightgrey"),
+                Operation::HiddenInput(_) => Some("plum"),
+                Operation::Constraint(_) => Some("gold"),
+                Operation::InvokeGadget(..) => Some("palegreen"),
+                _ => None,
+            };
+
+            let fill = color
+                .map(|c| format!(" style=filled fillcolor={c}"))
+                .unwrap_or_default();
+
+            format!(
+                "label=\"{}: {}\" shape={}{}",
+                index.index(),
+                op.render(),
+                shape,
+                fill
+            )
+        },
+    );
+
+    format!("{data:?}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use petgraph::stable_graph::NodeIndex;
+    use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
+
+    use super::*;
+    use crate::{BigInt, Gadget, Result};
+
+    struct TestGadget;
+
+    impl Gadget for TestGadget {
+        fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> Result<Vec<BigInt>> {
+            Ok(vec![])
+        }
+
+        fn gen_circuit(
+            &self,
+            _gadget_inputs: &[NodeIndex],
+            _hidden_inputs: &[NodeIndex],
+        ) -> Vec<NodeIndex> {
+            vec![]
+        }
+
+        fn gadget_input_count(&self) -> usize {
+            1
+        }
+
+        fn hidden_input_count(&self) -> usize {
+            1
+        }
+
+        fn debug_name(&self) -> &'static str {
+            "TestGadget"
+        }
+    }
+
+    #[test]
+    fn can_render_compiled_program_to_dot() {
+        let mut prog = CompiledZkpProgram::new();
+
+        let mut add_node = |op: Operation, edges: &[(NodeIndex, EdgeInfo)]| {
+            let n = prog.add_node(NodeInfo { operation: op });
+
+            for (source, edge) in edges {
+                prog.add_edge(*source, n, *edge);
+            }
+
+            n
+        };
+
+        // a * h + p * k == c, where h is a gadget's hidden input.
+        let a = add_node(Operation::PrivateInput(0), &[]);
+        let p = add_node(Operation::PublicInput(0), &[]);
+        let k = add_node(Operation::ConstantInput(0), &[]);
+        add_node(
+            Operation::InvokeGadget(Arc::new(TestGadget), 0),
+            &[(a, EdgeInfo::Ordered(0))],
+        );
+        let h = add_node(Operation::HiddenInput(0), &[]);
+
+        let mul_1 = add_node(Operation::Mul, &[(a, EdgeInfo::Left), (h, EdgeInfo::Right)]);
+        let mul_2 = add_node(Operation::Mul, &[(p, EdgeInfo::Left), (k, EdgeInfo::Right)]);
+        let add = add_node(
+            Operation::Add,
+            &[(mul_1, EdgeInfo::Left), (mul_2, EdgeInfo::Right)],
+        );
+
+        add_node(
+            Operation::Constraint(BigInt::from_u32(42)),
+            &[(add, EdgeInfo::Unordered)],
+        );
+
+        let dot = render_compiled_zkp_program(&prog);
+
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains(
+            "label=\"0: PrivateInput(0)\" shape=invhouse style=filled fillcolor=lightcoral"
+        ));
+        assert!(dot.contains(
+            "label=\"1: PublicInput(0)\" shape=invhouse style=filled fillcolor=lightblue"
+        ));
+        assert!(dot.contains(
+            "label=\"2: ConstantInput(0)\" shape=invhouse style=filled fillcolor=lightgrey"
+        ));
+        assert!(dot.contains(
+            "label=\"3: InvokeGadget(TestGadget)\" shape=ellipse style=filled fillcolor=palegreen"
+        ));
+        assert!(
+            dot.contains("label=\"4: HiddenInput(0)\" shape=invhouse style=filled fillcolor=plum")
+        );
+        assert!(dot.contains("label=\"5: Mul\" shape=ellipse]"));
+        assert!(dot.contains("shape=house style=filled fillcolor=gold"));
+        assert!(dot.contains("0 -> 3 [ label=\"Ordered(0)\"]"));
+        assert!(dot.contains("0 -> 5 [ label=\"Left\"]"));
+        assert!(dot.contains("4 -> 5 [ label=\"Right\"]"));
+        assert!(dot.contains("7 -> 8 [ label=\"Unordered\"]"));
+    }
+}
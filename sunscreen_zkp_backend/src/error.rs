@@ -15,6 +15,13 @@ pub enum Error {
      */
     BulletproofsR1CSError(Box<bulletproofs::r1cs::R1CSError>),
 
+    #[cfg(feature = "groth16")]
+    #[error("Groth16 error: {0}")]
+    /**
+     * Encountered an error when creating or verifying a Groth16 proof.
+     */
+    Groth16Error(ark_relations::r1cs::SynthesisError),
+
     #[error("Value {0} is out of range for the chosen backend")]
     /**
      * Encountered a value out of range for the field type in the chosen backend.
@@ -33,6 +40,26 @@ pub enum Error {
      */
     IncorrectProofType,
 
+    #[error("Unsupported proof wire format version {0}")]
+    /**
+     * Attempted to deserialize a proof written in an unsupported version of
+     * the wire format. See [`Proof::to_wire`](crate::Proof::to_wire).
+     */
+    UnsupportedProofVersion(u16),
+
+    #[error("Unsupported proof system {0}")]
+    /**
+     * Attempted to deserialize a proof from a proof system this build
+     * doesn't support. See [`Proof::from_bytes`](crate::Proof::from_bytes).
+     */
+    UnsupportedProofSystem(Box<String>),
+
+    #[error("Malformed proof: {0}")]
+    /**
+     * Attempted to deserialize an invalid proof.
+     */
+    MalformedProof(Box<String>),
+
     #[error("The backend graph is malformed {0}")]
     /**
      * The program is malformed and caused a query failure.
@@ -56,6 +83,13 @@ pub enum Error {
      * A constraint could not be satisfied.
      */
     UnsatisfiableConstraint(NodeIndex),
+
+    #[error("The backend's parameters can't create proofs.")]
+    /**
+     * Attempted to create a proof with a backend that only holds the
+     * parameters needed to verify proofs.
+     */
+    MissingProverParameters,
 }
 
 impl Error {
@@ -80,6 +114,20 @@ impl Error {
         Self::MalformedZkpProgram(Box::new(msg.to_owned()))
     }
 
+    /**
+     * Create an [`Error::MalformedProof`].
+     */
+    pub fn malformed_proof(msg: &str) -> Self {
+        Self::MalformedProof(Box::new(msg.to_owned()))
+    }
+
+    /**
+     * Create an [`Error::UnsupportedProofSystem`].
+     */
+    pub fn unsupported_proof_system(name: &str) -> Self {
+        Self::UnsupportedProofSystem(Box::new(name.to_owned()))
+    }
+
     /**
      * Create an [`Error::InputsMismatch`].
      */
@@ -94,6 +142,13 @@ impl From<bulletproofs::r1cs::R1CSError> for Error {
     }
 }
 
+#[cfg(feature = "groth16")]
+impl From<ark_relations::r1cs::SynthesisError> for Error {
+    fn from(e: ark_relations::r1cs::SynthesisError) -> Self {
+        Self::Groth16Error(e)
+    }
+}
+
 const_assert!(std::mem::size_of::<Error>() <= 16);
 
 /**
@@ -22,6 +22,13 @@ pub enum Operation {
     Constraint(BigInt),
 
     Constant(BigInt),
+
+    /**
+     * Marks the invocation of the named gadget. It has no edges and doesn't
+     * contribute to the circuit; the following `usize` nodes in node index
+     * order belong to the gadget.
+     */
+    Gadget(&'static str, usize),
 }
 
 impl OperationTrait for Operation {
@@ -0,0 +1,805 @@
+use std::{collections::HashMap, time::Instant};
+
+use ark_bn254::{Bn254, Fr};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Groth16, Proof as Groth16Proof, ProvingKey, VerifyingKey};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, LinearCombination,
+    SynthesisError, Variable,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use crypto_bigint::{Encoding, U512};
+use log::trace;
+use merlin::Transcript;
+use petgraph::stable_graph::NodeIndex;
+use rand::thread_rng;
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+use sunscreen_compiler_common::forward_traverse;
+
+use crate::{
+    exec::Operation, jit::jit_verifier, jit_prover, BigInt, CompiledZkpProgram, Error,
+    ExecutableZkpProgram, FieldSpec, Proof, Result, ZkpBackend,
+};
+
+/**
+ * The name of Groth16 proofs in [`Proof::Custom`].
+ */
+pub const GROTH16_NAME: &str = "groth16";
+
+/**
+ * A node in a [`Groth16Circuit`]. Operands refer to the index of an earlier
+ * gate.
+ */
+#[derive(Clone)]
+enum Gate {
+    Input(usize),
+    HiddenInput(Option<Fr>),
+    Constant(Fr),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+    Neg(usize),
+    Constraint(Vec<usize>, Fr),
+}
+
+/**
+ * The output of a gate during synthesis.
+ */
+#[derive(Clone)]
+enum Wire {
+    /**
+     * A linear combination of circuit variables and its value if we're
+     * proving.
+     */
+    Variable(LinearCombination<Fr>, Option<Fr>),
+    Constant(Fr),
+}
+
+/**
+ * An [`ExecutableZkpProgram`] lowered to a Groth16 R1CS circuit.
+ *
+ * # Remarks
+ * Unlike Bulletproofs, a Groth16 trusted setup is specific to a circuit. To
+ * let one setup serve every set of public inputs, each constraint's
+ * expected value is a Groth16 public input rather than a constant baked
+ * into the circuit. As [`jit_prover`] and [`jit_verifier`] implement public
+ * inputs as constraints, their values are public inputs as well.
+ *
+ * Operations whose operands are all constant are folded while lowering, so
+ * the prover and verifier always agree on which values are variables.
+ */
+#[derive(Clone)]
+struct Groth16Circuit {
+    gates: Vec<Gate>,
+    use_counts: Vec<usize>,
+    inputs: Option<Vec<Fr>>,
+}
+
+impl Groth16Circuit {
+    /**
+     * Lower the given graph to a circuit. Pass `inputs` when proving.
+     */
+    fn new(graph: &ExecutableZkpProgram, inputs: Option<Vec<Fr>>) -> Result<Self> {
+        let mut gates = vec![];
+        let mut use_counts = vec![];
+        let mut gate_ids = HashMap::<NodeIndex, usize>::new();
+
+        forward_traverse(&graph.0, |query, idx| {
+            let node = query.get_node(idx).unwrap();
+
+            let mut operand = |x: NodeIndex| {
+                let id = gate_ids[&x];
+                use_counts[id] += 1;
+
+                id
+            };
+
+            let gate = match node.operation {
+                Operation::Input(x) => Gate::Input(x),
+                Operation::HiddenInput(x) => Gate::HiddenInput(x.map(Fr::try_from).transpose()?),
+                Operation::Constant(x) => Gate::Constant(x.try_into()?),
+                Operation::Add => {
+                    let (left, right) = query.get_binary_operands(idx)?;
+
+                    Gate::Add(operand(left), operand(right))
+                }
+                Operation::Sub => {
+                    let (left, right) = query.get_binary_operands(idx)?;
+
+                    Gate::Sub(operand(left), operand(right))
+                }
+                Operation::Mul => {
+                    let (left, right) = query.get_binary_operands(idx)?;
+
+                    Gate::Mul(operand(left), operand(right))
+                }
+                Operation::Neg => Gate::Neg(operand(query.get_unary_operand(idx)?)),
+                // Gadget markers don't appear in the circuit.
+                Operation::Gadget(..) => return Ok(()),
+                Operation::Constraint(x) => {
+                    let x: Fr = x.try_into()?;
+                    let mut operands = vec![];
+
+                    for o in query.get_unordered_operands(idx)? {
+                        match gates[gate_ids[&o]] {
+                            Gate::Constant(c) if c != x => {
+                                return Err(Error::UnsatisfiableConstraint(idx));
+                            }
+                            Gate::Constant(_) => {}
+                            _ => operands.push(operand(o)),
+                        }
+                    }
+
+                    Gate::Constraint(operands, x)
+                }
+            };
+
+            let constant = |x: usize| match gates[x] {
+                Gate::Constant(c) => Some(c),
+                _ => None,
+            };
+
+            let gate = match gate {
+                Gate::Add(a, b) => match (constant(a), constant(b)) {
+                    (Some(a), Some(b)) => Gate::Constant(a + b),
+                    _ => gate,
+                },
+                Gate::Sub(a, b) => match (constant(a), constant(b)) {
+                    (Some(a), Some(b)) => Gate::Constant(a - b),
+                    _ => gate,
+                },
+                Gate::Mul(a, b) => match (constant(a), constant(b)) {
+                    (Some(a), Some(b)) => Gate::Constant(a * b),
+                    _ => gate,
+                },
+                Gate::Neg(a) => match constant(a) {
+                    Some(a) => Gate::Constant(-a),
+                    _ => gate,
+                },
+                _ => gate,
+            };
+
+            gate_ids.insert(idx, gates.len());
+            gates.push(gate);
+            use_counts.push(0);
+
+            Ok::<(), Error>(())
+        })?;
+
+        Ok(Self {
+            gates,
+            use_counts,
+            inputs,
+        })
+    }
+
+    /**
+     * The number of [`Operation::Input`] nodes in the circuit.
+     */
+    fn input_count(&self) -> usize {
+        self.gates
+            .iter()
+            .filter(|x| matches!(x, Gate::Input(_)))
+            .count()
+    }
+
+    /**
+     * The Groth16 public inputs, in the order synthesis allocates them.
+     */
+    fn public_inputs(&self) -> Vec<Fr> {
+        self.gates
+            .iter()
+            .flat_map(|x| match x {
+                Gate::Constraint(operands, x) => vec![*x; operands.len()],
+                _ => vec![],
+            })
+            .collect()
+    }
+}
+
+impl ConstraintSynthesizer<Fr> for Groth16Circuit {
+    fn generate_constraints(
+        self,
+        cs: ConstraintSystemRef<Fr>,
+    ) -> std::result::Result<(), SynthesisError> {
+        let mut wires: Vec<Option<Wire>> = vec![None; self.gates.len()];
+        let mut use_counts = self.use_counts.clone();
+
+        // Linear combinations contain a Vec, so we drop each wire after its
+        // last use rather than hold every one until the end.
+        let mut take = |wires: &mut Vec<Option<Wire>>, x: usize| {
+            use_counts[x] -= 1;
+
+            let wire = if use_counts[x] == 0 {
+                wires[x].take()
+            } else {
+                wires[x].clone()
+            };
+
+            wire.unwrap_or_else(|| panic!("traversal error: dependency {} not found", x))
+        };
+
+        let witness = |value: Option<Fr>| {
+            let var = cs.new_witness_variable(|| value.ok_or(SynthesisError::AssignmentMissing))?;
+
+            Ok::<_, SynthesisError>(Wire::Variable(var.into(), value))
+        };
+
+        let one = || LinearCombination::from(Variable::One);
+
+        for (i, gate) in self.gates.iter().enumerate() {
+            let wire = match gate {
+                Gate::Input(x) => witness(self.inputs.as_ref().map(|inputs| inputs[*x]))?,
+                Gate::HiddenInput(x) => witness(*x)?,
+                Gate::Constant(x) => Wire::Constant(*x),
+                Gate::Add(a, b) | Gate::Sub(a, b) => {
+                    let (a, b) = (take(&mut wires, *a), take(&mut wires, *b));
+
+                    let b = match (gate, b) {
+                        (Gate::Sub(..), Wire::Variable(lc, v)) => {
+                            Wire::Variable(-lc, v.map(|v| -v))
+                        }
+                        (Gate::Sub(..), Wire::Constant(c)) => Wire::Constant(-c),
+                        (_, b) => b,
+                    };
+
+                    match (a, b) {
+                        (Wire::Variable(a, a_v), Wire::Variable(b, b_v)) => {
+                            Wire::Variable(a + b, a_v.zip(b_v).map(|(a, b)| a + b))
+                        }
+                        (Wire::Variable(x, v), Wire::Constant(c))
+                        | (Wire::Constant(c), Wire::Variable(x, v)) => {
+                            Wire::Variable(x + (c, Variable::One), v.map(|v| v + c))
+                        }
+                        (Wire::Constant(a), Wire::Constant(b)) => Wire::Constant(a + b),
+                    }
+                }
+                Gate::Mul(a, b) => match (take(&mut wires, *a), take(&mut wires, *b)) {
+                    (Wire::Variable(a, a_v), Wire::Variable(b, b_v)) => {
+                        let value = a_v.zip(b_v).map(|(a, b)| a * b);
+                        let o = cs.new_witness_variable(|| {
+                            value.ok_or(SynthesisError::AssignmentMissing)
+                        })?;
+
+                        cs.enforce_constraint(a, b, o.into())?;
+
+                        Wire::Variable(o.into(), value)
+                    }
+                    (Wire::Variable(x, v), Wire::Constant(c))
+                    | (Wire::Constant(c), Wire::Variable(x, v)) => {
+                        Wire::Variable(x * c, v.map(|v| v * c))
+                    }
+                    (Wire::Constant(a), Wire::Constant(b)) => Wire::Constant(a * b),
+                },
+                Gate::Neg(a) => match take(&mut wires, *a) {
+                    Wire::Variable(x, v) => Wire::Variable(-x, v.map(|v| -v)),
+                    Wire::Constant(c) => Wire::Constant(-c),
+                },
+                Gate::Constraint(operands, x) => {
+                    for o in operands {
+                        if let Wire::Variable(lc, _) = take(&mut wires, *o) {
+                            let expected = cs.new_input_variable(|| Ok(*x))?;
+
+                            cs.enforce_constraint(lc, one(), expected.into())?;
+                        }
+                    }
+
+                    continue;
+                }
+            };
+
+            wires[i] = Some(wire);
+        }
+
+        Ok(())
+    }
+}
+
+fn serialize_canonical<T, S>(x: &T, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    T: CanonicalSerialize,
+    S: Serializer,
+{
+    let mut bytes = vec![];
+
+    x.serialize_compressed(&mut bytes)
+        .map_err(S::Error::custom)?;
+
+    serializer.serialize_bytes(&bytes)
+}
+
+fn deserialize_canonical<'de, T, D>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    T: CanonicalDeserialize,
+    D: Deserializer<'de>,
+{
+    let bytes = Vec::<u8>::deserialize(deserializer)?;
+
+    T::deserialize_compressed(bytes.as_slice()).map_err(D::Error::custom)
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+/**
+ * The trusted setup a prover needs to create Groth16 proofs.
+ */
+pub struct Groth16ProverParameters(
+    #[serde(
+        serialize_with = "serialize_canonical",
+        deserialize_with = "deserialize_canonical"
+    )]
+    pub ProvingKey<Bn254>,
+);
+
+#[derive(Clone, Serialize, Deserialize)]
+/**
+ * The trusted setup a verifier needs to check Groth16 proofs.
+ */
+pub struct Groth16VerifierParameters(
+    #[serde(
+        serialize_with = "serialize_canonical",
+        deserialize_with = "deserialize_canonical"
+    )]
+    pub VerifyingKey<Bn254>,
+);
+
+impl Groth16ProverParameters {
+    /**
+     * Runs the Groth16 trusted setup for the given program.
+     *
+     * # Remarks
+     * The setup is specific to the program and its constant inputs, but
+     * works for any public inputs. Get `graph` by calling
+     * [`Groth16Backend::jit_verifier`] with any public inputs of the
+     * correct count.
+     *
+     * Whoever runs the setup learns the toxic waste needed to forge proofs.
+     * Production deployments should instead run a multi-party ceremony and
+     * load the result through serde.
+     */
+    pub fn setup(graph: &ExecutableZkpProgram) -> Result<Self> {
+        let circuit = Groth16Circuit::new(graph, None)?;
+
+        let (proving_key, _) =
+            Groth16::<Bn254>::circuit_specific_setup(circuit, &mut thread_rng())?;
+
+        Ok(Self(proving_key))
+    }
+
+    /**
+     * Returns the parameters needed to verify proofs made with these
+     * parameters.
+     */
+    pub fn verifier_parameters(&self) -> Groth16VerifierParameters {
+        Groth16VerifierParameters(self.0.vk.clone())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+/**
+ * The trusted setup held by a [`Groth16Backend`].
+ */
+pub enum Groth16Parameters {
+    /**
+     * Parameters for creating and verifying proofs.
+     */
+    Prover(Box<Groth16ProverParameters>),
+
+    /**
+     * Parameters for only verifying proofs.
+     */
+    Verifier(Box<Groth16VerifierParameters>),
+}
+
+impl Groth16Parameters {
+    /**
+     * Returns the parameters needed to verify proofs.
+     */
+    pub fn verifier_parameters(&self) -> Groth16VerifierParameters {
+        match self {
+            Self::Prover(x) => x.verifier_parameters(),
+            Self::Verifier(x) => (**x).clone(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+/**
+ * A Groth16 backend over the BN254 curve.
+ *
+ * # Remarks
+ * Groth16 proofs are a constant 128 bytes (compressed) and verify with a
+ * fixed number of pairings regardless of the program's size, which makes
+ * them suitable for verifying on chain. In exchange, each program needs a
+ * trusted setup (see [`Groth16ProverParameters::setup`]), which the backend
+ * holds in [`parameters`](Self::parameters).
+ *
+ * Proofs are [`Proof::Custom`] values named [`GROTH16_NAME`] whose data is
+ * the compressed arkworks encoding of the proof.
+ */
+pub struct Groth16Backend {
+    /**
+     * The trusted setup for the program this backend proves and verifies.
+     */
+    pub parameters: Groth16Parameters,
+}
+
+impl Groth16Backend {
+    /**
+     * Create a [`Groth16Backend`] using the given trusted setup.
+     */
+    pub fn new(parameters: Groth16Parameters) -> Self {
+        Self { parameters }
+    }
+}
+
+impl ZkpBackend for Groth16Backend {
+    type Field = Bn254FieldSpec;
+
+    type ProverParameters = Groth16ProverParameters;
+    type VerifierParameters = Groth16VerifierParameters;
+
+    fn prove(&self, graph: &ExecutableZkpProgram, inputs: &[BigInt]) -> Result<Proof> {
+        let parameters = match &self.parameters {
+            Groth16Parameters::Prover(x) => x,
+            Groth16Parameters::Verifier(_) => return Err(Error::MissingProverParameters),
+        };
+
+        self.prove_with_parameters(graph, inputs, parameters, &mut Transcript::new(b"unused"))
+    }
+
+    /**
+     * Create a proof for the given executable Sunscreen program with the
+     * given inputs.
+     *
+     * # Remarks
+     * Groth16 proofs are non-interactive without a Fiat-Shamir transcript,
+     * so `transcript` is unused.
+     */
+    fn prove_with_parameters(
+        &self,
+        graph: &ExecutableZkpProgram,
+        inputs: &[BigInt],
+        parameters: &Self::ProverParameters,
+        _transcript: &mut Transcript,
+    ) -> Result<Proof> {
+        let inputs = inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+
+        let now = Instant::now();
+
+        let circuit = Groth16Circuit::new(graph, Some(inputs))?;
+
+        let expected_input_count = circuit.input_count();
+        let input_count = circuit.inputs.as_ref().map(|x| x.len()).unwrap_or_default();
+
+        if expected_input_count != input_count {
+            return Err(Error::inputs_mismatch(&format!(
+                "Internal error: Groth16 runtime arguments mismatch. Expected {}, got {}.",
+                expected_input_count, input_count
+            )));
+        }
+
+        // Groth16 doesn't check the witness satisfies the circuit and
+        // produces a garbage proof if it doesn't, so we check first.
+        let cs = ConstraintSystem::new_ref();
+        circuit.clone().generate_constraints(cs.clone())?;
+
+        if !cs.is_satisfied()? {
+            return Err(Error::Groth16Error(SynthesisError::Unsatisfiable));
+        }
+
+        trace!("Groth16 encode time {}s", now.elapsed().as_secs_f64());
+
+        let now = Instant::now();
+
+        let proof = Groth16::<Bn254>::prove(&parameters.0, circuit, &mut thread_rng())?;
+
+        trace!("Groth16 prover time {}s", now.elapsed().as_secs_f64());
+
+        let mut data = vec![];
+
+        proof
+            .serialize_compressed(&mut data)
+            .map_err(|e| Error::malformed_proof(&e.to_string()))?;
+
+        Ok(Proof::Custom {
+            name: GROTH16_NAME.to_owned(),
+            data,
+        })
+    }
+
+    fn verify(&self, graph: &ExecutableZkpProgram, proof: &Proof) -> Result<()> {
+        let parameters = self.parameters.verifier_parameters();
+
+        self.verify_with_parameters(graph, proof, &parameters, &mut Transcript::new(b"unused"))
+    }
+
+    /**
+     * Verify the given proof for the given executable Sunscreen program.
+     *
+     * # Remarks
+     * Groth16 proofs are non-interactive without a Fiat-Shamir transcript,
+     * so `transcript` is unused.
+     */
+    fn verify_with_parameters(
+        &self,
+        graph: &ExecutableZkpProgram,
+        proof: &Proof,
+        parameters: &Self::VerifierParameters,
+        _transcript: &mut Transcript,
+    ) -> Result<()> {
+        let proof = match proof {
+            Proof::Custom { name, data } if name == GROTH16_NAME => {
+                Groth16Proof::<Bn254>::deserialize_compressed(data.as_slice())
+                    .map_err(|e| Error::malformed_proof(&e.to_string()))?
+            }
+            _ => {
+                return Err(Error::IncorrectProofType);
+            }
+        };
+
+        trace!("Starting backend verify...");
+
+        let public_inputs = Groth16Circuit::new(graph, None)?.public_inputs();
+
+        let now = Instant::now();
+
+        let is_valid = Groth16::<Bn254>::verify(&parameters.0, &public_inputs, &proof)?;
+
+        trace!("Groth16 verify time {}s", now.elapsed().as_secs_f64());
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(Error::Groth16Error(SynthesisError::Unsatisfiable))
+        }
+    }
+
+    fn jit_prover(
+        &self,
+        prog: &CompiledZkpProgram,
+        private_inputs: &[BigInt],
+        public_inputs: &[BigInt],
+        constant_inputs: &[BigInt],
+    ) -> Result<ExecutableZkpProgram> {
+        let private_inputs = private_inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+        let public_inputs = public_inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+        let constant_inputs = constant_inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+
+        jit_prover::<Bn254FieldSpec>(prog, &private_inputs, &public_inputs, &constant_inputs)
+    }
+
+    fn jit_verifier(
+        &self,
+        prog: &CompiledZkpProgram,
+        constant_inputs: &[BigInt],
+        public_inputs: &[BigInt],
+    ) -> Result<ExecutableZkpProgram> {
+        let constant_inputs = constant_inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+
+        let public_inputs = public_inputs
+            .iter()
+            .map(Fr::try_from)
+            .collect::<Result<Vec<Fr>>>()?;
+
+        jit_verifier::<Bn254FieldSpec>(prog, &constant_inputs, &public_inputs)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+/// The specification for the BN254 scalar field used by Groth16.
+pub struct Bn254FieldSpec {}
+
+impl FieldSpec for Bn254FieldSpec {
+    type BackendField = Fr;
+
+    // 21888242871839275222246405745257275088548364400416034343698204186575808495617
+    const FIELD_MODULUS: BigInt = BigInt::from_words([
+        0x43e1f593f0000001,
+        0x2833e84879b97091,
+        0xb85045b68181585d,
+        0x30644e72e131a029,
+        0x0,
+        0x0,
+        0x0,
+        0x0,
+    ]);
+}
+
+fn try_uint_to_fr(x: &U512) -> Result<Fr> {
+    // U512 values are little endian. Thus, we attempt to convert the lower
+    // 256 bits to a field element and assert the upper bytes are zero.
+    let bytes = x.to_le_bytes();
+    let (lower, upper) = bytes.split_at(32);
+
+    if upper.iter().any(|x| *x != 0) {
+        return Err(Error::out_of_range(&x.to_string()));
+    }
+
+    let mut limbs = [0u64; 4];
+
+    for (limb, bytes) in limbs.iter_mut().zip(lower.chunks(8)) {
+        *limb = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    Fr::from_bigint(ark_ff::BigInt(limbs)).ok_or_else(|| Error::out_of_range(&x.to_string()))
+}
+
+impl TryFrom<BigInt> for Fr {
+    type Error = Error;
+
+    fn try_from(value: BigInt) -> Result<Self> {
+        try_uint_to_fr(&value)
+    }
+}
+
+impl TryFrom<&BigInt> for Fr {
+    type Error = Error;
+
+    fn try_from(value: &BigInt) -> Result<Self> {
+        try_uint_to_fr(value)
+    }
+}
+
+fn fr_to_uint(x: &Fr) -> U512 {
+    let mut bytes = x.into_bigint().to_bytes_le();
+    bytes.resize(64, 0);
+
+    U512::from_le_slice(&bytes)
+}
+
+impl crate::ZkpFrom<Fr> for BigInt {
+    fn zkp_from(val: Fr) -> BigInt {
+        BigInt(fr_to_uint(&val))
+    }
+}
+
+impl crate::ZkpFrom<&Fr> for BigInt {
+    fn zkp_from(val: &Fr) -> BigInt {
+        BigInt(fr_to_uint(val))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
+
+    use super::*;
+    use crate::{exec::Operation as BackendOperation, ZkpFrom};
+
+    #[test]
+    fn field_modulus_matches_bn254() {
+        let r_min_1 = Bn254FieldSpec::FIELD_MODULUS.wrapping_sub(&U512::ONE);
+        let fr = Fr::try_from(BigInt(r_min_1)).unwrap();
+
+        assert_eq!(fr, -Fr::from(1u64));
+        assert_eq!(BigInt::zkp_from(fr), BigInt(r_min_1));
+        assert!(Fr::try_from(Bn254FieldSpec::FIELD_MODULUS).is_err());
+    }
+
+    #[test]
+    fn can_run_simple_proof() {
+        let make_graph = |expected: u32| {
+            let mut graph = ExecutableZkpProgram::new();
+
+            let mut add_node = |op: BackendOperation, edges: &[(NodeIndex, EdgeInfo)]| {
+                let n = graph.add_node(NodeInfo { operation: op });
+
+                for (source, edge) in edges {
+                    graph.add_edge(*source, n, *edge);
+                }
+
+                n
+            };
+
+            let in_0 = add_node(BackendOperation::Input(0), &[]);
+            let in_1 = add_node(BackendOperation::Input(1), &[]);
+            let in_2 = add_node(BackendOperation::Input(2), &[]);
+
+            let mul_1 = add_node(
+                BackendOperation::Mul,
+                &[(in_0, EdgeInfo::Left), (in_1, EdgeInfo::Right)],
+            );
+            let add_1 = add_node(
+                BackendOperation::Add,
+                &[(in_2, EdgeInfo::Left), (mul_1, EdgeInfo::Right)],
+            );
+
+            let _ = add_node(
+                BackendOperation::Constraint(BigInt::from_u32(expected)),
+                &[(add_1, EdgeInfo::Unordered)],
+            );
+
+            graph
+        };
+
+        let graph = make_graph(42);
+
+        let parameters = Groth16ProverParameters::setup(&graph).unwrap();
+        let backend = Groth16Backend::new(Groth16Parameters::Prover(Box::new(parameters.clone())));
+
+        let prove = |graph: &ExecutableZkpProgram, inputs: [u32; 3]| {
+            backend.prove(graph, &inputs.map(BigInt::from_u32)).unwrap()
+        };
+
+        // 10 * 4 + 2 == 42
+        let proof = prove(&graph, [10, 4, 2]);
+
+        assert!(matches!(&proof, Proof::Custom { name, .. } if name == GROTH16_NAME));
+        backend.verify(&graph, &proof).unwrap();
+
+        // A verifier needs only the verifying key and can check the proof
+        // after it round trips through the wire format.
+        let verifier = Groth16Backend::new(Groth16Parameters::Verifier(Box::new(
+            parameters.verifier_parameters(),
+        )));
+        let proof = Proof::from_wire(&proof.to_wire()).unwrap();
+
+        verifier.verify(&graph, &proof).unwrap();
+
+        assert_eq!(
+            verifier.prove(&graph, &[BigInt::ONE; 3]).err(),
+            Some(Error::MissingProverParameters)
+        );
+
+        // 8 * 5 + 3 == 42.
+        // Proving should fail.
+        assert_eq!(
+            backend
+                .prove(&graph, &[8, 5, 3].map(BigInt::from_u32))
+                .err(),
+            Some(Error::Groth16Error(SynthesisError::Unsatisfiable))
+        );
+
+        // The same setup works with a different expected value, but the
+        // proof doesn't verify against the original one.
+        let graph_43 = make_graph(43);
+        let proof = prove(&graph_43, [8, 5, 3]);
+
+        backend.verify(&graph_43, &proof).unwrap();
+        assert!(backend.verify(&graph, &proof).is_err());
+    }
+
+    #[test]
+    fn can_roundtrip_parameters_through_serde() {
+        let mut graph = ExecutableZkpProgram::new();
+
+        let input = graph.add_node(NodeInfo {
+            operation: BackendOperation::Input(0),
+        });
+        let square = graph.add_node(NodeInfo {
+            operation: BackendOperation::Mul,
+        });
+        graph.add_edge(input, square, EdgeInfo::Left);
+        graph.add_edge(input, square, EdgeInfo::Right);
+        let constraint = graph.add_node(NodeInfo {
+            operation: BackendOperation::Constraint(BigInt::from_u32(49)),
+        });
+        graph.add_edge(square, constraint, EdgeInfo::Unordered);
+
+        let parameters = Groth16ProverParameters::setup(&graph).unwrap();
+        let backend = Groth16Backend::new(Groth16Parameters::Prover(Box::new(parameters)));
+
+        let serialized = bincode::serialize(&backend).unwrap();
+        let backend: Groth16Backend = bincode::deserialize(&serialized).unwrap();
+
+        let proof = backend.prove(&graph, &[BigInt::from_u32(7)]).unwrap();
+
+        backend.verify(&graph, &proof).unwrap();
+    }
+}
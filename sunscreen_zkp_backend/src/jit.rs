@@ -41,8 +41,13 @@ pub enum Operation {
 
     /**
      * Compute the given [`Gadget`]'s hidden inputs for the gadget input arguments.
+     *
+     * # Remarks
+     * The second field is the number of nodes that follow this one in node
+     * index order and belong to the gadget: its hidden inputs and the
+     * circuit it generated, including those of any gadgets it invokes.
      */
-    InvokeGadget(Arc<dyn Gadget>),
+    InvokeGadget(Arc<dyn Gadget>, usize),
 
     /**
      * Add 2 values.
@@ -98,9 +103,10 @@ impl Hash for Operation {
                 state.write_u8(4);
                 x.hash(state);
             }
-            Self::InvokeGadget(g) => {
+            Self::InvokeGadget(g, n) => {
                 state.write_u8(5);
                 g.type_id().hash(state);
+                state.write_usize(*n);
             }
             Self::Add => state.write_u8(6),
             Self::Sub => state.write_u8(7),
@@ -122,7 +128,9 @@ impl PartialEq for Operation {
             (Self::HiddenInput(x), Self::HiddenInput(y)) => x == y,
             (Self::Constraint(x), Self::Constraint(y)) => x == y,
             (Self::Constant(x), Self::Constant(y)) => x == y,
-            (Self::InvokeGadget(x), Self::InvokeGadget(y)) => x.type_id() == y.type_id(),
+            (Self::InvokeGadget(x, m), Self::InvokeGadget(y, n)) => {
+                x.type_id() == y.type_id() && m == n
+            }
             (Self::Add, Self::Add) => true,
             (Self::Sub, Self::Sub) => true,
             (Self::Mul, Self::Mul) => true,
@@ -143,7 +151,7 @@ impl Debug for Operation {
             Self::HiddenInput(x) => write!(f, "HiddenInput({x})"),
             Self::Constraint(x) => write!(f, "Constraint({x:#?})"),
             Self::Constant(x) => write!(f, "Constant({x:#?})"),
-            Self::InvokeGadget(g) => write!(f, "InvokeGadget({})", g.debug_name()),
+            Self::InvokeGadget(g, _) => write!(f, "InvokeGadget({})", g.debug_name()),
             Self::Add => write!(f, "Add"),
             Self::Sub => write!(f, "Sub"),
             Self::Mul => write!(f, "Mul"),
@@ -170,7 +178,7 @@ impl OperationTrait for Operation {
     }
 
     fn is_ordered(&self) -> bool {
-        matches!(self, Operation::InvokeGadget(_))
+        matches!(self, Operation::InvokeGadget(..))
     }
 }
 
@@ -238,6 +246,10 @@ fn validate_zkp_program(prog: &CompiledZkpProgram) -> Result<()> {
  * # Remarks
  * This method computes [`Gadget`]'s hidden inputs from their gadget inputs. To do this,
  * we first directly run the execution graph and store the outputs of each node.
+ *
+ * This is equivalent to calling [`specialize_constants`] followed by
+ * [`jit_prover_with_public`]. When creating many proofs with the same
+ * constant inputs, call those directly to specialize the program only once.
  */
 pub fn jit_prover<U>(
     prog: &CompiledZkpProgram,
@@ -248,7 +260,131 @@ pub fn jit_prover<U>(
 where
     U: FieldSpec,
 {
+    let partial = specialize_constants::<U>(prog, constant_inputs)?;
+
+    jit_prover_with_public::<U>(&partial, public_inputs, private_inputs)
+}
+
+#[derive(Clone)]
+/**
+ * A [`CompiledZkpProgram`] whose constant inputs have been bound to values
+ * and whose constant-only arithmetic has been folded.
+ *
+ * # Remarks
+ * Create one with [`specialize_constants`] and pass it to
+ * [`jit_prover_with_public`] for each proof.
+ */
+pub struct PartiallyJittedProgram {
+    prog: CompiledZkpProgram,
+}
+
+impl PartiallyJittedProgram {
+    /**
+     * The program with each constant input replaced by its constant value
+     * and each constant-only subgraph folded into a single constant.
+     */
+    pub fn program(&self) -> &CompiledZkpProgram {
+        &self.prog
+    }
+}
+
+/**
+ * Binds the constant inputs of a [`CompiledZkpProgram`], replacing each
+ * [`Operation::ConstantInput`] with an [`Operation::Constant`] holding its
+ * value, then folds constant-only arithmetic.
+ *
+ * # Remarks
+ * Each [`Operation::Add`], [`Operation::Sub`], [`Operation::Mul`] and
+ * [`Operation::Neg`] whose operands are all constants becomes an
+ * [`Operation::Constant`] holding its result. Constraints and gadgets are
+ * left in place, as they must still appear in the proof. No nodes are
+ * removed, so each [`Operation::InvokeGadget`] still covers the nodes of
+ * its circuit.
+ *
+ * This validates the program, so the result can be JIT compiled with
+ * [`jit_prover_with_public`] any number of times without repeating this
+ * work.
+ */
+pub fn specialize_constants<U>(
+    prog: &CompiledZkpProgram,
+    constant_inputs: &[U::BackendField],
+) -> Result<PartiallyJittedProgram>
+where
+    U: FieldSpec,
+{
+    validate_zkp_program(prog)?;
+    verify_constant_inputs(prog, constant_inputs)?;
+
     let mut prog = prog.clone();
+    let mut constants: HashMap<NodeIndex, U::BackendField> = HashMap::new();
+
+    forward_traverse(&prog, |query, id| {
+        let node = query.get_node(id).unwrap();
+
+        let value = match node.operation {
+            Operation::ConstantInput(x) => Some(constant_inputs[x].clone()),
+            Operation::Constant(x) => Some(U::BackendField::try_from(x)?),
+            Operation::Add | Operation::Sub | Operation::Mul => {
+                let (left, right) = query.get_binary_operands(id)?;
+
+                match (constants.get(&left), constants.get(&right)) {
+                    (Some(l), Some(r)) => Some(match node.operation {
+                        Operation::Add => l.clone() + r.clone(),
+                        Operation::Sub => l.clone() - r.clone(),
+                        _ => l.clone() * r.clone(),
+                    }),
+                    _ => None,
+                }
+            }
+            Operation::Neg => {
+                let left = query.get_unary_operand(id)?;
+
+                constants.get(&left).map(|x| -x.clone())
+            }
+            _ => None,
+        };
+
+        if let Some(value) = value {
+            constants.insert(id, value);
+        }
+
+        Ok::<_, Error>(())
+    })?;
+
+    for (id, value) in constants {
+        prog.node_weight_mut(id).unwrap().operation = Operation::Constant(value.zkp_into());
+
+        let operands = prog
+            .edges_directed(id, Direction::Incoming)
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+
+        for e in operands {
+            prog.remove_edge(e);
+        }
+    }
+
+    Ok(PartiallyJittedProgram { prog })
+}
+
+/**
+ * Just in time compile a [`PartiallyJittedProgram`] into an
+ * [`ExecutableZkpProgram`] for creating proofs.
+ *
+ * # Remarks
+ * See [`jit_prover`]. As [`specialize_constants`] already validated the
+ * program and folded its constants, this only binds the public and
+ * private inputs and computes the gadgets' hidden inputs.
+ */
+pub fn jit_prover_with_public<U>(
+    partial: &PartiallyJittedProgram,
+    public_inputs: &[U::BackendField],
+    private_inputs: &[U::BackendField],
+) -> Result<ExecutableZkpProgram>
+where
+    U: FieldSpec,
+{
+    let mut prog = partial.prog.clone();
 
     let expected_private_inputs = prog
         .node_weights()
@@ -263,11 +399,8 @@ where
         )));
     }
 
-    verify_constant_inputs(&prog, constant_inputs)?;
     constrain_public_inputs::<U>(&mut prog, public_inputs)?;
 
-    validate_zkp_program(&prog)?;
-
     let mut node_outputs: HashMap<NodeIndex, U::BackendField> = HashMap::new();
 
     // Run the graph as a computation (not a ZKP) to compute all the
@@ -292,8 +425,11 @@ where
 
                 node_outputs.insert(id, private_inputs[x].clone());
             }
-            Operation::ConstantInput(x) => {
-                node_outputs.insert(id, constant_inputs[x].clone());
+            Operation::ConstantInput(_) => {
+                return Err(Error::malformed_zkp_program(&format!(
+                    "JIT error: Node {:#?}: constant input was not specialized.",
+                    id
+                )));
             }
             Operation::HiddenInput(_) => {} // Gadgets populate these outputs.
             Operation::Add => {
@@ -338,7 +474,7 @@ where
             Operation::Constant(x) => {
                 node_outputs.insert(id, U::BackendField::try_from(x)?);
             }
-            Operation::InvokeGadget(ref g) => {
+            Operation::InvokeGadget(ref g, _) => {
                 // Have the gadget tell us what the values are for the
                 // hidden inputs and assign their value.
                 let arg_indices = query.get_ordered_operands(id)?;
@@ -424,7 +560,7 @@ where
         Ok::<_, Error>(())
     })?;
 
-    jit_common::<U>(prog, constant_inputs, public_inputs, Some(node_outputs))
+    jit_common::<U>(prog, &[], public_inputs, Some(node_outputs))
 }
 
 /**
@@ -463,13 +599,22 @@ fn jit_common<U>(
 where
     U: FieldSpec,
 {
-    // Remove Gadgets, as we should have already extracted their outputs.
+    // Detach gadgets from the circuit, as we should have already extracted
+    // their outputs. They remain as markers attributing nodes to gadgets.
     for n in prog
         .node_indices()
-        .filter(|x| matches!(prog[*x].operation, Operation::InvokeGadget(_)))
+        .filter(|x| matches!(prog[*x].operation, Operation::InvokeGadget(..)))
         .collect::<Vec<NodeIndex>>()
     {
-        prog.remove_node(n);
+        let edges = prog
+            .edges_directed(n, Direction::Incoming)
+            .chain(prog.edges_directed(n, Direction::Outgoing))
+            .map(|e| e.id())
+            .collect::<Vec<_>>();
+
+        for e in edges {
+            prog.remove_edge(e);
+        }
     }
 
     let executable_graph = prog.map(
@@ -495,7 +640,9 @@ where
                 ))),
                 None => NodeInfo::new(ExecOperation::HiddenInput(None)),
             },
-            Operation::InvokeGadget(_) => unreachable!("Not all gadgets processed and removed"),
+            Operation::InvokeGadget(ref g, n) => {
+                NodeInfo::new(ExecOperation::Gadget(g.debug_name(), n))
+            }
         },
         |_, e| *e,
     );
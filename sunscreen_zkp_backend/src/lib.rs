@@ -10,9 +10,18 @@
  */
 pub mod bulletproofs;
 
+#[cfg(feature = "groth16")]
+/**
+ * Types for working with Groth16 over BN254 as the ZKP backend.
+ */
+pub mod groth16;
+
+mod dot;
 mod error;
 mod exec;
 mod jit;
+mod stats;
+mod wire;
 
 use std::{
     any::Any,
@@ -24,12 +33,18 @@ use crypto_bigint::{
     subtle::{Choice, ConditionallySelectable},
     Limb, NonZero, U512,
 };
+pub use dot::render_compiled_zkp_program;
 pub use error::*;
 pub use exec::ExecutableZkpProgram;
-pub use jit::{jit_prover, jit_verifier, CompiledZkpProgram, Operation};
+pub use jit::{
+    jit_prover, jit_prover_with_public, jit_verifier, specialize_constants, CompiledZkpProgram,
+    Operation, PartiallyJittedProgram,
+};
 use merlin::Transcript;
 use petgraph::stable_graph::NodeIndex;
 use serde::{Deserialize, Serialize};
+pub use stats::{ConstraintCount, ConstraintStats, GadgetStats};
+pub use wire::PROOF_WIRE_VERSION;
 
 // Converting between U512 and backend numeric types requires an
 // assumption about endianess. We require little endian for now unless
@@ -119,6 +134,26 @@ pub trait Gadget: Any + Send + Sync {
      */
     fn hidden_input_count(&self) -> usize;
 
+    /**
+     * Returns the indices of the outputs from
+     * [`gen_circuit`](Gadget::gen_circuit) the verifier should learn and
+     * check, such as the digest of a hash gadget. Defaults to none.
+     *
+     * # Remarks
+     * A public output is still computed inside the circuit from the
+     * gadget's inputs. It isn't a hidden input, so
+     * [`compute_hidden_inputs`](Gadget::compute_hidden_inputs) doesn't
+     * produce its value. Instead, the caller binds each public output to a
+     * value both the prover and verifier supply as a public input, and the
+     * proof fails unless the two are equal.
+     *
+     * Each index must be less than the number of outputs `gen_circuit`
+     * returns.
+     */
+    fn public_outputs(&self) -> Vec<usize> {
+        vec![]
+    }
+
     /**
      * The gadget's name used to implement Operation's [`Debug`] trait.
      */
@@ -455,9 +490,35 @@ pub trait ZkpBackend {
     fn jit_verifier(
         &self,
         prog: &CompiledZkpProgram,
-        public_inputs: &[BigInt],
         constant_inputs: &[BigInt],
+        public_inputs: &[BigInt],
     ) -> Result<ExecutableZkpProgram>;
+
+    /**
+     * JIT the given frontend-compiled ZKP program for a verifier and
+     * verify the given proof against the resulting executable program.
+     *
+     * # Remarks
+     * The inputs are in the same order as in
+     * [`jit_verifier`](Self::jit_verifier).
+     *
+     * JIT compilation constrains each of the program's public inputs to
+     * the given values, so verification fails unless the prover used the
+     * same public inputs. Prefer this over [`verify`](Self::verify) when
+     * you didn't JIT the [`ExecutableZkpProgram`] yourself, as the
+     * public inputs baked into it may not be the ones you expect.
+     */
+    fn verify_with_inputs(
+        &self,
+        prog: &CompiledZkpProgram,
+        proof: &Proof,
+        constant_inputs: &[BigInt],
+        public_inputs: &[BigInt],
+    ) -> Result<()> {
+        let graph = self.jit_verifier(prog, constant_inputs, public_inputs)?;
+
+        self.verify(&graph, proof)
+    }
 }
 
 /**
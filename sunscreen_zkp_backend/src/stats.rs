@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+
+use petgraph::stable_graph::NodeIndex;
+use sunscreen_compiler_common::GraphQuery;
+
+use crate::{
+    exec::Operation as ExecOperation, CompiledZkpProgram, ExecutableZkpProgram, Operation,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/**
+ * The size of a ZKP program's circuit. See [`ConstraintCount`].
+ */
+pub struct ConstraintStats {
+    /**
+     * The number of multiplication gates. Multiplying by a constant is a
+     * linear operation and doesn't count.
+     */
+    pub multiplications: usize,
+
+    /**
+     * The number of addition and subtraction gates.
+     */
+    pub additions: usize,
+
+    /**
+     * The number of constraints.
+     */
+    pub constraints: usize,
+
+    /**
+     * The circuit each [`Gadget`](crate::Gadget) contributes to the program,
+     * keyed by [`debug_name`](crate::Gadget::debug_name).
+     */
+    pub gadgets: HashMap<&'static str, GadgetStats>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/**
+ * The part of a ZKP program's circuit generated by one type of
+ * [`Gadget`](crate::Gadget), summed over all its invocations. See
+ * [`ConstraintStats::gadgets`].
+ */
+pub struct GadgetStats {
+    /**
+     * The number of times the program invokes the gadget.
+     */
+    pub invocations: usize,
+
+    /**
+     * The number of multiplication gates in the gadget's circuits.
+     */
+    pub multiplications: usize,
+
+    /**
+     * The number of addition and subtraction gates in the gadget's circuits.
+     */
+    pub additions: usize,
+
+    /**
+     * The number of constraints in the gadget's circuits.
+     */
+    pub constraints: usize,
+}
+
+/**
+ * Measures the size of a ZKP program's circuit.
+ */
+pub trait ConstraintCount {
+    /**
+     * Counts the gates and constraints in this program's circuit.
+     *
+     * # Remarks
+     * Most backends' proving cost scales with the number of multiplication
+     * gates (e.g. Bulletproofs), so [`ConstraintStats::multiplications`] is
+     * usually the number to watch.
+     *
+     * Invoking a gadget inlines its circuit, so the gadget's gates appear
+     * in the totals as well as in [`ConstraintStats::gadgets`]. When one
+     * gadget invokes another, the inner gadget's gates count towards both.
+     * JIT compilation keeps track of gadget invocations, so an
+     * [`ExecutableZkpProgram`] reports the same gadgets as the
+     * [`CompiledZkpProgram`] it came from. JIT compilation also constrains
+     * each public input, so an [`ExecutableZkpProgram`] has one more
+     * constraint per public input than the [`CompiledZkpProgram`] it came
+     * from.
+     */
+    fn constraint_count(&self) -> ConstraintStats;
+}
+
+/**
+ * The contribution of a single node to [`ConstraintStats`].
+ */
+enum Gate {
+    Multiplication,
+    Addition,
+    Constraint,
+    Gadget(&'static str, usize),
+    None,
+}
+
+/**
+ * Sums the gates in node index order, attributing the nodes following each
+ * gadget invocation to that gadget.
+ */
+fn count_gates(gates: impl Iterator<Item = Gate>) -> ConstraintStats {
+    let mut stats = ConstraintStats::default();
+
+    // The gadgets whose nodes we're in and the position of their last node.
+    let mut enclosing: Vec<(&'static str, usize)> = vec![];
+
+    for (position, gate) in gates.enumerate() {
+        enclosing.retain(|(_, end)| *end >= position);
+
+        let (multiplications, additions, constraints) = match gate {
+            Gate::Multiplication => (1, 0, 0),
+            Gate::Addition => (0, 1, 0),
+            Gate::Constraint => (0, 0, 1),
+            Gate::Gadget(name, span) => {
+                stats.gadgets.entry(name).or_default().invocations += 1;
+                enclosing.push((name, position + span));
+
+                continue;
+            }
+            Gate::None => continue,
+        };
+
+        stats.multiplications += multiplications;
+        stats.additions += additions;
+        stats.constraints += constraints;
+
+        // A gadget nested in another of the same type counts once.
+        let mut names = enclosing.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        for name in names {
+            let gadget = stats.gadgets.entry(name).or_default();
+
+            gadget.multiplications += multiplications;
+            gadget.additions += additions;
+            gadget.constraints += constraints;
+        }
+    }
+
+    stats
+}
+
+impl ConstraintCount for ExecutableZkpProgram {
+    fn constraint_count(&self) -> ConstraintStats {
+        let query = GraphQuery::new(self);
+        let is_constant = |id: NodeIndex| matches!(self[id].operation, ExecOperation::Constant(_));
+
+        count_gates(self.node_indices().map(|id| match self[id].operation {
+            ExecOperation::Add | ExecOperation::Sub => Gate::Addition,
+            ExecOperation::Mul => {
+                let (left, right) = query
+                    .get_binary_operands(id)
+                    .expect("Multiplication should have 2 operands");
+
+                if !is_constant(left) && !is_constant(right) {
+                    Gate::Multiplication
+                } else {
+                    Gate::None
+                }
+            }
+            ExecOperation::Constraint(_) => Gate::Constraint,
+            ExecOperation::Gadget(name, span) => Gate::Gadget(name, span),
+            _ => Gate::None,
+        }))
+    }
+}
+
+impl ConstraintCount for CompiledZkpProgram {
+    fn constraint_count(&self) -> ConstraintStats {
+        let query = GraphQuery::new(self);
+        let is_constant = |id: NodeIndex| {
+            matches!(
+                self[id].operation,
+                Operation::Constant(_) | Operation::ConstantInput(_)
+            )
+        };
+
+        count_gates(self.node_indices().map(|id| match &self[id].operation {
+            Operation::Add | Operation::Sub => Gate::Addition,
+            Operation::Mul => {
+                let (left, right) = query
+                    .get_binary_operands(id)
+                    .expect("Multiplication should have 2 operands");
+
+                if !is_constant(left) && !is_constant(right) {
+                    Gate::Multiplication
+                } else {
+                    Gate::None
+                }
+            }
+            Operation::Constraint(_) => Gate::Constraint,
+            Operation::InvokeGadget(g, span) => Gate::Gadget(g.debug_name(), *span),
+            _ => Gate::None,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sunscreen_compiler_common::{EdgeInfo, NodeInfo};
+
+    use super::*;
+    use crate::{BigInt, Gadget, Result};
+
+    struct TestGadget(&'static str);
+
+    impl Gadget for TestGadget {
+        fn compute_hidden_inputs(&self, _gadget_inputs: &[BigInt]) -> Result<Vec<BigInt>> {
+            Ok(vec![])
+        }
+
+        fn gen_circuit(
+            &self,
+            _gadget_inputs: &[NodeIndex],
+            _hidden_inputs: &[NodeIndex],
+        ) -> Vec<NodeIndex> {
+            vec![]
+        }
+
+        fn gadget_input_count(&self) -> usize {
+            1
+        }
+
+        fn hidden_input_count(&self) -> usize {
+            0
+        }
+
+        fn debug_name(&self) -> &'static str {
+            self.0
+        }
+    }
+
+    #[test]
+    fn can_count_compiled_program_constraints() {
+        let mut prog = CompiledZkpProgram::new();
+
+        let mut add_node = |op: Operation, edges: &[(NodeIndex, EdgeInfo)]| {
+            let n = prog.add_node(NodeInfo { operation: op });
+
+            for (source, edge) in edges {
+                prog.add_edge(*source, n, *edge);
+            }
+
+            n
+        };
+
+        // (a * b + c * k - a) * a == 42, where k is a constant input.
+        let a = add_node(Operation::PrivateInput(0), &[]);
+        let b = add_node(Operation::PrivateInput(1), &[]);
+        let c = add_node(Operation::PublicInput(0), &[]);
+        let k = add_node(Operation::ConstantInput(0), &[]);
+
+        let mul_1 = add_node(Operation::Mul, &[(a, EdgeInfo::Left), (b, EdgeInfo::Right)]);
+        let mul_2 = add_node(Operation::Mul, &[(c, EdgeInfo::Left), (k, EdgeInfo::Right)]);
+        let add = add_node(
+            Operation::Add,
+            &[(mul_1, EdgeInfo::Left), (mul_2, EdgeInfo::Right)],
+        );
+        let sub = add_node(
+            Operation::Sub,
+            &[(add, EdgeInfo::Left), (a, EdgeInfo::Right)],
+        );
+        let mul_3 = add_node(
+            Operation::Mul,
+            &[(sub, EdgeInfo::Left), (a, EdgeInfo::Right)],
+        );
+
+        add_node(
+            Operation::Constraint(BigInt::from_u32(42)),
+            &[(mul_3, EdgeInfo::Unordered)],
+        );
+
+        for _ in 0..2 {
+            add_node(
+                Operation::InvokeGadget(Arc::new(TestGadget("TestGadget")), 0),
+                &[(a, EdgeInfo::Ordered(0))],
+            );
+        }
+
+        let stats = prog.constraint_count();
+
+        assert_eq!(stats.multiplications, 2);
+        assert_eq!(stats.additions, 2);
+        assert_eq!(stats.constraints, 1);
+        assert_eq!(
+            stats.gadgets,
+            HashMap::from([(
+                "TestGadget",
+                GadgetStats {
+                    invocations: 2,
+                    ..GadgetStats::default()
+                }
+            )])
+        );
+    }
+
+    #[test]
+    fn can_attribute_gates_to_gadgets() {
+        let mut prog = CompiledZkpProgram::new();
+
+        let mut add_node = |op: Operation, edges: &[(NodeIndex, EdgeInfo)]| {
+            let n = prog.add_node(NodeInfo { operation: op });
+
+            for (source, edge) in edges {
+                prog.add_edge(*source, n, *edge);
+            }
+
+            n
+        };
+
+        // The outer gadget spans the 4 nodes after it, one of which invokes
+        // the inner gadget spanning the addition.
+        let a = add_node(Operation::PrivateInput(0), &[]);
+        add_node(
+            Operation::InvokeGadget(Arc::new(TestGadget("Outer")), 4),
+            &[(a, EdgeInfo::Ordered(0))],
+        );
+        let mul = add_node(Operation::Mul, &[(a, EdgeInfo::Left), (a, EdgeInfo::Right)]);
+        add_node(
+            Operation::InvokeGadget(Arc::new(TestGadget("Inner")), 1),
+            &[(mul, EdgeInfo::Ordered(0))],
+        );
+        let add = add_node(
+            Operation::Add,
+            &[(mul, EdgeInfo::Left), (a, EdgeInfo::Right)],
+        );
+        add_node(
+            Operation::Constraint(BigInt::from_u32(0)),
+            &[(add, EdgeInfo::Unordered)],
+        );
+        add_node(
+            Operation::Constraint(BigInt::from_u32(1)),
+            &[(a, EdgeInfo::Unordered)],
+        );
+
+        let expected = ConstraintStats {
+            multiplications: 1,
+            additions: 1,
+            constraints: 2,
+            gadgets: HashMap::from([
+                (
+                    "Outer",
+                    GadgetStats {
+                        invocations: 1,
+                        multiplications: 1,
+                        additions: 1,
+                        constraints: 1,
+                    },
+                ),
+                (
+                    "Inner",
+                    GadgetStats {
+                        invocations: 1,
+                        additions: 1,
+                        ..GadgetStats::default()
+                    },
+                ),
+            ]),
+        };
+
+        assert_eq!(prog.constraint_count(), expected);
+
+        #[cfg(feature = "bulletproofs")]
+        {
+            use crate::{bulletproofs::BulletproofsFieldSpec, jit_verifier};
+
+            let executable = jit_verifier::<BulletproofsFieldSpec>(&prog, &[], &[]).unwrap();
+
+            assert_eq!(executable.constraint_count(), expected);
+        }
+    }
+
+    #[test]
+    fn can_count_executable_program_constraints() {
+        let mut prog = ExecutableZkpProgram::new();
+
+        let mut add_node = |op: ExecOperation, edges: &[(NodeIndex, EdgeInfo)]| {
+            let n = prog.add_node(NodeInfo { operation: op });
+
+            for (source, edge) in edges {
+                prog.add_edge(*source, n, *edge);
+            }
+
+            n
+        };
+
+        // (a * 3 - b) * (a * b) == 42
+        let a = add_node(ExecOperation::Input(0), &[]);
+        let b = add_node(ExecOperation::HiddenInput(None), &[]);
+        let three = add_node(ExecOperation::Constant(BigInt::from_u32(3)), &[]);
+
+        let mul_1 = add_node(
+            ExecOperation::Mul,
+            &[(a, EdgeInfo::Left), (three, EdgeInfo::Right)],
+        );
+        let sub = add_node(
+            ExecOperation::Sub,
+            &[(mul_1, EdgeInfo::Left), (b, EdgeInfo::Right)],
+        );
+        let mul_2 = add_node(
+            ExecOperation::Mul,
+            &[(a, EdgeInfo::Left), (b, EdgeInfo::Right)],
+        );
+        let mul_3 = add_node(
+            ExecOperation::Mul,
+            &[(sub, EdgeInfo::Left), (mul_2, EdgeInfo::Right)],
+        );
+
+        add_node(
+            ExecOperation::Constraint(BigInt::from_u32(42)),
+            &[(mul_3, EdgeInfo::Unordered)],
+        );
+        add_node(
+            ExecOperation::Constraint(BigInt::from_u32(7)),
+            &[(a, EdgeInfo::Unordered)],
+        );
+
+        let stats = prog.constraint_count();
+
+        assert_eq!(stats.multiplications, 2);
+        assert_eq!(stats.additions, 1);
+        assert_eq!(stats.constraints, 2);
+        assert!(stats.gadgets.is_empty());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{Error, Proof, Result};
+
+/**
+ * The magic bytes that begin every proof in the wire format.
+ */
+const WIRE_MAGIC: [u8; 4] = *b"SZKP";
+
+/**
+ * The current version of the proof wire format. See [`Proof::to_wire`].
+ */
+pub const PROOF_WIRE_VERSION: u16 = 1;
+
+const BULLETPROOFS_NAME: &str = "bulletproofs";
+
+/**
+ * The tag byte beginning a Bulletproofs proof in [`Proof::to_bytes`]'s
+ * format.
+ */
+const BULLETPROOFS_TAG: u8 = 0;
+
+/**
+ * The tag byte beginning a custom proof in [`Proof::to_bytes`]'s format.
+ */
+const CUSTOM_TAG: u8 = 1;
+
+impl Proof {
+    /**
+     * The name of the proof system that produced this proof, as written in
+     * its wire format.
+     */
+    pub fn system_name(&self) -> &str {
+        match self {
+            #[cfg(feature = "bulletproofs")]
+            Self::Bulletproofs(_) => BULLETPROOFS_NAME,
+            Self::Custom { name, .. } => name,
+        }
+    }
+
+    /**
+     * Serializes this proof to a compact byte format tagged with its proof
+     * system.
+     *
+     * # Remarks
+     * The first byte identifies the proof system:
+     * * `0` for Bulletproofs, followed by the proof encoded with
+     *   `R1CSProof::to_bytes`.
+     * * `1` for custom proofs, followed by the length of the proof
+     *   system's name as a little-endian `u16`, the UTF-8 name, and the
+     *   proof data.
+     *
+     * Unlike [`Proof`]'s serde implementation, this lets
+     * [`Proof::from_bytes`] report proofs from a proof system this build
+     * doesn't support. Use [`Proof::to_wire`] for proofs that must remain
+     * readable across format versions.
+     *
+     * # Panics
+     * If the proof system's name exceeds `u16::MAX` bytes.
+     */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            #[cfg(feature = "bulletproofs")]
+            Self::Bulletproofs(p) => {
+                let mut bytes = vec![BULLETPROOFS_TAG];
+                bytes.extend_from_slice(&p.0.to_bytes());
+
+                bytes
+            }
+            Self::Custom { name, data } => {
+                let name_len = u16::try_from(name.len()).expect("Proof system name is too long.");
+
+                let mut bytes = Vec::with_capacity(3 + name.len() + data.len());
+                bytes.push(CUSTOM_TAG);
+                bytes.extend_from_slice(&name_len.to_le_bytes());
+                bytes.extend_from_slice(name.as_bytes());
+                bytes.extend_from_slice(data);
+
+                bytes
+            }
+        }
+    }
+
+    /**
+     * Deserializes a proof written by [`Proof::to_bytes`].
+     *
+     * # Remarks
+     * Returns [`Error::UnsupportedProofSystem`] if `bytes` holds a
+     * Bulletproofs proof and the `bulletproofs` feature is disabled, or
+     * begins with an unknown tag. Returns [`Error::MalformedProof`] if
+     * `bytes` is otherwise invalid.
+     */
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = WireReader(bytes);
+
+        let [tag] = reader.take_array("tag")?;
+
+        match tag {
+            BULLETPROOFS_TAG => {
+                #[cfg(feature = "bulletproofs")]
+                {
+                    let proof = bulletproofs::r1cs::R1CSProof::from_bytes(reader.0)
+                        .map_err(|e| Error::malformed_proof(&e.to_string()))?;
+
+                    Ok(Self::Bulletproofs(Box::new(
+                        crate::bulletproofs::BulletproofsR1CSProof(proof),
+                    )))
+                }
+
+                #[cfg(not(feature = "bulletproofs"))]
+                Err(Error::unsupported_proof_system(BULLETPROOFS_NAME))
+            }
+            CUSTOM_TAG => {
+                let name_len = u16::from_le_bytes(reader.take_array("name length")?);
+                let name = std::str::from_utf8(reader.take(name_len as usize, "name")?)
+                    .map_err(|_| Error::malformed_proof("name isn't valid UTF-8"))?;
+
+                Ok(Self::Custom {
+                    name: name.to_owned(),
+                    data: reader.0.to_owned(),
+                })
+            }
+            _ => Err(Error::unsupported_proof_system(&format!("with tag {tag}"))),
+        }
+    }
+
+    /**
+     * Serializes this proof to a versioned, self-describing byte format
+     * suitable for persisting or transmitting proofs across versions and
+     * languages.
+     *
+     * # Remarks
+     * The format consists of the following fields, with integers encoded
+     * little-endian:
+     *
+     * | Field | Size (bytes) | Description |
+     * |-------|--------------|-------------|
+     * | magic | 4 | The ASCII string `SZKP`. |
+     * | version | 2 | The format version, currently [`PROOF_WIRE_VERSION`]. |
+     * | name length | 2 | The length of the following name. |
+     * | name | name length | The UTF-8 name of the proof system (see [`Proof::system_name`]). |
+     * | data length | 8 | The length of the following data. |
+     * | data | data length | The proof, encoded as defined by the proof system. |
+     *
+     * Bulletproofs proofs have the name `bulletproofs` and are encoded with
+     * `R1CSProof::to_bytes`.
+     * Groth16 proofs are custom proofs named `groth16` holding the
+     * compressed arkworks encoding of the proof.
+     * Custom proofs write their `name` and `data` as is.
+     *
+     * # Panics
+     * If the proof system's name exceeds `u16::MAX` bytes.
+     */
+    pub fn to_wire(&self) -> Vec<u8> {
+        let name = self.system_name().as_bytes();
+
+        let data = match self {
+            #[cfg(feature = "bulletproofs")]
+            Self::Bulletproofs(p) => p.0.to_bytes(),
+            Self::Custom { data, .. } => data.clone(),
+        };
+
+        let name_len = u16::try_from(name.len()).expect("Proof system name is too long.");
+
+        let mut wire = Vec::with_capacity(16 + name.len() + data.len());
+        wire.extend_from_slice(&WIRE_MAGIC);
+        wire.extend_from_slice(&PROOF_WIRE_VERSION.to_le_bytes());
+        wire.extend_from_slice(&name_len.to_le_bytes());
+        wire.extend_from_slice(name);
+        wire.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        wire.extend_from_slice(&data);
+
+        wire
+    }
+
+    /**
+     * Deserializes a proof written by [`Proof::to_wire`].
+     *
+     * # Remarks
+     * Proofs named `bulletproofs` decode as [`Proof::Bulletproofs`] when the
+     * `bulletproofs` feature is enabled. All other proofs, including
+     * Bulletproofs proofs when the feature is disabled, decode as
+     * [`Proof::Custom`]; verifying them with an incompatible backend fails
+     * with [`Error::IncorrectProofType`].
+     *
+     * Returns [`Error::UnsupportedProofVersion`] if `bytes` was written with
+     * a different format version and [`Error::MalformedProof`] if `bytes`
+     * is otherwise invalid.
+     */
+    pub fn from_wire(bytes: &[u8]) -> Result<Self> {
+        let mut reader = WireReader(bytes);
+
+        if reader.take(WIRE_MAGIC.len(), "magic")? != WIRE_MAGIC {
+            return Err(Error::malformed_proof("missing magic bytes"));
+        }
+
+        let version = u16::from_le_bytes(reader.take_array("version")?);
+
+        if version != PROOF_WIRE_VERSION {
+            return Err(Error::UnsupportedProofVersion(version));
+        }
+
+        let name_len = u16::from_le_bytes(reader.take_array("name length")?);
+        let name = std::str::from_utf8(reader.take(name_len as usize, "name")?)
+            .map_err(|_| Error::malformed_proof("name isn't valid UTF-8"))?;
+
+        let data_len = u64::from_le_bytes(reader.take_array("data length")?);
+        let data_len = usize::try_from(data_len)
+            .map_err(|_| Error::malformed_proof("data length overflows usize"))?;
+        let data = reader.take(data_len, "data")?;
+
+        if !reader.0.is_empty() {
+            return Err(Error::malformed_proof("trailing bytes after data"));
+        }
+
+        match name {
+            #[cfg(feature = "bulletproofs")]
+            BULLETPROOFS_NAME => {
+                let proof = bulletproofs::r1cs::R1CSProof::from_bytes(data)
+                    .map_err(|e| Error::malformed_proof(&e.to_string()))?;
+
+                Ok(Self::Bulletproofs(Box::new(
+                    crate::bulletproofs::BulletproofsR1CSProof(proof),
+                )))
+            }
+            _ => Ok(Self::Custom {
+                name: name.to_owned(),
+                data: data.to_owned(),
+            }),
+        }
+    }
+}
+
+struct WireReader<'a>(&'a [u8]);
+
+impl<'a> WireReader<'a> {
+    fn take(&mut self, len: usize, field: &str) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::malformed_proof(&format!("truncated {field}")));
+        }
+
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+
+        Ok(head)
+    }
+
+    fn take_array<const N: usize>(&mut self, field: &str) -> Result<[u8; N]> {
+        // take returns exactly N bytes on success.
+        Ok(self.take(N, field)?.try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_proof() -> Proof {
+        Proof::Custom {
+            name: "kitty".to_owned(),
+            data: vec![1, 2, 3, 4, 5],
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_custom_proof() {
+        let wire = custom_proof().to_wire();
+
+        assert_eq!(wire.len(), 4 + 2 + 2 + 5 + 8 + 5);
+
+        match Proof::from_wire(&wire).unwrap() {
+            Proof::Custom { name, data } => {
+                assert_eq!(name, "kitty");
+                assert_eq!(data, vec![1, 2, 3, 4, 5]);
+            }
+            #[allow(unreachable_patterns)]
+            _ => panic!("Expected a custom proof"),
+        }
+    }
+
+    #[test]
+    fn can_roundtrip_custom_proof_bytes() {
+        let bytes = custom_proof().to_bytes();
+
+        assert_eq!(bytes.len(), 1 + 2 + 5 + 5);
+
+        match Proof::from_bytes(&bytes).unwrap() {
+            Proof::Custom { name, data } => {
+                assert_eq!(name, "kitty");
+                assert_eq!(data, vec![1, 2, 3, 4, 5]);
+            }
+            #[allow(unreachable_patterns)]
+            _ => panic!("Expected a custom proof"),
+        }
+    }
+
+    #[test]
+    fn from_bytes_rejects_unsupported_proof_systems() {
+        assert!(matches!(
+            Proof::from_bytes(&[7, 1, 2, 3]),
+            Err(Error::UnsupportedProofSystem(_))
+        ));
+
+        #[cfg(not(feature = "bulletproofs"))]
+        assert_eq!(
+            Proof::from_bytes(&[BULLETPROOFS_TAG, 1, 2, 3]).err(),
+            Some(Error::unsupported_proof_system(BULLETPROOFS_NAME))
+        );
+
+        for bytes in [&[][..], &[CUSTOM_TAG, 5, 0, b'k'][..]] {
+            assert!(matches!(
+                Proof::from_bytes(bytes),
+                Err(Error::MalformedProof(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn rejects_version_mismatch() {
+        let mut wire = custom_proof().to_wire();
+        wire[4..6].copy_from_slice(&(PROOF_WIRE_VERSION + 1).to_le_bytes());
+
+        assert!(matches!(
+            Proof::from_wire(&wire),
+            Err(Error::UnsupportedProofVersion(v)) if v == PROOF_WIRE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_proofs() {
+        let wire = custom_proof().to_wire();
+
+        let mut bad_magic = wire.clone();
+        bad_magic[0] = b'X';
+
+        let mut trailing = wire.clone();
+        trailing.push(0);
+
+        for bytes in [
+            &bad_magic[..],
+            &wire[..wire.len() - 1],
+            &trailing[..],
+            &[][..],
+        ] {
+            assert!(matches!(
+                Proof::from_wire(bytes),
+                Err(Error::MalformedProof(_))
+            ));
+        }
+    }
+}